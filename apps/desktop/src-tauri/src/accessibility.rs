@@ -0,0 +1,217 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::i18n;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+static PREFS: Mutex<AccessibilityPrefs> = Mutex::new(AccessibilityPrefs {
+    reduce_motion: false,
+    increase_contrast: false,
+});
+
+/// OS display preferences the UI should honour
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessibilityPrefs {
+    pub reduce_motion: bool,
+    pub increase_contrast: bool,
+}
+
+/// Ask VoiceOver to speak a message. The bubble never takes focus, so without this
+/// screen reader users get no feedback for changes they can't see.
+#[cfg(target_os = "macos")]
+fn post_announcement(message: &str) {
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::NSString;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" {
+        static NSAccessibilityAnnouncementRequestedNotification: id;
+        static NSAccessibilityAnnouncementKey: id;
+        static NSAccessibilityPriorityKey: id;
+        fn NSAccessibilityPostNotificationWithUserInfo(element: id, notification: id, info: id);
+    }
+
+    // NSAccessibilityPriorityHigh, which interrupts whatever VoiceOver is saying
+    const PRIORITY_HIGH: isize = 90;
+
+    // SAFETY: runs on the main thread; the objects created here are autoreleased or
+    // released before returning, and the notification constants are AppKit globals
+    unsafe {
+        let application: id = msg_send![class!(NSApplication), sharedApplication];
+        let text = NSString::alloc(nil).init_str(message);
+        let priority: id = msg_send![class!(NSNumber), numberWithInteger: PRIORITY_HIGH];
+        let keys = [NSAccessibilityAnnouncementKey, NSAccessibilityPriorityKey];
+        let values = [text, priority];
+        let info: id = msg_send![
+            class!(NSDictionary),
+            dictionaryWithObjects: values.as_ptr()
+            forKeys: keys.as_ptr()
+            count: keys.len()
+        ];
+        NSAccessibilityPostNotificationWithUserInfo(
+            application,
+            NSAccessibilityAnnouncementRequestedNotification,
+            info,
+        );
+        let _: () = msg_send![text, release];
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn post_announcement(_message: &str) {}
+
+/// Speak a message through the platform screen reader, if one is running
+pub fn announce(app: &AppHandle, message: String) {
+    let _ = app.run_on_main_thread(move || post_announcement(&message));
+}
+
+/// Announce the message for an i18n key
+pub fn announce_key(app: &AppHandle, key: &str) {
+    announce(app, i18n::t(key));
+}
+
+#[cfg(target_os = "macos")]
+fn read_prefs() -> AccessibilityPrefs {
+    use cocoa::base::{id, BOOL, YES};
+    use objc::{class, msg_send, sel, sel_impl};
+
+    // SAFETY: read-only NSWorkspace properties, documented as safe off the main thread
+    unsafe {
+        let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let reduce_motion: BOOL = msg_send![workspace, accessibilityDisplayShouldReduceMotion];
+        let increase_contrast: BOOL =
+            msg_send![workspace, accessibilityDisplayShouldIncreaseContrast];
+        AccessibilityPrefs {
+            reduce_motion: reduce_motion == YES,
+            increase_contrast: increase_contrast == YES,
+        }
+    }
+}
+
+/// Value of `name` in `reg query` output, e.g. "    Flags    REG_SZ    126"
+#[cfg(any(target_os = "windows", test))]
+fn reg_value<'a>(output: &'a str, name: &str) -> Option<&'a str> {
+    output.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        if !fields.next()?.eq_ignore_ascii_case(name) {
+            return None;
+        }
+        fields.next()?.starts_with("REG_").then_some(())?;
+        fields.next()
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn read_prefs() -> AccessibilityPrefs {
+    use std::process::Command;
+
+    // HCF_HIGHCONTRASTON
+    const HIGH_CONTRAST_ON: u32 = 0x1;
+
+    let query = |key: &str, name: &str| {
+        let output = Command::new("reg")
+            .args(["query", key, "/v", name])
+            .output()
+            .ok()?;
+        reg_value(&String::from_utf8_lossy(&output.stdout), name).map(str::to_string)
+    };
+    let high_contrast = query("HKCU\\Control Panel\\Accessibility\\HighContrast", "Flags")
+        .and_then(|flags| flags.parse::<u32>().ok())
+        .is_some_and(|flags| flags & HIGH_CONTRAST_ON != 0);
+    // "Animate windows when minimizing and maximizing", off with "Show animations" off
+    let animations_off = query("HKCU\\Control Panel\\Desktop\\WindowMetrics", "MinAnimate")
+        .is_some_and(|value| value == "0");
+    AccessibilityPrefs {
+        reduce_motion: animations_off,
+        increase_contrast: high_contrast,
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn read_prefs() -> AccessibilityPrefs {
+    use std::process::Command;
+
+    let gsettings = |schema: &str, key: &str| {
+        let output = Command::new("gsettings")
+            .args(["get", schema, key])
+            .output()
+            .ok()?;
+        Some(String::from_utf8_lossy(&output.stdout).trim() == "true")
+    };
+    AccessibilityPrefs {
+        reduce_motion: gsettings("org.gnome.desktop.interface", "enable-animations")
+            .is_some_and(|enabled| !enabled),
+        increase_contrast: gsettings("org.gnome.desktop.a11y.interface", "high-contrast")
+            .unwrap_or(false),
+    }
+}
+
+/// Turn off the window open and close animations while reduce motion is on
+#[cfg(target_os = "macos")]
+fn apply_window_animations(app: &AppHandle, reduce_motion: bool) {
+    use cocoa::base::id;
+    use objc::{msg_send, sel, sel_impl};
+    use tauri::Manager;
+
+    // NSWindowAnimationBehaviorNone and NSWindowAnimationBehaviorDefault
+    let behavior: isize = if reduce_motion { 2 } else { 0 };
+    for window in app.webview_windows().into_values() {
+        let Ok(ns_window) = window.ns_window() else {
+            continue;
+        };
+        // SAFETY: called on the main thread with a live NSWindow owned by tauri
+        unsafe {
+            let _: () = msg_send![ns_window as id, setAnimationBehavior: behavior];
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn apply_window_animations(_app: &AppHandle, _reduce_motion: bool) {}
+
+pub fn prefs() -> AccessibilityPrefs {
+    PREFS.lock().map(|prefs| *prefs).unwrap_or_default()
+}
+
+/// Follow the OS preferences, re-applying them each poll so windows created since
+/// (the picker, the switch button) pick them up too
+pub fn start_monitor(app: AppHandle) {
+    thread::spawn(move || loop {
+        let current = read_prefs();
+        let changed = PREFS
+            .lock()
+            .map(|mut prefs| std::mem::replace(&mut *prefs, current) != current)
+            .unwrap_or(false);
+        if changed {
+            let _ = app.emit("accessibility-prefs-changed", current);
+        }
+        let handle = app.clone();
+        let _ =
+            app.run_on_main_thread(move || apply_window_animations(&handle, current.reduce_motion));
+        thread::sleep(POLL_INTERVAL);
+    });
+}
+
+#[tauri::command]
+pub async fn get_accessibility_prefs() -> AccessibilityPrefs {
+    prefs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_reg_query_values() {
+        let output = "\r\nHKEY_CURRENT_USER\\Control Panel\\Accessibility\\HighContrast\r\n    Flags    REG_SZ    127\r\n\r\n";
+        assert_eq!(reg_value(output, "Flags"), Some("127"));
+        assert_eq!(reg_value(output, "MinAnimate"), None);
+        assert_eq!(reg_value("HKEY_CURRENT_USER\\Flags", "Flags"), None);
+    }
+}
@@ -0,0 +1,166 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::thread;
+
+// Frontmost app when the current (or last) dictation started
+static AT_START: Mutex<Option<ActiveApp>> = Mutex::new(None);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveApp {
+    pub name: String,
+    /// Bundle identifier on macOS, executable file name such as "Code.exe" elsewhere
+    pub bundle_id: String,
+    pub window_title: String,
+}
+
+/// Query the frontmost application and its focused window title via System Events
+#[cfg(target_os = "macos")]
+pub fn frontmost_app() -> Option<ActiveApp> {
+    use std::process::Command;
+
+    let script = r#"
+        tell application "System Events"
+            set frontApp to first application process whose frontmost is true
+            set appName to name of frontApp
+            set bundleId to bundle identifier of frontApp
+            set windowTitle to ""
+            try
+                set windowTitle to name of front window of frontApp
+            end try
+        end tell
+        return appName & linefeed & bundleId & linefeed & windowTitle
+    "#;
+
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.trim_end_matches('\n').splitn(3, '\n');
+    Some(ActiveApp {
+        name: lines.next()?.to_string(),
+        bundle_id: lines.next().unwrap_or_default().to_string(),
+        window_title: lines.next().unwrap_or_default().to_string(),
+    })
+}
+
+/// The foreground window's title and the executable of the process owning it
+#[cfg(target_os = "windows")]
+pub fn frontmost_app() -> Option<ActiveApp> {
+    use std::path::Path;
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, GetWindowTextW, GetWindowThreadProcessId,
+    };
+
+    // SAFETY: plain query, null when no window has the focus
+    let window = unsafe { GetForegroundWindow() };
+    if window.is_null() {
+        return None;
+    }
+    let mut title = [0u16; 512];
+    // SAFETY: the length passed is that of `title`
+    let length = unsafe { GetWindowTextW(window, title.as_mut_ptr(), title.len() as i32) };
+    let window_title = String::from_utf16_lossy(&title[..length.max(0) as usize]);
+
+    let mut process_id = 0u32;
+    // SAFETY: `window` is a window handle and `process_id` a valid out pointer
+    unsafe { GetWindowThreadProcessId(window, &mut process_id) };
+    // SAFETY: the handle is checked for null and closed below
+    let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, process_id) };
+    if process.is_null() {
+        return None;
+    }
+    let mut image = [0u16; 1024];
+    let mut size = image.len() as u32;
+    // SAFETY: `size` holds the length of `image` and receives the length written
+    let ok = unsafe {
+        QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, image.as_mut_ptr(), &mut size)
+    };
+    // SAFETY: `process` was opened above
+    unsafe { CloseHandle(process) };
+    if ok == 0 {
+        return None;
+    }
+    let image = String::from_utf16_lossy(&image[..size as usize]);
+    let path = Path::new(&image);
+    Some(ActiveApp {
+        name: path.file_stem()?.to_string_lossy().into_owned(),
+        bundle_id: path.file_name()?.to_string_lossy().into_owned(),
+        window_title,
+    })
+}
+
+/// The active X11 window through xdotool, with the process behind it from /proc.
+/// Wayland compositors don't tell other clients which window has the focus, so only
+/// XWayland windows are seen there.
+#[cfg(target_os = "linux")]
+pub fn frontmost_app() -> Option<ActiveApp> {
+    use std::fs;
+    use std::process::Command;
+
+    let output = Command::new("xdotool")
+        .args(["getactivewindow", "getwindowpid", "getwindowname"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    let pid: u32 = lines.next()?.trim().parse().ok()?;
+    let window_title = lines.next().unwrap_or_default().to_string();
+    let executable = fs::read_link(format!("/proc/{pid}/exe"))
+        .ok()
+        .and_then(|path| Some(path.file_name()?.to_string_lossy().into_owned()))
+        .unwrap_or_default();
+    let name = fs::read_to_string(format!("/proc/{pid}/comm"))
+        .map(|name| name.trim().to_string())
+        .unwrap_or_else(|_| executable.clone());
+    Some(ActiveApp {
+        name,
+        bundle_id: executable,
+        window_title,
+    })
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+pub fn frontmost_app() -> Option<ActiveApp> {
+    None
+}
+
+/// Note the frontmost app for a dictation that is starting, off the caller's thread
+/// since the query can take a moment
+pub fn capture_in_background() {
+    if let Ok(mut at_start) = AT_START.lock() {
+        *at_start = None;
+    }
+    thread::spawn(|| {
+        let frontmost = frontmost_app();
+        if let Ok(mut at_start) = AT_START.lock() {
+            *at_start = frontmost;
+        }
+    });
+}
+
+/// Frontmost app when the current or last dictation started
+pub fn at_start() -> Option<ActiveApp> {
+    AT_START.lock().ok().and_then(|at_start| at_start.clone())
+}
+
+/// The app the current or last dictation was started in, or the frontmost one when
+/// nothing has been dictated yet
+#[tauri::command]
+pub async fn get_active_app() -> Option<ActiveApp> {
+    at_start().or_else(frontmost_app)
+}
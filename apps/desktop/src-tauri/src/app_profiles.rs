@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::AppHandle;
+
+use crate::active_app::{self, ActiveApp};
+use crate::notation::NotationStyle;
+use crate::{i18n, prompts, settings};
+
+// Settings that change with the app being dictated into, keyed by app name or bundle
+// identifier (executable name on Windows and Linux) as in app_rules. The app is the
+// one that was frontmost when the dictation started.
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AppProfile {
+    /// Ask before pasting, e.g. so a dictation never runs as a command in a terminal
+    pub confirm_paste: bool,
+    /// Post-processing template for dictations into the app, unless the shortcut has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_template: Option<String>,
+    /// Most characters the app's text fields take, see char_limit.rs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub character_limit: Option<usize>,
+    /// How spoken notation is written in the app, instead of the global setting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notation: Option<NotationStyle>,
+}
+
+fn find<'a>(profiles: &'a BTreeMap<String, AppProfile>, app: &ActiveApp) -> Option<&'a AppProfile> {
+    profiles
+        .iter()
+        .find(|(key, _)| {
+            key.eq_ignore_ascii_case(&app.name) || key.eq_ignore_ascii_case(&app.bundle_id)
+        })
+        .map(|(_, profile)| profile)
+}
+
+/// Profile of the app the current dictation was started in
+pub fn current(app: &AppHandle) -> Option<AppProfile> {
+    let profiles = settings::current(app).app_profiles;
+    if profiles.is_empty() {
+        return None;
+    }
+    find(&profiles, &active_app::at_start()?).cloned()
+}
+
+#[tauri::command]
+pub async fn list_app_profiles(app: AppHandle) -> BTreeMap<String, AppProfile> {
+    settings::current(&app).app_profiles
+}
+
+/// Save the profile for `app_key`, or remove it when None
+#[tauri::command]
+pub async fn set_app_profile(
+    app: AppHandle,
+    app_key: String,
+    profile: Option<AppProfile>,
+) -> Result<BTreeMap<String, AppProfile>, String> {
+    let app_key = app_key.trim().to_string();
+    if app_key.is_empty() {
+        return Err(i18n::t("error.app_profile_app"));
+    }
+    if let Some(name) = profile
+        .as_ref()
+        .and_then(|profile| profile.prompt_template.as_ref())
+    {
+        prompts::get_template(&app, name)
+            .ok_or_else(|| i18n::tf("error.template_not_found", &[("name", name)]))?;
+    }
+    let updated = settings::update(&app, |settings| {
+        let profiles = &mut settings.app_profiles;
+        // Keys match case-insensitively, so "terminal" replaces "Terminal"
+        profiles.retain(|key, _| !key.eq_ignore_ascii_case(&app_key));
+        if let Some(profile) = profile {
+            profiles.insert(app_key, profile);
+        }
+    })?;
+    Ok(updated.app_profiles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_name_or_bundle_id() {
+        let terminal = AppProfile {
+            confirm_paste: true,
+            ..AppProfile::default()
+        };
+        let profiles = BTreeMap::from([("com.apple.Terminal".to_string(), terminal.clone())]);
+        let frontmost = ActiveApp {
+            name: "Terminal".to_string(),
+            bundle_id: "com.apple.terminal".to_string(),
+            window_title: "zsh".to_string(),
+        };
+        assert_eq!(find(&profiles, &frontmost), Some(&terminal));
+        let other = ActiveApp {
+            name: "Notes".to_string(),
+            bundle_id: "com.apple.Notes".to_string(),
+            window_title: String::new(),
+        };
+        assert_eq!(find(&profiles, &other), None);
+    }
+}
@@ -0,0 +1,183 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::active_app::{self, ActiveApp};
+use crate::{append_e2e_log, settings, show_main_window, IS_RECORDING};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RuleAction {
+    /// Show the bubble in standby while the app is frontmost
+    ShowStandby,
+    /// Ignore every dictation trigger while the app is frontmost
+    NeverTrigger,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppRule {
+    /// 0 when saving a new rule
+    #[serde(default)]
+    pub id: u64,
+    /// App name or bundle identifier, compared case-insensitively
+    pub app: String,
+    pub action: RuleAction,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl AppRule {
+    fn matches(&self, app: &ActiveApp) -> bool {
+        self.enabled
+            && (self.app.eq_ignore_ascii_case(&app.name)
+                || self.app.eq_ignore_ascii_case(&app.bundle_id))
+    }
+}
+
+// Frontmost app as last seen by the watcher, so triggers don't have to query it
+static FRONTMOST: Mutex<Option<ActiveApp>> = Mutex::new(None);
+
+fn matching(rules: &[AppRule], app: Option<&ActiveApp>, action: RuleAction) -> bool {
+    app.is_some_and(|app| {
+        rules
+            .iter()
+            .any(|rule| rule.action == action && rule.matches(app))
+    })
+}
+
+/// Whether a "never trigger" rule covers the frontmost app
+pub fn blocks_dictation(app: &AppHandle) -> bool {
+    let rules = settings::current(app).app_rules;
+    if rules.is_empty() {
+        return false;
+    }
+    let frontmost = FRONTMOST
+        .lock()
+        .ok()
+        .and_then(|frontmost| frontmost.clone());
+    matching(&rules, frontmost.as_ref(), RuleAction::NeverTrigger)
+}
+
+/// Watch the frontmost app and apply standby rules when it changes
+pub fn start_monitor(app: AppHandle) {
+    thread::spawn(move || {
+        let mut standby = false;
+        loop {
+            thread::sleep(POLL_INTERVAL);
+
+            let rules = settings::current(&app).app_rules;
+            if rules.is_empty() {
+                if let Ok(mut frontmost) = FRONTMOST.lock() {
+                    *frontmost = None;
+                }
+                continue;
+            }
+
+            let current = active_app::frontmost_app();
+            let changed = match FRONTMOST.lock() {
+                Ok(mut frontmost) if *frontmost != current => {
+                    *frontmost = current.clone();
+                    true
+                }
+                _ => false,
+            };
+            if !changed {
+                continue;
+            }
+
+            let wants_standby = matching(&rules, current.as_ref(), RuleAction::ShowStandby);
+            if wants_standby == standby {
+                continue;
+            }
+            standby = wants_standby;
+            append_e2e_log(if standby {
+                "standby-started"
+            } else {
+                "standby-ended"
+            });
+            let _ = app.emit("bubble-standby", current.as_ref().filter(|_| standby));
+            if IS_RECORDING.load(Ordering::SeqCst) {
+                continue;
+            }
+            if standby {
+                show_main_window(&app);
+            } else if let Some(window) = app.get_webview_window("main") {
+                let _ = window.hide();
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn list_app_rules(app: AppHandle) -> Vec<AppRule> {
+    settings::current(&app).app_rules
+}
+
+/// Add a rule, or replace the one with the same id
+#[tauri::command]
+pub async fn save_app_rule(app: AppHandle, mut rule: AppRule) -> Result<Vec<AppRule>, String> {
+    rule.app = rule.app.trim().to_string();
+    if rule.app.is_empty() {
+        return Err("Choose an app for the rule".to_string());
+    }
+    let settings = settings::update(&app, |settings| {
+        let rules = &mut settings.app_rules;
+        match rules.iter_mut().find(|existing| existing.id == rule.id) {
+            Some(existing) if rule.id != 0 => *existing = rule,
+            _ => {
+                rule.id = rules.iter().map(|rule| rule.id).max().unwrap_or(0) + 1;
+                rules.push(rule);
+            }
+        }
+    })?;
+    Ok(settings.app_rules)
+}
+
+#[tauri::command]
+pub async fn delete_app_rule(app: AppHandle, id: u64) -> Result<Vec<AppRule>, String> {
+    let settings = settings::update(&app, |settings| {
+        settings.app_rules.retain(|rule| rule.id != id)
+    })?;
+    Ok(settings.app_rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_enabled_rules_by_name_or_bundle_id() {
+        let slack = ActiveApp {
+            name: "Slack".to_string(),
+            bundle_id: "com.tinyspeck.slackmacgap".to_string(),
+            window_title: String::new(),
+        };
+        let rule = |app: &str, action, enabled| AppRule {
+            id: 1,
+            app: app.to_string(),
+            action,
+            enabled,
+        };
+
+        let rules = vec![rule("slack", RuleAction::ShowStandby, true)];
+        assert!(matching(&rules, Some(&slack), RuleAction::ShowStandby));
+        assert!(!matching(&rules, Some(&slack), RuleAction::NeverTrigger));
+        assert!(!matching(&rules, None, RuleAction::ShowStandby));
+
+        let rules = vec![
+            rule("com.tinyspeck.slackmacgap", RuleAction::NeverTrigger, false),
+            rule("Terminal", RuleAction::NeverTrigger, true),
+        ];
+        assert!(!matching(&rules, Some(&slack), RuleAction::NeverTrigger));
+    }
+}
@@ -0,0 +1,108 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::history::{HistoryEntry, HistoryStore};
+use crate::voice_commands::COMMAND_HISTORY_TAG;
+
+/// Typing speed assumed when estimating how long a dictation would have taken to type
+const TYPING_WORDS_PER_MINUTE: f64 = 40.0;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppStats {
+    pub app: String,
+    pub sessions: usize,
+    pub words: usize,
+    /// Estimated minutes the same words would have taken to type
+    pub typing_minutes: f64,
+    pub last_used_ms: u64,
+}
+
+/// Per-app totals of dictated text, busiest app first. Spoken commands and
+/// entries without a recorded app are left out.
+pub fn stats(entries: &[HistoryEntry]) -> Vec<AppStats> {
+    let mut stats: Vec<AppStats> = Vec::new();
+    for entry in entries {
+        if entry.tag.as_deref() == Some(COMMAND_HISTORY_TAG) {
+            continue;
+        }
+        let Some(app) = &entry.app else {
+            continue;
+        };
+        let words = entry.text.split_whitespace().count();
+        match stats.iter_mut().find(|stats| stats.app == *app) {
+            Some(stats) => {
+                stats.sessions += 1;
+                stats.words += words;
+                stats.last_used_ms = stats.last_used_ms.max(entry.timestamp_ms);
+            }
+            None => stats.push(AppStats {
+                app: app.clone(),
+                sessions: 1,
+                words,
+                typing_minutes: 0.0,
+                last_used_ms: entry.timestamp_ms,
+            }),
+        }
+    }
+    for stats in &mut stats {
+        stats.typing_minutes = stats.words as f64 / TYPING_WORDS_PER_MINUTE;
+    }
+    stats.sort_by(|a, b| b.words.cmp(&a.words).then(a.app.cmp(&b.app)));
+    stats
+}
+
+/// Usage per target app, optionally limited to dictations made since `since_ms`
+#[tauri::command]
+pub async fn get_app_stats(app: AppHandle, since_ms: Option<u64>) -> Result<Vec<AppStats>, String> {
+    let state = app.state::<Mutex<HistoryStore>>();
+    let store = state.lock().map_err(|e| e.to_string())?;
+    let entries = match since_ms {
+        Some(since_ms) => store.between(since_ms, u64::MAX),
+        None => store.all().to_vec(),
+    };
+    Ok(stats(&entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp_ms: u64, app: Option<&str>, text: &str) -> HistoryEntry {
+        serde_json::from_value(serde_json::json!({
+            "id": timestamp_ms,
+            "timestampMs": timestamp_ms,
+            "text": text,
+            "app": app,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn totals_words_and_sessions_per_app() {
+        let mut command = entry(5, Some("Slack"), "open the calendar");
+        command.tag = Some(COMMAND_HISTORY_TAG.to_string());
+        let entries = vec![
+            entry(1, Some("Slack"), "see you at noon"),
+            entry(2, Some("Mail"), "one two three four five six seven eight"),
+            entry(3, Some("Slack"), "sounds good"),
+            entry(4, None, "no app recorded"),
+            command,
+        ];
+        let stats = stats(&entries);
+        let totals: Vec<(&str, usize, usize, u64)> = stats
+            .iter()
+            .map(|stats| {
+                (
+                    stats.app.as_str(),
+                    stats.sessions,
+                    stats.words,
+                    stats.last_used_ms,
+                )
+            })
+            .collect();
+        assert_eq!(totals, vec![("Mail", 1, 8, 2), ("Slack", 2, 6, 3)]);
+        assert_eq!(stats[0].typing_minutes, 0.2);
+    }
+}
@@ -0,0 +1,216 @@
+use serde::Serialize;
+use std::io::Cursor;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::ipc::Response;
+use tauri::{AppHandle, Emitter};
+
+use crate::audio_file::resample;
+use crate::feature_flags::{self, Flag};
+use crate::microphone::{rms, Microphone};
+use crate::transcription::{Transcript, TranscriptionOptions, SAMPLE_RATE};
+use crate::{chunking, i18n, partials, settings};
+
+/// How often captured audio is reported to listeners
+const CHUNK_INTERVAL: Duration = Duration::from_millis(100);
+
+static CAPTURING: AtomicBool = AtomicBool::new(false);
+// Keeps a capture that is still winding down from being mistaken for the next one
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+static CAPTURED: Mutex<Option<CapturedAudio>> = Mutex::new(None);
+
+/// Mono audio recorded by the backend for the last dictation
+#[derive(Debug, Clone)]
+struct CapturedAudio {
+    samples: Vec<f32>,
+    sample_rate: u32,
+}
+
+impl CapturedAudio {
+    fn duration_ms(&self) -> u64 {
+        self.samples.len() as u64 * 1000 / u64::from(self.sample_rate.max(1))
+    }
+
+    /// 16-bit PCM WAV at the capture rate
+    fn to_wav(&self) -> Result<Vec<u8>, String> {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: self.sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut buffer = Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut buffer, spec).map_err(|e| e.to_string())?;
+        for sample in pcm(&self.samples) {
+            writer.write_sample(sample).map_err(|e| e.to_string())?;
+        }
+        writer.finalize().map_err(|e| e.to_string())?;
+        Ok(buffer.into_inner())
+    }
+}
+
+fn pcm(samples: &[f32]) -> impl Iterator<Item = i16> + '_ {
+    samples
+        .iter()
+        .map(|sample| (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AudioChunk {
+    /// Counts up from 0 within a dictation
+    sequence: u64,
+    /// RMS of the chunk, 0.0..=1.0
+    level: f32,
+    /// Audio captured so far in this dictation
+    duration_ms: u64,
+    /// The chunk as 16 kHz PCM, only sent in streaming mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    samples: Option<Vec<i16>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AudioCaptured {
+    duration_ms: u64,
+    sample_rate: u32,
+}
+
+fn capture(app: &AppHandle, generation: u64) -> Result<(), String> {
+    let mut microphone = Microphone::open(app)?;
+    let mut captured = CapturedAudio {
+        samples: Vec::new(),
+        sample_rate: microphone.sample_rate,
+    };
+    let streaming = feature_flags::is_enabled(app, Flag::StreamingMode);
+    let mut live = streaming.then(|| partials::Bridge::start(app));
+    let mut sequence = 0;
+    while CAPTURING.load(Ordering::SeqCst) && GENERATION.load(Ordering::SeqCst) == generation {
+        let chunk: Vec<f32> = microphone.frames(CHUNK_INTERVAL).concat();
+        if chunk.is_empty() {
+            continue;
+        }
+        captured.samples.extend_from_slice(&chunk);
+        let samples: Option<Vec<i16>> =
+            streaming.then(|| pcm(&resample(&chunk, captured.sample_rate, SAMPLE_RATE)).collect());
+        if let (Some(live), Some(samples)) = (live.as_mut(), samples.as_ref()) {
+            live.send(samples.clone());
+        }
+        let _ = app.emit(
+            "audio-chunk",
+            AudioChunk {
+                sequence,
+                level: rms(&chunk),
+                duration_ms: captured.duration_ms(),
+                samples,
+            },
+        );
+        sequence += 1;
+    }
+
+    // A newer dictation has started, so this audio belongs to nobody
+    if GENERATION.load(Ordering::SeqCst) != generation {
+        return Ok(());
+    }
+    let summary = AudioCaptured {
+        duration_ms: captured.duration_ms(),
+        sample_rate: captured.sample_rate,
+    };
+    if let Ok(mut last) = CAPTURED.lock() {
+        *last = Some(captured);
+    }
+    let _ = app.emit("audio-captured", summary);
+    Ok(())
+}
+
+/// Start recording in the backend when native capture is enabled, so a throttled
+/// or hidden webview can't lose audio
+pub fn start(app: &AppHandle) {
+    if !settings::current(app).native_capture {
+        return;
+    }
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    CAPTURING.store(true, Ordering::SeqCst);
+    if let Ok(mut last) = CAPTURED.lock() {
+        *last = None;
+    }
+    let app = app.clone();
+    thread::spawn(move || {
+        if let Err(e) = capture(&app, generation) {
+            eprintln!("[voiceflow] Native capture failed: {e}");
+            let _ = app.emit("audio-capture-failed", e);
+        }
+    });
+}
+
+/// Finish the capture; the audio becomes available once `audio-captured` is emitted
+pub fn stop() {
+    CAPTURING.store(false, Ordering::SeqCst);
+}
+
+/// Stop and throw the audio away
+pub fn discard() {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    CAPTURING.store(false, Ordering::SeqCst);
+    if let Ok(mut last) = CAPTURED.lock() {
+        *last = None;
+    }
+}
+
+fn captured() -> Result<CapturedAudio, String> {
+    CAPTURED
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone()
+        .ok_or_else(|| i18n::t("error.no_captured_audio"))
+}
+
+#[tauri::command]
+pub async fn set_native_capture(app: AppHandle, enabled: bool) -> Result<(), String> {
+    settings::update(&app, |settings| settings.native_capture = enabled)?;
+    Ok(())
+}
+
+/// The last dictation's audio as a WAV file, sent as raw bytes
+#[tauri::command]
+pub async fn get_captured_audio() -> Result<Response, String> {
+    Ok(Response::new(captured()?.to_wav()?))
+}
+
+/// Transcribe the last dictation's audio through the sidecar, for passing on to
+/// record_transcription
+#[tauri::command]
+pub async fn transcribe_captured_audio(app: AppHandle) -> Result<Transcript, String> {
+    let captured = captured()?;
+    let options = TranscriptionOptions {
+        alternatives: settings::current(&app).alternative_count,
+        ..TranscriptionOptions::default()
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        let resampled = resample(&captured.samples, captured.sample_rate, SAMPLE_RATE);
+        let samples: Vec<i16> = pcm(&resampled).collect();
+        chunking::transcribe_long(&samples, &options)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_capture_as_pcm_wav() {
+        let captured = CapturedAudio {
+            samples: vec![0.0, 0.5, -1.0, 2.0],
+            sample_rate: 8000,
+        };
+        let wav = captured.to_wav().unwrap();
+        let mut reader = hound::WavReader::new(Cursor::new(wav)).unwrap();
+        assert_eq!(reader.spec().sample_rate, 8000);
+        let samples: Vec<i16> = reader.samples::<i16>().map(Result::unwrap).collect();
+        assert_eq!(samples, vec![0, 16383, -32767, 32767]);
+    }
+}
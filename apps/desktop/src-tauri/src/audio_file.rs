@@ -0,0 +1,56 @@
+use std::path::Path;
+
+use crate::transcription::SAMPLE_RATE;
+
+/// Decode a WAV file into the 16kHz mono 16-bit PCM the sidecar expects
+pub fn read_pcm16_mono(path: &Path) -> Result<Vec<i16>, String> {
+    let mut reader =
+        hound::WavReader::open(path).map_err(|e| format!("Failed to open audio file: {e}"))?;
+    let spec = reader.spec();
+
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("Failed to decode audio file: {e}"))?,
+        hound::SampleFormat::Int => {
+            let scale = (1_i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|sample| sample.map(|value| value as f32 / scale))
+                .collect::<Result<_, _>>()
+                .map_err(|e| format!("Failed to decode audio file: {e}"))?
+        }
+    };
+
+    let channels = usize::from(spec.channels.max(1));
+    let mono: Vec<f32> = samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+
+    Ok(resample(&mono, spec.sample_rate, SAMPLE_RATE)
+        .into_iter()
+        .map(|sample| (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+        .collect())
+}
+
+// Linear interpolation is plenty for speech going into the model
+pub fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    let ratio = from_rate as f64 / to_rate as f64;
+    let output_len = (samples.len() as f64 / ratio) as usize;
+    (0..output_len)
+        .map(|index| {
+            let position = index as f64 * ratio;
+            let base = position as usize;
+            let fraction = (position - base as f64) as f32;
+            let current = samples[base];
+            let next = samples.get(base + 1).copied().unwrap_or(current);
+            current + (next - current) * fraction
+        })
+        .collect()
+}
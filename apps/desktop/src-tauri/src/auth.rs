@@ -0,0 +1,115 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+use crate::{i18n, settings};
+
+/// How long one successful authentication keeps history unlocked
+const UNLOCK_GRACE: Duration = Duration::from_secs(5 * 60);
+
+static UNLOCKED_AT: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Ask for Touch ID, an Apple Watch or the account password. Blocks until the user answers.
+#[cfg(target_os = "macos")]
+fn authenticate(reason: &str) -> Result<(), String> {
+    use block::ConcreteBlock;
+    use cocoa::base::{id, nil, BOOL, YES};
+    use cocoa::foundation::NSString;
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::sync::mpsc;
+
+    #[link(name = "LocalAuthentication", kind = "framework")]
+    extern "C" {}
+
+    // LAPolicyDeviceOwnerAuthentication, which falls back to the password without biometrics
+    const POLICY: isize = 2;
+
+    // SAFETY: the context and reason string are owned here and released after the reply
+    // has arrived; the copied block keeps the sender alive for as long as LAContext needs it
+    unsafe {
+        let context: id = msg_send![class!(LAContext), new];
+        let mut error: id = nil;
+        let available: BOOL = msg_send![context, canEvaluatePolicy: POLICY error: &mut error];
+        if available != YES {
+            let _: () = msg_send![context, release];
+            return Err(i18n::t("error.authentication_unavailable"));
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let reply = ConcreteBlock::new(move |success: BOOL, _error: id| {
+            let _ = sender.send(success == YES);
+        })
+        .copy();
+        let reason = NSString::alloc(nil).init_str(reason);
+        let _: () =
+            msg_send![context, evaluatePolicy: POLICY localizedReason: reason reply: &*reply];
+        let granted = receiver.recv().unwrap_or(false);
+        let _: () = msg_send![reason, release];
+        let _: () = msg_send![context, release];
+        if granted {
+            Ok(())
+        } else {
+            Err(i18n::t("error.authentication_failed"))
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn authenticate(_reason: &str) -> Result<(), String> {
+    Err(i18n::t("error.authentication_unavailable"))
+}
+
+fn unlocked() -> bool {
+    UNLOCKED_AT
+        .lock()
+        .ok()
+        .and_then(|unlocked_at| *unlocked_at)
+        .is_some_and(|unlocked_at| unlocked_at.elapsed() < UNLOCK_GRACE)
+}
+
+fn authenticate_and_unlock(reason: String) -> Result<(), String> {
+    authenticate(&reason)?;
+    if let Ok(mut unlocked_at) = UNLOCKED_AT.lock() {
+        *unlocked_at = Some(Instant::now());
+    }
+    Ok(())
+}
+
+/// Blocking variant of `unlock`, for callers outside the async runtime
+pub fn unlock_blocking(app: &AppHandle, reason_key: &str) -> Result<(), String> {
+    if !settings::current(app).require_authentication || unlocked() {
+        return Ok(());
+    }
+    authenticate_and_unlock(i18n::t(reason_key))
+}
+
+/// Make sure the user authenticated recently when the setting asks for it.
+/// `reason_key` is the i18n key of the reason shown in the system prompt.
+pub async fn unlock(app: &AppHandle, reason_key: &str) -> Result<(), String> {
+    if !settings::current(app).require_authentication || unlocked() {
+        return Ok(());
+    }
+    let reason = i18n::t(reason_key);
+    tauri::async_runtime::spawn_blocking(move || authenticate_and_unlock(reason))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Turning the lock on or off needs the owner either way, so it can't just be switched off
+#[tauri::command]
+pub async fn set_require_authentication(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let reason = i18n::t("auth.settings");
+    tauri::async_runtime::spawn_blocking(move || authenticate_and_unlock(reason))
+        .await
+        .map_err(|e| e.to_string())??;
+    settings::update(&app, |settings| settings.require_authentication = enabled)?;
+    Ok(())
+}
+
+/// Lock history again straight away instead of waiting for the grace period to end
+#[tauri::command]
+pub async fn lock_history() {
+    if let Ok(mut unlocked_at) = UNLOCKED_AT.lock() {
+        *unlocked_at = None;
+    }
+}
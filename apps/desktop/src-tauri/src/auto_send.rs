@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_global_shortcut::Shortcut;
+
+use crate::active_app::{self, ActiveApp};
+use crate::{append_e2e_log, i18n, keyboard, register_shortcuts, settings, ShortcutConfig};
+
+// The running dictation was started with the dictate-and-send shortcut
+static SESSION: AtomicBool = AtomicBool::new(false);
+// History id of the recorded dictation to send once it has been pasted
+static PENDING: Mutex<Option<u64>> = Mutex::new(None);
+
+/// Time for the target app to take the pasted text before Return is pressed
+const SEND_DELAY: Duration = Duration::from_millis(150);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DictateAndSendSettings {
+    /// Records like the main shortcut, then presses Return after pasting; None to leave unbound
+    pub shortcut: Option<ShortcutConfig>,
+    /// App names or bundle identifiers where Return is never pressed, such as terminals
+    /// where it would run a command
+    pub guarded_apps: Vec<String>,
+}
+
+impl Default for DictateAndSendSettings {
+    fn default() -> Self {
+        Self {
+            shortcut: None,
+            guarded_apps: ["Terminal", "iTerm2", "Mail", "Microsoft Outlook"]
+                .map(str::to_string)
+                .to_vec(),
+        }
+    }
+}
+
+fn is_guarded(guarded_apps: &[String], app: &ActiveApp) -> bool {
+    guarded_apps.iter().any(|guarded| {
+        guarded.eq_ignore_ascii_case(&app.name) || guarded.eq_ignore_ascii_case(&app.bundle_id)
+    })
+}
+
+pub fn shortcut(app: &AppHandle) -> Option<Shortcut> {
+    settings::current(app)
+        .dictate_and_send
+        .shortcut?
+        .to_shortcut()
+}
+
+pub fn begin_session(is_send: bool) {
+    SESSION.store(is_send, Ordering::SeqCst);
+}
+
+/// Consume the dictate-and-send flag for the dictation that just finished
+pub fn take_session() -> bool {
+    clear_pending(None);
+    SESSION.swap(false, Ordering::SeqCst)
+}
+
+/// Send the dictation with `history_id` once the webview has pasted it
+pub fn send_after_paste(history_id: u64) {
+    if let Ok(mut pending) = PENDING.lock() {
+        *pending = Some(history_id);
+    }
+}
+
+/// Forget the pending send, or only the one for `history_id` when given
+fn clear_pending(history_id: Option<u64>) {
+    if let Ok(mut pending) = PENDING.lock() {
+        if history_id.is_none() || *pending == history_id {
+            *pending = None;
+        }
+    }
+}
+
+/// Press Return after the dictation with `history_id` was pasted, unless the frontmost
+/// app is guarded
+pub fn pasted(app: &AppHandle, history_id: u64) {
+    let due = PENDING
+        .lock()
+        .ok()
+        .and_then(|mut pending| pending.take_if(|pending| *pending == history_id));
+    if due.is_none() {
+        return;
+    }
+    let guarded_apps = settings::current(app).dictate_and_send.guarded_apps;
+    if let Some(frontmost) = active_app::frontmost_app() {
+        if is_guarded(&guarded_apps, &frontmost) {
+            append_e2e_log("auto-send-guarded");
+            return;
+        }
+    }
+    thread::sleep(SEND_DELAY);
+    match keyboard::press_chord("return") {
+        Ok(()) => append_e2e_log("auto-send"),
+        Err(e) => eprintln!("[voiceflow] Failed to send the dictation: {e}"),
+    }
+}
+
+/// The user declined to paste the dictation, so it must not be sent later either
+#[tauri::command]
+pub async fn cancel_auto_send(history_id: u64) {
+    clear_pending(Some(history_id));
+}
+
+#[tauri::command]
+pub async fn set_dictate_and_send(
+    app: AppHandle,
+    shortcut: Option<ShortcutConfig>,
+    guarded_apps: Option<Vec<String>>,
+) -> Result<(), String> {
+    if let Some(config) = &shortcut {
+        config
+            .to_shortcut()
+            .ok_or_else(|| i18n::t("error.invalid_shortcut"))?;
+    }
+    settings::update(&app, |settings| {
+        settings.dictate_and_send.shortcut = shortcut;
+        if let Some(guarded_apps) = guarded_apps {
+            settings.dictate_and_send.guarded_apps = guarded_apps;
+        }
+    })?;
+    register_shortcuts(&app);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guards_apps_by_name_or_bundle_id() {
+        let guarded = DictateAndSendSettings::default().guarded_apps;
+        let app = |name: &str, bundle_id: &str| ActiveApp {
+            name: name.to_string(),
+            bundle_id: bundle_id.to_string(),
+            window_title: String::new(),
+        };
+        assert!(is_guarded(&guarded, &app("terminal", "com.apple.Terminal")));
+        assert!(!is_guarded(
+            &guarded,
+            &app("Slack", "com.tinyspeck.slackmacgap")
+        ));
+        assert!(is_guarded(
+            &["com.tinyspeck.slackmacgap".to_string()],
+            &app("Slack", "com.tinyspeck.slackmacgap")
+        ));
+    }
+}
@@ -0,0 +1,293 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::transcription::TranscriptionOptions;
+use crate::{audio_file, chunking, ensure_sidecar_running, history};
+
+pub const BATCH_HISTORY_TAG: &str = "batch";
+/// Attempts per file before it is marked failed, so a sidecar crash doesn't fail the file outright
+const MAX_ATTEMPTS: u32 = 2;
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FileStatus {
+    Pending,
+    /// Being transcribed; found like this after a crash it is simply retried
+    Running,
+    Done,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchFile {
+    pub path: PathBuf,
+    pub status: FileStatus,
+    #[serde(default)]
+    pub attempts: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchJob {
+    pub id: u64,
+    pub created_ms: u64,
+    pub files: Vec<BatchFile>,
+}
+
+impl BatchJob {
+    fn is_finished(&self) -> bool {
+        self.files
+            .iter()
+            .all(|file| matches!(file.status, FileStatus::Done | FileStatus::Failed))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BatchProgress {
+    job_id: u64,
+    done: usize,
+    total: usize,
+}
+
+/// Queue of batch jobs, saved after every file so a crash loses at most the file in flight
+pub struct BatchJobStore {
+    jobs: Vec<BatchJob>,
+    jobs_path: PathBuf,
+}
+
+impl BatchJobStore {
+    pub fn new(data_dir: PathBuf) -> Self {
+        let jobs_path = data_dir.join("batch_jobs.json");
+        let jobs = Self::load_jobs(&jobs_path).unwrap_or_default();
+        Self { jobs, jobs_path }
+    }
+
+    fn load_jobs(path: &PathBuf) -> Option<Vec<BatchJob>> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn save_jobs(&self) -> Result<(), String> {
+        if let Some(parent) = self.jobs_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string_pretty(&self.jobs).map_err(|e| e.to_string())?;
+        fs::write(&self.jobs_path, content).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    fn add(&mut self, paths: Vec<PathBuf>) -> Result<BatchJob, String> {
+        let id = self.jobs.iter().map(|job| job.id).max().unwrap_or(0) + 1;
+        let job = BatchJob {
+            id,
+            created_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_millis() as u64)
+                .unwrap_or_default(),
+            files: paths
+                .into_iter()
+                .map(|path| BatchFile {
+                    path,
+                    status: FileStatus::Pending,
+                    attempts: 0,
+                    history_id: None,
+                    error: None,
+                })
+                .collect(),
+        };
+        self.jobs.push(job.clone());
+        self.save_jobs()?;
+        Ok(job)
+    }
+
+    /// Claim the next file to transcribe, returning its job and index
+    fn claim_next(&mut self) -> Result<Option<(u64, usize, PathBuf)>, String> {
+        let next = self.jobs.iter_mut().find_map(|job| {
+            let index = job
+                .files
+                .iter()
+                .position(|file| file.status == FileStatus::Pending)?;
+            Some((job.id, index, &mut job.files[index]))
+        });
+        let Some((job_id, index, file)) = next else {
+            return Ok(None);
+        };
+        file.status = FileStatus::Running;
+        file.attempts += 1;
+        let path = file.path.clone();
+        self.save_jobs()?;
+        Ok(Some((job_id, index, path)))
+    }
+
+    fn finish(
+        &mut self,
+        job_id: u64,
+        index: usize,
+        result: Result<u64, String>,
+    ) -> Result<Option<BatchProgress>, String> {
+        let Some(job) = self.jobs.iter_mut().find(|job| job.id == job_id) else {
+            return Ok(None);
+        };
+        if let Some(file) = job.files.get_mut(index) {
+            match result {
+                Ok(history_id) => {
+                    file.status = FileStatus::Done;
+                    file.history_id = Some(history_id);
+                    file.error = None;
+                }
+                Err(error) => {
+                    file.status = if file.attempts >= MAX_ATTEMPTS {
+                        FileStatus::Failed
+                    } else {
+                        FileStatus::Pending
+                    };
+                    file.error = Some(error);
+                }
+            }
+        }
+        let progress = BatchProgress {
+            job_id,
+            done: job
+                .files
+                .iter()
+                .filter(|file| matches!(file.status, FileStatus::Done | FileStatus::Failed))
+                .count(),
+            total: job.files.len(),
+        };
+        self.save_jobs()?;
+        Ok(Some(progress))
+    }
+
+    /// Put files interrupted mid-transcription back in the queue
+    fn requeue_interrupted(&mut self) -> Result<usize, String> {
+        let mut requeued = 0;
+        for file in self.jobs.iter_mut().flat_map(|job| job.files.iter_mut()) {
+            if file.status == FileStatus::Running {
+                file.status = FileStatus::Pending;
+                requeued += 1;
+            }
+        }
+        if requeued > 0 {
+            self.save_jobs()?;
+        }
+        Ok(requeued)
+    }
+}
+
+fn with_store<T>(
+    app: &AppHandle,
+    f: impl FnOnce(&mut BatchJobStore) -> Result<T, String>,
+) -> Result<T, String> {
+    let state = app.state::<Mutex<BatchJobStore>>();
+    let mut store = state.lock().map_err(|e| e.to_string())?;
+    f(&mut store)
+}
+
+fn transcribe_file(app: &AppHandle, path: &Path) -> Result<u64, String> {
+    ensure_sidecar_running(app)?;
+    let samples = audio_file::read_pcm16_mono(path)?;
+    let transcript = chunking::transcribe_long(&samples, &TranscriptionOptions::default())?;
+    let entry = history::add_entry(app, transcript, Some(BATCH_HISTORY_TAG.to_string()))?;
+    Ok(entry.id)
+}
+
+/// Work through pending files on a background thread unless a runner is already active
+fn run(app: &AppHandle) {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    thread::spawn(move || {
+        loop {
+            let next = match with_store(&app, BatchJobStore::claim_next) {
+                Ok(Some(next)) => next,
+                Ok(None) => break,
+                Err(error) => {
+                    eprintln!("[voiceflow] Batch queue unavailable: {error}");
+                    break;
+                }
+            };
+            let (job_id, index, path) = next;
+            let result = transcribe_file(&app, &path);
+            if let Err(error) = &result {
+                eprintln!(
+                    "[voiceflow] Batch transcription of {} failed: {error}",
+                    path.display()
+                );
+            }
+            match with_store(&app, |store| store.finish(job_id, index, result)) {
+                Ok(Some(progress)) => {
+                    let _ = app.emit("batch-progress", progress);
+                }
+                Ok(None) => {}
+                Err(error) => eprintln!("[voiceflow] Failed to save batch progress: {error}"),
+            }
+        }
+        RUNNING.store(false, Ordering::SeqCst);
+    });
+}
+
+/// Continue jobs left unfinished by a crash or quit, called once at startup
+pub fn resume(app: &AppHandle) -> Result<Vec<BatchJob>, String> {
+    let requeued = with_store(app, BatchJobStore::requeue_interrupted)?;
+    if requeued > 0 {
+        eprintln!("[voiceflow] Resuming {requeued} interrupted batch file(s)");
+    }
+    let pending: Vec<BatchJob> = with_store(app, |store| {
+        Ok(store
+            .jobs
+            .iter()
+            .filter(|job| !job.is_finished())
+            .cloned()
+            .collect())
+    })?;
+    if !pending.is_empty() {
+        run(app);
+    }
+    Ok(pending)
+}
+
+/// Queue every WAV file in `dir` for transcription into history
+#[tauri::command]
+pub async fn start_batch_transcription(app: AppHandle, dir: PathBuf) -> Result<BatchJob, String> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+        .map_err(|e| format!("Failed to read folder: {e}"))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("wav"))
+        })
+        .collect();
+    if paths.is_empty() {
+        return Err("No .wav files in this folder".to_string());
+    }
+    paths.sort();
+
+    let job = with_store(&app, |store| store.add(paths))?;
+    run(&app);
+    Ok(job)
+}
+
+#[tauri::command]
+pub async fn list_batch_jobs(app: AppHandle) -> Result<Vec<BatchJob>, String> {
+    with_store(&app, |store| Ok(store.jobs.clone()))
+}
+
+#[tauri::command]
+pub async fn resume_pending_jobs(app: AppHandle) -> Result<Vec<BatchJob>, String> {
+    resume(&app)
+}
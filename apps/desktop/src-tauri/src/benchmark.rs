@@ -0,0 +1,132 @@
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+
+use crate::postprocess::{self, PromptContext};
+use crate::profiles::ProfileManager;
+use crate::settings::SettingsManager;
+use crate::transcription::{self, TranscriptionOptions};
+use crate::{audio_file, ensure_sidecar_running, evaluation};
+
+/// Reference clip looked up in the app resources, with its transcript next to it as .txt
+const REFERENCE_CLIP: &str = "benchmark/reference.wav";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkResult {
+    pub profile: String,
+    pub text: String,
+    pub transcription_ms: u64,
+    /// Post-processing with the profile's model, zero when it's disabled
+    pub processing_ms: u64,
+    pub total_ms: u64,
+    /// Word error rate against the reference transcript, when there is one
+    pub wer: Option<f32>,
+    pub error: Option<String>,
+}
+
+fn reference_clip(app: &AppHandle, clip: Option<PathBuf>) -> Result<PathBuf, String> {
+    if let Some(clip) = clip {
+        return Ok(clip);
+    }
+    let bundled = app
+        .path()
+        .resource_dir()
+        .map_err(|e| e.to_string())?
+        .join(REFERENCE_CLIP);
+    if bundled.is_file() {
+        Ok(bundled)
+    } else {
+        Err("No reference clip is bundled, pass the path of a WAV file".to_string())
+    }
+}
+
+/// Transcribe a reference clip once and post-process it with each profile's model,
+/// timing every stage and scoring against the clip's .txt transcript if present
+#[tauri::command]
+pub async fn run_benchmark(
+    app: AppHandle,
+    profiles: Vec<String>,
+    clip: Option<PathBuf>,
+) -> Result<Vec<BenchmarkResult>, String> {
+    let clip = reference_clip(&app, clip)?;
+    let reference = fs::read_to_string(clip.with_extension("txt")).ok();
+
+    let profile_dirs = {
+        let state = app.state::<Mutex<ProfileManager>>();
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        let installed = manager.list().profiles;
+        let profiles = if profiles.is_empty() {
+            installed.clone()
+        } else {
+            profiles
+        };
+        profiles
+            .into_iter()
+            .map(|name| {
+                let dir = installed
+                    .contains(&name)
+                    .then(|| manager.profile_dir(&name));
+                (name, dir)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    ensure_sidecar_running(&app)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let samples = audio_file::read_pcm16_mono(&clip)?;
+        // The sidecar serves a single speech model, so one transcription covers every profile
+        let started = Instant::now();
+        let transcript = transcription::transcribe(&samples, &TranscriptionOptions::default())?;
+        let transcription_ms = started.elapsed().as_millis() as u64;
+
+        let results = profile_dirs
+            .into_iter()
+            .map(|(profile, dir)| {
+                let Some(dir) = dir else {
+                    return BenchmarkResult {
+                        error: Some(format!("Profile \"{profile}\" not found")),
+                        profile,
+                        text: String::new(),
+                        transcription_ms: 0,
+                        processing_ms: 0,
+                        total_ms: 0,
+                        wer: None,
+                    };
+                };
+                let settings = SettingsManager::new(dir).get().post_processing;
+                let context = PromptContext {
+                    language: transcript.language.clone(),
+                    selection: None,
+                    tab: None,
+                };
+                let started = Instant::now();
+                let processed =
+                    postprocess::process_with(&app, &settings, &transcript.text, None, &context);
+                let processing_ms = started.elapsed().as_millis() as u64;
+
+                let (text, error) = match processed {
+                    Ok(cleaned) => (cleaned.unwrap_or_else(|| transcript.text.clone()), None),
+                    Err(error) => (transcript.text.clone(), Some(error)),
+                };
+                BenchmarkResult {
+                    wer: reference
+                        .as_deref()
+                        .and_then(|reference| evaluation::word_error_rate(reference, &text)),
+                    profile,
+                    text,
+                    transcription_ms,
+                    processing_ms,
+                    total_ms: transcription_ms + processing_ms,
+                    error,
+                }
+            })
+            .collect();
+        Ok(results)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::thread;
+use tauri::AppHandle;
+
+use crate::active_app::{self, ActiveApp};
+use crate::settings;
+
+// Front tab when the current (or last) dictation started
+static AT_START: Mutex<Option<BrowserTab>> = Mutex::new(None);
+
+/// Browsers whose front tab can be read through AppleScript
+const SAFARI_BROWSERS: &[&str] = &["com.apple.Safari", "com.apple.SafariTechnologyPreview"];
+const CHROMIUM_BROWSERS: &[&str] = &[
+    "com.google.Chrome",
+    "com.google.Chrome.canary",
+    "com.brave.Browser",
+    "com.microsoft.edgemac",
+    "com.vivaldi.Vivaldi",
+    "company.thebrowser.Browser",
+];
+
+/// Uses a post-processing template while the active tab is on a site, e.g. a formal tone
+/// in Google Docs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteTemplate {
+    /// Matched case-insensitively against the tab URL, e.g. "docs.google.com"
+    pub site: String,
+    pub template: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BrowserContextSettings {
+    /// Browser tabs are only read when explicitly enabled
+    pub enabled: bool,
+    pub site_templates: Vec<SiteTemplate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrowserTab {
+    /// Without query string, fragment or credentials
+    pub url: String,
+    pub title: String,
+}
+
+/// Drop the parts of a URL that tend to carry tokens and personal data
+fn strip_private_parts(url: &str) -> String {
+    let url = url.split(['?', '#']).next().unwrap_or_default();
+    match url.split_once("://") {
+        Some((scheme, rest)) => {
+            let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+            let host = authority.rsplit('@').next().unwrap_or(authority);
+            format!("{scheme}://{host}{path}")
+        }
+        None => url.to_string(),
+    }
+}
+
+fn site_template(rules: &[SiteTemplate], url: &str) -> Option<String> {
+    let url = url.to_lowercase();
+    rules
+        .iter()
+        .find(|rule| {
+            let site = rule.site.trim().to_lowercase();
+            !site.is_empty() && url.contains(&site)
+        })
+        .map(|rule| rule.template.clone())
+}
+
+#[cfg(target_os = "macos")]
+fn front_tab_script(bundle_id: &str) -> Option<String> {
+    if SAFARI_BROWSERS.contains(&bundle_id) {
+        return Some(format!(
+            r#"tell application id "{bundle_id}" to return (URL of front document) & linefeed & (name of front document)"#
+        ));
+    }
+    CHROMIUM_BROWSERS.contains(&bundle_id).then(|| {
+        format!(
+            r#"tell application id "{bundle_id}" to return (URL of active tab of front window) & linefeed & (title of active tab of front window)"#
+        )
+    })
+}
+
+/// URL and title of the browser's front tab
+#[cfg(target_os = "macos")]
+fn read_front_tab(frontmost: &ActiveApp) -> Option<(String, String)> {
+    use std::process::Command;
+
+    let script = front_tab_script(&frontmost.bundle_id)?;
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        eprintln!(
+            "[voiceflow] Failed to read the browser tab: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (url, title) = stdout.trim_end_matches('\n').split_once('\n')?;
+    Some((url.to_string(), title.to_string()))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn read_front_tab(_frontmost: &ActiveApp) -> Option<(String, String)> {
+    None
+}
+
+/// Whether the app is a browser VoiceFlow knows how to read tabs from
+fn is_supported(frontmost: &ActiveApp) -> bool {
+    let bundle_id = frontmost.bundle_id.as_str();
+    SAFARI_BROWSERS.contains(&bundle_id) || CHROMIUM_BROWSERS.contains(&bundle_id)
+}
+
+/// Active tab of the frontmost browser, only when browser context is enabled
+pub fn active_tab(app: &AppHandle, frontmost: Option<&ActiveApp>) -> Option<BrowserTab> {
+    let frontmost = frontmost.filter(|frontmost| is_supported(frontmost))?;
+    if !settings::current(app).browser_context.enabled {
+        return None;
+    }
+    let (url, title) = read_front_tab(frontmost)?;
+    let url = strip_private_parts(url.trim());
+    (!url.is_empty()).then(|| BrowserTab {
+        url,
+        title: title.trim().to_string(),
+    })
+}
+
+/// Note the front tab for a dictation that is starting, off the caller's thread since
+/// reading it runs AppleScript
+pub fn capture_in_background(app: &AppHandle) {
+    if let Ok(mut at_start) = AT_START.lock() {
+        *at_start = None;
+    }
+    if !settings::current(app).browser_context.enabled {
+        return;
+    }
+
+    let app = app.clone();
+    thread::spawn(move || {
+        let tab = active_tab(&app, active_app::frontmost_app().as_ref());
+        if let Ok(mut at_start) = AT_START.lock() {
+            *at_start = tab;
+        }
+    });
+}
+
+/// Front tab when the current or last dictation started
+pub fn at_start() -> Option<BrowserTab> {
+    AT_START.lock().ok().and_then(|at_start| at_start.clone())
+}
+
+/// Post-processing template of the first site rule matching the tab
+pub fn template(app: &AppHandle, tab: Option<&BrowserTab>) -> Option<String> {
+    let tab = tab?;
+    site_template(
+        &settings::current(app).browser_context.site_templates,
+        &tab.url,
+    )
+}
+
+#[tauri::command]
+pub async fn set_browser_context(
+    app: AppHandle,
+    browser_context: BrowserContextSettings,
+) -> Result<(), String> {
+    settings::update(&app, |settings| settings.browser_context = browser_context)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_query_fragment_and_credentials() {
+        assert_eq!(
+            strip_private_parts("https://user:pw@docs.google.com/document/d/1?usp=share#h.2"),
+            "https://docs.google.com/document/d/1"
+        );
+        assert_eq!(
+            strip_private_parts("https://example.com"),
+            "https://example.com"
+        );
+        assert_eq!(strip_private_parts("about:blank"), "about:blank");
+    }
+
+    #[test]
+    fn picks_the_first_matching_site_template() {
+        let rules = vec![
+            SiteTemplate {
+                site: "Docs.Google.com".to_string(),
+                template: "formalize email".to_string(),
+            },
+            SiteTemplate {
+                site: "google.com".to_string(),
+                template: "verbatim".to_string(),
+            },
+        ];
+        assert_eq!(
+            site_template(&rules, "https://docs.google.com/document/d/1"),
+            Some("formalize email".to_string())
+        );
+        assert_eq!(
+            site_template(&rules, "https://mail.google.com/"),
+            Some("verbatim".to_string())
+        );
+        assert_eq!(site_template(&rules, "https://github.com/"), None);
+    }
+}
@@ -0,0 +1,110 @@
+/// A calendar event, with start and end in seconds relative to now
+#[derive(Debug, Clone, PartialEq)]
+struct Event {
+    title: String,
+    start: i64,
+    end: i64,
+}
+
+/// Title of the event that overlaps the window the most, earlier events winning ties
+fn best_match(events: &[Event], start: i64, end: i64) -> Option<String> {
+    events
+        .iter()
+        .filter(|event| !event.title.trim().is_empty())
+        .map(|event| (event.end.min(end) - event.start.max(start), event))
+        .filter(|(overlap, _)| *overlap > 0)
+        .max_by(|(a, first), (b, second)| a.cmp(b).then(second.start.cmp(&first.start)))
+        .map(|(_, event)| event.title.trim().to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn parse_events(output: &str) -> Vec<Event> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.rsplitn(3, '\t');
+            let end = fields.next()?.trim().parse().ok()?;
+            let start = fields.next()?.trim().parse().ok()?;
+            let title = fields.next()?.to_string();
+            Some(Event { title, start, end })
+        })
+        .collect()
+}
+
+/// Non-all-day events overlapping the window from `start_ago` to `end_ago` seconds ago,
+/// read from Calendar (and so from every account synced into it)
+#[cfg(target_os = "macos")]
+fn events_between(start_ago: u64, end_ago: u64) -> Result<Vec<Event>, String> {
+    use std::process::Command;
+
+    let script = format!(
+        r#"
+        set nowDate to current date
+        set windowStart to nowDate - {start_ago}
+        set windowEnd to nowDate - {end_ago}
+        set output to ""
+        tell application "Calendar"
+            repeat with cal in calendars
+                set found to (every event of cal whose start date < windowEnd and end date > windowStart and allday event is false)
+                repeat with ev in found
+                    set output to output & (summary of ev) & tab & ((start date of ev) - nowDate) & tab & ((end date of ev) - nowDate) & linefeed
+                end repeat
+            end repeat
+        end tell
+        return output
+    "#
+    );
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .map_err(|e| crate::i18n::tf("error.osascript", &[("error", &e.to_string())]))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(parse_events(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn events_between(_start_ago: u64, _end_ago: u64) -> Result<Vec<Event>, String> {
+    Ok(Vec::new())
+}
+
+/// Title of the calendar event a recording made between `start_ago` and `end_ago`
+/// seconds ago most likely belongs to
+pub fn event_title(start_ago: u64, end_ago: u64) -> Option<String> {
+    match events_between(start_ago, end_ago) {
+        Ok(events) => best_match(&events, -(start_ago as i64), -(end_ago as i64)),
+        Err(error) => {
+            eprintln!("[voiceflow] Failed to read calendar events: {error}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(title: &str, start: i64, end: i64) -> Event {
+        Event {
+            title: title.to_string(),
+            start,
+            end,
+        }
+    }
+
+    #[test]
+    fn picks_the_event_overlapping_the_recording_most() {
+        let events = vec![
+            event("Standup", -3600, -3000),
+            event("Weekly sync", -3000, 0),
+            event("Lunch", -600, 1200),
+        ];
+        assert_eq!(
+            best_match(&events, -3300, -300),
+            Some("Weekly sync".to_string())
+        );
+        assert_eq!(best_match(&events, -5000, -4000), None);
+    }
+}
@@ -0,0 +1,254 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use crate::transcription::{TranscriptSegment, TranscriptWord};
+use crate::{auth, history, i18n};
+
+const MAX_LINES_PER_CUE: usize = 2;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptionFormat {
+    Srt,
+    Vtt,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CaptionOptions {
+    /// Maximum characters per caption line
+    pub max_line_length: usize,
+    /// Maximum seconds a single cue stays on screen
+    pub max_cue_duration: f64,
+}
+
+impl Default for CaptionOptions {
+    fn default() -> Self {
+        Self {
+            max_line_length: 42,
+            max_cue_duration: 6.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Cue {
+    start: f64,
+    end: f64,
+    lines: Vec<String>,
+}
+
+/// Spread each segment's duration across its words in proportion to their length
+fn words_from_segments(segments: &[TranscriptSegment]) -> Vec<TranscriptWord> {
+    let mut words = Vec::new();
+    for segment in segments {
+        let parts: Vec<&str> = segment.text.split_whitespace().collect();
+        let total_chars: usize = parts.iter().map(|part| part.chars().count()).sum();
+        if total_chars == 0 {
+            continue;
+        }
+
+        let per_char = (segment.end - segment.start).max(0.0) / total_chars as f64;
+        let mut cursor = segment.start;
+        for part in parts {
+            let end = cursor + per_char * part.chars().count() as f64;
+            words.push(TranscriptWord {
+                text: part.to_string(),
+                start: cursor,
+                end,
+            });
+            cursor = end;
+        }
+    }
+    words
+}
+
+fn wrap_lines(words: &[&str], max_line_length: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in words {
+        match lines.last_mut() {
+            Some(line) if line.chars().count() + 1 + word.chars().count() <= max_line_length => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+    lines
+}
+
+fn build_cues(words: &[TranscriptWord], options: &CaptionOptions) -> Vec<Cue> {
+    let max_line_length = options.max_line_length.max(1);
+    let mut cues = Vec::new();
+    let mut current: Vec<&TranscriptWord> = Vec::new();
+
+    let flush = |current: &mut Vec<&TranscriptWord>, cues: &mut Vec<Cue>| {
+        let (Some(first), Some(last)) = (current.first(), current.last()) else {
+            return;
+        };
+        let texts: Vec<&str> = current.iter().map(|word| word.text.trim()).collect();
+        cues.push(Cue {
+            start: first.start,
+            end: last.end,
+            lines: wrap_lines(&texts, max_line_length),
+        });
+        current.clear();
+    };
+
+    for word in words.iter().filter(|word| !word.text.trim().is_empty()) {
+        if let Some(start) = current.first().map(|first| first.start) {
+            let mut texts: Vec<&str> = current.iter().map(|word| word.text.trim()).collect();
+            texts.push(word.text.trim());
+            let too_long = wrap_lines(&texts, max_line_length).len() > MAX_LINES_PER_CUE;
+            let too_slow = word.end - start > options.max_cue_duration;
+            if too_long || too_slow {
+                flush(&mut current, &mut cues);
+            }
+        }
+        current.push(word);
+    }
+    flush(&mut current, &mut cues);
+    cues
+}
+
+fn format_timestamp(seconds: f64, separator: char) -> String {
+    let total_ms = (seconds.max(0.0) * 1000.0).round() as u64;
+    let hours = total_ms / 3_600_000;
+    let minutes = total_ms / 60_000 % 60;
+    let secs = total_ms / 1000 % 60;
+    let millis = total_ms % 1000;
+    format!("{hours:02}:{minutes:02}:{secs:02}{separator}{millis:03}")
+}
+
+fn render(cues: &[Cue], format: CaptionFormat) -> String {
+    let mut output = String::new();
+    if let CaptionFormat::Vtt = format {
+        output.push_str("WEBVTT\n\n");
+    }
+
+    for (index, cue) in cues.iter().enumerate() {
+        let separator = match format {
+            CaptionFormat::Srt => {
+                output.push_str(&format!("{}\n", index + 1));
+                ','
+            }
+            CaptionFormat::Vtt => '.',
+        };
+        output.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            format_timestamp(cue.start, separator),
+            format_timestamp(cue.end, separator),
+            cue.lines.join("\n")
+        ));
+    }
+    output
+}
+
+/// Build caption text from word timings, falling back to segment timings
+pub fn captions_for(
+    words: &[TranscriptWord],
+    segments: &[TranscriptSegment],
+    format: CaptionFormat,
+    options: &CaptionOptions,
+) -> Result<String, String> {
+    let words = if words.is_empty() {
+        words_from_segments(segments)
+    } else {
+        words.to_vec()
+    };
+    if words.is_empty() {
+        return Err(i18n::t("error.captions_no_timing"));
+    }
+
+    Ok(render(&build_cues(&words, options), format))
+}
+
+#[tauri::command]
+pub async fn export_captions(
+    app: AppHandle,
+    id: u64,
+    format: CaptionFormat,
+    path: PathBuf,
+    options: Option<CaptionOptions>,
+) -> Result<(), String> {
+    auth::unlock(&app, "auth.export").await?;
+    let entry = history::get_entry(&app, id)?;
+    let captions = captions_for(
+        &entry.words,
+        &entry.segments,
+        format,
+        &options.unwrap_or_default(),
+    )?;
+    fs::write(&path, captions)
+        .map_err(|e| i18n::tf("error.export_captions", &[("error", &e.to_string())]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str, start: f64, end: f64) -> TranscriptWord {
+        TranscriptWord {
+            text: text.to_string(),
+            start,
+            end,
+        }
+    }
+
+    #[test]
+    fn formats_timestamps_per_format() {
+        assert_eq!(format_timestamp(3723.456, ','), "01:02:03,456");
+        assert_eq!(format_timestamp(0.5, '.'), "00:00:00.500");
+    }
+
+    #[test]
+    fn splits_cues_on_line_length() {
+        let words = [
+            word("hello", 0.0, 0.4),
+            word("there", 0.4, 0.8),
+            word("general", 0.8, 1.2),
+            word("kenobi", 1.2, 1.6),
+        ];
+        let options = CaptionOptions {
+            max_line_length: 12,
+            max_cue_duration: 10.0,
+        };
+        let srt = captions_for(&words, &[], CaptionFormat::Srt, &options).unwrap();
+        assert_eq!(
+            srt,
+            "1\n00:00:00,000 --> 00:00:01,200\nhello there\ngeneral\n\n\
+             2\n00:00:01,200 --> 00:00:01,600\nkenobi\n\n"
+        );
+    }
+
+    #[test]
+    fn splits_cues_on_duration() {
+        let words = [word("one", 0.0, 2.0), word("two", 2.0, 4.0)];
+        let options = CaptionOptions {
+            max_line_length: 42,
+            max_cue_duration: 3.0,
+        };
+        let vtt = captions_for(&words, &[], CaptionFormat::Vtt, &options).unwrap();
+        assert_eq!(
+            vtt,
+            "WEBVTT\n\n00:00:00.000 --> 00:00:02.000\none\n\n\
+             00:00:02.000 --> 00:00:04.000\ntwo\n\n"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_segment_timings() {
+        let segments = [TranscriptSegment {
+            text: "ab cd".to_string(),
+            start: 0.0,
+            end: 4.0,
+            ..Default::default()
+        }];
+        let words = words_from_segments(&segments);
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[1].start, 2.0);
+        assert!(captions_for(&[], &[], CaptionFormat::Srt, &CaptionOptions::default()).is_err());
+    }
+}
@@ -0,0 +1,307 @@
+use tauri::AppHandle;
+
+use crate::settings;
+
+// Dictating into the middle of a sentence shouldn't produce "said That" or
+// "word.Next". Just before inserting, the text left of the caret decides whether the
+// transcript starts a sentence and whether it needs a space in front.
+
+// Enough to see past the spaces after the previous word
+const CONTEXT_UNITS: isize = 8;
+
+const SENTENCE_ENDS: [char; 4] = ['.', '!', '?', '…'];
+// Text typed right after these continues them without a space
+const OPENERS: [char; 10] = ['(', '[', '{', '"', '\'', '“', '‘', '«', '/', '-'];
+// Transcripts starting with these attach to the previous word
+const CLOSERS: [char; 8] = ['.', ',', ';', ':', '!', '?', ')', '…'];
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::ffi::{c_char, c_void, CString};
+    use std::ptr;
+
+    #[repr(C)]
+    struct CFRange {
+        location: isize,
+        length: isize,
+    }
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXUIElementCreateSystemWide() -> *const c_void;
+        fn AXUIElementCopyAttributeValue(
+            element: *const c_void,
+            attribute: *const c_void,
+            value: *mut *const c_void,
+        ) -> i32;
+        fn AXUIElementCopyParameterizedAttributeValue(
+            element: *const c_void,
+            attribute: *const c_void,
+            parameter: *const c_void,
+            result: *mut *const c_void,
+        ) -> i32;
+        fn AXValueCreate(value_type: u32, value: *const c_void) -> *const c_void;
+        fn AXValueGetValue(value: *const c_void, value_type: u32, out: *mut c_void) -> bool;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFStringCreateWithCString(
+            allocator: *const c_void,
+            string: *const c_char,
+            encoding: u32,
+        ) -> *const c_void;
+        fn CFStringGetCString(
+            string: *const c_void,
+            buffer: *mut c_char,
+            size: isize,
+            encoding: u32,
+        ) -> bool;
+        fn CFRelease(cf: *const c_void);
+        fn CFGetTypeID(cf: *const c_void) -> usize;
+        fn CFNumberGetTypeID() -> usize;
+        fn CFNumberGetValue(number: *const c_void, number_type: isize, value: *mut c_void) -> bool;
+    }
+
+    // kAXErrorSuccess
+    const AX_SUCCESS: i32 = 0;
+    // kAXValueTypeCFRange
+    const AX_VALUE_CF_RANGE: u32 = 4;
+    // kCFStringEncodingUTF8
+    const UTF8: u32 = 0x0800_0100;
+    // kCFNumberSInt64Type
+    const NUMBER_SINT64: isize = 4;
+
+    /// A CoreFoundation object released when dropped
+    struct Owned(*const c_void);
+
+    impl Owned {
+        fn new(object: *const c_void) -> Option<Self> {
+            (!object.is_null()).then_some(Self(object))
+        }
+    }
+
+    impl Drop for Owned {
+        fn drop(&mut self) {
+            // SAFETY: the object was returned by a Create or Copy function and is only
+            // released here
+            unsafe { CFRelease(self.0) };
+        }
+    }
+
+    fn cf_string(text: &str) -> Option<Owned> {
+        let text = CString::new(text).ok()?;
+        // SAFETY: `text` is NUL-terminated and outlives the call
+        Owned::new(unsafe { CFStringCreateWithCString(ptr::null(), text.as_ptr(), UTF8) })
+    }
+
+    fn attribute(element: &Owned, name: &str) -> Option<Owned> {
+        let name = cf_string(name)?;
+        let mut value = ptr::null();
+        // SAFETY: both objects are live and `value` is a valid out pointer
+        let status = unsafe { AXUIElementCopyAttributeValue(element.0, name.0, &mut value) };
+        (status == AX_SUCCESS).then(|| Owned::new(value)).flatten()
+    }
+
+    fn number(element: &Owned, name: &str) -> Option<i64> {
+        let value = attribute(element, name)?;
+        let mut number = 0i64;
+        // SAFETY: the value is checked to be a CFNumber before it is read into an i64
+        let read = unsafe {
+            CFGetTypeID(value.0) == CFNumberGetTypeID()
+                && CFNumberGetValue(
+                    value.0,
+                    NUMBER_SINT64,
+                    &mut number as *mut i64 as *mut c_void,
+                )
+        };
+        read.then_some(number)
+    }
+
+    fn focused_element() -> Option<Owned> {
+        // SAFETY: the system-wide element is released by `Owned`
+        let system = Owned::new(unsafe { AXUIElementCreateSystemWide() })?;
+        attribute(&system, "AXFocusedUIElement")
+    }
+
+    fn selected_range(element: &Owned) -> Option<CFRange> {
+        let selection = attribute(element, "AXSelectedTextRange")?;
+        let mut range = CFRange {
+            location: 0,
+            length: 0,
+        };
+        // SAFETY: `range` is a CFRange, which is what the value is asked to hold
+        let read = unsafe {
+            AXValueGetValue(
+                selection.0,
+                AX_VALUE_CF_RANGE,
+                &mut range as *mut CFRange as *mut c_void,
+            )
+        };
+        (read && range.location >= 0 && range.length >= 0).then_some(range)
+    }
+
+    /// Up to `max_units` UTF-16 units before the caret in the focused text element,
+    /// through the Accessibility API. Empty at the start of a field, None when the
+    /// element doesn't expose its text, like secure fields and most terminals.
+    pub fn text_before_caret(max_units: isize) -> Option<String> {
+        let focused = focused_element()?;
+        let range = selected_range(&focused)?;
+        let start = (range.location - max_units).max(0);
+        let before = CFRange {
+            location: start,
+            length: range.location - start,
+        };
+        if before.length == 0 {
+            return Some(String::new());
+        }
+
+        // SAFETY: `before` is a CFRange and the created value is released by `Owned`
+        let parameter = Owned::new(unsafe {
+            AXValueCreate(
+                AX_VALUE_CF_RANGE,
+                &before as *const CFRange as *const c_void,
+            )
+        })?;
+        let name = cf_string("AXStringForRange")?;
+        let mut text = ptr::null();
+        // SAFETY: all objects are live and `text` is a valid out pointer
+        let status = unsafe {
+            AXUIElementCopyParameterizedAttributeValue(focused.0, name.0, parameter.0, &mut text)
+        };
+        if status != AX_SUCCESS {
+            return None;
+        }
+        let text = Owned::new(text)?;
+        // Each UTF-16 unit takes at most 3 bytes in UTF-8
+        let mut buffer = vec![0 as c_char; (max_units * 3 + 1) as usize];
+        // SAFETY: the buffer size passed matches its length
+        let copied =
+            unsafe { CFStringGetCString(text.0, buffer.as_mut_ptr(), buffer.len() as isize, UTF8) };
+        if !copied {
+            return None;
+        }
+        let bytes: Vec<u8> = buffer
+            .into_iter()
+            .take_while(|byte| *byte != 0)
+            .map(|byte| byte as u8)
+            .collect();
+        String::from_utf8(bytes).ok()
+    }
+
+    /// Characters in the focused text element that stay when the selection is
+    /// replaced, in UTF-16 units
+    pub fn kept_length() -> Option<usize> {
+        let focused = focused_element()?;
+        let range = selected_range(&focused)?;
+        let length = number(&focused, "AXNumberOfCharacters")?;
+        Some((length - range.length as i64).max(0) as usize)
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    pub fn text_before_caret(_max_units: isize) -> Option<String> {
+        None
+    }
+
+    pub fn kept_length() -> Option<usize> {
+        None
+    }
+}
+
+fn capitalize_first(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Lowercase the first word unless it looks like a name the recognizer spelled on
+/// purpose, such as "I", "NASA" or "iPhone"
+fn lowercase_first(text: &str) -> String {
+    let word: String = text.chars().take_while(|c| c.is_alphanumeric()).collect();
+    let mut letters = word.chars();
+    let Some(first) = letters.next() else {
+        return text.to_string();
+    };
+    if !first.is_uppercase() || word.chars().count() == 1 || letters.any(char::is_uppercase) {
+        return text.to_string();
+    }
+    first.to_lowercase().chain(text.chars().skip(1)).collect()
+}
+
+/// Fit `text` to what is left of the caret: a capital after a sentence end or at the
+/// start of a field, lowercase mid-sentence, and a space when the caret touches a word
+pub fn fit_to_context(text: &str, before: &str) -> String {
+    let Some(last) = before.chars().last() else {
+        return capitalize_first(text);
+    };
+    let previous = before.trim_end().chars().last();
+    let starts_sentence = match previous {
+        None => true,
+        Some(previous) => SENTENCE_ENDS.contains(&previous) || last == '\n',
+    };
+    let fitted = if starts_sentence {
+        capitalize_first(text)
+    } else if OPENERS.contains(&last) {
+        text.to_string()
+    } else {
+        lowercase_first(text)
+    };
+    let needs_space = !last.is_whitespace()
+        && !OPENERS.contains(&last)
+        && !fitted.starts_with(char::is_whitespace)
+        && !fitted.starts_with(CLOSERS);
+    if needs_space && !fitted.is_empty() {
+        format!(" {fitted}")
+    } else {
+        fitted
+    }
+}
+
+/// Length of the focused field's text once its selection is replaced, None when the
+/// field doesn't expose it
+pub fn field_length() -> Option<usize> {
+    platform::kept_length()
+}
+
+/// `text` adjusted to the focused field when context-aware insertion is on and the
+/// field's text can be read, unchanged otherwise
+pub fn adapt(app: &AppHandle, text: String) -> String {
+    if !settings::current(app).fit_to_caret_context {
+        return text;
+    }
+    match platform::text_before_caret(CONTEXT_UNITS) {
+        Some(before) => fit_to_context(&text, &before),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn continues_a_sentence() {
+        assert_eq!(fit_to_context("Then we left.", "we ate"), " then we left.");
+        assert_eq!(fit_to_context("Then we left.", "we ate "), "then we left.");
+        assert_eq!(
+            fit_to_context(", then we left.", "we ate"),
+            ", then we left."
+        );
+        assert_eq!(fit_to_context("I left.", "and then "), "I left.");
+        assert_eq!(fit_to_context("NASA called.", "then "), "NASA called.");
+        assert_eq!(fit_to_context("iPhone", "my "), "iPhone");
+    }
+
+    #[test]
+    fn starts_a_sentence() {
+        assert_eq!(fit_to_context("then we left.", ""), "Then we left.");
+        assert_eq!(fit_to_context("then we left.", "We ate."), " Then we left.");
+        assert_eq!(fit_to_context("then we left.", "We ate. "), "Then we left.");
+        assert_eq!(fit_to_context("then we left.", "We ate\n"), "Then we left.");
+        assert_eq!(fit_to_context("Aside", "text ("), "Aside");
+    }
+}
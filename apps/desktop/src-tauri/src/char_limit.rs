@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::{app_profiles, caret_context, clipboard, settings};
+
+// Fields like a post composer only take so many characters. The limit comes from the
+// profile of the app being dictated into, and what the field already holds from the
+// Accessibility API, so only fields that expose their length are checked.
+
+/// What to do when a transcript is longer than the field has room for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CharacterLimitMode {
+    #[default]
+    Off,
+    /// Paste everything and report how far over the limit it went
+    Warn,
+    /// Paste what fits, cut at a word, and leave the rest on the clipboard
+    Truncate,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CharacterLimitExceeded {
+    limit: usize,
+    /// Characters the field had room for
+    remaining: usize,
+    length: usize,
+    /// The part that wasn't pasted, now on the clipboard
+    overflow: Option<String>,
+}
+
+/// Split `text` so the first part has at most `room` characters, ending at a word
+/// boundary unless a single word is longer than that
+fn split_at_word(text: &str, room: usize) -> (String, String) {
+    let Some((cut, _)) = text.char_indices().nth(room) else {
+        return (text.to_string(), String::new());
+    };
+    let head = &text[..cut];
+    let at_boundary = text[cut..].starts_with(char::is_whitespace);
+    let cut = match head.rfind(char::is_whitespace) {
+        Some(space) if !at_boundary => space,
+        _ => cut,
+    };
+    (
+        text[..cut].trim_end().to_string(),
+        text[cut..].trim_start().to_string(),
+    )
+}
+
+/// The part of `text` to insert, and the overflow to leave on the clipboard once it
+/// is in, reporting a `character-limit-exceeded` event when the field is too full
+pub fn fit(app: &AppHandle, text: String) -> (String, Option<String>) {
+    let mode = settings::current(app).character_limit;
+    if mode == CharacterLimitMode::Off {
+        return (text, None);
+    }
+    let Some(limit) = app_profiles::current(app).and_then(|profile| profile.character_limit) else {
+        return (text, None);
+    };
+    let Some(used) = caret_context::field_length() else {
+        return (text, None);
+    };
+    let remaining = limit.saturating_sub(used);
+    let length = text.chars().count();
+    if length <= remaining {
+        return (text, None);
+    }
+    let (text, overflow) = match mode {
+        CharacterLimitMode::Truncate => {
+            let (head, overflow) = split_at_word(&text, remaining);
+            (head, Some(overflow).filter(|overflow| !overflow.is_empty()))
+        }
+        _ => (text, None),
+    };
+    let _ = app.emit(
+        "character-limit-exceeded",
+        CharacterLimitExceeded {
+            limit,
+            remaining,
+            length,
+            overflow: overflow.clone(),
+        },
+    );
+    (text, overflow)
+}
+
+/// Put the overflow of a truncated transcript on the clipboard, in place of any
+/// contents waiting to be restored
+pub fn keep_overflow(app: &AppHandle, overflow: &str) {
+    clipboard::discard();
+    if let Err(e) = app.clipboard().write_text(overflow.to_string()) {
+        eprintln!("[voiceflow] Failed to keep the overflow on the clipboard: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_at_the_last_word_that_fits() {
+        let (head, rest) = split_at_word("ship it today please", 10);
+        assert_eq!((head.as_str(), rest.as_str()), ("ship it", "today please"));
+        let (head, rest) = split_at_word("ship it today", 7);
+        assert_eq!((head.as_str(), rest.as_str()), ("ship it", "today"));
+        let (head, rest) = split_at_word("supercalifragilistic", 5);
+        assert_eq!((head.as_str(), rest.as_str()), ("super", "califragilistic"));
+        let (head, rest) = split_at_word("fits", 10);
+        assert_eq!((head.as_str(), rest.as_str()), ("fits", ""));
+    }
+}
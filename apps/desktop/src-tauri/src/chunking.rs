@@ -0,0 +1,270 @@
+use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::transcription::{self, Transcript, TranscriptionOptions, SAMPLE_RATE};
+
+const SAMPLES_PER_SECOND: usize = SAMPLE_RATE as usize;
+/// Shorter files go to the sidecar in one piece
+const MIN_CHUNKED_SAMPLES: usize = 90 * SAMPLES_PER_SECOND;
+const CHUNK_SAMPLES: usize = 30 * SAMPLES_PER_SECOND;
+/// How far from the nominal boundary to look for a pause to cut at
+const SEARCH_SAMPLES: usize = 3 * SAMPLES_PER_SECOND;
+const WINDOW_SAMPLES: usize = 30 * SAMPLES_PER_SECOND / 1000;
+/// Audio repeated before each cut so a word split by the cut is heard whole
+const OVERLAP_SAMPLES: usize = SAMPLES_PER_SECOND / 2;
+/// Most words the overlap can plausibly repeat
+const MAX_OVERLAP_WORDS: usize = 8;
+const MAX_PARALLEL: usize = 3;
+
+struct Chunk {
+    /// Audio sent to the sidecar, including the overlap
+    audio: Range<usize>,
+    /// Where this chunk's own audio begins; earlier words belong to the previous chunk
+    start: usize,
+}
+
+fn energy(window: &[i16]) -> u64 {
+    window
+        .iter()
+        .map(|sample| i64::from(*sample).pow(2) as u64)
+        .sum()
+}
+
+/// Cut roughly every `CHUNK_SAMPLES` at the quietest window nearby, so cuts land in pauses
+fn cut_points(samples: &[i16]) -> Vec<usize> {
+    let mut cuts = Vec::new();
+    let mut last = 0;
+    while samples.len() - last > CHUNK_SAMPLES + SEARCH_SAMPLES {
+        let target = last + CHUNK_SAMPLES;
+        let cut = (target - SEARCH_SAMPLES..target + SEARCH_SAMPLES)
+            .step_by(WINDOW_SAMPLES)
+            .min_by_key(|start| energy(&samples[*start..start + WINDOW_SAMPLES]))
+            .map_or(target, |start| start + WINDOW_SAMPLES / 2);
+        cuts.push(cut);
+        last = cut;
+    }
+    cuts
+}
+
+fn chunks(samples: &[i16]) -> Vec<Chunk> {
+    let mut bounds = vec![0];
+    bounds.extend(cut_points(samples));
+    bounds.push(samples.len());
+    bounds
+        .windows(2)
+        .map(|pair| Chunk {
+            audio: pair[0].saturating_sub(OVERLAP_SAMPLES)..pair[1],
+            start: pair[0],
+        })
+        .collect()
+}
+
+fn normalized(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Drop the start of `next` that repeats the end of `previous` because of the overlap
+fn dedupe_overlap(previous: &str, next: &str) -> String {
+    let previous: Vec<String> = previous.split_whitespace().map(normalized).collect();
+    let next_words: Vec<&str> = next.split_whitespace().collect();
+    let longest = MAX_OVERLAP_WORDS.min(previous.len()).min(next_words.len());
+    let repeated = (1..=longest)
+        .rev()
+        .find(|len| {
+            previous[previous.len() - len..]
+                .iter()
+                .zip(&next_words[..*len])
+                .all(|(expected, word)| *expected == normalized(word))
+        })
+        .unwrap_or(0);
+    next_words[repeated..].join(" ")
+}
+
+/// What one chunk added to the stitched text, and the same stretch in its alternatives
+struct ChunkText {
+    text: String,
+    alternatives: Vec<String>,
+}
+
+/// Shift a chunk's timings to the whole file and append the part not already covered
+fn stitch(stitched: &mut Transcript, chunk: &Chunk, mut transcript: Transcript) -> ChunkText {
+    let offset = chunk.audio.start as f64 / SAMPLE_RATE as f64;
+    let start = chunk.start as f64 / SAMPLE_RATE as f64;
+
+    for segment in &mut transcript.segments {
+        segment.start += offset;
+        segment.end += offset;
+    }
+    for word in &mut transcript.words {
+        word.start += offset;
+        word.end += offset;
+    }
+    stitched.segments.extend(
+        transcript
+            .segments
+            .into_iter()
+            .filter(|segment| segment.end > start),
+    );
+    stitched.words.extend(
+        transcript
+            .words
+            .into_iter()
+            .filter(|word| word.start >= start),
+    );
+
+    let alternatives = transcript
+        .alternatives
+        .iter()
+        .map(|alternative| dedupe_overlap(&stitched.text, alternative))
+        .collect();
+    let text = dedupe_overlap(&stitched.text, &transcript.text);
+    if !text.is_empty() {
+        if !stitched.text.is_empty() {
+            stitched.text.push(' ');
+        }
+        stitched.text.push_str(&text);
+    }
+    if stitched.language.is_none() {
+        stitched.language = transcript.language;
+    }
+    ChunkText { text, alternatives }
+}
+
+/// Alternatives for the whole recording, each replacing one chunk's text with one of
+/// its hypotheses. Every chunk's best-ranked alternative comes before any second ones.
+fn combine_alternatives(chunks: &[ChunkText], count: usize) -> Vec<String> {
+    let ranks = chunks
+        .iter()
+        .map(|chunk| chunk.alternatives.len())
+        .max()
+        .unwrap_or(0);
+    let mut combined: Vec<String> = Vec::new();
+    for rank in 0..ranks {
+        for (index, chunk) in chunks.iter().enumerate() {
+            let Some(alternative) = chunk.alternatives.get(rank) else {
+                continue;
+            };
+            if combined.len() == count {
+                return combined;
+            }
+            let text = chunks
+                .iter()
+                .enumerate()
+                .map(|(other, chunk)| {
+                    if other == index {
+                        alternative
+                    } else {
+                        &chunk.text
+                    }
+                })
+                .filter(|text| !text.is_empty())
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(" ");
+            if !combined.contains(&text) {
+                combined.push(text);
+            }
+        }
+    }
+    combined
+}
+
+/// Transcribe long audio as pause-aligned chunks, a few at a time, and stitch the results.
+/// Speaker labels aren't comparable between chunks, so diarized jobs go through in one piece.
+/// Alternatives differ from the stitched text in one chunk each.
+pub fn transcribe_long(
+    samples: &[i16],
+    options: &TranscriptionOptions,
+) -> Result<Transcript, String> {
+    if samples.len() < MIN_CHUNKED_SAMPLES || options.diarize {
+        return transcription::transcribe(samples, options);
+    }
+
+    let chunks = chunks(samples);
+    let results: Vec<Mutex<Option<Result<Transcript, String>>>> =
+        chunks.iter().map(|_| Mutex::new(None)).collect();
+    let next = AtomicUsize::new(0);
+
+    thread::scope(|scope| {
+        for _ in 0..MAX_PARALLEL.min(chunks.len()) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let Some(chunk) = chunks.get(index) else {
+                    break;
+                };
+                let result = transcription::transcribe(&samples[chunk.audio.clone()], options);
+                if let Ok(mut slot) = results[index].lock() {
+                    *slot = Some(result);
+                }
+            });
+        }
+    });
+
+    let mut stitched = Transcript::default();
+    let mut texts = Vec::new();
+    for (chunk, result) in chunks.iter().zip(results) {
+        let result = result
+            .into_inner()
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Chunk was not transcribed".to_string())?;
+        texts.push(stitch(&mut stitched, chunk, result?));
+    }
+    stitched.alternatives = combine_alternatives(&texts, options.alternatives as usize);
+    Ok(stitched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cuts_land_in_pauses() {
+        let mut samples = vec![1000_i16; 60 * SAMPLES_PER_SECOND];
+        let pause = 31 * SAMPLES_PER_SECOND;
+        samples[pause..pause + SAMPLES_PER_SECOND / 5].fill(0);
+        let cuts = cut_points(&samples);
+        assert_eq!(cuts.len(), 1);
+        assert!((pause..pause + SAMPLES_PER_SECOND / 5).contains(&cuts[0]));
+    }
+
+    #[test]
+    fn removes_words_repeated_by_the_overlap() {
+        assert_eq!(
+            dedupe_overlap("we met on Tuesday.", "Tuesday and talked"),
+            "and talked"
+        );
+        assert_eq!(
+            dedupe_overlap("hello there", "general Kenobi"),
+            "general Kenobi"
+        );
+    }
+
+    #[test]
+    fn alternatives_replace_one_chunk_at_a_time() {
+        let chunk = |text: &str, alternatives: &[&str]| ChunkText {
+            text: text.to_string(),
+            alternatives: alternatives.iter().map(|text| text.to_string()).collect(),
+        };
+        let chunks = [
+            chunk(
+                "we met on Tuesday",
+                &["we met on Thursday", "we meet on Tuesday"],
+            ),
+            chunk("and talked", &["and walked"]),
+        ];
+        assert_eq!(
+            combine_alternatives(&chunks, 5),
+            [
+                "we met on Thursday and talked",
+                "we met on Tuesday and walked",
+                "we meet on Tuesday and talked",
+            ]
+        );
+        assert_eq!(combine_alternatives(&chunks, 1).len(), 1);
+    }
+}
@@ -0,0 +1,390 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+#[cfg(not(target_os = "macos"))]
+use tauri::image::Image;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::{i18n, settings};
+
+const VERIFY_TIMEOUT: Duration = Duration::from_millis(100);
+const VERIFY_INTERVAL: Duration = Duration::from_millis(10);
+
+pub const MIN_RESTORE_DELAY_MS: u64 = 50;
+pub const MAX_RESTORE_DELAY_MS: u64 = 10_000;
+
+// Contents saved before the first of a run of pastes, put back once the last one is done
+static PRESERVED: Mutex<Option<Snapshot>> = Mutex::new(None);
+// Bumped by every paste so only the latest scheduled restore runs
+static RESTORE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ClipboardRestoreSettings {
+    /// Put back what was on the clipboard after pasting a transcript
+    pub enabled: bool,
+    /// How long the target app gets to read the pasted text before it is replaced
+    pub delay_ms: u64,
+}
+
+impl Default for ClipboardRestoreSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            delay_ms: 500,
+        }
+    }
+}
+
+impl ClipboardRestoreSettings {
+    pub fn is_valid(&self) -> bool {
+        (MIN_RESTORE_DELAY_MS..=MAX_RESTORE_DELAY_MS).contains(&self.delay_ms)
+    }
+
+    pub fn delay(&self) -> Duration {
+        Duration::from_millis(self.delay_ms)
+    }
+}
+
+/// The clipboard's contents at some point
+enum Snapshot {
+    /// Every item on the general pasteboard with the data of each of its types, so
+    /// rich text, images and app-private formats all come back
+    #[cfg(target_os = "macos")]
+    Pasteboard(Vec<Vec<(String, Vec<u8>)>>),
+    #[cfg(not(target_os = "macos"))]
+    Text(String),
+    #[cfg(not(target_os = "macos"))]
+    Image(Image<'static>),
+    #[cfg(not(target_os = "macos"))]
+    Empty,
+}
+
+/// NSPasteboard's change count, bumped by every write from any app
+#[cfg(target_os = "macos")]
+fn change_count() -> Option<isize> {
+    use objc::runtime::Object;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    // SAFETY: generalPasteboard returns the shared pasteboard, which is never nil
+    unsafe {
+        let pasteboard: *mut Object = msg_send![class!(NSPasteboard), generalPasteboard];
+        Some(msg_send![pasteboard, changeCount])
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn change_count() -> Option<isize> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+mod pasteboard {
+    use objc::runtime::Object;
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::ffi::{c_char, c_void, CStr, CString};
+
+    /// SAFETY: call inside an autorelease pool
+    unsafe fn general() -> *mut Object {
+        msg_send![class!(NSPasteboard), generalPasteboard]
+    }
+
+    /// SAFETY: `array` must be an NSArray
+    unsafe fn objects(array: *mut Object) -> Vec<*mut Object> {
+        if array.is_null() {
+            return Vec::new();
+        }
+        let count: usize = msg_send![array, count];
+        (0..count)
+            .map(|index| msg_send![array, objectAtIndex: index])
+            .collect()
+    }
+
+    /// SAFETY: `string` must be an NSString
+    unsafe fn to_string(string: *mut Object) -> Option<String> {
+        let utf8: *const c_char = msg_send![string, UTF8String];
+        (!utf8.is_null()).then(|| CStr::from_ptr(utf8).to_string_lossy().into_owned())
+    }
+
+    /// The data of every type of every item on the general pasteboard
+    pub fn read() -> Vec<Vec<(String, Vec<u8>)>> {
+        // SAFETY: everything autoreleased here is released when the pool drains, and
+        // the bytes are copied out before that
+        unsafe {
+            let pool: *mut Object = msg_send![class!(NSAutoreleasePool), new];
+            let items = objects(msg_send![general(), pasteboardItems])
+                .into_iter()
+                .map(|item| {
+                    objects(msg_send![item, types])
+                        .into_iter()
+                        .filter_map(|pasteboard_type| {
+                            let data: *mut Object = msg_send![item, dataForType: pasteboard_type];
+                            if data.is_null() {
+                                return None;
+                            }
+                            let length: usize = msg_send![data, length];
+                            let bytes: *const u8 = msg_send![data, bytes];
+                            let bytes = if length == 0 {
+                                Vec::new()
+                            } else {
+                                std::slice::from_raw_parts(bytes, length).to_vec()
+                            };
+                            Some((to_string(pasteboard_type)?, bytes))
+                        })
+                        .collect()
+                })
+                .collect();
+            let _: () = msg_send![pool, drain];
+            items
+        }
+    }
+
+    /// Replace the general pasteboard's contents with `items`
+    pub fn write(items: &[Vec<(String, Vec<u8>)>]) -> Result<(), String> {
+        // SAFETY: the items are owned by the array once added, and everything
+        // autoreleased here is released when the pool drains
+        unsafe {
+            let pool: *mut Object = msg_send![class!(NSAutoreleasePool), new];
+            let array: *mut Object = msg_send![class!(NSMutableArray), array];
+            for types in items {
+                let item: *mut Object = msg_send![class!(NSPasteboardItem), new];
+                for (pasteboard_type, bytes) in types {
+                    let Ok(name) = CString::new(pasteboard_type.as_str()) else {
+                        continue;
+                    };
+                    let name: *mut Object =
+                        msg_send![class!(NSString), stringWithUTF8String: name.as_ptr()];
+                    let data: *mut Object = msg_send![class!(NSData),
+                        dataWithBytes: bytes.as_ptr() as *const c_void
+                        length: bytes.len()];
+                    let _: i8 = msg_send![item, setData: data forType: name];
+                }
+                let _: () = msg_send![array, addObject: item];
+                let _: () = msg_send![item, release];
+            }
+            let pasteboard = general();
+            let _: isize = msg_send![pasteboard, clearContents];
+            let written: i8 = msg_send![pasteboard, writeObjects: array];
+            let _: () = msg_send![pool, drain];
+            if written == 0 && !items.is_empty() {
+                return Err("Failed to write the clipboard".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn snapshot(_app: &AppHandle) -> Snapshot {
+    Snapshot::Pasteboard(pasteboard::read())
+}
+
+/// Only text and images can be read back portably; other formats are lost
+#[cfg(not(target_os = "macos"))]
+fn snapshot(app: &AppHandle) -> Snapshot {
+    let clipboard = app.clipboard();
+    if let Ok(text) = clipboard.read_text() {
+        return Snapshot::Text(text);
+    }
+    match clipboard.read_image() {
+        Ok(image) => Snapshot::Image(Image::new_owned(
+            image.rgba().to_vec(),
+            image.width(),
+            image.height(),
+        )),
+        Err(_) => Snapshot::Empty,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn restore(_app: &AppHandle, snapshot: Snapshot) -> Result<(), String> {
+    match snapshot {
+        Snapshot::Pasteboard(items) => pasteboard::write(&items),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn restore(app: &AppHandle, snapshot: Snapshot) -> Result<(), String> {
+    let clipboard = app.clipboard();
+    match snapshot {
+        Snapshot::Text(text) => clipboard.write_text(text),
+        Snapshot::Image(image) => clipboard.write_image(&image),
+        Snapshot::Empty => clipboard.clear(),
+    }
+    .map_err(|e| format!("Failed to restore the clipboard: {e}"))
+}
+
+/// Save the clipboard before a paste overwrites it. When pastes follow each other
+/// faster than the restore delay, the contents from before the first one are kept.
+pub fn preserve(app: &AppHandle) {
+    if let Ok(mut preserved) = PRESERVED.lock() {
+        if preserved.is_none() {
+            *preserved = Some(snapshot(app));
+        }
+    }
+}
+
+/// Forget the saved contents, leaving what was just written on the clipboard
+pub fn discard() {
+    RESTORE_GENERATION.fetch_add(1, Ordering::SeqCst);
+    if let Ok(mut preserved) = PRESERVED.lock() {
+        preserved.take();
+    }
+}
+
+/// Put the saved contents back after `delay`, unless another paste came after this
+/// one or something other than `pasted` was copied in the meantime
+pub fn restore_later(app: &AppHandle, pasted: &str, delay: Duration) {
+    let generation = RESTORE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let written = change_count();
+    let app = app.clone();
+    let pasted = pasted.to_string();
+    thread::spawn(move || {
+        thread::sleep(delay);
+        if RESTORE_GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        let Some(snapshot) = PRESERVED
+            .lock()
+            .ok()
+            .and_then(|mut preserved| preserved.take())
+        else {
+            return;
+        };
+        let untouched = match written {
+            Some(written) => change_count() == Some(written),
+            None => app.clipboard().read_text().ok().as_deref() == Some(pasted.as_str()),
+        };
+        if !untouched {
+            return;
+        }
+        if let Err(e) = restore(&app, snapshot) {
+            eprintln!("[voiceflow] {e}");
+        }
+    });
+}
+
+/// The pasteboard also takes RTF, which Pages and TextEdit prefer over HTML
+#[cfg(target_os = "macos")]
+fn write_rich(_app: &AppHandle, text: &str, html: &str, rtf: &str) -> Result<(), String> {
+    let flavor = |name: &str, data: &str| (name.to_string(), data.as_bytes().to_vec());
+    pasteboard::write(&[vec![
+        flavor("public.utf8-plain-text", text),
+        flavor("public.html", html),
+        flavor("public.rtf", rtf),
+    ]])
+}
+
+/// Word and browsers take the HTML version just as well
+#[cfg(not(target_os = "macos"))]
+fn write_rich(app: &AppHandle, text: &str, html: &str, _rtf: &str) -> Result<(), String> {
+    app.clipboard()
+        .write_html(html.to_string(), Some(text.to_string()))
+        .map_err(|e| format!("Failed to write clipboard: {e}"))
+}
+
+fn write_and_verify(
+    app: &AppHandle,
+    text: &str,
+    html: Option<&str>,
+    rtf: Option<&str>,
+) -> Result<bool, String> {
+    let before = change_count();
+    match (html, rtf) {
+        (Some(html), Some(rtf)) => write_rich(app, text, html, rtf)?,
+        (Some(html), None) => app
+            .clipboard()
+            .write_html(html.to_string(), Some(text.to_string()))
+            .map_err(|e| format!("Failed to write clipboard: {e}"))?,
+        (None, _) => app
+            .clipboard()
+            .write_text(text.to_string())
+            .map_err(|e| format!("Failed to write clipboard: {e}"))?,
+    }
+
+    let deadline = Instant::now() + VERIFY_TIMEOUT;
+    loop {
+        let changed = before.is_none() || change_count() != before;
+        if changed && app.clipboard().read_text().ok().as_deref() == Some(text) {
+            return Ok(true);
+        }
+        if Instant::now() >= deadline {
+            return Ok(false);
+        }
+        thread::sleep(VERIFY_INTERVAL);
+    }
+}
+
+/// Put `text` on the clipboard and confirm it landed before anything pastes it,
+/// retrying the write once so a lost write can't paste the previous contents
+pub fn write_verified(app: &AppHandle, text: &str) -> Result<(), String> {
+    write_with_retry(app, text, None, None)
+}
+
+/// Same as `write_verified` with an HTML version for apps that accept rich text,
+/// `text` being the plain fallback
+pub fn write_html_verified(app: &AppHandle, html: &str, text: &str) -> Result<(), String> {
+    write_with_retry(app, text, Some(html), None)
+}
+
+/// Same as `write_html_verified` with an RTF version as well, where the platform
+/// clipboard can carry one
+pub fn write_rich_verified(
+    app: &AppHandle,
+    html: &str,
+    rtf: &str,
+    text: &str,
+) -> Result<(), String> {
+    write_with_retry(app, text, Some(html), Some(rtf))
+}
+
+fn write_with_retry(
+    app: &AppHandle,
+    text: &str,
+    html: Option<&str>,
+    rtf: Option<&str>,
+) -> Result<(), String> {
+    if write_and_verify(app, text, html, rtf)? {
+        return Ok(());
+    }
+    eprintln!("[voiceflow] Clipboard write not visible, retrying");
+    if write_and_verify(app, text, html, rtf)? {
+        return Ok(());
+    }
+    Err(i18n::t("error.clipboard_not_updated"))
+}
+
+#[tauri::command]
+pub async fn set_clipboard_restore(
+    app: AppHandle,
+    config: ClipboardRestoreSettings,
+) -> Result<ClipboardRestoreSettings, String> {
+    if !config.is_valid() {
+        return Err(i18n::t("error.clipboard_restore_delay"));
+    }
+    let updated = settings::update(&app, |settings| settings.restore_clipboard = config)?;
+    Ok(updated.restore_clipboard)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restore_delay_is_bounded() {
+        assert!(ClipboardRestoreSettings::default().is_valid());
+        let instant = ClipboardRestoreSettings {
+            enabled: true,
+            delay_ms: 0,
+        };
+        assert!(!instant.is_valid());
+        let slow = ClipboardRestoreSettings {
+            enabled: true,
+            delay_ms: MAX_RESTORE_DELAY_MS,
+        };
+        assert_eq!(slow.delay(), Duration::from_secs(10));
+    }
+}
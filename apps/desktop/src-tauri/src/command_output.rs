@@ -0,0 +1,230 @@
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::history::HistoryEntry;
+use crate::scratchpad::OutputTarget;
+use crate::{i18n, settings, show_main_window};
+
+// The "command" output target pipes each transcript into a shell command's stdin,
+// e.g. `llm`, `pbcopy` or a note capture CLI, instead of pasting it. The command
+// runs through `sh -c` (`cmd /C` on Windows); what it prints is reported in a
+// "command-output" event and shown in the bubble when `showOutput` is on.
+
+pub const MIN_TIMEOUT_SECS: u64 = 1;
+pub const MAX_TIMEOUT_SECS: u64 = 600;
+// How much of stdout and stderr is kept for the event
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+// After a kill, background processes the command started may still hold its pipes
+const KILLED_OUTPUT_GRACE: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CommandOutputSettings {
+    /// Shell command the transcript is piped into
+    pub command: String,
+    /// Seconds before the command is killed
+    pub timeout_secs: u64,
+    /// Show what the command printed in the bubble
+    pub show_output: bool,
+}
+
+impl Default for CommandOutputSettings {
+    fn default() -> Self {
+        Self {
+            command: String::new(),
+            timeout_secs: 30,
+            show_output: false,
+        }
+    }
+}
+
+impl CommandOutputSettings {
+    pub fn is_valid(&self) -> bool {
+        (MIN_TIMEOUT_SECS..=MAX_TIMEOUT_SECS).contains(&self.timeout_secs)
+    }
+}
+
+/// How a run of the command went
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandOutput {
+    /// None when the command was killed or ended by a signal
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub timed_out: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CommandOutputEvent {
+    entry_id: u64,
+    #[serde(flatten)]
+    output: CommandOutput,
+    /// Why the command couldn't be run at all
+    error: Option<String>,
+    show_output: bool,
+}
+
+fn shell(command: &str) -> Command {
+    #[cfg(target_os = "windows")]
+    let mut shell = {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    };
+    #[cfg(not(target_os = "windows"))]
+    let mut shell = {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    shell.arg(command);
+    shell
+}
+
+fn read_all(mut pipe: impl Read + Send + 'static) -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        let _ = pipe.read_to_end(&mut bytes);
+        bytes.truncate(MAX_OUTPUT_BYTES);
+        let _ = sender.send(String::from_utf8_lossy(&bytes).into_owned());
+    });
+    receiver
+}
+
+/// Run `command` with `text` on its stdin, killing it after `timeout`
+pub fn run(command: &str, text: &str, timeout: Duration) -> Result<CommandOutput, String> {
+    if command.trim().is_empty() {
+        return Err(i18n::t("error.output_command_empty"));
+    }
+    let mut child = shell(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run command: {e}"))?;
+
+    // Written from a thread so a command that doesn't read its input can't block us
+    let stdin = child.stdin.take();
+    let input = text.to_string();
+    thread::spawn(move || {
+        if let Some(mut stdin) = stdin {
+            let _ = stdin.write_all(input.as_bytes());
+        }
+    });
+    let stdout = child.stdout.take().map(read_all);
+    let stderr = child.stderr.take().map(read_all);
+
+    let deadline = Instant::now() + timeout;
+    let mut timed_out = false;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if Instant::now() >= deadline => {
+                timed_out = true;
+                let _ = child.kill();
+                break child.wait().ok();
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(50)),
+            Err(e) => return Err(format!("Failed to wait for command: {e}")),
+        }
+    };
+
+    let collected = |reader: Option<Receiver<String>>| {
+        let reader = reader?;
+        if timed_out {
+            reader.recv_timeout(KILLED_OUTPUT_GRACE).ok()
+        } else {
+            reader.recv().ok()
+        }
+    };
+    Ok(CommandOutput {
+        exit_code: status
+            .and_then(|status| status.code())
+            .filter(|_| !timed_out),
+        stdout: collected(stdout).unwrap_or_default(),
+        stderr: collected(stderr).unwrap_or_default(),
+        timed_out,
+    })
+}
+
+pub fn is_target(app: &AppHandle) -> bool {
+    settings::current(app).output_target == OutputTarget::Command
+}
+
+/// Pipe a finished dictation into the configured command without holding up the
+/// pipeline, reporting the result once it exits
+pub fn send(app: &AppHandle, entry: &HistoryEntry) {
+    let config = settings::current(app).output_command;
+    let app = app.clone();
+    let entry_id = entry.id;
+    let text = entry.text.clone();
+    thread::spawn(move || {
+        let timeout = Duration::from_secs(config.timeout_secs);
+        let (output, error) = match run(&config.command, &text, timeout) {
+            Ok(output) => (output, None),
+            Err(e) => {
+                eprintln!("[voiceflow] Output command failed: {e}");
+                (CommandOutput::default(), Some(e))
+            }
+        };
+        if config.show_output {
+            let handle = app.clone();
+            let _ = app.run_on_main_thread(move || show_main_window(&handle));
+        }
+        let _ = app.emit(
+            "command-output",
+            CommandOutputEvent {
+                entry_id,
+                output,
+                error,
+                show_output: config.show_output,
+            },
+        );
+    });
+}
+
+#[tauri::command]
+pub async fn set_output_command(
+    app: AppHandle,
+    config: CommandOutputSettings,
+) -> Result<CommandOutputSettings, String> {
+    if !config.is_valid() {
+        return Err(i18n::t("error.output_command_timeout"));
+    }
+    let updated = settings::update(&app, |settings| settings.output_command = config)?;
+    Ok(updated.output_command)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pipes_text_and_captures_output() {
+        let output = run(
+            "tr a-z A-Z; echo oops >&2; exit 3",
+            "hello",
+            Duration::from_secs(5),
+        )
+        .unwrap();
+        assert_eq!(output.stdout, "HELLO");
+        assert_eq!(output.stderr, "oops\n");
+        assert_eq!(output.exit_code, Some(3));
+        assert!(!output.timed_out);
+    }
+
+    #[test]
+    fn kills_commands_that_run_too_long() {
+        let output = run("echo started; sleep 5", "", Duration::from_millis(200)).unwrap();
+        assert!(output.timed_out);
+        assert_eq!(output.exit_code, None);
+    }
+}
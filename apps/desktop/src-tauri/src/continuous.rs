@@ -0,0 +1,94 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::microphone::{rms, Microphone, FRAME_DURATION, SPEECH_THRESHOLD};
+use crate::{append_e2e_log, start_recording, stop_recording, IS_RECORDING};
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Pause that finalizes the current utterance
+const SEGMENT_END_SILENCE: Duration = Duration::from_millis(800);
+/// Restart anyway if the finished segment never comes back through the pipeline
+const RESTART_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::SeqCst)
+}
+
+// Ends each recording once speech is followed by a pause
+fn segment(app: &AppHandle) -> Result<(), String> {
+    let mut microphone = Microphone::open(app)?;
+    let mut heard_speech = false;
+    let mut silence = Duration::ZERO;
+    let mut idle_since: Option<Instant> = None;
+
+    while is_active() {
+        let frames = microphone.frames(Duration::from_millis(200));
+        if !IS_RECORDING.load(Ordering::SeqCst) {
+            heard_speech = false;
+            silence = Duration::ZERO;
+            let idle = *idle_since.get_or_insert_with(Instant::now);
+            if idle.elapsed() >= RESTART_TIMEOUT {
+                segment_finished(app);
+            }
+            continue;
+        }
+        idle_since = None;
+
+        for frame in frames {
+            if rms(&frame) > SPEECH_THRESHOLD {
+                heard_speech = true;
+                silence = Duration::ZERO;
+            } else {
+                silence += FRAME_DURATION;
+            }
+        }
+        if heard_speech && silence >= SEGMENT_END_SILENCE {
+            append_e2e_log("continuous-segment-end");
+            stop_recording(app);
+        }
+    }
+    Ok(())
+}
+
+/// Start recording again after a segment was transcribed, while the mode is on
+pub fn segment_finished(app: &AppHandle) {
+    if is_active() {
+        start_recording(app);
+    }
+}
+
+pub fn start(app: &AppHandle) {
+    if ACTIVE.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let _ = app.emit("continuous-dictation-changed", true);
+    start_recording(app);
+
+    let app = app.clone();
+    thread::spawn(move || {
+        if let Err(e) = segment(&app) {
+            eprintln!("[voiceflow] Continuous dictation stopped: {e}");
+            stop(&app);
+        }
+    });
+}
+
+pub fn stop(app: &AppHandle) {
+    if ACTIVE.swap(false, Ordering::SeqCst) {
+        let _ = app.emit("continuous-dictation-changed", false);
+    }
+    stop_recording(app);
+}
+
+#[tauri::command]
+pub async fn start_continuous_dictation(app: AppHandle) {
+    start(&app);
+}
+
+#[tauri::command]
+pub async fn stop_continuous_dictation(app: AppHandle) {
+    stop(&app);
+}
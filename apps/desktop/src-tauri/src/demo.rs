@@ -0,0 +1,104 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+
+const FLAG: &str = "--demo";
+const ENV_VAR: &str = "VOICEFLOW_DEMO";
+const DIR_PREFIX: &str = "voiceflow-demo-";
+
+// Throwaway directory standing in for the config and data dirs, set once at launch
+static DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+fn requested() -> bool {
+    std::env::args().any(|arg| arg == FLAG)
+        || std::env::var(ENV_VAR).is_ok_and(|value| !value.is_empty() && value != "0")
+}
+
+/// Demo directories left behind by a session that didn't exit cleanly
+fn remove_stale(temp: &Path) {
+    let Ok(entries) = fs::read_dir(temp) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        if name.to_string_lossy().starts_with(DIR_PREFIX) {
+            let _ = fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+/// Create the directory readable by the current user only, as the temp dir may be shared
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder.create(dir)
+}
+
+/// Switch to demo mode when launched with --demo or VOICEFLOW_DEMO set. Must run before
+/// any store is created so nothing touches the user's real profile.
+pub fn init() {
+    DIR.get_or_init(|| {
+        if !requested() {
+            return None;
+        }
+        let temp = std::env::temp_dir();
+        remove_stale(&temp);
+        let dir = temp.join(format!("{DIR_PREFIX}{}", std::process::id()));
+        match create_private_dir(&dir) {
+            Ok(()) => {
+                eprintln!("[voiceflow] Demo mode, keeping state in {}", dir.display());
+                Some(dir)
+            }
+            Err(e) => {
+                // Refuse to fall back to the real profile, which demo mode promises not to touch
+                eprintln!("[voiceflow] Failed to create the demo directory: {e}");
+                std::process::exit(1);
+            }
+        }
+    });
+}
+
+pub fn is_active() -> bool {
+    DIR.get().is_some_and(Option::is_some)
+}
+
+fn demo_dir(name: &str) -> Option<PathBuf> {
+    DIR.get()?.as_ref().map(|dir| dir.join(name))
+}
+
+/// Where settings, profiles and templates live: the real app config dir, or a
+/// throwaway one in demo mode
+pub fn config_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    match demo_dir("config") {
+        Some(dir) => Ok(dir),
+        None => app.path().app_config_dir().map_err(|e| e.to_string()),
+    }
+}
+
+/// Where history and other app data live, throwaway in demo mode
+pub fn data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    match demo_dir("data") {
+        Some(dir) => Ok(dir),
+        None => app.path().app_data_dir().map_err(|e| e.to_string()),
+    }
+}
+
+/// Delete everything the demo session stored
+pub fn wipe() {
+    if let Some(Some(dir)) = DIR.get() {
+        if let Err(e) = fs::remove_dir_all(dir) {
+            eprintln!("[voiceflow] Failed to wipe demo state: {e}");
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn is_demo_mode() -> bool {
+    is_active()
+}
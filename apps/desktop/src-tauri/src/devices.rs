@@ -0,0 +1,178 @@
+use cpal::traits::{DeviceTrait, HostTrait};
+use serde::Serialize;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::{i18n, settings};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Common rates reported for devices that accept a continuous range
+const STANDARD_SAMPLE_RATES: [u32; 9] = [
+    8_000, 11_025, 16_000, 22_050, 32_000, 44_100, 48_000, 88_200, 96_000,
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InputDevice {
+    /// Stable identifier to pass to set_audio_input_device
+    pub id: String,
+    pub name: String,
+    /// Whether this is the OS default input
+    pub is_default: bool,
+    pub sample_rates: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeviceChanged {
+    /// The new OS default input, None when no microphone is connected
+    default_device: Option<InputDevice>,
+    /// The configured device is what gets recorded from, if it's still connected
+    selected_connected: bool,
+}
+
+fn device_id(device: &cpal::Device) -> Option<String> {
+    device.id().ok().map(|id| id.to_string())
+}
+
+/// Sample rates within the device's supported ranges: the standard rates they cover
+/// plus the bounds of each range
+fn sample_rates(ranges: &[(u32, u32)]) -> Vec<u32> {
+    let mut rates: Vec<u32> = STANDARD_SAMPLE_RATES
+        .iter()
+        .copied()
+        .filter(|rate| ranges.iter().any(|(min, max)| (min..=max).contains(&rate)))
+        .chain(ranges.iter().flat_map(|&(min, max)| [min, max]))
+        .collect();
+    rates.sort_unstable();
+    rates.dedup();
+    rates
+}
+
+fn describe(device: &cpal::Device, default_id: Option<&str>) -> Option<InputDevice> {
+    let id = device_id(device)?;
+    let name = device
+        .description()
+        .map(|description| description.name().to_string())
+        .unwrap_or_else(|_| id.clone());
+    let ranges: Vec<(u32, u32)> = device
+        .supported_input_configs()
+        .map(|configs| {
+            configs
+                .map(|config| (config.min_sample_rate(), config.max_sample_rate()))
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(InputDevice {
+        is_default: default_id == Some(id.as_str()),
+        id,
+        name,
+        sample_rates: sample_rates(&ranges),
+    })
+}
+
+fn default_device() -> Option<InputDevice> {
+    let device = cpal::default_host().default_input_device()?;
+    let id = device_id(&device);
+    describe(&device, id.as_deref())
+}
+
+pub fn list() -> Result<Vec<InputDevice>, String> {
+    let host = cpal::default_host();
+    let default_id = host
+        .default_input_device()
+        .and_then(|device| device_id(&device));
+    let devices = host
+        .input_devices()
+        .map_err(|e| format!("Failed to list input devices: {e}"))?;
+    Ok(devices
+        .filter_map(|device| describe(&device, default_id.as_deref()))
+        .collect())
+}
+
+fn find(id: &str) -> Option<cpal::Device> {
+    cpal::default_host()
+        .input_devices()
+        .ok()?
+        .find(|device| device_id(device).as_deref() == Some(id))
+}
+
+/// The configured microphone, falling back to the OS default when none is set or
+/// it has been disconnected
+pub fn input_device(app: &AppHandle) -> Result<cpal::Device, String> {
+    if let Some(id) = settings::current(app).input_device {
+        match find(&id) {
+            Some(device) => return Ok(device),
+            None => eprintln!("[voiceflow] Input device {id} not connected, using the default"),
+        }
+    }
+    cpal::default_host()
+        .default_input_device()
+        .ok_or_else(|| i18n::t("error.no_input_device"))
+}
+
+/// Watch the OS default input and tell the webview when it changes
+pub fn start_monitor(app: AppHandle) {
+    thread::spawn(move || {
+        let mut current = default_device().map(|device| device.id);
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let default_device = default_device();
+            let id = default_device.as_ref().map(|device| device.id.clone());
+            if id == current {
+                continue;
+            }
+            current = id;
+            let selected_connected = match settings::current(&app).input_device {
+                Some(selected) => find(&selected).is_some(),
+                None => default_device.is_some(),
+            };
+            let _ = app.emit(
+                "device-changed",
+                DeviceChanged {
+                    default_device,
+                    selected_connected,
+                },
+            );
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn list_audio_input_devices() -> Result<Vec<InputDevice>, String> {
+    tauri::async_runtime::spawn_blocking(list)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Record from the given device from the next dictation on, or from the OS default
+/// when `id` is None
+#[tauri::command]
+pub async fn set_audio_input_device(app: AppHandle, id: Option<String>) -> Result<(), String> {
+    if let Some(id) = &id {
+        if find(id).is_none() {
+            return Err(i18n::tf("error.unknown_input_device", &[("id", id)]));
+        }
+    }
+    settings::update(&app, |settings| settings.input_device = id)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_rates_within_supported_ranges() {
+        assert_eq!(
+            sample_rates(&[(44_100, 48_000), (16_000, 16_000)]),
+            vec![16_000, 44_100, 48_000]
+        );
+        assert_eq!(
+            sample_rates(&[(12_000, 24_000)]),
+            vec![12_000, 16_000, 22_050, 24_000]
+        );
+        assert!(sample_rates(&[]).is_empty());
+    }
+}
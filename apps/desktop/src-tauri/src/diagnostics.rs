@@ -0,0 +1,163 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::demo;
+use crate::housekeeping::{self, HousekeepingReport};
+
+#[cfg(unix)]
+const DIR_MODE: u32 = 0o700;
+#[cfg(unix)]
+const FILE_MODE: u32 = 0o600;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionIssue {
+    pub path: String,
+    pub issue: String,
+    /// Whether the startup check repaired it
+    pub fixed: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionReport {
+    /// Per-user directories that were checked
+    pub roots: Vec<String>,
+    pub issues: Vec<PermissionIssue>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostics {
+    pub version: String,
+    pub os: String,
+    pub permissions: PermissionReport,
+    /// Last cleanup of logs and leftover files, None until it first ran
+    pub housekeeping: Option<HousekeepingReport>,
+}
+
+/// Everything VoiceFlow writes: config, history, logs and caches
+pub fn data_roots(app: &AppHandle) -> Vec<PathBuf> {
+    if demo::is_active() {
+        return [demo::config_dir(app), demo::data_dir(app)]
+            .into_iter()
+            .filter_map(Result::ok)
+            .collect();
+    }
+    let path = app.path();
+    [
+        path.app_config_dir(),
+        path.app_data_dir(),
+        path.app_log_dir(),
+        path.app_cache_dir(),
+    ]
+    .into_iter()
+    .filter_map(Result::ok)
+    .fold(Vec::new(), |mut roots, root| {
+        // Config and data share a directory on macOS
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+        roots
+    })
+}
+
+#[cfg(unix)]
+fn audit_path(path: &Path, is_root: bool, issues: &mut Vec<PermissionIssue>) {
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+    // Never follow links out of the data directories
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return;
+    };
+    if metadata.file_type().is_symlink() {
+        if is_root {
+            issues.push(PermissionIssue {
+                path: path.display().to_string(),
+                issue: "Data directory is a symlink".to_string(),
+                fixed: false,
+            });
+        }
+        return;
+    }
+
+    // SAFETY: getuid has no preconditions and cannot fail
+    let uid = unsafe { libc::getuid() };
+    if metadata.uid() != uid {
+        issues.push(PermissionIssue {
+            path: path.display().to_string(),
+            issue: format!("Owned by uid {} instead of {uid}", metadata.uid()),
+            fixed: false,
+        });
+        return;
+    }
+
+    let expected = if metadata.is_dir() {
+        DIR_MODE
+    } else {
+        FILE_MODE
+    };
+    let mode = metadata.permissions().mode() & 0o777;
+    if mode & !expected != 0 {
+        let fixed = fs::set_permissions(path, fs::Permissions::from_mode(mode & expected)).is_ok();
+        issues.push(PermissionIssue {
+            path: path.display().to_string(),
+            issue: format!("Mode {mode:o} allows access by other users"),
+            fixed,
+        });
+    }
+
+    if metadata.is_dir() {
+        if let Ok(entries) = fs::read_dir(path) {
+            for entry in entries.filter_map(|entry| entry.ok()) {
+                audit_path(&entry.path(), false, issues);
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn audit_path(_path: &Path, _is_root: bool, _issues: &mut Vec<PermissionIssue>) {
+    // Per-user AppData directories already carry owner-only ACLs
+}
+
+/// Create missing data directories and tighten anything other users can read
+pub fn check_permissions(app: &AppHandle) -> PermissionReport {
+    let mut report = PermissionReport::default();
+    for root in data_roots(app) {
+        if let Err(e) = fs::create_dir_all(&root) {
+            report.issues.push(PermissionIssue {
+                path: root.display().to_string(),
+                issue: format!("Failed to create directory: {e}"),
+                fixed: false,
+            });
+            continue;
+        }
+        audit_path(&root, true, &mut report.issues);
+        report.roots.push(root.display().to_string());
+    }
+
+    for issue in &report.issues {
+        let action = if issue.fixed { "fixed" } else { "not fixed" };
+        eprintln!(
+            "[voiceflow] Permission check: {} ({}, {action})",
+            issue.path, issue.issue
+        );
+    }
+    report
+}
+
+#[tauri::command]
+pub async fn get_diagnostics(app: AppHandle) -> Result<Diagnostics, String> {
+    let state = app.state::<Mutex<PermissionReport>>();
+    let permissions = state.lock().map_err(|e| e.to_string())?.clone();
+    Ok(Diagnostics {
+        version: app.package_info().version.to_string(),
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        permissions,
+        housekeeping: housekeeping::last_report(),
+    })
+}
@@ -0,0 +1,129 @@
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::auth;
+use crate::history::{self, HistoryEntry};
+use crate::i18n;
+use crate::voice_commands::COMMAND_HISTORY_TAG;
+
+const UNKNOWN_APP: &str = "Other";
+
+fn local_time(entry: &HistoryEntry) -> Option<DateTime<Local>> {
+    Local
+        .timestamp_millis_opt(entry.timestamp_ms as i64)
+        .single()
+}
+
+/// Markdown digest of a day's dictations, grouped by app in order of first use
+fn format_digest(date: NaiveDate, entries: &[HistoryEntry]) -> String {
+    let mut groups: Vec<(&str, Vec<&HistoryEntry>)> = Vec::new();
+    for entry in entries {
+        let app = entry.app.as_deref().unwrap_or(UNKNOWN_APP);
+        match groups.iter_mut().find(|(name, _)| *name == app) {
+            Some((_, group)) => group.push(entry),
+            None => groups.push((app, vec![entry])),
+        }
+    }
+
+    let mut digest = format!("# Dictation digest for {}\n", date.format("%A, %-d %B %Y"));
+    for (app, group) in groups {
+        digest.push_str(&format!("\n## {app}\n\n"));
+        for entry in group {
+            let time = local_time(entry)
+                .map(|time| time.format("%H:%M").to_string())
+                .unwrap_or_default();
+            digest.push_str(&format!("- {time} {}\n", entry.text.trim()));
+        }
+    }
+    digest
+}
+
+fn day_bounds(date: NaiveDate) -> Result<(u64, u64), String> {
+    let start_of = |date: NaiveDate| {
+        date.and_hms_opt(0, 0, 0)
+            .and_then(|midnight| Local.from_local_datetime(&midnight).earliest())
+            .map(|start| start.timestamp_millis().max(0) as u64)
+    };
+    let next = date.succ_opt().ok_or_else(|| i18n::t("error.date_range"))?;
+    match (start_of(date), start_of(next)) {
+        (Some(from), Some(to)) => Ok((from, to)),
+        _ => Err(i18n::t("error.date_range")),
+    }
+}
+
+/// Put a digest of the given day's dictations (YYYY-MM-DD, today by default) on the clipboard
+#[tauri::command]
+pub async fn copy_daily_digest(app: AppHandle, date: Option<String>) -> Result<String, String> {
+    auth::unlock(&app, "auth.export").await?;
+    let date = match date {
+        Some(date) => NaiveDate::parse_from_str(&date, "%Y-%m-%d").map_err(|e| {
+            i18n::tf(
+                "error.invalid_date",
+                &[("date", &date), ("error", &e.to_string())],
+            )
+        })?,
+        None => Local::now().date_naive(),
+    };
+    let (from_ms, to_ms) = day_bounds(date)?;
+    let entries: Vec<HistoryEntry> = history::entries_between(&app, from_ms, to_ms)
+        .into_iter()
+        .filter(|entry| entry.tag.as_deref() != Some(COMMAND_HISTORY_TAG))
+        .collect();
+
+    let digest = format_digest(date, &entries);
+    app.clipboard()
+        .write_text(digest.clone())
+        .map_err(|e| i18n::tf("error.copy_digest", &[("error", &e.to_string())]))?;
+    Ok(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(text: &str, app: Option<&str>) -> HistoryEntry {
+        let timestamp = Local.with_ymd_and_hms(2026, 3, 2, 9, 5, 0).unwrap();
+        HistoryEntry {
+            id: 1,
+            timestamp_ms: timestamp.timestamp_millis() as u64,
+            text: text.to_string(),
+            tag: None,
+            segments: Vec::new(),
+            words: Vec::new(),
+            confidence: None,
+            alternatives: Vec::new(),
+            language: None,
+            insertion: None,
+            app: app.map(str::to_string),
+            capture: None,
+            origin: None,
+            project: None,
+            title: None,
+            url: None,
+            suggestions: Vec::new(),
+            original_text: None,
+            edits: Vec::new(),
+            latency_ms: None,
+            duration_ms: None,
+            model: None,
+            metadata: Default::default(),
+        }
+    }
+
+    #[test]
+    fn groups_entries_by_app_in_order_of_first_use() {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let entries = [
+            entry("Standup notes", Some("Notes")),
+            entry("On my way", None),
+            entry("Ship it", Some("Notes")),
+        ];
+        assert_eq!(
+            format_digest(date, &entries),
+            "# Dictation digest for Monday, 2 March 2026\n\n\
+             ## Notes\n\n- 09:05 Standup notes\n- 09:05 Ship it\n\n\
+             ## Other\n\n- 09:05 On my way\n"
+        );
+    }
+}
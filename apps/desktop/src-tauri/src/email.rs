@@ -0,0 +1,310 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+use crate::active_app::{self, ActiveApp};
+use crate::settings;
+
+const GREETINGS: &[&str] = &[
+    "good afternoon",
+    "good evening",
+    "good morning",
+    "greetings",
+    "hello",
+    "dear",
+    "hey",
+    "hi",
+];
+
+const SIGN_OFFS: &[&str] = &[
+    "all the best",
+    "best regards",
+    "best wishes",
+    "kind regards",
+    "warm regards",
+    "many thanks",
+    "thank you",
+    "sincerely",
+    "regards",
+    "thanks",
+    "cheers",
+    "best",
+];
+
+/// Spoken names after a sign-off are at most this long; anything longer is body text
+const MAX_NAME_WORDS: usize = 4;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EmailSettings {
+    pub enabled: bool,
+    /// Appended below the sign-off, replacing a spoken name
+    pub signature: String,
+    /// App names, bundle identifiers or window title fragments (for webmail in a browser)
+    pub apps: Vec<String>,
+    /// Post-processing template used while an email app is frontmost, empty to keep the usual one
+    pub template: String,
+}
+
+impl Default for EmailSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            signature: String::new(),
+            apps: ["Mail", "Microsoft Outlook", "Gmail"]
+                .map(str::to_string)
+                .to_vec(),
+            template: "formalize email".to_string(),
+        }
+    }
+}
+
+impl EmailSettings {
+    fn matches(&self, app: &ActiveApp) -> bool {
+        let title = app.window_title.to_lowercase();
+        self.enabled
+            && self.apps.iter().any(|target| {
+                let target = target.trim();
+                !target.is_empty()
+                    && (target.eq_ignore_ascii_case(&app.name)
+                        || target.eq_ignore_ascii_case(&app.bundle_id)
+                        || title.contains(&target.to_lowercase()))
+            })
+    }
+}
+
+/// A dictated email split into its parts
+#[derive(Debug, Clone, PartialEq)]
+struct Email {
+    salutation: Option<String>,
+    paragraphs: Vec<String>,
+    sign_off: Option<String>,
+    /// Name spoken after the sign-off
+    name: Option<String>,
+}
+
+// Plain text of the last formatted email and its HTML, picked up when it is pasted
+static RICH_TEXT: Mutex<Option<(String, String)>> = Mutex::new(None);
+
+fn starts_with_phrase(text: &str, phrase: &str) -> bool {
+    text.get(..phrase.len())
+        .is_some_and(|head| head.eq_ignore_ascii_case(phrase))
+        && text[phrase.len()..]
+            .chars()
+            .next()
+            .is_none_or(|c| !c.is_alphanumeric())
+}
+
+/// "Hi Anna," at the very start, up to the first comma, colon, exclamation mark or line break
+fn split_salutation(text: &str) -> (Option<String>, &str) {
+    if !GREETINGS
+        .iter()
+        .any(|greeting| starts_with_phrase(text, greeting))
+    {
+        return (None, text);
+    }
+    let Some(end) = text.find([',', ':', '!', '\n', '.']) else {
+        return (None, text);
+    };
+    let salutation = text[..end].trim();
+    if salutation.split_whitespace().count() > MAX_NAME_WORDS + 2 {
+        return (None, text);
+    }
+    (Some(salutation.to_string()), &text[end + 1..])
+}
+
+/// The last sign-off starting a sentence, followed by nothing but an optional name
+fn split_sign_off(text: &str) -> (&str, Option<String>, Option<String>) {
+    let sentence_starts = std::iter::once(0).chain(
+        text.char_indices()
+            .filter(|(_, c)| matches!(c, '.' | '!' | '?' | '\n' | ','))
+            .map(|(index, c)| index + c.len_utf8()),
+    );
+    for start in sentence_starts.collect::<Vec<_>>().into_iter().rev() {
+        let rest = &text[start..];
+        let offset = rest.len() - rest.trim_start().len();
+        let rest = rest.trim_start();
+        let Some(phrase) = SIGN_OFFS
+            .iter()
+            .find(|phrase| starts_with_phrase(rest, phrase))
+        else {
+            continue;
+        };
+        let name = rest[phrase.len()..]
+            .trim_start_matches([',', '.', '!'])
+            .trim()
+            .trim_end_matches('.')
+            .trim();
+        if name.split_whitespace().count() > MAX_NAME_WORDS || name.contains(['.', '!', '?', '\n'])
+        {
+            continue;
+        }
+        let sign_off = rest[..phrase.len()].to_string();
+        let name = (!name.is_empty()).then(|| name.to_string());
+        return (&text[..start + offset], Some(sign_off), name);
+    }
+    (text, None, None)
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn parse(text: &str) -> Email {
+    let (salutation, rest) = split_salutation(text.trim());
+    let (body, sign_off, name) = split_sign_off(rest);
+    let paragraphs = body
+        .split("\n\n")
+        .map(|paragraph| {
+            let paragraph = paragraph.trim().trim_start_matches([',', '.', '!', ':']);
+            capitalize(paragraph.trim())
+        })
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect();
+    Email {
+        salutation: salutation.map(|salutation| capitalize(&salutation)),
+        paragraphs,
+        sign_off: sign_off.map(|sign_off| capitalize(&sign_off)),
+        name,
+    }
+}
+
+impl Email {
+    /// Blocks of lines, separated by blank lines in plain text and paragraphs in HTML
+    fn blocks(&self, signature: &str) -> Vec<Vec<String>> {
+        let mut blocks = Vec::new();
+        if let Some(salutation) = &self.salutation {
+            blocks.push(vec![format!("{salutation},")]);
+        }
+        for paragraph in &self.paragraphs {
+            blocks.push(paragraph.lines().map(str::to_string).collect());
+        }
+        let signature = signature.trim();
+        let mut closing = Vec::new();
+        if let Some(sign_off) = &self.sign_off {
+            closing.push(format!("{sign_off},"));
+        }
+        if !signature.is_empty() {
+            closing.extend(signature.lines().map(|line| line.trim_end().to_string()));
+        } else if let Some(name) = &self.name {
+            closing.push(name.clone());
+        }
+        if !closing.is_empty() {
+            blocks.push(closing);
+        }
+        blocks
+    }
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Plain text and HTML versions of the dictated email with the signature applied
+fn render(text: &str, signature: &str) -> (String, String) {
+    let blocks = parse(text).blocks(signature);
+    let plain = blocks
+        .iter()
+        .map(|lines| lines.join("\n"))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let html = blocks
+        .iter()
+        .map(|lines| {
+            let lines: Vec<String> = lines.iter().map(|line| escape_html(line)).collect();
+            format!("<p>{}</p>", lines.join("<br>"))
+        })
+        .collect::<String>();
+    (plain, html)
+}
+
+/// Whether dictations go into an email right now
+fn is_target(app: &AppHandle) -> bool {
+    let email = settings::current(app).email;
+    email.enabled && active_app::frontmost_app().is_some_and(|frontmost| email.matches(&frontmost))
+}
+
+/// Post-processing template for an email being dictated, None outside email apps
+pub fn template(app: &AppHandle) -> Option<String> {
+    let template = settings::current(app).email.template;
+    (!template.trim().is_empty() && is_target(app)).then_some(template)
+}
+
+/// Lay out a dictation as an email when an email app is frontmost, keeping the HTML
+/// so pasting the returned text inserts rich text
+pub fn format(app: &AppHandle, text: &str) -> Option<String> {
+    if !is_target(app) {
+        return None;
+    }
+    let (plain, html) = render(text, &settings::current(app).email.signature);
+    if let Ok(mut rich) = RICH_TEXT.lock() {
+        *rich = Some((plain.clone(), html));
+    }
+    Some(plain)
+}
+
+/// HTML for text that was formatted as an email, so the paste carries its formatting
+pub fn html_for(text: &str) -> Option<String> {
+    let rich = RICH_TEXT.lock().ok()?;
+    rich.as_ref()
+        .filter(|(plain, _)| plain == text)
+        .map(|(_, html)| html.clone())
+}
+
+#[tauri::command]
+pub async fn get_email_settings(app: AppHandle) -> EmailSettings {
+    settings::current(&app).email
+}
+
+#[tauri::command]
+pub async fn set_email_settings(app: AppHandle, email: EmailSettings) -> Result<(), String> {
+    settings::update(&app, |settings| settings.email = email)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_salutation_body_and_sign_off() {
+        let email = parse("hi Anna, the report is attached. let me know. best regards Marco");
+        assert_eq!(
+            email,
+            Email {
+                salutation: Some("Hi Anna".to_string()),
+                paragraphs: vec!["The report is attached. let me know.".to_string()],
+                sign_off: Some("Best regards".to_string()),
+                name: Some("Marco".to_string()),
+            }
+        );
+
+        let plain = parse("Thanks for the quick reply, the best option is Tuesday.");
+        assert_eq!(plain.salutation, None);
+        assert_eq!(plain.sign_off, None);
+    }
+
+    #[test]
+    fn renders_signature_as_plain_text_and_html() {
+        let (plain, html) = render(
+            "Dear Sam, prices go up <5%> next month. Thanks, Marco.",
+            "Marco Rossi\nAcme & Co",
+        );
+        assert_eq!(
+            plain,
+            "Dear Sam,\n\nPrices go up <5%> next month.\n\nThanks,\nMarco Rossi\nAcme & Co"
+        );
+        assert_eq!(
+            html,
+            "<p>Dear Sam,</p><p>Prices go up &lt;5%&gt; next month.</p>\
+             <p>Thanks,<br>Marco Rossi<br>Acme &amp; Co</p>"
+        );
+    }
+}
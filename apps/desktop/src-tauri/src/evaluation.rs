@@ -0,0 +1,168 @@
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter};
+
+use crate::transcription::TranscriptionOptions;
+use crate::{audio_file, chunking, ensure_sidecar_running, pipeline};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileEvaluation {
+    pub file: PathBuf,
+    pub text: String,
+    pub reference_words: usize,
+    pub errors: usize,
+    pub wer: Option<f32>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvaluationReport {
+    pub files: Vec<FileEvaluation>,
+    /// Errors over all reference words, so longer files weigh more
+    pub wer: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EvaluationProgress {
+    done: usize,
+    total: usize,
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric() || *c == '\'')
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Word-level edit distance and the number of reference words it is measured against
+pub fn word_errors(reference: &str, hypothesis: &str) -> (usize, usize) {
+    let reference = words(reference);
+    let hypothesis = words(hypothesis);
+
+    let mut previous: Vec<usize> = (0..=hypothesis.len()).collect();
+    for (i, expected) in reference.iter().enumerate() {
+        let mut current = vec![i + 1; hypothesis.len() + 1];
+        for (j, actual) in hypothesis.iter().enumerate() {
+            let substitution = previous[j] + usize::from(expected != actual);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    (previous[hypothesis.len()], reference.len())
+}
+
+fn rate(errors: usize, reference_words: usize) -> Option<f32> {
+    (reference_words > 0).then(|| errors as f32 / reference_words as f32)
+}
+
+pub fn word_error_rate(reference: &str, hypothesis: &str) -> Option<f32> {
+    let (errors, reference_words) = word_errors(reference, hypothesis);
+    rate(errors, reference_words)
+}
+
+/// WAV files in the directory that have a reference transcript next to them as .txt
+fn pairs(dir: &PathBuf) -> Result<Vec<(PathBuf, String)>, String> {
+    let mut pairs: Vec<(PathBuf, String)> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read evaluation folder: {e}"))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("wav"))
+        })
+        .filter_map(|path| {
+            let reference = fs::read_to_string(path.with_extension("txt")).ok()?;
+            Some((path, reference))
+        })
+        .collect();
+    pairs.sort();
+    Ok(pairs)
+}
+
+/// Transcribe every audio/reference pair in `dir` through the full text pipeline
+/// and report word error rate per file and overall
+#[tauri::command]
+pub async fn run_evaluation(app: AppHandle, dir: PathBuf) -> Result<EvaluationReport, String> {
+    let pairs = pairs(&dir)?;
+    if pairs.is_empty() {
+        return Err("No .wav files with a matching .txt reference in this folder".to_string());
+    }
+    ensure_sidecar_running(&app)?;
+
+    let total = pairs.len();
+    let mut files = Vec::with_capacity(total);
+    for (done, (file, reference)) in pairs.into_iter().enumerate() {
+        let path = file.clone();
+        let transcribed = tauri::async_runtime::spawn_blocking(move || {
+            let samples = audio_file::read_pcm16_mono(&path)?;
+            chunking::transcribe_long(&samples, &TranscriptionOptions::default())
+        })
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|result| result);
+
+        let evaluation = match transcribed {
+            Ok(transcript) => {
+                let text = pipeline::transform(&app, transcript).await.text;
+                let (errors, reference_words) = word_errors(&reference, &text);
+                FileEvaluation {
+                    file,
+                    text,
+                    reference_words,
+                    errors,
+                    wer: rate(errors, reference_words),
+                    error: None,
+                }
+            }
+            Err(error) => FileEvaluation {
+                file,
+                text: String::new(),
+                reference_words: words(&reference).len(),
+                errors: 0,
+                wer: None,
+                error: Some(error),
+            },
+        };
+        files.push(evaluation);
+        let _ = app.emit(
+            "evaluation-progress",
+            EvaluationProgress {
+                done: done + 1,
+                total,
+            },
+        );
+    }
+
+    let scored = files.iter().filter(|file| file.error.is_none());
+    let (errors, reference_words) = scored.fold((0, 0), |(errors, words), file| {
+        (errors + file.errors, words + file.reference_words)
+    });
+    Ok(EvaluationReport {
+        wer: rate(errors, reference_words),
+        files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn word_error_rate_counts_edits_per_reference_word() {
+        assert_eq!(word_error_rate("Hello, world!", "hello world"), Some(0.0));
+        assert_eq!(
+            word_error_rate("the quick brown fox", "the quack brown"),
+            Some(0.5)
+        );
+        assert_eq!(word_error_rate("", "anything"), None);
+    }
+}
@@ -0,0 +1,273 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::session::now_ms;
+use crate::{i18n, settings};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Remote values are fetched again once the cached ones are this old
+const CACHE_TTL_MS: u64 = 6 * 60 * 60 * 1000;
+const MAX_REMOTE_BYTES: u64 = 64 * 1024;
+
+/// Experimental behavior that is off until rolled out remotely or overridden locally
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flag {
+    /// Insert through the Accessibility API by default, pasting only as a fallback
+    NewPasteStrategy,
+    /// Send native capture audio to the webview while recording
+    StreamingMode,
+}
+
+impl Flag {
+    pub const ALL: [Flag; 2] = [Flag::NewPasteStrategy, Flag::StreamingMode];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Flag::NewPasteStrategy => "newPasteStrategy",
+            Flag::StreamingMode => "streamingMode",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Flag::NewPasteStrategy => {
+                "Insert through the Accessibility API, falling back to the clipboard"
+            }
+            Flag::StreamingMode => "Stream captured audio to the webview while recording",
+        }
+    }
+
+    fn default_value(self) -> bool {
+        false
+    }
+
+    fn from_name(name: &str) -> Option<Flag> {
+        Flag::ALL.into_iter().find(|flag| flag.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureFlag {
+    pub name: String,
+    pub description: String,
+    /// What the app currently does
+    pub enabled: bool,
+    pub default_value: bool,
+    /// Value from the remote flag file, None when it doesn't set this flag
+    pub remote: Option<bool>,
+    /// Value set with set_flag_override, which wins over everything else
+    pub local_override: Option<bool>,
+}
+
+/// Last successful fetch of the remote flag file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct RemoteCache {
+    url: String,
+    fetched_ms: u64,
+    flags: HashMap<String, bool>,
+}
+
+pub struct FeatureFlagManager {
+    overrides: HashMap<String, bool>,
+    remote: RemoteCache,
+    overrides_path: PathBuf,
+    cache_path: PathBuf,
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Option<T> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    fs::write(path, content).map_err(|e| e.to_string())
+}
+
+impl FeatureFlagManager {
+    pub fn new(config_dir: PathBuf) -> Self {
+        let overrides_path = config_dir.join("feature_flags.json");
+        let cache_path = config_dir.join("feature_flags_remote.json");
+        Self {
+            overrides: read_json(&overrides_path).unwrap_or_default(),
+            remote: read_json(&cache_path).unwrap_or_default(),
+            overrides_path,
+            cache_path,
+        }
+    }
+
+    /// Local override first, then the remote value, then the built-in default
+    pub fn is_enabled(&self, flag: Flag) -> bool {
+        self.overrides
+            .get(flag.name())
+            .or_else(|| self.remote.flags.get(flag.name()))
+            .copied()
+            .unwrap_or(flag.default_value())
+    }
+
+    pub fn list(&self) -> Vec<FeatureFlag> {
+        Flag::ALL
+            .into_iter()
+            .map(|flag| FeatureFlag {
+                name: flag.name().to_string(),
+                description: flag.description().to_string(),
+                enabled: self.is_enabled(flag),
+                default_value: flag.default_value(),
+                remote: self.remote.flags.get(flag.name()).copied(),
+                local_override: self.overrides.get(flag.name()).copied(),
+            })
+            .collect()
+    }
+
+    /// Force a flag on or off, or go back to the remote or default value with None
+    pub fn set_override(&mut self, flag: Flag, enabled: Option<bool>) -> Result<(), String> {
+        match enabled {
+            Some(enabled) => self.overrides.insert(flag.name().to_string(), enabled),
+            None => self.overrides.remove(flag.name()),
+        };
+        write_json(&self.overrides_path, &self.overrides)
+    }
+
+    fn needs_fetch(&self, url: &str, now: u64) -> bool {
+        self.remote.url != url || now.saturating_sub(self.remote.fetched_ms) >= CACHE_TTL_MS
+    }
+
+    fn store_remote(&mut self, url: &str, flags: HashMap<String, bool>) -> Result<(), String> {
+        self.remote = RemoteCache {
+            url: url.to_string(),
+            fetched_ms: now_ms(),
+            flags,
+        };
+        write_json(&self.cache_path, &self.remote)
+    }
+}
+
+/// Known flags set to a boolean in a remote flag file; anything else is ignored
+fn parse_remote(content: &[u8]) -> Result<HashMap<String, bool>, String> {
+    let values: HashMap<String, serde_json::Value> =
+        serde_json::from_slice(content).map_err(|e| format!("Invalid feature flag file: {e}"))?;
+    Ok(values
+        .into_iter()
+        .filter(|(name, _)| Flag::from_name(name).is_some())
+        .filter_map(|(name, value)| value.as_bool().map(|enabled| (name, enabled)))
+        .collect())
+}
+
+fn fetch(url: &str) -> Result<HashMap<String, bool>, String> {
+    let content = ureq::get(url)
+        .call()
+        .map_err(|e| format!("Failed to fetch feature flags: {e}"))?
+        .body_mut()
+        .with_config()
+        .limit(MAX_REMOTE_BYTES)
+        .read_to_vec()
+        .map_err(|e| format!("Failed to fetch feature flags: {e}"))?;
+    parse_remote(&content)
+}
+
+pub fn is_enabled(app: &AppHandle, flag: Flag) -> bool {
+    let state = app.state::<Mutex<FeatureFlagManager>>();
+    let enabled = state.lock().map(|manager| manager.is_enabled(flag));
+    enabled.unwrap_or(flag.default_value())
+}
+
+/// Fetch the remote flag file when its cached values have expired. Without a
+/// configured URL remote values are dropped; after a failed fetch the cached
+/// ones stay in use.
+pub fn refresh(app: &AppHandle) -> Result<(), String> {
+    let url = settings::current(app).feature_flags_url.unwrap_or_default();
+    let state = app.state::<Mutex<FeatureFlagManager>>();
+    {
+        let mut manager = state.lock().map_err(|e| e.to_string())?;
+        if url.is_empty() {
+            if manager.remote.url.is_empty() {
+                return Ok(());
+            }
+            return manager.store_remote("", HashMap::new());
+        }
+        if !manager.needs_fetch(&url, now_ms()) {
+            return Ok(());
+        }
+    }
+    let flags = fetch(&url)?;
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.store_remote(&url, flags)
+}
+
+pub fn start_refresh(app: AppHandle) {
+    thread::spawn(move || loop {
+        if let Err(e) = refresh(&app) {
+            eprintln!("[voiceflow] {e}");
+        }
+        thread::sleep(CHECK_INTERVAL);
+    });
+}
+
+#[tauri::command]
+pub async fn list_feature_flags(app: AppHandle) -> Result<Vec<FeatureFlag>, String> {
+    let state = app.state::<Mutex<FeatureFlagManager>>();
+    let manager = state.lock().map_err(|e| e.to_string())?;
+    Ok(manager.list())
+}
+
+/// Developer override for a flag; None clears it
+#[tauri::command]
+pub async fn set_flag_override(
+    app: AppHandle,
+    name: String,
+    enabled: Option<bool>,
+) -> Result<Vec<FeatureFlag>, String> {
+    let flag = Flag::from_name(&name)
+        .ok_or_else(|| i18n::tf("error.unknown_feature_flag", &[("name", &name)]))?;
+    let state = app.state::<Mutex<FeatureFlagManager>>();
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.set_override(flag, enabled)?;
+    Ok(manager.list())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn override_wins_over_remote_and_default() {
+        let dir = std::env::temp_dir().join(format!("voiceflow-flags-test-{}", now_ms()));
+        let mut manager = FeatureFlagManager::new(dir.clone());
+        assert!(!manager.is_enabled(Flag::StreamingMode));
+
+        let remote =
+            parse_remote(br#"{"streamingMode": true, "newPasteStrategy": "yes", "unknown": true}"#)
+                .unwrap();
+        assert_eq!(remote.len(), 1);
+        manager
+            .store_remote("https://example.com/flags.json", remote)
+            .unwrap();
+        assert!(manager.is_enabled(Flag::StreamingMode));
+        assert!(!manager.needs_fetch("https://example.com/flags.json", now_ms()));
+        assert!(manager.needs_fetch("https://example.com/other.json", now_ms()));
+
+        manager
+            .set_override(Flag::StreamingMode, Some(false))
+            .unwrap();
+        assert!(!manager.is_enabled(Flag::StreamingMode));
+
+        let reloaded = FeatureFlagManager::new(dir.clone());
+        let streaming = &reloaded.list()[1];
+        assert_eq!(streaming.remote, Some(true));
+        assert_eq!(streaming.local_override, Some(false));
+        assert!(!streaming.enabled);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
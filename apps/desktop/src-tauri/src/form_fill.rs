@@ -0,0 +1,252 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+use crate::{keyboard, paste_text, show_main_window};
+
+/// A piece of a template with numbered placeholders, in insertion order
+#[derive(Debug, Clone, PartialEq)]
+pub enum Part {
+    Text(String),
+    /// Move to the next field of the target app
+    Tab,
+    /// `{1}` or `{1:Label}`, dictated by the user when reached
+    Field {
+        number: u32,
+        label: Option<String>,
+    },
+}
+
+/// Sent when the next field is waiting to be dictated
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldPrompt {
+    pub number: u32,
+    pub label: Option<String>,
+    /// 1-based position among the template's distinct fields
+    pub index: usize,
+    pub total: usize,
+}
+
+struct FillSession {
+    parts: Vec<Part>,
+    position: usize,
+    values: HashMap<u32, String>,
+}
+
+static SESSION: Mutex<Option<FillSession>> = Mutex::new(None);
+
+fn push_text(parts: &mut Vec<Part>, literal: &str) {
+    for (index, piece) in literal.split('\t').enumerate() {
+        if index > 0 {
+            parts.push(Part::Tab);
+        }
+        if !piece.is_empty() {
+            parts.push(Part::Text(piece.to_string()));
+        }
+    }
+}
+
+/// Split a template into text, tabs and numbered fields. Tabs in the text move between
+/// fields, and so does nothing at all: `{1}{2}` fills two consecutive form fields.
+pub fn parse(body: &str) -> Vec<Part> {
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut rest = body;
+    while let Some(start) = rest.find('{') {
+        literal.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let field = after.find('}').and_then(|end| {
+            let inner = &after[..end];
+            let (number, label) = match inner.split_once(':') {
+                Some((number, label)) => (number, Some(label.trim().to_string())),
+                None => (inner, None),
+            };
+            let number = number
+                .trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|number| *number > 0)?;
+            let label = label.filter(|label| !label.is_empty());
+            Some((Part::Field { number, label }, end))
+        });
+        match field {
+            Some((field, end)) => {
+                if literal.is_empty() && matches!(parts.last(), Some(Part::Field { .. })) {
+                    parts.push(Part::Tab);
+                }
+                push_text(&mut parts, &literal);
+                literal.clear();
+                parts.push(field);
+                rest = &after[end + 1..];
+            }
+            None => {
+                literal.push('{');
+                rest = after;
+            }
+        }
+    }
+    literal.push_str(rest);
+    push_text(&mut parts, &literal);
+    parts
+}
+
+pub fn has_fields(body: &str) -> bool {
+    parse(body)
+        .iter()
+        .any(|part| matches!(part, Part::Field { .. }))
+}
+
+pub fn is_active() -> bool {
+    SESSION
+        .lock()
+        .map(|session| session.is_some())
+        .unwrap_or(false)
+}
+
+/// Distinct field numbers in the order they are reached
+fn field_order(parts: &[Part]) -> Vec<u32> {
+    let mut order = Vec::new();
+    for part in parts {
+        if let Part::Field { number, .. } = part {
+            if !order.contains(number) {
+                order.push(*number);
+            }
+        }
+    }
+    order
+}
+
+/// Insert parts up to the next field that still needs dictating, then prompt for it
+fn advance(app: &AppHandle) -> Result<(), String> {
+    loop {
+        let mut guard = SESSION.lock().map_err(|e| e.to_string())?;
+        let Some(session) = guard.as_mut() else {
+            return Ok(());
+        };
+        let Some(part) = session.parts.get(session.position).cloned() else {
+            *guard = None;
+            drop(guard);
+            let _ = app.emit("template-fill-finished", ());
+            return Ok(());
+        };
+
+        let result = match &part {
+            Part::Text(text) => paste_text(app, Some(text)),
+            Part::Tab => keyboard::press_chord("tab"),
+            Part::Field { number, label } => match session.values.get(number) {
+                Some(value) if value.is_empty() => Ok(()),
+                // A field used twice is filled with the same value
+                Some(value) => paste_text(app, Some(value)),
+                None => {
+                    let order = field_order(&session.parts);
+                    let prompt = FieldPrompt {
+                        number: *number,
+                        label: label.clone(),
+                        index: order.iter().position(|n| n == number).unwrap_or(0) + 1,
+                        total: order.len(),
+                    };
+                    drop(guard);
+                    let _ = app.emit("template-field-requested", &prompt);
+                    show_main_window(app);
+                    return Ok(());
+                }
+            },
+        };
+        if let Err(error) = result {
+            *guard = None;
+            drop(guard);
+            let _ = app.emit("template-fill-finished", ());
+            return Err(error);
+        }
+        session.position += 1;
+    }
+}
+
+/// Start inserting a template, prompting for each numbered field in turn
+pub fn start(app: &AppHandle, body: &str) -> Result<(), String> {
+    {
+        let mut session = SESSION.lock().map_err(|e| e.to_string())?;
+        *session = Some(FillSession {
+            parts: parse(body),
+            position: 0,
+            values: HashMap::new(),
+        });
+    }
+    advance(app)
+}
+
+/// Insert a dictation into the field being prompted for and move on
+pub fn field_dictated(app: &AppHandle, text: String) -> Result<(), String> {
+    {
+        let mut guard = SESSION.lock().map_err(|e| e.to_string())?;
+        let Some(session) = guard.as_mut() else {
+            return Ok(());
+        };
+        if let Some(Part::Field { number, .. }) = session.parts.get(session.position) {
+            session.values.insert(*number, text.trim().to_string());
+        }
+    }
+    advance(app)
+}
+
+/// Leave the current field as it is and move on to the next part
+#[tauri::command]
+pub async fn skip_template_field(app: AppHandle) -> Result<(), String> {
+    {
+        let mut guard = SESSION.lock().map_err(|e| e.to_string())?;
+        let Some(session) = guard.as_mut() else {
+            return Ok(());
+        };
+        if let Some(Part::Field { number, .. }) = session.parts.get(session.position) {
+            session.values.insert(*number, String::new());
+        }
+    }
+    advance(&app)
+}
+
+#[tauri::command]
+pub async fn cancel_template_fill(app: AppHandle) -> Result<(), String> {
+    let cancelled = SESSION.lock().map_err(|e| e.to_string())?.take().is_some();
+    if cancelled {
+        let _ = app.emit("template-fill-finished", ());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(number: u32, label: Option<&str>) -> Part {
+        Part::Field {
+            number,
+            label: label.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn parses_fields_text_and_tabs() {
+        assert_eq!(
+            parse("{1:First name}{2}\t{3:Email} {x} {0}"),
+            vec![
+                field(1, Some("First name")),
+                Part::Tab,
+                field(2, None),
+                Part::Tab,
+                field(3, Some("Email")),
+                Part::Text(" {x} {0}".to_string()),
+            ]
+        );
+        assert_eq!(
+            parse("Hi {1:Name},\nthanks"),
+            vec![
+                Part::Text("Hi ".to_string()),
+                field(1, Some("Name")),
+                Part::Text(",\nthanks".to_string()),
+            ]
+        );
+        assert!(!has_fields("Dear {name}"));
+    }
+}
@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+use crate::{append_e2e_log, history_picker, i18n, settings, show_or_create_main_app};
+
+pub const TAP_MS_RANGE: std::ops::RangeInclusive<u64> = 100..=1000;
+pub const DOUBLE_PRESS_MS_RANGE: std::ops::RangeInclusive<u64> = 150..=1500;
+
+static PRESSES: Mutex<Presses> = Mutex::new(Presses {
+    pressed_at: None,
+    last_tap: None,
+});
+
+/// What a quick double-press of the record shortcut does
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DoublePressAction {
+    /// Every press records, as without gestures
+    #[default]
+    None,
+    OpenMainApp,
+    HistoryPicker,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ShortcutGestureSettings {
+    pub double_press: DoublePressAction,
+    /// Presses released within this are taps, which don't produce a dictation
+    pub tap_ms: u64,
+    /// Longest gap between a tap and the next press for them to count as a double-press
+    pub double_press_ms: u64,
+}
+
+impl Default for ShortcutGestureSettings {
+    fn default() -> Self {
+        Self {
+            double_press: DoublePressAction::None,
+            tap_ms: 250,
+            double_press_ms: 400,
+        }
+    }
+}
+
+impl ShortcutGestureSettings {
+    pub fn is_valid(&self) -> bool {
+        TAP_MS_RANGE.contains(&self.tap_ms) && DOUBLE_PRESS_MS_RANGE.contains(&self.double_press_ms)
+    }
+}
+
+/// Press timings of the record shortcut
+#[derive(Debug, Default)]
+struct Presses {
+    pressed_at: Option<Instant>,
+    /// When the last tap was released
+    last_tap: Option<Instant>,
+}
+
+impl Presses {
+    /// Returns true when this press completes a double-press
+    fn press(&mut self, now: Instant, gestures: &ShortcutGestureSettings) -> bool {
+        let window = Duration::from_millis(gestures.double_press_ms);
+        if self
+            .last_tap
+            .take()
+            .is_some_and(|tap| now.duration_since(tap) <= window)
+        {
+            self.pressed_at = None;
+            return true;
+        }
+        self.pressed_at = Some(now);
+        false
+    }
+
+    /// Returns true when the press being released was a tap
+    fn release(&mut self, now: Instant, gestures: &ShortcutGestureSettings) -> bool {
+        let tap = Duration::from_millis(gestures.tap_ms);
+        let is_tap = self
+            .pressed_at
+            .take()
+            .is_some_and(|pressed| now.duration_since(pressed) <= tap);
+        self.last_tap = is_tap.then_some(now);
+        is_tap
+    }
+}
+
+fn gestures(app: &AppHandle) -> Option<ShortcutGestureSettings> {
+    let gestures = settings::current(app).shortcut_gestures;
+    (gestures.double_press != DoublePressAction::None).then_some(gestures)
+}
+
+/// Record shortcut pressed. Returns true when it was a double-press that has
+/// been handled, in which case no dictation should start.
+pub fn pressed(app: &AppHandle) -> bool {
+    let Some(gestures) = gestures(app) else {
+        return false;
+    };
+    let double_press = PRESSES
+        .lock()
+        .map(|mut presses| presses.press(Instant::now(), &gestures))
+        .unwrap_or(false);
+    if double_press {
+        append_e2e_log("shortcut-double-pressed");
+        match gestures.double_press {
+            DoublePressAction::None => {}
+            DoublePressAction::OpenMainApp => show_or_create_main_app(app),
+            DoublePressAction::HistoryPicker => history_picker::toggle(app),
+        }
+    }
+    double_press
+}
+
+/// Record shortcut released. Returns true for a tap, whose recording should be
+/// dropped rather than transcribed.
+pub fn released(app: &AppHandle) -> bool {
+    let Some(gestures) = gestures(app) else {
+        return false;
+    };
+    PRESSES
+        .lock()
+        .map(|mut presses| presses.release(Instant::now(), &gestures))
+        .unwrap_or(false)
+}
+
+#[tauri::command]
+pub async fn set_shortcut_gestures(
+    app: AppHandle,
+    gestures: ShortcutGestureSettings,
+) -> Result<(), String> {
+    if !gestures.is_valid() {
+        return Err(i18n::t("error.gesture_timing"));
+    }
+    settings::update(&app, |settings| settings.shortcut_gestures = gestures)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_tap_then_quick_press() {
+        let gestures = ShortcutGestureSettings::default();
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut presses = Presses::default();
+
+        // A hold is a dictation, not a tap
+        assert!(!presses.press(at(0), &gestures));
+        assert!(!presses.release(at(1000), &gestures));
+        assert!(!presses.press(at(1100), &gestures));
+
+        // Tap, then press again within the window
+        assert!(presses.release(at(1200), &gestures));
+        assert!(presses.press(at(1500), &gestures));
+
+        // Too slow for a double-press
+        assert!(!presses.press(at(3000), &gestures));
+        assert!(presses.release(at(3100), &gestures));
+        assert!(!presses.press(at(4000), &gestures));
+    }
+}
@@ -0,0 +1,258 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::history::{self, HistoryEntry, HistoryStore};
+use crate::{postprocess, settings};
+
+const CHECK_PROMPT: &str = "You proofread dictated text in the language with code {language}. \
+    Find grammar, spelling and style problems. Reply with a JSON array only, one object per \
+    problem with \"original\" (the exact text to change, copied from the input), \"replacement\" \
+    and a short \"message\". Reply [] when the text is fine.";
+
+/// A proposed fix for part of a transcript
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Suggestion {
+    /// Character offsets of the text to replace
+    pub start: usize,
+    pub end: usize,
+    pub original: String,
+    pub replacement: String,
+    pub message: String,
+}
+
+/// A problem as reported by the model, before it is located in the text
+#[derive(Debug, Deserialize)]
+struct Finding {
+    original: String,
+    replacement: String,
+    #[serde(default)]
+    message: String,
+}
+
+/// The JSON array in a reply, ignoring code fences or prose around it
+fn parse_findings(reply: &str) -> Result<Vec<Finding>, String> {
+    let start = reply
+        .find('[')
+        .ok_or("The grammar check reply has no JSON array")?;
+    let end = reply.rfind(']').filter(|end| *end > start);
+    let end = end.ok_or("The grammar check reply has no JSON array")?;
+    serde_json::from_str(&reply[start..=end])
+        .map_err(|e| format!("Invalid grammar check reply: {e}"))
+}
+
+fn char_offset(text: &str, byte: usize) -> usize {
+    text[..byte].chars().count()
+}
+
+fn byte_offset(text: &str, chars: usize) -> usize {
+    text.char_indices()
+        .nth(chars)
+        .map_or(text.len(), |(index, _)| index)
+}
+
+/// Place findings in the text, preferring the occurrence after the previous finding and
+/// dropping findings that aren't in the text, change nothing or overlap an earlier one
+fn locate(text: &str, findings: Vec<Finding>) -> Vec<Suggestion> {
+    let mut suggestions: Vec<Suggestion> = Vec::new();
+    let mut cursor = 0;
+    for finding in findings {
+        if finding.original.is_empty() || finding.original == finding.replacement {
+            continue;
+        }
+        let Some(start) = text[cursor..]
+            .find(&finding.original)
+            .map(|index| cursor + index)
+            .or_else(|| text.find(&finding.original))
+        else {
+            continue;
+        };
+        let end = start + finding.original.len();
+        let start = char_offset(text, start);
+        let end_chars = char_offset(text, end);
+        let overlaps = suggestions
+            .iter()
+            .any(|other| start < other.end && other.start < end_chars);
+        if overlaps {
+            continue;
+        }
+        cursor = end;
+        suggestions.push(Suggestion {
+            start,
+            end: end_chars,
+            original: finding.original,
+            replacement: finding.replacement,
+            message: finding.message.trim().to_string(),
+        });
+    }
+    suggestions.sort_by_key(|suggestion| suggestion.start);
+    suggestions
+}
+
+/// Apply one suggestion, shifting the ones after it to the new text
+fn apply(text: &mut String, suggestions: &mut Vec<Suggestion>, index: usize) -> Result<(), String> {
+    if index >= suggestions.len() {
+        return Err(format!("Suggestion {index} not found"));
+    }
+    let suggestion = suggestions.remove(index);
+    let start = byte_offset(text, suggestion.start);
+    let end = byte_offset(text, suggestion.end);
+    if text[start..end] != suggestion.original {
+        return Err("The text changed since the suggestion was made".to_string());
+    }
+    text.replace_range(start..end, &suggestion.replacement);
+
+    let removed = suggestion.end - suggestion.start;
+    let added = suggestion.replacement.chars().count();
+    for other in suggestions.iter_mut() {
+        if other.start >= suggestion.end {
+            other.start = other.start + added - removed;
+            other.end = other.end + added - removed;
+        }
+    }
+    Ok(())
+}
+
+fn run_check(
+    app: &AppHandle,
+    text: &str,
+    language: Option<&str>,
+) -> Result<Vec<Suggestion>, String> {
+    let settings = settings::current(app).post_processing;
+    let system = CHECK_PROMPT.replace("{language}", language.unwrap_or("en"));
+    let reply = postprocess::complete(&settings, &system, text)?;
+    Ok(locate(text, parse_findings(&reply)?))
+}
+
+/// Suggestions for the text when the grammar check is enabled, empty when it is off or fails
+pub async fn check(app: &AppHandle, text: &str, language: Option<&str>) -> Vec<Suggestion> {
+    if !settings::current(app).grammar_check || text.trim().is_empty() {
+        return Vec::new();
+    }
+    let app = app.clone();
+    let text = text.to_string();
+    let language = language.map(str::to_string);
+    let result =
+        tauri::async_runtime::spawn_blocking(move || run_check(&app, &text, language.as_deref()))
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|result| result);
+    result.unwrap_or_else(|error| {
+        eprintln!("[voiceflow] Grammar check failed: {error}");
+        Vec::new()
+    })
+}
+
+/// Store suggestions with a recorded entry, including entries that aren't persisted
+pub fn attach(
+    app: &AppHandle,
+    mut entry: HistoryEntry,
+    suggestions: Vec<Suggestion>,
+) -> Result<HistoryEntry, String> {
+    if suggestions.is_empty() {
+        return Ok(entry);
+    }
+    if entry.id != 0 {
+        let stored = suggestions.clone();
+        history::update_entry(app, entry.id, |entry| {
+            entry.suggestions = stored;
+            Ok(())
+        })?;
+    }
+    // The stored text may be redacted, the returned one is what gets pasted
+    entry.suggestions = suggestions;
+    Ok(entry)
+}
+
+#[tauri::command]
+pub async fn set_grammar_check(app: AppHandle, enabled: bool) -> Result<(), String> {
+    settings::update(&app, |settings| settings.grammar_check = enabled)?;
+    Ok(())
+}
+
+/// Apply a suggestion to a history entry's text, returning the updated entry
+#[tauri::command]
+pub async fn apply_suggestion(
+    app: AppHandle,
+    history_id: u64,
+    index: usize,
+) -> Result<HistoryEntry, String> {
+    let state = app.state::<Mutex<HistoryStore>>();
+    let mut store = state.lock().map_err(|e| e.to_string())?;
+    store.update(history_id, |entry| {
+        apply(&mut entry.text, &mut entry.suggestions, index)?;
+        // Timings no longer line up with the corrected text
+        entry.words.clear();
+        entry.segments.clear();
+        Ok(())
+    })
+}
+
+/// Drop a suggestion the user doesn't want
+#[tauri::command]
+pub async fn dismiss_suggestion(
+    app: AppHandle,
+    history_id: u64,
+    index: usize,
+) -> Result<HistoryEntry, String> {
+    let state = app.state::<Mutex<HistoryStore>>();
+    let mut store = state.lock().map_err(|e| e.to_string())?;
+    store.update(history_id, |entry| {
+        if index >= entry.suggestions.len() {
+            return Err(format!("Suggestion {index} not found"));
+        }
+        entry.suggestions.remove(index);
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(original: &str, replacement: &str) -> Finding {
+        Finding {
+            original: original.to_string(),
+            replacement: replacement.to_string(),
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn locates_findings_in_order_without_overlaps() {
+        let text = "Thé team have went home, the team have left.";
+        let suggestions = locate(
+            text,
+            vec![
+                finding("team have", "team has"),
+                finding("team have", "team has"),
+                finding("have went", "went"),
+                finding("missing", "gone"),
+                finding("home", "home"),
+            ],
+        );
+        let spans: Vec<(usize, usize)> = suggestions.iter().map(|s| (s.start, s.end)).collect();
+        assert_eq!(spans, vec![(4, 13), (29, 38)]);
+    }
+
+    #[test]
+    fn applies_a_suggestion_and_shifts_the_rest() {
+        let mut text = "Thé team have went home, the team have left.".to_string();
+        let mut suggestions = locate(
+            &text,
+            vec![
+                finding("have went", "went"),
+                finding("team have left", "team left"),
+            ],
+        );
+        apply(&mut text, &mut suggestions, 0).unwrap();
+        assert_eq!(text, "Thé team went home, the team have left.");
+        apply(&mut text, &mut suggestions, 0).unwrap();
+        assert_eq!(text, "Thé team went home, the team left.");
+        assert!(apply(&mut text, &mut suggestions, 0).is_err());
+
+        let reply = "```json\n[{\"original\": \"a\", \"replacement\": \"b\"}]\n```";
+        assert_eq!(parse_findings(reply).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,605 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::auth;
+use crate::grammar::Suggestion;
+use crate::history_db::{HistoryDb, HistoryPage};
+use crate::insertion::InsertionOutcome;
+use crate::microphone::{self, CaptureStats};
+use crate::settings;
+use crate::text_diff::{self, TextEdit};
+use crate::transcription::{self, Transcript, TranscriptSegment, TranscriptWord};
+use crate::{active_app, browser, i18n, incognito, project, redaction, session, vocabulary};
+
+/// Device and id an entry had where it was dictated, for entries synced from elsewhere
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncOrigin {
+    pub device: String,
+    pub id: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub id: u64,
+    pub timestamp_ms: u64,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub segments: Vec<TranscriptSegment>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<TranscriptWord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternatives: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// How the text was inserted into the target app, once the webview reports it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub insertion: Option<InsertionOutcome>,
+    /// Frontmost app the dictation was made in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+    /// Dropped frames and overruns when the audio was captured natively
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capture: Option<CaptureStats>,
+    /// Set on entries imported by history sync
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<SyncOrigin>,
+    /// Project that was current when the dictation was made
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Display name, e.g. the calendar event a meeting transcript belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Browser tab the dictation was made in, when browser context is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Grammar and style fixes the user can still apply
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<Suggestion>,
+    /// Text as first stored, kept once the user has edited the entry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_text: Option<String>,
+    /// Word changes from `original_text` to `text`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub edits: Vec<TextEdit>,
+    /// From stopping the recording until the transcript was stored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    /// Length of the recording
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// Transcription model that produced the text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Key-value pairs added by transcript plugins
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// Where and how a dictation was made, recorded alongside its transcript
+#[derive(Debug, Clone, Default)]
+pub struct EntryContext {
+    /// Frontmost app
+    pub app: Option<String>,
+    pub project: Option<String>,
+    /// Active browser tab URL
+    pub url: Option<String>,
+    pub capture: Option<CaptureStats>,
+    pub latency_ms: Option<u64>,
+    pub duration_ms: Option<u64>,
+}
+
+impl HistoryEntry {
+    /// Render the entry for export, prefixing speaker turns when segments are diarized
+    pub fn formatted_text(&self) -> String {
+        if self
+            .segments
+            .iter()
+            .all(|segment| segment.speaker.is_none())
+        {
+            return self.text.clone();
+        }
+
+        let mut turns: Vec<(String, Vec<&str>)> = Vec::new();
+        for segment in &self.segments {
+            let speaker = segment
+                .speaker
+                .clone()
+                .unwrap_or_else(|| "Unknown".to_string());
+            match turns.last_mut() {
+                Some((current, parts)) if *current == speaker => parts.push(segment.text.trim()),
+                _ => turns.push((speaker, vec![segment.text.trim()])),
+            }
+        }
+
+        turns
+            .into_iter()
+            .map(|(speaker, parts)| format!("{speaker}: {}", parts.join(" ")))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+/// Tag for entries brought over from another dictation app
+pub const IMPORTED_TAG: &str = "imported";
+
+/// History as kept in memory for lookups, written through to history.db
+pub struct HistoryStore {
+    entries: Vec<HistoryEntry>,
+    db: HistoryDb,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+impl HistoryStore {
+    pub fn new(data_dir: PathBuf) -> Result<Self, String> {
+        let mut db = match HistoryDb::open(&data_dir.join("history.db")) {
+            Ok(db) => db,
+            Err(e) => {
+                eprintln!("[voiceflow] Keeping history in memory only: {e}");
+                HistoryDb::open_in_memory()?
+            }
+        };
+        if let Err(e) = Self::migrate_json(&data_dir.join("history.json"), &mut db) {
+            eprintln!("[voiceflow] Failed to migrate history.json: {e}");
+        }
+        let entries = db.load().unwrap_or_else(|e| {
+            eprintln!("[voiceflow] Failed to load history: {e}");
+            Vec::new()
+        });
+        Ok(Self { entries, db })
+    }
+
+    /// Move history from the JSON file used before the database, keeping the
+    /// file as history.json.migrated
+    fn migrate_json(path: &Path, db: &mut HistoryDb) -> Result<(), String> {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Ok(());
+        };
+        if !db.is_empty()? {
+            return Err("history.db already has entries".to_string());
+        }
+        let entries: Vec<HistoryEntry> =
+            serde_json::from_str(&content).map_err(|e| e.to_string())?;
+        db.save(&entries)?;
+        fs::rename(path, path.with_extension("json.migrated")).map_err(|e| e.to_string())
+    }
+
+    /// Build an entry for the transcript, storing it unless `persist` is false,
+    /// in which case it gets id 0 and never touches disk
+    pub fn add(
+        &mut self,
+        transcript: Transcript,
+        tag: Option<String>,
+        context: EntryContext,
+        persist: bool,
+    ) -> Result<HistoryEntry, String> {
+        let id = if persist {
+            self.entries.iter().map(|entry| entry.id).max().unwrap_or(0) + 1
+        } else {
+            0
+        };
+        let entry = HistoryEntry {
+            id,
+            timestamp_ms: now_ms(),
+            confidence: transcript.overall_confidence(),
+            text: transcript.text,
+            tag,
+            segments: transcript.segments,
+            words: transcript.words,
+            alternatives: transcript.alternatives,
+            language: transcript.language,
+            insertion: None,
+            app: context.app,
+            capture: context.capture,
+            origin: None,
+            project: context.project,
+            title: None,
+            url: context.url,
+            suggestions: Vec::new(),
+            original_text: None,
+            edits: Vec::new(),
+            latency_ms: context.latency_ms,
+            duration_ms: context.duration_ms,
+            model: Some(transcription::MODEL.to_string()),
+            metadata: BTreeMap::new(),
+        };
+        if !persist {
+            return Ok(entry);
+        }
+        self.db.save(std::slice::from_ref(&entry))?;
+        self.entries.push(entry.clone());
+        Ok(entry)
+    }
+
+    /// Most recent dictation made on this device
+    pub fn last(&self) -> Option<HistoryEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| entry.origin.is_none())
+            .cloned()
+    }
+
+    pub fn all(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// Up to `limit` entries, newest first
+    pub fn recent(&self, limit: usize) -> Vec<HistoryEntry> {
+        self.entries.iter().rev().take(limit).cloned().collect()
+    }
+
+    /// Entries dictated on this device, as opposed to synced from another
+    pub fn local_entries(&self) -> Vec<HistoryEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.origin.is_none())
+            .cloned()
+            .collect()
+    }
+
+    /// Add another device's entries that aren't here yet, keeping history in time order.
+    /// Returns how many were new.
+    pub fn import(&mut self, device: &str, entries: Vec<HistoryEntry>) -> Result<usize, String> {
+        let mut next_id = self.entries.iter().map(|entry| entry.id).max().unwrap_or(0) + 1;
+        let mut imported = Vec::new();
+        for mut entry in entries {
+            let origin = SyncOrigin {
+                device: device.to_string(),
+                id: entry.id,
+            };
+            if self
+                .entries
+                .iter()
+                .any(|existing| existing.origin.as_ref() == Some(&origin))
+            {
+                continue;
+            }
+            entry.id = next_id;
+            entry.origin = Some(origin);
+            next_id += 1;
+            imported.push(entry);
+        }
+        self.insert(imported)
+    }
+
+    /// Add dictations exported from another app, skipping ones already imported
+    /// (same time and text). Returns how many were new.
+    pub fn add_imported(&mut self, dictations: Vec<(u64, String)>) -> Result<usize, String> {
+        let mut next_id = self.entries.iter().map(|entry| entry.id).max().unwrap_or(0) + 1;
+        let mut imported = Vec::new();
+        for (timestamp_ms, text) in dictations {
+            if self
+                .entries
+                .iter()
+                .any(|entry| entry.timestamp_ms == timestamp_ms && entry.text == text)
+            {
+                continue;
+            }
+            imported.push(HistoryEntry {
+                id: next_id,
+                timestamp_ms,
+                text,
+                tag: Some(IMPORTED_TAG.to_string()),
+                segments: Vec::new(),
+                words: Vec::new(),
+                confidence: None,
+                alternatives: Vec::new(),
+                language: None,
+                insertion: None,
+                app: None,
+                capture: None,
+                origin: None,
+                project: None,
+                title: None,
+                url: None,
+                suggestions: Vec::new(),
+                original_text: None,
+                edits: Vec::new(),
+                latency_ms: None,
+                duration_ms: None,
+                model: None,
+                metadata: BTreeMap::new(),
+            });
+            next_id += 1;
+        }
+        self.insert(imported)
+    }
+
+    /// Store new entries, keeping history in time order. Returns how many there were.
+    fn insert(&mut self, entries: Vec<HistoryEntry>) -> Result<usize, String> {
+        if entries.is_empty() {
+            return Ok(0);
+        }
+        self.db.save(&entries)?;
+        let count = entries.len();
+        self.entries.extend(entries);
+        self.entries.sort_by_key(|entry| entry.timestamp_ms);
+        Ok(count)
+    }
+
+    /// Entries with `from_ms <= timestamp_ms < to_ms`, oldest first
+    pub fn between(&self, from_ms: u64, to_ms: u64) -> Vec<HistoryEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| (from_ms..to_ms).contains(&entry.timestamp_ms))
+            .cloned()
+            .collect()
+    }
+
+    pub fn get(&self, id: u64) -> Option<HistoryEntry> {
+        self.entries.iter().find(|entry| entry.id == id).cloned()
+    }
+
+    pub fn update(
+        &mut self,
+        id: u64,
+        apply: impl FnOnce(&mut HistoryEntry) -> Result<(), String>,
+    ) -> Result<HistoryEntry, String> {
+        let entry = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.id == id)
+            .ok_or_else(|| i18n::tf("error.history_entry_not_found", &[("id", &id.to_string())]))?;
+        apply(entry)?;
+        let updated = entry.clone();
+        self.db.save(std::slice::from_ref(&updated))?;
+        Ok(updated)
+    }
+
+    pub fn remove(&mut self, id: u64) -> Result<(), String> {
+        if !self.entries.iter().any(|entry| entry.id == id) {
+            return Err(i18n::tf(
+                "error.history_entry_not_found",
+                &[("id", &id.to_string())],
+            ));
+        }
+        self.db.delete(id)?;
+        self.entries.retain(|entry| entry.id != id);
+        Ok(())
+    }
+
+    pub fn clear(&mut self) -> Result<(), String> {
+        self.db.clear()?;
+        self.entries.clear();
+        Ok(())
+    }
+
+    pub fn page(&self, page: usize, query: Option<&str>) -> Result<HistoryPage, String> {
+        self.db.page(page, query)
+    }
+
+    pub fn vacuum(&self) -> Result<(), String> {
+        self.db.vacuum()
+    }
+}
+
+pub fn add_entry(
+    app: &AppHandle,
+    transcript: Transcript,
+    tag: Option<String>,
+) -> Result<HistoryEntry, String> {
+    add(app, transcript, tag, false, false)
+}
+
+/// Add a dictation made through the pipeline, which isn't stored when it was incognito
+pub fn add_dictation(
+    app: &AppHandle,
+    transcript: Transcript,
+    tag: Option<String>,
+) -> Result<HistoryEntry, String> {
+    add(app, transcript, tag, incognito::is_active(), true)
+}
+
+fn add(
+    app: &AppHandle,
+    transcript: Transcript,
+    tag: Option<String>,
+    incognito: bool,
+    dictated: bool,
+) -> Result<HistoryEntry, String> {
+    let persist = !settings::current(app).disable_history && !incognito;
+    let text = transcript.text.clone();
+    let transcript = redaction::redact_transcript(app, transcript);
+    // Captured when the dictation started, so nothing here waits on AppleScript. Files
+    // transcribed in the background weren't dictated into any app.
+    let (active, tab) = if dictated {
+        (active_app::at_start(), browser::at_start())
+    } else {
+        (None, None)
+    };
+    let context = EntryContext {
+        project: project::current(app, active.as_ref()),
+        url: tab.map(|tab| tab.url),
+        app: active.map(|active| active.name),
+        capture: Some(microphone::session_stats()).filter(|stats| !stats.is_empty()),
+        latency_ms: session::latency_ms().filter(|_| dictated),
+        duration_ms: session::recording_ms().filter(|_| dictated),
+    };
+    let state = app.state::<Mutex<HistoryStore>>();
+    let mut store = state.lock().map_err(|e| e.to_string())?;
+    let mut entry = store.add(transcript, tag, context, persist)?;
+    // Only the stored copy is redacted; the returned text is what gets pasted
+    entry.text = text;
+    Ok(entry)
+}
+
+pub fn entries_between(app: &AppHandle, from_ms: u64, to_ms: u64) -> Vec<HistoryEntry> {
+    let state = app.state::<Mutex<HistoryStore>>();
+    let entries = state.lock().map(|store| store.between(from_ms, to_ms));
+    entries.unwrap_or_default()
+}
+
+pub fn last_entry(app: &AppHandle) -> Option<HistoryEntry> {
+    let state = app.state::<Mutex<HistoryStore>>();
+    let store = state.lock().ok()?;
+    store.last()
+}
+
+pub fn update_entry(
+    app: &AppHandle,
+    id: u64,
+    apply: impl FnOnce(&mut HistoryEntry) -> Result<(), String>,
+) -> Result<HistoryEntry, String> {
+    let state = app.state::<Mutex<HistoryStore>>();
+    let mut store = state.lock().map_err(|e| e.to_string())?;
+    store.update(id, apply)
+}
+
+pub fn get_entry(app: &AppHandle, id: u64) -> Result<HistoryEntry, String> {
+    let state = app.state::<Mutex<HistoryStore>>();
+    let store = state.lock().map_err(|e| e.to_string())?;
+    store
+        .get(id)
+        .ok_or_else(|| i18n::tf("error.history_entry_not_found", &[("id", &id.to_string())]))
+}
+
+/// A page of the history tab, newest first, optionally only entries whose text or
+/// app contains `query`
+#[tauri::command]
+pub async fn get_history(
+    app: AppHandle,
+    page: Option<usize>,
+    query: Option<String>,
+) -> Result<HistoryPage, String> {
+    auth::unlock(&app, "auth.history").await?;
+    let state = app.state::<Mutex<HistoryStore>>();
+    let store = state.lock().map_err(|e| e.to_string())?;
+    store.page(page.unwrap_or(0), query.as_deref())
+}
+
+#[tauri::command]
+pub async fn delete_history_entry(app: AppHandle, id: u64) -> Result<(), String> {
+    let state = app.state::<Mutex<HistoryStore>>();
+    let mut store = state.lock().map_err(|e| e.to_string())?;
+    store.remove(id)
+}
+
+#[tauri::command]
+pub async fn clear_history(app: AppHandle) -> Result<(), String> {
+    let state = app.state::<Mutex<HistoryStore>>();
+    let mut store = state.lock().map_err(|e| e.to_string())?;
+    store.clear()
+}
+
+#[tauri::command]
+pub async fn get_history_entry(app: AppHandle, id: u64) -> Result<HistoryEntry, String> {
+    auth::unlock(&app, "auth.history").await?;
+    get_entry(&app, id)
+}
+
+/// Alternative hypotheses for a dictation session, keyed by its history entry id
+#[tauri::command]
+pub async fn get_alternatives(app: AppHandle, id: u64) -> Result<Vec<String>, String> {
+    auth::unlock(&app, "auth.history").await?;
+    Ok(get_entry(&app, id)?.alternatives)
+}
+
+/// Replace the session's text with one of its alternatives, keeping the old text selectable
+#[tauri::command]
+pub async fn choose_alternative(
+    app: AppHandle,
+    id: u64,
+    index: usize,
+) -> Result<HistoryEntry, String> {
+    auth::unlock(&app, "auth.history").await?;
+    let state = app.state::<Mutex<HistoryStore>>();
+    let mut store = state.lock().map_err(|e| e.to_string())?;
+    store.update(id, |entry| {
+        let alternative = entry
+            .alternatives
+            .get_mut(index)
+            .ok_or_else(|| format!("Alternative {index} not found"))?;
+        std::mem::swap(&mut entry.text, alternative);
+        // Timings and confidence describe the original hypothesis only
+        entry.words.clear();
+        entry.segments.clear();
+        entry.confidence = None;
+        Ok(())
+    })
+}
+
+/// Replace an entry's text with the user's correction, keeping the original and
+/// the word changes between them
+#[tauri::command]
+pub async fn edit_history_entry(
+    app: AppHandle,
+    id: u64,
+    new_text: String,
+) -> Result<HistoryEntry, String> {
+    auth::unlock(&app, "auth.history").await?;
+    let text = redaction::redact(&app, &new_text);
+    let mut new_edits = Vec::new();
+    let entry = update_entry(&app, id, |entry| {
+        let original = entry
+            .original_text
+            .take()
+            .unwrap_or_else(|| entry.text.clone());
+        let edits = text_diff::word_diff(&original, &text);
+        new_edits = edits
+            .iter()
+            .filter(|edit| !entry.edits.contains(edit))
+            .cloned()
+            .collect();
+        // Editing back to the original leaves nothing to keep
+        entry.original_text = (!edits.is_empty()).then_some(original);
+        entry.edits = edits;
+        entry.text = text;
+        // Timings and offsets describe the transcribed text only
+        entry.words.clear();
+        entry.segments.clear();
+        entry.suggestions.clear();
+        Ok(())
+    })?;
+    vocabulary::learn_from_edits(&app, &new_edits);
+    Ok(entry)
+}
+
+#[tauri::command]
+pub async fn merge_speakers(
+    app: AppHandle,
+    id: u64,
+    from: String,
+    into: String,
+) -> Result<HistoryEntry, String> {
+    auth::unlock(&app, "auth.history").await?;
+    let state = app.state::<Mutex<HistoryStore>>();
+    let mut store = state.lock().map_err(|e| e.to_string())?;
+    store.update(id, |entry| {
+        let mut merged = false;
+        for segment in &mut entry.segments {
+            if segment.speaker.as_deref() == Some(from.as_str()) {
+                segment.speaker = Some(into.clone());
+                merged = true;
+            }
+        }
+        if merged {
+            Ok(())
+        } else {
+            Err(format!("Speaker \"{from}\" not found in entry {id}"))
+        }
+    })
+}
+
+#[tauri::command]
+pub async fn export_history_entry(app: AppHandle, id: u64, path: PathBuf) -> Result<(), String> {
+    auth::unlock(&app, "auth.export").await?;
+    let entry = get_entry(&app, id)?;
+    fs::write(&path, entry.formatted_text()).map_err(|e| format!("Failed to export: {e}"))
+}
@@ -0,0 +1,245 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use crate::history::HistoryEntry;
+
+/// Entries per page of get_history
+pub const PAGE_SIZE: usize = 50;
+
+// The full entry is kept as JSON; the other columns are what the history tab
+// searches and sorts by
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS entries (
+        id INTEGER PRIMARY KEY,
+        timestamp_ms INTEGER NOT NULL,
+        duration_ms INTEGER,
+        text TEXT NOT NULL,
+        model TEXT,
+        app TEXT,
+        entry TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS entries_timestamp ON entries (timestamp_ms);
+";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPage {
+    /// Newest first
+    pub entries: Vec<HistoryEntry>,
+    /// Matching entries across all pages
+    pub total: usize,
+    pub page: usize,
+    pub page_size: usize,
+}
+
+/// SQLite file holding the history, history.db in the app data dir
+pub struct HistoryDb {
+    connection: Connection,
+}
+
+fn db_error(e: rusqlite::Error) -> String {
+    format!("History database error: {e}")
+}
+
+/// LIKE pattern matching `query` anywhere, with its wildcards taken literally
+fn like_pattern(query: &str) -> String {
+    let escaped = query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{escaped}%")
+}
+
+impl HistoryDb {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let connection = Connection::open(path).map_err(db_error)?;
+        Self::init(connection)
+    }
+
+    pub fn open_in_memory() -> Result<Self, String> {
+        Self::init(Connection::open_in_memory().map_err(db_error)?)
+    }
+
+    fn init(connection: Connection) -> Result<Self, String> {
+        connection.execute_batch(SCHEMA).map_err(db_error)?;
+        Ok(Self { connection })
+    }
+
+    /// Whether SQLite considers the file intact
+    pub fn is_intact(&self) -> bool {
+        self.connection
+            .query_row("PRAGMA quick_check", [], |row| row.get::<_, String>(0))
+            .is_ok_and(|result| result == "ok")
+    }
+
+    pub fn is_empty(&self) -> Result<bool, String> {
+        let first: Option<i64> = self
+            .connection
+            .query_row("SELECT id FROM entries LIMIT 1", [], |row| row.get(0))
+            .optional()
+            .map_err(db_error)?;
+        Ok(first.is_none())
+    }
+
+    /// All entries, oldest first. Rows that no longer parse are skipped.
+    pub fn load(&self) -> Result<Vec<HistoryEntry>, String> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT entry FROM entries ORDER BY timestamp_ms, id")
+            .map_err(db_error)?;
+        let rows = statement
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(db_error)?;
+        let mut entries = Vec::new();
+        for row in rows {
+            match serde_json::from_str(&row.map_err(db_error)?) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => eprintln!("[voiceflow] Skipping unreadable history row: {e}"),
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Insert or replace `entries` in one transaction
+    pub fn save(&mut self, entries: &[HistoryEntry]) -> Result<(), String> {
+        let transaction = self.connection.transaction().map_err(db_error)?;
+        {
+            let mut statement = transaction
+                .prepare_cached(
+                    "INSERT OR REPLACE INTO entries
+                        (id, timestamp_ms, duration_ms, text, model, app, entry)
+                        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                )
+                .map_err(db_error)?;
+            for entry in entries {
+                let json = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+                statement
+                    .execute(params![
+                        entry.id as i64,
+                        entry.timestamp_ms as i64,
+                        entry.duration_ms.map(|ms| ms as i64),
+                        entry.text,
+                        entry.model,
+                        entry.app,
+                        json,
+                    ])
+                    .map_err(db_error)?;
+            }
+        }
+        transaction.commit().map_err(db_error)
+    }
+
+    pub fn delete(&self, id: u64) -> Result<(), String> {
+        self.connection
+            .execute("DELETE FROM entries WHERE id = ?1", [id as i64])
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    pub fn clear(&self) -> Result<(), String> {
+        self.connection
+            .execute("DELETE FROM entries", [])
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    /// Give the space of deleted entries back to the file system
+    pub fn vacuum(&self) -> Result<(), String> {
+        self.connection.execute_batch("VACUUM").map_err(db_error)
+    }
+
+    /// One page of entries, newest first, whose text or app contains `query`
+    /// (case-insensitive for ASCII)
+    pub fn page(&self, page: usize, query: Option<&str>) -> Result<HistoryPage, String> {
+        let pattern = query
+            .map(str::trim)
+            .filter(|query| !query.is_empty())
+            .map(like_pattern);
+        let filter = "?1 IS NULL OR text LIKE ?1 ESCAPE '\\' OR app LIKE ?1 ESCAPE '\\'";
+        let total: i64 = self
+            .connection
+            .query_row(
+                &format!("SELECT COUNT(*) FROM entries WHERE {filter}"),
+                [&pattern],
+                |row| row.get(0),
+            )
+            .map_err(db_error)?;
+        let mut statement = self
+            .connection
+            .prepare(&format!(
+                "SELECT entry FROM entries WHERE {filter}
+                    ORDER BY timestamp_ms DESC, id DESC LIMIT ?2 OFFSET ?3"
+            ))
+            .map_err(db_error)?;
+        let rows = statement
+            .query_map(
+                params![pattern, PAGE_SIZE as i64, (page * PAGE_SIZE) as i64],
+                |row| row.get::<_, String>(0),
+            )
+            .map_err(db_error)?;
+        let mut entries = Vec::new();
+        for row in rows {
+            if let Ok(entry) = serde_json::from_str(&row.map_err(db_error)?) {
+                entries.push(entry);
+            }
+        }
+        Ok(HistoryPage {
+            entries,
+            total: total as usize,
+            page,
+            page_size: PAGE_SIZE,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u64, text: &str, app: &str) -> HistoryEntry {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "timestampMs": id * 1000,
+            "text": text,
+            "app": app,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn stores_and_searches_entries() {
+        let mut db = HistoryDb::open_in_memory().unwrap();
+        db.save(&[
+            entry(1, "Ship the release", "Slack"),
+            entry(2, "100% done", "Mail"),
+            entry(3, "lunch at noon", "Slack"),
+        ])
+        .unwrap();
+        let mut edited = entry(1, "Ship the release today", "Slack");
+        edited.duration_ms = Some(1200);
+        db.save(&[edited]).unwrap();
+
+        let loaded = db.load().unwrap();
+        assert_eq!(loaded.len(), 3);
+        assert_eq!(loaded[0].text, "Ship the release today");
+        assert_eq!(loaded[0].duration_ms, Some(1200));
+
+        let ids = |page: HistoryPage| -> Vec<u64> { page.entries.iter().map(|e| e.id).collect() };
+        assert_eq!(ids(db.page(0, None).unwrap()), vec![3, 2, 1]);
+        assert_eq!(ids(db.page(0, Some("slack")).unwrap()), vec![3, 1]);
+        assert_eq!(ids(db.page(0, Some("0%")).unwrap()), vec![2]);
+        assert_eq!(db.page(1, None).unwrap().total, 3);
+        assert!(db.page(1, None).unwrap().entries.is_empty());
+
+        db.delete(3).unwrap();
+        assert_eq!(db.load().unwrap().len(), 2);
+        db.clear().unwrap();
+        assert!(db.is_empty().unwrap());
+        assert!(db.is_intact());
+    }
+}
@@ -0,0 +1,250 @@
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::history::HistoryStore;
+use crate::i18n;
+
+/// Local date-time layouts seen in plain-text exports, most specific first
+const TEXT_TIMESTAMP_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d %H:%M",
+];
+
+const TEXT_KEYS: &[&str] = &["text", "transcript", "transcription", "content", "body"];
+const TIME_KEYS: &[&str] = &[
+    "timestamp",
+    "timestampMs",
+    "createdAt",
+    "created_at",
+    "date",
+    "time",
+];
+/// Keys other tools nest their entry list under when the export isn't a bare array
+const LIST_KEYS: &[&str] = &["history", "entries", "transcripts", "items", "data"];
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    /// One dictation per timestamped line, e.g. `[2024-05-01 09:30] Hello`
+    Text,
+    /// An array of objects with a text and a timestamp field
+    Json,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    pub imported: usize,
+    pub duplicates: usize,
+    pub skipped: usize,
+}
+
+fn local_millis(time: NaiveDateTime) -> Option<u64> {
+    Local
+        .from_local_datetime(&time)
+        .earliest()
+        .map(|time| time.timestamp_millis().max(0) as u64)
+}
+
+fn parse_timestamp(value: &str) -> Option<u64> {
+    let value = value.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Some(time.timestamp_millis().max(0) as u64);
+    }
+    TEXT_TIMESTAMP_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .and_then(local_millis)
+}
+
+/// Split a line into its leading timestamp and the rest, if it starts with one
+fn split_timestamp(line: &str) -> Option<(u64, &str)> {
+    if let Some(rest) = line.strip_prefix('[') {
+        let (time, text) = rest.split_once(']')?;
+        return Some((parse_timestamp(time)?, text));
+    }
+    // Try the longest prefix first so seconds aren't mistaken for text
+    let mut boundaries: Vec<usize> = line
+        .char_indices()
+        .filter(|(_, c)| c.is_whitespace())
+        .map(|(index, _)| index)
+        .chain([line.len()])
+        .collect();
+    boundaries.reverse();
+    boundaries.into_iter().find_map(|end| {
+        let timestamp = parse_timestamp(&line[..end])?;
+        Some((timestamp, &line[end..]))
+    })
+}
+
+/// Plain-text exports: a timestamped line starts a dictation, untimestamped lines continue it
+fn parse_text(content: &str) -> (Vec<(u64, String)>, usize) {
+    let mut dictations: Vec<(u64, String)> = Vec::new();
+    let mut skipped = 0;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        match split_timestamp(trimmed) {
+            Some((timestamp, text)) => {
+                let text = text.trim_start_matches([' ', '\t', '-', ':', '|']).trim();
+                dictations.push((timestamp, text.to_string()));
+            }
+            None => match dictations.last_mut() {
+                Some((_, text)) => {
+                    if !text.is_empty() {
+                        text.push('\n');
+                    }
+                    text.push_str(trimmed);
+                }
+                None => skipped += 1,
+            },
+        }
+    }
+    let before = dictations.len();
+    dictations.retain(|(_, text)| !text.is_empty());
+    let empty = before - dictations.len();
+    (dictations, skipped + empty)
+}
+
+fn json_timestamp(value: &Value) -> Option<u64> {
+    match value {
+        // Unix seconds or milliseconds, told apart by magnitude
+        Value::Number(number) => {
+            let number = number.as_f64()?;
+            if number < 0.0 {
+                None
+            } else if number < 1e11 {
+                Some((number * 1000.0) as u64)
+            } else {
+                Some(number as u64)
+            }
+        }
+        Value::String(text) => parse_timestamp(text),
+        _ => None,
+    }
+}
+
+fn field<'a>(object: &'a serde_json::Map<String, Value>, keys: &[&str]) -> Option<&'a Value> {
+    keys.iter().find_map(|key| object.get(*key))
+}
+
+fn parse_json(content: &str) -> Result<(Vec<(u64, String)>, usize), String> {
+    let value: Value = serde_json::from_str(content)
+        .map_err(|e| i18n::tf("error.import_json", &[("error", &e.to_string())]))?;
+    let items = match &value {
+        Value::Array(items) => items,
+        Value::Object(object) => field(object, LIST_KEYS)
+            .and_then(Value::as_array)
+            .ok_or_else(|| i18n::t("error.import_no_entries"))?,
+        _ => return Err(i18n::t("error.import_no_entries")),
+    };
+
+    let mut dictations = Vec::new();
+    let mut skipped = 0;
+    for item in items {
+        let parsed = item.as_object().and_then(|object| {
+            let text = field(object, TEXT_KEYS)?.as_str()?.trim();
+            let timestamp = json_timestamp(field(object, TIME_KEYS)?)?;
+            (!text.is_empty()).then(|| (timestamp, text.to_string()))
+        });
+        match parsed {
+            Some(dictation) => dictations.push(dictation),
+            None => skipped += 1,
+        }
+    }
+    Ok((dictations, skipped))
+}
+
+/// Bring dictations exported from another app into the history, where stats pick them up too
+#[tauri::command]
+pub async fn import_history(
+    app: AppHandle,
+    path: PathBuf,
+    format: ImportFormat,
+) -> Result<ImportSummary, String> {
+    let content = fs::read_to_string(&path).map_err(|e| {
+        i18n::tf(
+            "error.read_file",
+            &[
+                ("path", &path.display().to_string()),
+                ("error", &e.to_string()),
+            ],
+        )
+    })?;
+    let (dictations, skipped) = match format {
+        ImportFormat::Text => parse_text(&content),
+        ImportFormat::Json => parse_json(&content)?,
+    };
+    if dictations.is_empty() {
+        return Err(i18n::t("error.import_no_dictations"));
+    }
+
+    let found = dictations.len();
+    let state = app.state::<Mutex<HistoryStore>>();
+    let mut store = state.lock().map_err(|e| e.to_string())?;
+    let imported = store.add_imported(dictations)?;
+    Ok(ImportSummary {
+        imported,
+        duplicates: found - imported,
+        skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(text: &str) -> u64 {
+        local_millis(NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn parses_timestamped_text_lines() {
+        let content = "Exported history\n\
+            [2024-05-01 09:30] Hello there\n\
+            and a second line\n\
+            2024-05-01 10:15:20 - Buy milk\n\
+            2024-05-02T08:00:00Z\tStand-up notes\n";
+        let (dictations, skipped) = parse_text(content);
+        assert_eq!(skipped, 1);
+        assert_eq!(
+            dictations,
+            vec![
+                (
+                    local("2024-05-01 09:30:00"),
+                    "Hello there\nand a second line".to_string()
+                ),
+                (local("2024-05-01 10:15:20"), "Buy milk".to_string()),
+                (1_714_636_800_000, "Stand-up notes".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_json_exports_with_varied_fields() {
+        let content = r#"{"transcripts": [
+            {"transcript": "First", "createdAt": "2024-05-02T08:00:00Z"},
+            {"text": "Second", "timestamp": 1714636800},
+            {"text": "No time"}
+        ]}"#;
+        let (dictations, skipped) = parse_json(content).unwrap();
+        assert_eq!(skipped, 1);
+        assert_eq!(
+            dictations,
+            vec![
+                (1_714_636_800_000, "First".to_string()),
+                (1_714_636_800_000, "Second".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,322 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{
+    AppHandle, Emitter, Manager, PhysicalPosition, Position, WebviewUrl, WebviewWindowBuilder,
+};
+use tauri_plugin_global_shortcut::Shortcut;
+
+use crate::history::{HistoryEntry, HistoryStore};
+use crate::voice_commands::COMMAND_HISTORY_TAG;
+use crate::{append_e2e_log, auth, insertion, screen_share, settings, ShortcutConfig};
+
+#[cfg(target_os = "macos")]
+use tauri_nspanel::objc2::{runtime::NSObjectProtocol, ClassType, Message};
+#[cfg(target_os = "macos")]
+use tauri_nspanel::{ManagerExt, WebviewWindowExt};
+
+pub const WINDOW_LABEL: &str = "history-picker";
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 420.0;
+/// Entries searched, newest first
+const SEARCH_DEPTH: usize = 1000;
+const DEFAULT_RESULTS: usize = 50;
+
+// Unlike the bubble this panel takes keyboard input, but still without activating
+// VoiceFlow, so the app that was frontmost stays the paste target
+#[cfg(target_os = "macos")]
+tauri_nspanel::panel!(HistoryPickerPanel {
+    config: {
+        can_become_key_window: true,
+        can_become_main_window: false,
+        is_floating_panel: true,
+    }
+});
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PickerResult {
+    pub entry: HistoryEntry,
+    /// Char indices of `entry.text` that matched the query, for highlighting
+    pub matches: Vec<usize>,
+}
+
+/// Score `text` against `query` as an in-order, case-insensitive subsequence, favouring
+/// consecutive runs and word starts. Returns None when not every query char is found.
+fn fuzzy_match(query: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+
+    let mut score = 0;
+    let mut matches = Vec::with_capacity(query.len());
+    let mut wanted = query.iter().peekable();
+    let mut previous: Option<char> = None;
+    for (index, c) in text.chars().enumerate() {
+        let Some(&&next) = wanted.peek() else {
+            break;
+        };
+        if c.to_lowercase().eq(std::iter::once(next)) {
+            score += 1;
+            if matches.last() == Some(&index.wrapping_sub(1)) {
+                score += 5;
+            }
+            if previous.is_none_or(|previous| !previous.is_alphanumeric()) {
+                score += 3;
+            }
+            matches.push(index);
+            wanted.next();
+        }
+        previous = Some(c);
+    }
+    if wanted.peek().is_some() {
+        return None;
+    }
+    // Prefer matches that start early and are tightly packed
+    let span = (matches[matches.len() - 1] - matches[0]) as i64;
+    Some((score * 10 - span - matches[0] as i64, matches))
+}
+
+/// Rank `entries` (newest first) against the query, keeping newer entries first on ties
+fn search(entries: Vec<HistoryEntry>, query: &str, limit: usize) -> Vec<PickerResult> {
+    let mut scored: Vec<(i64, usize, PickerResult)> = entries
+        .into_iter()
+        .filter(|entry| entry.tag.as_deref() != Some(COMMAND_HISTORY_TAG))
+        .filter(|entry| !entry.text.trim().is_empty())
+        .enumerate()
+        .filter_map(|(rank, entry)| {
+            let (score, matches) = fuzzy_match(query, &entry.text)?;
+            Some((score, rank, PickerResult { entry, matches }))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, _, result)| result)
+        .collect()
+}
+
+pub fn shortcut(app: &AppHandle) -> Option<Shortcut> {
+    settings::current(app)
+        .history_picker_shortcut?
+        .to_shortcut()
+}
+
+/// Center a floating window horizontally in the upper third of the monitor the cursor is on
+pub fn position(app: &AppHandle, window: &tauri::WebviewWindow) {
+    let monitor = app
+        .cursor_position()
+        .ok()
+        .and_then(|cursor| app.monitor_from_point(cursor.x, cursor.y).ok().flatten())
+        .or_else(|| app.primary_monitor().ok().flatten());
+    let (Some(monitor), Ok(size)) = (monitor, window.outer_size()) else {
+        return;
+    };
+
+    let screen = monitor.size();
+    let screen_pos = monitor.position();
+    let x = screen_pos.x + (screen.width as i32 - size.width as i32) / 2;
+    let y = screen_pos.y + (screen.height as i32 - size.height as i32) / 3;
+    let _ = window.set_position(Position::Physical(PhysicalPosition::new(x, y)));
+}
+
+#[cfg(target_os = "macos")]
+fn setup_panel(window: &tauri::WebviewWindow) {
+    use tauri_nspanel::objc2_app_kit::{NSWindowCollectionBehavior, NSWindowStyleMask};
+
+    let Ok(panel) = window.to_panel::<HistoryPickerPanel<_>>() else {
+        return;
+    };
+    panel.set_level(25);
+    panel.set_style_mask(NSWindowStyleMask::NonactivatingPanel);
+    panel.set_collection_behavior(
+        NSWindowCollectionBehavior::CanJoinAllSpaces
+            | NSWindowCollectionBehavior::Transient
+            | NSWindowCollectionBehavior::FullScreenAuxiliary,
+    );
+}
+
+pub fn show(app: &AppHandle) {
+    if screen_share::should_hide_overlay(app) {
+        append_e2e_log("history-picker-suppressed");
+        return;
+    }
+    let window = match app.get_webview_window(WINDOW_LABEL) {
+        Some(window) => window,
+        None => {
+            let builder = WebviewWindowBuilder::new(
+                app,
+                WINDOW_LABEL,
+                WebviewUrl::App("/history-picker".into()),
+            )
+            .title("VoiceFlow")
+            .inner_size(WIDTH, HEIGHT)
+            .resizable(false)
+            .decorations(false)
+            .transparent(true)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .visible(false);
+            match builder.build() {
+                Ok(window) => {
+                    #[cfg(target_os = "macos")]
+                    setup_panel(&window);
+                    window
+                }
+                Err(e) => {
+                    eprintln!("[voiceflow] Failed to create history picker: {e}");
+                    return;
+                }
+            }
+        }
+    };
+
+    position(app, &window);
+    let _ = app.emit_to(WINDOW_LABEL, "history-picker-opened", ());
+
+    #[cfg(target_os = "macos")]
+    if let Ok(panel) = app.get_webview_panel(WINDOW_LABEL) {
+        panel.show_and_make_key();
+        return;
+    }
+    let _ = window.show();
+    let _ = window.set_focus();
+}
+
+pub fn hide(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        let _ = window.hide();
+    }
+}
+
+pub fn toggle(app: &AppHandle) {
+    let visible = app
+        .get_webview_window(WINDOW_LABEL)
+        .and_then(|window| window.is_visible().ok())
+        .unwrap_or(false);
+    if visible {
+        hide(app);
+        return;
+    }
+    // Authentication blocks until the user answers, so it can't run on the shortcut handler
+    let app = app.clone();
+    thread::spawn(move || match auth::unlock_blocking(&app, "auth.history") {
+        Ok(()) => {
+            let handle = app.clone();
+            let _ = app.run_on_main_thread(move || show(&handle));
+        }
+        Err(error) => eprintln!("[voiceflow] History picker stays locked: {error}"),
+    });
+}
+
+#[tauri::command]
+pub async fn show_history_picker(app: AppHandle) -> Result<(), String> {
+    auth::unlock(&app, "auth.history").await?;
+    show(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn hide_history_picker(app: AppHandle) {
+    hide(&app);
+}
+
+/// Recent transcripts matching `query`, best match first; newest first for an empty query
+#[tauri::command]
+pub async fn search_history(
+    app: AppHandle,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<PickerResult>, String> {
+    auth::unlock(&app, "auth.history").await?;
+    let entries = {
+        let state = app.state::<Mutex<HistoryStore>>();
+        let store = state.lock().map_err(|e| e.to_string())?;
+        store.recent(SEARCH_DEPTH)
+    };
+    Ok(search(entries, &query, limit.unwrap_or(DEFAULT_RESULTS)))
+}
+
+/// Close the picker and paste the chosen entry into the app that was frontmost
+#[tauri::command]
+pub async fn pick_history_entry(app: AppHandle, id: u64) -> Result<(), String> {
+    let text = crate::history::get_entry(&app, id)?.text;
+    hide(&app);
+    // Give the previous app time to become key again before pasting
+    thread::sleep(Duration::from_millis(150));
+    let (strategy, result) = insertion::paste_or_type(&app, Some(&text));
+    insertion::report(&app, None, strategy, Some(text), &result);
+    result
+}
+
+#[tauri::command]
+pub async fn set_history_picker_shortcut(
+    app: AppHandle,
+    shortcut: Option<ShortcutConfig>,
+) -> Result<(), String> {
+    if let Some(config) = &shortcut {
+        config
+            .to_shortcut()
+            .ok_or_else(|| crate::i18n::t("error.invalid_shortcut"))?;
+    }
+    settings::update(&app, |settings| settings.history_picker_shortcut = shortcut)?;
+    crate::register_shortcuts(&app);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u64, text: &str) -> HistoryEntry {
+        HistoryEntry {
+            id,
+            timestamp_ms: id,
+            text: text.to_string(),
+            tag: None,
+            segments: Vec::new(),
+            words: Vec::new(),
+            confidence: None,
+            alternatives: Vec::new(),
+            language: None,
+            insertion: None,
+            app: None,
+            capture: None,
+            origin: None,
+            project: None,
+            title: None,
+            url: None,
+            suggestions: Vec::new(),
+            original_text: None,
+            edits: Vec::new(),
+            latency_ms: None,
+            duration_ms: None,
+            model: None,
+            metadata: Default::default(),
+        }
+    }
+
+    #[test]
+    fn ranks_word_start_and_consecutive_matches_first() {
+        let entries = vec![
+            entry(3, "big update gets eaten today"),
+            entry(2, "Quarterly budget review"),
+            entry(1, "buy a bag of dates"),
+        ];
+        let results = search(entries, "budget", 10);
+        let ids: Vec<u64> = results.iter().map(|result| result.entry.id).collect();
+        assert_eq!(ids, vec![2, 3]);
+        assert_eq!(results[0].matches, vec![10, 11, 12, 13, 14, 15]);
+
+        let all = search(vec![entry(1, "a"), entry(0, "b")], "", 10);
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].entry.id, 1);
+    }
+}
@@ -0,0 +1,550 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, Url};
+
+use crate::history::{HistoryEntry, HistoryStore};
+use crate::{i18n, keychain, settings};
+
+// The sync key and the endpoint password live in the OS keychain; history_sync.json
+// only holds where to sync and this device's id.
+
+const KEY_INFO: &str = "voiceflow-keyinfo.json";
+const MANIFEST: &str = "voiceflow-devices.bin";
+const PBKDF2_ITERATIONS: u32 = 600_000;
+/// Sealed into the key info so another device can tell whether its passphrase matches
+const KEY_CHECK: &[u8] = b"voiceflow-history-sync";
+const MAX_BLOB_BYTES: u64 = 256 * 1024 * 1024;
+/// Tries at adding this device to the device list while other devices change it too
+const MANIFEST_ATTEMPTS: usize = 5;
+const KEY_ACCOUNT: &str = "history-sync-key";
+const PASSWORD_ACCOUNT: &str = "history-sync-password";
+
+type Key = [u8; 32];
+
+/// Public parameters every paired device derives the key from
+#[derive(Debug, Serialize, Deserialize)]
+struct KeyInfo {
+    salt: String,
+    iterations: u32,
+    check: String,
+}
+
+/// Machine-local sync configuration; the passphrase itself is never stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HistorySyncConfig {
+    /// WebDAV collection or bucket URL the encrypted files are stored under
+    endpoint: String,
+    username: Option<String>,
+    /// Only read from files written before secrets moved to the keychain
+    #[serde(default, skip_serializing)]
+    password: Option<String>,
+    device_id: String,
+    #[serde(default, skip_serializing)]
+    key: String,
+    last_sync_ms: Option<u64>,
+}
+
+impl HistorySyncConfig {
+    /// Fill in the key and password from the keychain
+    fn with_secrets(mut self) -> Result<Self, String> {
+        self.key = keychain::get(KEY_ACCOUNT)?.ok_or_else(|| i18n::t("error.sync_key_invalid"))?;
+        self.password = keychain::get(PASSWORD_ACCOUNT)?;
+        Ok(self)
+    }
+
+    fn store_secrets(&self) -> Result<(), String> {
+        keychain::set(KEY_ACCOUNT, &self.key)?;
+        match &self.password {
+            Some(password) => keychain::set(PASSWORD_ACCOUNT, password),
+            None => keychain::delete(PASSWORD_ACCOUNT),
+        }
+    }
+}
+
+fn delete_secrets() -> Result<(), String> {
+    keychain::delete(KEY_ACCOUNT)?;
+    keychain::delete(PASSWORD_ACCOUNT)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistorySyncStatus {
+    pub enabled: bool,
+    pub endpoint: Option<String>,
+    pub device_id: Option<String>,
+    pub last_sync_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncSummary {
+    pub uploaded: usize,
+    pub imported: usize,
+    pub devices: usize,
+}
+
+pub struct HistorySync {
+    config: Option<HistorySyncConfig>,
+    config_path: PathBuf,
+}
+
+impl HistorySync {
+    pub fn new(data_dir: PathBuf) -> Self {
+        let config_path = data_dir.join("history_sync.json");
+        let config: Option<HistorySyncConfig> = fs::read_to_string(&config_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok());
+        let sync = Self {
+            config,
+            config_path,
+        };
+        // Older versions kept the key and password in the file itself
+        if let Some(config) = sync.config.as_ref().filter(|config| !config.key.is_empty()) {
+            match config.store_secrets().and_then(|_| sync.save_config()) {
+                Ok(()) => eprintln!("[voiceflow] Moved history sync secrets to the keychain"),
+                Err(e) => eprintln!("[voiceflow] Failed to move history sync secrets: {e}"),
+            }
+        }
+        sync
+    }
+
+    fn save_config(&self) -> Result<(), String> {
+        match &self.config {
+            Some(config) => {
+                let content = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+                fs::write(&self.config_path, content).map_err(|e| e.to_string())
+            }
+            None => match fs::remove_file(&self.config_path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+                _ => Ok(()),
+            },
+        }
+    }
+
+    fn status(&self) -> HistorySyncStatus {
+        HistorySyncStatus {
+            enabled: self.config.is_some(),
+            endpoint: self.config.as_ref().map(|config| config.endpoint.clone()),
+            device_id: self.config.as_ref().map(|config| config.device_id.clone()),
+            last_sync_ms: self.config.as_ref().and_then(|config| config.last_sync_ms),
+        }
+    }
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N], String> {
+    let mut bytes = [0; N];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| i18n::t("error.sync_random"))?;
+    Ok(bytes)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<Key, String> {
+    let iterations =
+        NonZeroU32::new(iterations).ok_or_else(|| i18n::t("error.sync_key_parameters"))?;
+    let mut key = [0; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+    Ok(key)
+}
+
+fn aead_key(key: &Key) -> Result<LessSafeKey, String> {
+    UnboundKey::new(&CHACHA20_POLY1305, key)
+        .map(LessSafeKey::new)
+        .map_err(|_| i18n::t("error.sync_key_invalid"))
+}
+
+/// Encrypt with a fresh random nonce, bound to `context` (e.g. the device id) as associated data
+fn seal(key: &Key, context: &str, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let nonce = random_bytes::<NONCE_LEN>()?;
+    let mut sealed = plaintext.to_vec();
+    aead_key(key)?
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(context.as_bytes()),
+            &mut sealed,
+        )
+        .map_err(|_| i18n::t("error.sync_encrypt"))?;
+    let mut output = nonce.to_vec();
+    output.extend(sealed);
+    Ok(output)
+}
+
+fn open(key: &Key, context: &str, data: &[u8]) -> Result<Vec<u8>, String> {
+    if data.len() < NONCE_LEN {
+        return Err(i18n::t("error.sync_decrypt"));
+    }
+    let (nonce, sealed) = data.split_at(NONCE_LEN);
+    let nonce =
+        Nonce::try_assume_unique_for_key(nonce).map_err(|_| i18n::t("error.sync_decrypt"))?;
+    let mut buffer = sealed.to_vec();
+    let plaintext = aead_key(key)?
+        .open_in_place(nonce, Aad::from(context.as_bytes()), &mut buffer)
+        .map_err(|_| i18n::t("error.sync_decrypt"))?;
+    Ok(plaintext.to_vec())
+}
+
+/// Plain HTTP GET/PUT against the endpoint, which WebDAV servers and S3-compatible
+/// gateways with credential-based access both accept
+struct Fetched {
+    body: Vec<u8>,
+    /// Version tag for conditional uploads, if the server sends one
+    etag: Option<String>,
+}
+
+struct Remote {
+    endpoint: String,
+    authorization: Option<String>,
+}
+
+impl Remote {
+    fn new(config: &HistorySyncConfig) -> Self {
+        let authorization = config.username.as_ref().map(|username| {
+            let password = config.password.as_deref().unwrap_or_default();
+            format!("Basic {}", BASE64.encode(format!("{username}:{password}")))
+        });
+        Self {
+            endpoint: config.endpoint.trim_end_matches('/').to_string(),
+            authorization,
+        }
+    }
+
+    fn url(&self, name: &str) -> String {
+        format!("{}/{name}", self.endpoint)
+    }
+
+    fn download_failed(name: &str, error: impl std::fmt::Display) -> String {
+        i18n::tf(
+            "error.sync_download",
+            &[("name", name), ("error", &error.to_string())],
+        )
+    }
+
+    fn upload_failed(name: &str, error: impl std::fmt::Display) -> String {
+        i18n::tf(
+            "error.sync_upload",
+            &[("name", name), ("error", &error.to_string())],
+        )
+    }
+
+    /// The file's content and ETag, None when it doesn't exist yet
+    fn fetch(&self, name: &str) -> Result<Option<Fetched>, String> {
+        let mut request = ureq::get(&self.url(name));
+        if let Some(authorization) = &self.authorization {
+            request = request.header("Authorization", authorization);
+        }
+        match request.call() {
+            Ok(mut response) => {
+                let etag = response
+                    .headers()
+                    .get("etag")
+                    .and_then(|etag| etag.to_str().ok())
+                    .map(str::to_string);
+                let body = response
+                    .body_mut()
+                    .with_config()
+                    .limit(MAX_BLOB_BYTES)
+                    .read_to_vec()
+                    .map_err(|e| Self::download_failed(name, e))?;
+                Ok(Some(Fetched { body, etag }))
+            }
+            Err(ureq::Error::StatusCode(404)) => Ok(None),
+            Err(e) => Err(Self::download_failed(name, e)),
+        }
+    }
+
+    fn get(&self, name: &str) -> Result<Option<Vec<u8>>, String> {
+        Ok(self.fetch(name)?.map(|fetched| fetched.body))
+    }
+
+    fn put(&self, name: &str, body: &[u8]) -> Result<(), String> {
+        let mut request = ureq::put(&self.url(name));
+        if let Some(authorization) = &self.authorization {
+            request = request.header("Authorization", authorization);
+        }
+        request
+            .send(body)
+            .map(|_| ())
+            .map_err(|e| Self::upload_failed(name, e))
+    }
+
+    /// Upload only if the file is still the version with `etag`, or still missing
+    /// when None. Returns false when someone else changed it in the meantime.
+    fn put_if_unchanged(
+        &self,
+        name: &str,
+        body: &[u8],
+        etag: Option<&str>,
+    ) -> Result<bool, String> {
+        let mut request = ureq::put(&self.url(name));
+        if let Some(authorization) = &self.authorization {
+            request = request.header("Authorization", authorization);
+        }
+        request = match etag {
+            Some(etag) => request.header("If-Match", etag),
+            None => request.header("If-None-Match", "*"),
+        };
+        match request.send(body) {
+            Ok(_) => Ok(true),
+            Err(ureq::Error::StatusCode(412)) => Ok(false),
+            Err(e) => Err(Self::upload_failed(name, e)),
+        }
+    }
+}
+
+/// Derive the shared key, creating the key info on first use or checking the
+/// passphrase against it when pairing another device
+fn pair(remote: &Remote, passphrase: &str) -> Result<Key, String> {
+    if let Some(content) = remote.get(KEY_INFO)? {
+        let info: KeyInfo = serde_json::from_slice(&content)
+            .map_err(|e| i18n::tf("error.sync_key_info", &[("error", &e.to_string())]))?;
+        let salt = BASE64.decode(&info.salt).map_err(|e| e.to_string())?;
+        let key = derive_key(passphrase, &salt, info.iterations)?;
+        let check = BASE64.decode(&info.check).map_err(|e| e.to_string())?;
+        return match open(&key, KEY_INFO, &check) {
+            Ok(check) if check == KEY_CHECK => Ok(key),
+            _ => Err(i18n::t("error.sync_passphrase_mismatch")),
+        };
+    }
+
+    let salt = random_bytes::<16>()?;
+    let key = derive_key(passphrase, &salt, PBKDF2_ITERATIONS)?;
+    let info = KeyInfo {
+        salt: BASE64.encode(salt),
+        iterations: PBKDF2_ITERATIONS,
+        check: BASE64.encode(seal(&key, KEY_INFO, KEY_CHECK)?),
+    };
+    let content = serde_json::to_vec_pretty(&info).map_err(|e| e.to_string())?;
+    remote.put(KEY_INFO, &content)?;
+    Ok(key)
+}
+
+fn history_blob(device_id: &str) -> String {
+    format!("voiceflow-history-{device_id}.bin")
+}
+
+fn decode_key(config: &HistorySyncConfig) -> Result<Key, String> {
+    BASE64
+        .decode(&config.key)
+        .map_err(|e| e.to_string())?
+        .try_into()
+        .map_err(|_| i18n::t("error.sync_key_invalid"))
+}
+
+/// The paired devices, adding this one if needed. Two devices registering at once
+/// can't drop each other: the list is only replaced while it is unchanged, and read
+/// again otherwise.
+fn register_device(remote: &Remote, key: &Key, device_id: &str) -> Result<Vec<String>, String> {
+    for _ in 0..MANIFEST_ATTEMPTS {
+        let (mut devices, etag): (Vec<String>, _) = match remote.fetch(MANIFEST)? {
+            Some(Fetched { body, etag }) => {
+                let devices =
+                    serde_json::from_slice(&open(key, MANIFEST, &body)?).map_err(|e| {
+                        i18n::tf("error.sync_device_list", &[("error", &e.to_string())])
+                    })?;
+                (devices, etag)
+            }
+            None => (Vec::new(), None),
+        };
+        if devices.iter().any(|device| device == device_id) {
+            return Ok(devices);
+        }
+        let existed = !devices.is_empty() || etag.is_some();
+        devices.push(device_id.to_string());
+        let manifest = serde_json::to_vec(&devices).map_err(|e| e.to_string())?;
+        let sealed = seal(key, MANIFEST, &manifest)?;
+        if existed && etag.is_none() {
+            // The server doesn't send ETags, so a plain upload is the best it allows
+            remote.put(MANIFEST, &sealed)?;
+            return Ok(devices);
+        }
+        if remote.put_if_unchanged(MANIFEST, &sealed, etag.as_deref())? {
+            return Ok(devices);
+        }
+    }
+    Err(i18n::t("error.sync_device_list_busy"))
+}
+
+/// Upload this device's entries and import everyone else's, all encrypted end to end
+fn sync(app: &AppHandle, config: &HistorySyncConfig) -> Result<SyncSummary, String> {
+    let key = decode_key(config)?;
+    let remote = Remote::new(config);
+    let devices = register_device(&remote, &key, &config.device_id)?;
+
+    let local = {
+        let state = app.state::<Mutex<HistoryStore>>();
+        let store = state.lock().map_err(|e| e.to_string())?;
+        store.local_entries()
+    };
+    let plaintext = serde_json::to_vec(&local).map_err(|e| e.to_string())?;
+    remote.put(
+        &history_blob(&config.device_id),
+        &seal(&key, &config.device_id, &plaintext)?,
+    )?;
+
+    let mut imported = 0;
+    for device in devices.iter().filter(|device| **device != config.device_id) {
+        let Some(sealed) = remote.get(&history_blob(device))? else {
+            continue;
+        };
+        let entries: Vec<HistoryEntry> = serde_json::from_slice(&open(&key, device, &sealed)?)
+            .map_err(|e| {
+                i18n::tf(
+                    "error.sync_invalid_history",
+                    &[("device", device), ("error", &e.to_string())],
+                )
+            })?;
+        let state = app.state::<Mutex<HistoryStore>>();
+        let mut store = state.lock().map_err(|e| e.to_string())?;
+        imported += store.import(device, entries)?;
+    }
+
+    Ok(SyncSummary {
+        uploaded: local.len(),
+        imported,
+        devices: devices.len(),
+    })
+}
+
+/// Endpoints must use https, since they get the password and the encrypted history;
+/// plain http is only accepted for a server on this machine
+fn check_endpoint(endpoint: &str) -> Result<(), String> {
+    let url = Url::parse(endpoint).map_err(|_| i18n::t("error.sync_endpoint"))?;
+    let local = matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+    match url.scheme() {
+        "https" => Ok(()),
+        "http" if local => Ok(()),
+        _ => Err(i18n::t("error.sync_endpoint")),
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub async fn get_history_sync_status(app: AppHandle) -> Result<HistorySyncStatus, String> {
+    let state = app.state::<Mutex<HistorySync>>();
+    let sync = state.lock().map_err(|e| e.to_string())?;
+    Ok(sync.status())
+}
+
+/// Pair this device with the endpoint using the shared passphrase
+#[tauri::command]
+pub async fn configure_history_sync(
+    app: AppHandle,
+    endpoint: String,
+    username: Option<String>,
+    password: Option<String>,
+    passphrase: String,
+) -> Result<HistorySyncStatus, String> {
+    check_endpoint(&endpoint)?;
+    if passphrase.chars().count() < 12 {
+        return Err(i18n::t("error.sync_passphrase_short"));
+    }
+
+    let mut config = HistorySyncConfig {
+        endpoint,
+        username,
+        password,
+        device_id: random_bytes::<8>()?
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect(),
+        key: String::new(),
+        last_sync_ms: None,
+    };
+    let remote = Remote::new(&config);
+    let key = tauri::async_runtime::spawn_blocking(move || pair(&remote, &passphrase))
+        .await
+        .map_err(|e| e.to_string())??;
+    config.key = BASE64.encode(key);
+    config.store_secrets()?;
+
+    let state = app.state::<Mutex<HistorySync>>();
+    let mut sync = state.lock().map_err(|e| e.to_string())?;
+    sync.config = Some(config);
+    sync.save_config()?;
+    Ok(sync.status())
+}
+
+#[tauri::command]
+pub async fn disable_history_sync(app: AppHandle) -> Result<HistorySyncStatus, String> {
+    let state = app.state::<Mutex<HistorySync>>();
+    let mut sync = state.lock().map_err(|e| e.to_string())?;
+    sync.config = None;
+    sync.save_config()?;
+    delete_secrets()?;
+    Ok(sync.status())
+}
+
+#[tauri::command]
+pub async fn sync_now(app: AppHandle) -> Result<SyncSummary, String> {
+    if settings::current(&app).disable_history {
+        return Err(i18n::t("error.sync_history_disabled"));
+    }
+    let config = {
+        let state = app.state::<Mutex<HistorySync>>();
+        let sync = state.lock().map_err(|e| e.to_string())?;
+        sync.config
+            .clone()
+            .ok_or_else(|| i18n::t("error.sync_not_configured"))?
+    };
+
+    let handle = app.clone();
+    let summary =
+        tauri::async_runtime::spawn_blocking(move || sync(&handle, &config.with_secrets()?))
+            .await
+            .map_err(|e| e.to_string())??;
+
+    let state = app.state::<Mutex<HistorySync>>();
+    let mut sync = state.lock().map_err(|e| e.to_string())?;
+    if let Some(config) = sync.config.as_mut() {
+        config.last_sync_ms = Some(now_ms());
+    }
+    sync.save_config()?;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_data_only_opens_with_the_same_key_and_context() {
+        let key = derive_key("correct horse battery", b"salt", 1).unwrap();
+        let sealed = seal(&key, "device-a", b"entries").unwrap();
+        assert_eq!(open(&key, "device-a", &sealed).unwrap(), b"entries");
+        assert!(open(&key, "device-b", &sealed).is_err());
+
+        let other = derive_key("wrong horse battery", b"salt", 1).unwrap();
+        assert!(open(&other, "device-a", &sealed).is_err());
+    }
+
+    #[test]
+    fn requires_https_except_on_this_machine() {
+        assert!(check_endpoint("https://dav.example.com/voiceflow").is_ok());
+        assert!(check_endpoint("http://localhost:8080/dav").is_ok());
+        assert!(check_endpoint("http://dav.example.com/voiceflow").is_err());
+        assert!(check_endpoint("ftp://localhost/dav").is_err());
+        assert!(check_endpoint("not a url").is_err());
+    }
+}
@@ -0,0 +1,212 @@
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::history::HistoryStore;
+use crate::session::now_ms;
+use crate::{demo, diagnostics};
+
+/// First run waits for startup to settle
+const FIRST_RUN_AFTER: Duration = Duration::from_secs(60);
+const RUN_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+/// Logs above this size are rotated, keeping one previous file
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+/// Leftovers younger than this may still be in use by a running write or download
+const STALE_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+/// Partial writes and downloads
+const TEMPORARY_EXTENSIONS: [&str; 4] = ["tmp", "part", "partial", "incomplete"];
+
+static LAST_REPORT: Mutex<Option<HousekeepingReport>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HousekeepingReport {
+    pub finished_ms: u64,
+    pub rotated_logs: usize,
+    pub removed_files: usize,
+    pub reclaimed_bytes: u64,
+}
+
+impl HousekeepingReport {
+    fn removed(&mut self, path: &Path, len: u64) {
+        match fs::remove_file(path) {
+            Ok(()) => {
+                self.removed_files += 1;
+                self.reclaimed_bytes += len;
+            }
+            Err(e) => eprintln!("[voiceflow] Failed to remove {}: {e}", path.display()),
+        }
+    }
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extensions.contains(&extension.to_lowercase().as_str()))
+}
+
+fn is_stale(metadata: &fs::Metadata, now: SystemTime) -> bool {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| now.duration_since(modified).ok())
+        .is_some_and(|age| age >= STALE_AFTER)
+}
+
+/// Move oversized logs to `<name>.log.1`, replacing the previous rotation
+fn rotate_logs(dir: &Path, report: &mut HousekeepingReport) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if !metadata.is_file() || !has_extension(&path, &["log"]) || metadata.len() <= MAX_LOG_BYTES
+        {
+            continue;
+        }
+        let rotated = path.with_extension("log.1");
+        let replaced = fs::metadata(&rotated).map(|old| old.len()).unwrap_or(0);
+        match fs::rename(&path, &rotated) {
+            Ok(()) => {
+                report.rotated_logs += 1;
+                report.reclaimed_bytes += replaced;
+            }
+            Err(e) => eprintln!("[voiceflow] Failed to rotate {}: {e}", path.display()),
+        }
+    }
+}
+
+/// Remove files with one of `extensions` that haven't been touched for a day,
+/// without following symlinks out of `dir`
+fn prune(dir: &Path, extensions: &[&str], now: SystemTime, report: &mut HousekeepingReport) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        let Ok(metadata) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if metadata.is_dir() {
+            prune(&path, extensions, now, report);
+        } else if metadata.is_file() && has_extension(&path, extensions) && is_stale(&metadata, now)
+        {
+            report.removed(&path, metadata.len());
+        }
+    }
+}
+
+/// Compact the history database, returning how many bytes it shrank by
+fn vacuum_history(app: &AppHandle) -> u64 {
+    let Ok(path) = demo::data_dir(app).map(|dir| dir.join("history.db")) else {
+        return 0;
+    };
+    let size = || {
+        fs::metadata(&path)
+            .map(|metadata| metadata.len())
+            .unwrap_or(0)
+    };
+    let before = size();
+    let state = app.state::<Mutex<HistoryStore>>();
+    let vacuumed = state
+        .lock()
+        .map_err(|e| e.to_string())
+        .and_then(|store| store.vacuum());
+    if let Err(e) = vacuumed {
+        eprintln!("[voiceflow] Failed to vacuum history: {e}");
+    }
+    before.saturating_sub(size())
+}
+
+pub fn run(app: &AppHandle) -> HousekeepingReport {
+    let now = SystemTime::now();
+    let mut report = HousekeepingReport::default();
+    if let Ok(log_dir) = app.path().app_log_dir() {
+        rotate_logs(&log_dir, &mut report);
+    }
+    for root in diagnostics::data_roots(app) {
+        prune(&root, &TEMPORARY_EXTENSIONS, now, &mut report);
+    }
+    // Audio in the cache is only ever a by-product of a dictation that has finished
+    if let Ok(cache_dir) = app.path().app_cache_dir() {
+        prune(&cache_dir, &["wav"], now, &mut report);
+    }
+    report.reclaimed_bytes += vacuum_history(app);
+    report.finished_ms = now_ms();
+
+    if report.removed_files > 0 || report.rotated_logs > 0 {
+        eprintln!(
+            "[voiceflow] Housekeeping reclaimed {} bytes ({} files removed, {} logs rotated)",
+            report.reclaimed_bytes, report.removed_files, report.rotated_logs
+        );
+    }
+    if let Ok(mut last) = LAST_REPORT.lock() {
+        *last = Some(report.clone());
+    }
+    let _ = app.emit("housekeeping-finished", &report);
+    report
+}
+
+pub fn last_report() -> Option<HousekeepingReport> {
+    LAST_REPORT.lock().ok().and_then(|last| last.clone())
+}
+
+pub fn start(app: AppHandle) {
+    thread::spawn(move || {
+        thread::sleep(FIRST_RUN_AFTER);
+        loop {
+            run(&app);
+            thread::sleep(RUN_INTERVAL);
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn run_housekeeping(app: AppHandle) -> Result<HousekeepingReport, String> {
+    tauri::async_runtime::spawn_blocking(move || run(&app))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prunes_stale_temporaries_and_rotates_large_logs() {
+        let dir = std::env::temp_dir().join(format!("voiceflow-housekeeping-test-{}", now_ms()));
+        let nested = dir.join("models");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(nested.join("model.bin.part"), [0; 10]).unwrap();
+        fs::write(dir.join("history.json.tmp"), [0; 5]).unwrap();
+        fs::write(dir.join("history.json"), [0; 7]).unwrap();
+        fs::write(
+            dir.join("voiceflow.log"),
+            vec![0; MAX_LOG_BYTES as usize + 1],
+        )
+        .unwrap();
+        fs::write(dir.join("voiceflow.log.1"), [0; 3]).unwrap();
+
+        let mut report = HousekeepingReport::default();
+        prune(&dir, &TEMPORARY_EXTENSIONS, SystemTime::now(), &mut report);
+        assert_eq!(report.removed_files, 0);
+
+        let later = SystemTime::now() + STALE_AFTER;
+        prune(&dir, &TEMPORARY_EXTENSIONS, later, &mut report);
+        rotate_logs(&dir, &mut report);
+        assert_eq!(report.removed_files, 2);
+        assert_eq!(report.rotated_logs, 1);
+        assert_eq!(report.reclaimed_bytes, 18);
+        assert!(dir.join("history.json").exists());
+        assert!(!dir.join("voiceflow.log").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,1479 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+use crate::{settings, tray};
+
+pub const DEFAULT_LOCALE: &str = "en";
+pub const SUPPORTED_LOCALES: &[&str] = &["en", "de", "es", "fr", "it"];
+
+// Resolved language for Rust-side strings, set from settings or the OS at startup
+static LOCALE: Mutex<String> = Mutex::new(String::new());
+
+type Table = &'static [(&'static str, &'static str)];
+
+const EN: Table = &[
+    ("tray.record", "Hold {shortcut} to Record"),
+    (
+        "tray.record_toggle",
+        "Press {shortcut} to Start or Stop Recording",
+    ),
+    ("tray.pause", "Pause Dictation"),
+    ("tray.scratchpad", "Scratchpad"),
+    ("tray.profile", "Profile"),
+    ("tray.settings", "Settings..."),
+    ("tray.quit", "Quit VoiceFlow"),
+    ("window.scratchpad", "Scratchpad"),
+    ("window.switch_button", "Switch Button"),
+    ("error.invalid_shortcut", "Invalid shortcut configuration"),
+    (
+        "error.register_shortcut",
+        "Failed to register shortcut: {error}",
+    ),
+    (
+        "error.template_not_found",
+        "Prompt template \"{name}\" not found",
+    ),
+    ("error.app_profile_app", "Choose an app for the profile"),
+    (
+        "error.no_previous_dictation",
+        "There is no previous dictation to correct",
+    ),
+    (
+        "error.confidence_range",
+        "Confidence threshold must be between 0 and 1",
+    ),
+    ("error.template_name_empty", "Template name cannot be empty"),
+    (
+        "error.wake_phrase",
+        "Wake phrase must contain letters or digits",
+    ),
+    (
+        "error.no_voice_command",
+        "No voice command matches \"{transcript}\"",
+    ),
+    ("error.no_input_device", "No input device available"),
+    ("error.no_captured_audio", "No recorded audio available"),
+    (
+        "error.clipboard_not_updated",
+        "Transcript did not reach the clipboard",
+    ),
+    (
+        "error.clipboard_restore_delay",
+        "The clipboard restore delay must be between 50 and 10000 ms",
+    ),
+    (
+        "error.profile_name_empty",
+        "Profile name must not be empty or padded with spaces",
+    ),
+    (
+        "error.profile_name_invalid",
+        "\"{name}\" is not a valid profile name",
+    ),
+    ("error.profile_exists", "Profile \"{name}\" already exists"),
+    ("error.profile_not_found", "Profile \"{name}\" not found"),
+    (
+        "error.default_profile_delete",
+        "The default profile can't be deleted",
+    ),
+    (
+        "error.delete_active_profile",
+        "Switch to another profile before deleting this one",
+    ),
+    (
+        "error.history_entry_not_found",
+        "History entry {id} not found",
+    ),
+    (
+        "error.shortcut_capture_permission",
+        "Keyboard capture needs Accessibility or Input Monitoring permission",
+    ),
+    (
+        "error.shortcut_capture_timeout",
+        "No key combination was pressed",
+    ),
+    (
+        "error.shortcut_key_unsupported",
+        "{key} can't be used as a shortcut",
+    ),
+    (
+        "error.shortcut_needs_modifier",
+        "Shortcuts need at least one modifier key",
+    ),
+    (
+        "error.shortcut_capture_unsupported",
+        "Shortcut capture is only supported on macOS",
+    ),
+    ("error.snippet_name_empty", "Snippet name cannot be empty"),
+    (
+        "error.snippet_trigger_empty",
+        "Snippet trigger phrase cannot be empty",
+    ),
+    (
+        "error.symbol_mapping_empty",
+        "Symbol phrases and symbols cannot be empty",
+    ),
+    (
+        "error.snippet_trigger_taken",
+        "Snippet \"{name}\" already uses this trigger phrase",
+    ),
+    ("error.snippet_not_found", "Snippet \"{name}\" not found"),
+    (
+        "error.correction_incognito",
+        "There is no dictation to correct in incognito mode",
+    ),
+    (
+        "error.correction_not_inserted",
+        "The last dictation wasn't inserted into an app, so there is nothing to correct",
+    ),
+    ("auth.history", "show your dictation history"),
+    ("auth.export", "export your dictations"),
+    ("auth.settings", "change the history lock"),
+    ("error.authentication_failed", "Authentication failed"),
+    (
+        "error.authentication_setting",
+        "Turn the history lock on or off with its own switch",
+    ),
+    (
+        "error.authentication_unavailable",
+        "System authentication isn't available on this device",
+    ),
+    ("a11y.recording", "Recording"),
+    ("a11y.transcribing", "Transcribing"),
+    ("a11y.pasted", "Pasted"),
+    ("a11y.paste_failed", "Couldn't paste: {error}"),
+    ("a11y.dictation_failed", "Dictation failed: {error}"),
+    (
+        "error.dwell_time",
+        "Dwell time must be between 0.3 and 10 seconds",
+    ),
+    (
+        "error.gesture_timing",
+        "Tap time must be 0.1 to 1 second and the double-press gap 0.15 to 1.5 seconds",
+    ),
+    ("weekly_summary.title", "Your week of dictation"),
+    (
+        "weekly_summary.words",
+        "{words} words in {dictations} dictations",
+    ),
+    ("weekly_summary.top_apps", "Mostly in {apps}"),
+    ("weekly_summary.latency", "Average latency: {latency} ms"),
+    (
+        "error.weekly_summary_schedule",
+        "Pick a weekday from Monday to Sunday and an hour from 0 to 23",
+    ),
+    (
+        "error.unknown_input_device",
+        "No input device with id \"{id}\"",
+    ),
+    (
+        "error.unknown_feature_flag",
+        "No feature flag named \"{name}\"",
+    ),
+    (
+        "error.paste_elevated_window",
+        "Can't paste into a window running as administrator. The text is on the clipboard.",
+    ),
+    (
+        "error.paste_tool_missing",
+        "Install {tools} to paste dictated text. The text is on the clipboard.",
+    ),
+    (
+        "error.type_tool_missing",
+        "Install {tools} to type dictated text.",
+    ),
+    (
+        "error.unknown_plugin",
+        "No plugin named \"{id}\" is installed",
+    ),
+    (
+        "error.unknown_script",
+        "No script named \"{name}\" is loaded",
+    ),
+    (
+        "error.provider_not_running",
+        "Provider \"{name}\" isn't running",
+    ),
+    (
+        "error.unknown_provider",
+        "No provider named \"{name}\" is configured",
+    ),
+    (
+        "error.url_actions_disabled",
+        "Workflow links are turned off in settings",
+    ),
+    (
+        "error.unknown_url_action",
+        "Unknown link action \"{action}\"",
+    ),
+    (
+        "error.url_action_declined",
+        "The link was not allowed to receive the dictation",
+    ),
+    ("error.keychain", "Couldn't access the keychain: {error}"),
+    ("error.sync_random", "Secure random numbers are unavailable"),
+    ("error.sync_key_parameters", "Invalid key parameters"),
+    (
+        "error.sync_key_invalid",
+        "The sync key is missing or invalid, set up history sync again",
+    ),
+    ("error.sync_encrypt", "Encryption failed"),
+    (
+        "error.sync_decrypt",
+        "Decryption failed, the data was changed or the key is wrong",
+    ),
+    ("error.sync_download", "Failed to download {name}: {error}"),
+    ("error.sync_upload", "Failed to upload {name}: {error}"),
+    ("error.sync_key_info", "Invalid key info: {error}"),
+    (
+        "error.sync_passphrase_mismatch",
+        "The passphrase doesn't match the one your other devices use",
+    ),
+    ("error.sync_device_list", "Invalid device list: {error}"),
+    (
+        "error.sync_device_list_busy",
+        "The device list keeps changing, try syncing again",
+    ),
+    (
+        "error.sync_invalid_history",
+        "Invalid history from {device}: {error}",
+    ),
+    (
+        "error.sync_endpoint",
+        "The sync endpoint must be an https:// URL, http:// is only allowed for localhost",
+    ),
+    (
+        "error.sync_passphrase_short",
+        "Use a passphrase of at least 12 characters",
+    ),
+    (
+        "error.sync_history_disabled",
+        "History is disabled, so there is nothing to sync",
+    ),
+    (
+        "error.sync_not_configured",
+        "History sync is not set up on this device",
+    ),
+    (
+        "error.voice_server_connection",
+        "Voice server connection failed: {error}",
+    ),
+    (
+        "error.voice_server_message",
+        "Invalid voice server message: {error}",
+    ),
+    (
+        "error.voice_server_closed",
+        "Voice server closed the connection",
+    ),
+    (
+        "error.voice_server_send",
+        "Failed to send to voice server: {error}",
+    ),
+    (
+        "error.voice_server_connect",
+        "Failed to connect to voice server: {error}",
+    ),
+    ("error.stream_audio", "Failed to stream audio: {error}"),
+    ("error.osascript", "Failed to run osascript: {error}"),
+    (
+        "error.inline_corrections_unsupported",
+        "Inline corrections are not supported on this platform",
+    ),
+    (
+        "error.synthetic_copy_unsupported",
+        "Synthetic copy is only supported on macOS",
+    ),
+    ("error.keyboard_event", "Failed to create keyboard event"),
+    (
+        "error.typing_unsupported",
+        "Typing text is not supported on this platform",
+    ),
+    (
+        "error.accessibility_insertion_unsupported",
+        "Accessibility insertion is only supported on macOS",
+    ),
+    ("error.key_chord_invalid", "Invalid key chord \"{chord}\""),
+    ("error.unknown_modifier", "Unknown modifier \"{modifier}\""),
+    ("error.unknown_key", "Unknown key \"{key}\""),
+    (
+        "error.key_chords_unsupported",
+        "Key chords are only supported on macOS",
+    ),
+    ("error.send_paste", "Failed to send Ctrl+V: {error}"),
+    ("error.type_text", "Failed to type text: {error}"),
+    (
+        "error.delete_words",
+        "Failed to delete the previous words: {error}",
+    ),
+    (
+        "error.no_display",
+        "No X11 or Wayland display to send keys to",
+    ),
+    ("error.run_tool", "Failed to run {tool}: {error}"),
+    ("error.tool_failed", "{tool} failed: {error}"),
+    ("error.import_json", "Invalid JSON export: {error}"),
+    (
+        "error.import_no_entries",
+        "No list of entries found in the JSON export",
+    ),
+    ("error.read_file", "Failed to read {path}: {error}"),
+    (
+        "error.import_no_dictations",
+        "No dictations with a timestamp found in this file",
+    ),
+    ("error.locate_app", "Failed to locate the app: {error}"),
+    ("error.home_unset", "HOME is not set"),
+    (
+        "error.run_key_read",
+        "Failed to read the Run key (error {code})",
+    ),
+    (
+        "error.run_key_write",
+        "Failed to write the Run key (error {code})",
+    ),
+    (
+        "error.run_key_update",
+        "Failed to update the Run key (error {code})",
+    ),
+    ("error.date_range", "Date out of range"),
+    ("error.invalid_date", "Invalid date \"{date}\": {error}"),
+    ("error.copy_digest", "Failed to copy digest: {error}"),
+    (
+        "error.captions_no_timing",
+        "Transcript has no timing information",
+    ),
+    (
+        "error.export_captions",
+        "Failed to export captions: {error}",
+    ),
+    (
+        "error.data_dir_unavailable",
+        "App data directory is unavailable",
+    ),
+    ("error.export", "Failed to export: {error}"),
+    ("error.already_recording", "A dictation is already running"),
+    (
+        "error.dictation_blocked",
+        "Dictation is turned off for the current app",
+    ),
+    (
+        "error.dictation_shortcut_required",
+        "The dictation shortcut can't be removed",
+    ),
+    (
+        "error.shortcut_in_use",
+        "This shortcut is already used by another action",
+    ),
+    (
+        "error.output_command_empty",
+        "Enter a command to send dictations to",
+    ),
+    (
+        "error.output_command_timeout",
+        "The command timeout must be between 1 and 600 seconds",
+    ),
+    (
+        "error.speech_unavailable",
+        "Text-to-speech isn't available: {error}",
+    ),
+    (
+        "error.read_back_rate",
+        "The read-back rate must be between 0.5 and 2",
+    ),
+    (
+        "error.search_template",
+        "The search URL must start with http:// or https:// and contain {text}",
+    ),
+    ("error.target_app_launch", "Couldn't open {app}: {error}"),
+    (
+        "error.target_app_not_frontmost",
+        "{app} didn't come to the front, so the transcript wasn't pasted",
+    ),
+    ("key.ctrl", "Ctrl"),
+    ("key.alt", "Alt"),
+    ("key.shift", "Shift"),
+    ("key.space", "Space"),
+];
+
+const DE: Table = &[
+    ("tray.record", "{shortcut} gedrückt halten zum Aufnehmen"),
+    ("tray.record_toggle", "{shortcut} drücken zum Starten oder Beenden der Aufnahme"),
+    ("tray.pause", "Diktat pausieren"),
+    ("tray.scratchpad", "Notizblock"),
+    ("tray.profile", "Profil"),
+    ("tray.settings", "Einstellungen…"),
+    ("tray.quit", "VoiceFlow beenden"),
+    ("window.scratchpad", "Notizblock"),
+    ("window.switch_button", "Schalter-Taste"),
+    ("error.invalid_shortcut", "Ungültige Tastenkombination"),
+    (
+        "error.register_shortcut",
+        "Tastenkombination konnte nicht registriert werden: {error}",
+    ),
+    (
+        "error.template_not_found",
+        "Prompt-Vorlage „{name}“ nicht gefunden",
+    ),
+    ("error.app_profile_app", "Wähle eine App für das Profil"),
+    (
+        "error.no_previous_dictation",
+        "Es gibt kein vorheriges Diktat zum Korrigieren",
+    ),
+    (
+        "error.confidence_range",
+        "Der Konfidenz-Schwellenwert muss zwischen 0 und 1 liegen",
+    ),
+    (
+        "error.template_name_empty",
+        "Der Vorlagenname darf nicht leer sein",
+    ),
+    (
+        "error.wake_phrase",
+        "Das Aktivierungswort muss Buchstaben oder Ziffern enthalten",
+    ),
+    (
+        "error.no_voice_command",
+        "Kein Sprachbefehl passt zu „{transcript}“",
+    ),
+    ("error.no_input_device", "Kein Eingabegerät verfügbar"),
+    ("error.no_captured_audio", "Keine Aufnahme verfügbar"),
+    (
+        "error.clipboard_not_updated",
+        "Die Transkription ist nicht in der Zwischenablage angekommen",
+    ),
+    (
+        "error.clipboard_restore_delay",
+        "Die Verzögerung beim Wiederherstellen der Zwischenablage muss zwischen 50 und 10000 ms liegen",
+    ),
+    (
+        "error.profile_name_empty",
+        "Der Profilname darf nicht leer sein oder mit Leerzeichen beginnen oder enden",
+    ),
+    (
+        "error.profile_name_invalid",
+        "„{name}“ ist kein gültiger Profilname",
+    ),
+    ("error.profile_exists", "Profil „{name}“ existiert bereits"),
+    ("error.profile_not_found", "Profil „{name}“ nicht gefunden"),
+    (
+        "error.default_profile_delete",
+        "Das Standardprofil kann nicht gelöscht werden",
+    ),
+    (
+        "error.delete_active_profile",
+        "Wechsle zu einem anderen Profil, bevor du dieses löschst",
+    ),
+    (
+        "error.history_entry_not_found",
+        "Verlaufseintrag {id} nicht gefunden",
+    ),
+    (
+        "error.shortcut_capture_permission",
+        "Für die Tastaturerfassung ist die Berechtigung für Bedienungshilfen oder Eingabeüberwachung nötig",
+    ),
+    (
+        "error.shortcut_capture_timeout",
+        "Es wurde keine Tastenkombination gedrückt",
+    ),
+    (
+        "error.shortcut_key_unsupported",
+        "{key} kann nicht als Tastenkombination verwendet werden",
+    ),
+    (
+        "error.shortcut_needs_modifier",
+        "Tastenkombinationen brauchen mindestens eine Sondertaste",
+    ),
+    (
+        "error.shortcut_capture_unsupported",
+        "Das Erfassen von Tastenkombinationen wird nur unter macOS unterstützt",
+    ),
+    ("error.snippet_name_empty", "Der Name des Textbausteins darf nicht leer sein"),
+    ("error.snippet_trigger_empty", "Die Auslösephrase des Textbausteins darf nicht leer sein"),
+    ("error.symbol_mapping_empty", "Phrase und Symbol dürfen nicht leer sein"),
+    (
+        "error.snippet_trigger_taken",
+        "Der Textbaustein „{name}“ verwendet diese Auslösephrase bereits",
+    ),
+    ("error.snippet_not_found", "Textbaustein „{name}“ nicht gefunden"),
+    ("error.correction_incognito", "Im Inkognito-Modus gibt es kein Diktat zum Korrigieren"),
+    ("error.correction_not_inserted", "Das letzte Diktat wurde in keine App eingefügt, es gibt also nichts zu korrigieren"),
+    ("auth.history", "deinen Diktatverlauf anzeigen"),
+    ("auth.export", "deine Diktate exportieren"),
+    ("auth.settings", "die Verlaufssperre ändern"),
+    ("error.authentication_failed", "Authentifizierung fehlgeschlagen"),
+    ("error.authentication_setting", "Schalte die Verlaufssperre mit ihrem eigenen Schalter ein oder aus"),
+    ("error.authentication_unavailable", "Die Systemauthentifizierung ist auf diesem Gerät nicht verfügbar"),
+    ("a11y.recording", "Aufnahme läuft"),
+    ("a11y.transcribing", "Wird transkribiert"),
+    ("a11y.pasted", "Eingefügt"),
+    ("a11y.paste_failed", "Einfügen fehlgeschlagen: {error}"),
+    ("a11y.dictation_failed", "Diktat fehlgeschlagen: {error}"),
+    ("error.dwell_time", "Verweilzeit muss zwischen 0,3 und 10 Sekunden liegen"),
+    ("error.gesture_timing", "Die Tippdauer muss 0,1 bis 1 Sekunde und der Doppeldruck-Abstand 0,15 bis 1,5 Sekunden betragen"),
+    ("weekly_summary.title", "Deine Diktierwoche"),
+    ("weekly_summary.words", "{words} Wörter in {dictations} Diktaten"),
+    ("weekly_summary.top_apps", "Vor allem in {apps}"),
+    ("weekly_summary.latency", "Durchschnittliche Latenz: {latency} ms"),
+    (
+        "error.weekly_summary_schedule",
+        "Wähle einen Wochentag von Montag bis Sonntag und eine Stunde von 0 bis 23",
+    ),
+    ("error.unknown_input_device", "Kein Eingabegerät mit der ID \"{id}\""),
+    ("error.unknown_feature_flag", "Kein Feature-Flag namens \"{name}\""),
+    (
+        "error.paste_elevated_window",
+        "In ein als Administrator ausgeführtes Fenster kann nicht eingefügt werden. Der Text ist in der Zwischenablage.",
+    ),
+    (
+        "error.paste_tool_missing",
+        "Installiere {tools}, um diktierten Text einzufügen. Der Text ist in der Zwischenablage.",
+    ),
+    ("error.type_tool_missing", "Installiere {tools}, um diktierten Text zu tippen."),
+    ("error.unknown_plugin", "Kein Plugin namens \"{id}\" installiert"),
+    ("error.unknown_script", "Kein Skript namens \"{name}\" geladen"),
+    ("error.provider_not_running", "Provider \"{name}\" läuft nicht"),
+    ("error.unknown_provider", "Kein Provider namens \"{name}\" eingerichtet"),
+    ("error.url_actions_disabled", "Workflow-Links sind in den Einstellungen ausgeschaltet"),
+    ("error.unknown_url_action", "Unbekannte Link-Aktion \"{action}\""),
+    ("error.url_action_declined", "Der Link durfte das Diktat nicht erhalten"),
+    ("error.keychain", "Zugriff auf den Schlüsselbund fehlgeschlagen: {error}"),
+    ("error.sync_random", "Sichere Zufallszahlen sind nicht verfügbar"),
+    ("error.sync_key_parameters", "Ungültige Schlüsselparameter"),
+    ("error.sync_key_invalid", "Der Sync-Schlüssel fehlt oder ist ungültig, richte die Verlaufssynchronisierung neu ein"),
+    ("error.sync_encrypt", "Verschlüsselung fehlgeschlagen"),
+    ("error.sync_decrypt", "Entschlüsselung fehlgeschlagen, die Daten wurden verändert oder der Schlüssel ist falsch"),
+    ("error.sync_download", "{name} konnte nicht heruntergeladen werden: {error}"),
+    ("error.sync_upload", "{name} konnte nicht hochgeladen werden: {error}"),
+    ("error.sync_key_info", "Ungültige Schlüsselinformationen: {error}"),
+    ("error.sync_passphrase_mismatch", "Die Passphrase stimmt nicht mit der deiner anderen Geräte überein"),
+    ("error.sync_device_list", "Ungültige Geräteliste: {error}"),
+    ("error.sync_device_list_busy", "Die Geräteliste ändert sich ständig, versuche es erneut"),
+    ("error.sync_invalid_history", "Ungültiger Verlauf von {device}: {error}"),
+    ("error.sync_endpoint", "Der Sync-Endpunkt muss eine https://-URL sein, http:// ist nur für localhost erlaubt"),
+    ("error.sync_passphrase_short", "Verwende eine Passphrase mit mindestens 12 Zeichen"),
+    ("error.sync_history_disabled", "Der Verlauf ist deaktiviert, es gibt nichts zu synchronisieren"),
+    ("error.sync_not_configured", "Die Verlaufssynchronisierung ist auf diesem Gerät nicht eingerichtet"),
+    ("error.voice_server_connection", "Verbindung zum Sprachserver fehlgeschlagen: {error}"),
+    ("error.voice_server_message", "Ungültige Nachricht vom Sprachserver: {error}"),
+    ("error.voice_server_closed", "Der Sprachserver hat die Verbindung geschlossen"),
+    ("error.voice_server_send", "Senden an den Sprachserver fehlgeschlagen: {error}"),
+    ("error.voice_server_connect", "Verbindung zum Sprachserver nicht möglich: {error}"),
+    ("error.stream_audio", "Audio konnte nicht übertragen werden: {error}"),
+    ("error.osascript", "osascript konnte nicht ausgeführt werden: {error}"),
+    ("error.inline_corrections_unsupported", "Direkte Korrekturen werden auf dieser Plattform nicht unterstützt"),
+    ("error.synthetic_copy_unsupported", "Simuliertes Kopieren wird nur unter macOS unterstützt"),
+    ("error.keyboard_event", "Tastaturereignis konnte nicht erstellt werden"),
+    ("error.typing_unsupported", "Text eingeben wird auf dieser Plattform nicht unterstützt"),
+    ("error.accessibility_insertion_unsupported", "Einfügen über die Bedienungshilfen wird nur unter macOS unterstützt"),
+    ("error.key_chord_invalid", "Ungültige Tastenkombination „{chord}“"),
+    ("error.unknown_modifier", "Unbekannte Sondertaste „{modifier}“"),
+    ("error.unknown_key", "Unbekannte Taste „{key}“"),
+    ("error.key_chords_unsupported", "Tastenkombinationen werden nur unter macOS unterstützt"),
+    ("error.send_paste", "Strg+V konnte nicht gesendet werden: {error}"),
+    ("error.type_text", "Text konnte nicht eingegeben werden: {error}"),
+    ("error.delete_words", "Die vorherigen Wörter konnten nicht gelöscht werden: {error}"),
+    ("error.no_display", "Keine X11- oder Wayland-Anzeige zum Senden von Tasten"),
+    ("error.run_tool", "{tool} konnte nicht ausgeführt werden: {error}"),
+    ("error.tool_failed", "{tool} ist fehlgeschlagen: {error}"),
+    ("error.import_json", "Ungültiger JSON-Export: {error}"),
+    ("error.import_no_entries", "Im JSON-Export wurde keine Liste von Einträgen gefunden"),
+    ("error.read_file", "{path} konnte nicht gelesen werden: {error}"),
+    ("error.import_no_dictations", "In dieser Datei wurden keine Diktate mit Zeitstempel gefunden"),
+    ("error.locate_app", "Die App wurde nicht gefunden: {error}"),
+    ("error.home_unset", "HOME ist nicht gesetzt"),
+    ("error.run_key_read", "Der Run-Schlüssel konnte nicht gelesen werden (Fehler {code})"),
+    ("error.run_key_write", "Der Run-Schlüssel konnte nicht geschrieben werden (Fehler {code})"),
+    ("error.run_key_update", "Der Run-Schlüssel konnte nicht aktualisiert werden (Fehler {code})"),
+    ("error.date_range", "Datum außerhalb des gültigen Bereichs"),
+    ("error.invalid_date", "Ungültiges Datum „{date}“: {error}"),
+    ("error.copy_digest", "Zusammenfassung konnte nicht kopiert werden: {error}"),
+    ("error.captions_no_timing", "Das Transkript enthält keine Zeitangaben"),
+    ("error.export_captions", "Untertitel konnten nicht exportiert werden: {error}"),
+    ("error.data_dir_unavailable", "Das App-Datenverzeichnis ist nicht verfügbar"),
+    ("error.export", "Export fehlgeschlagen: {error}"),
+    ("error.already_recording", "Es läuft bereits ein Diktat"),
+    ("error.dictation_blocked", "Diktieren ist für die aktuelle App ausgeschaltet"),
+    ("error.dictation_shortcut_required", "Das Diktier-Tastenkürzel kann nicht entfernt werden"),
+    ("error.shortcut_in_use", "Dieses Tastenkürzel wird bereits für eine andere Aktion verwendet"),
+    ("error.output_command_empty", "Gib einen Befehl ein, an den Diktate gesendet werden"),
+    (
+        "error.output_command_timeout",
+        "Das Zeitlimit für den Befehl muss zwischen 1 und 600 Sekunden liegen",
+    ),
+    ("error.speech_unavailable", "Sprachausgabe ist nicht verfügbar: {error}"),
+    ("error.read_back_rate", "Die Vorlesegeschwindigkeit muss zwischen 0,5 und 2 liegen"),
+    (
+        "error.search_template",
+        "Die Such-URL muss mit http:// oder https:// beginnen und {text} enthalten",
+    ),
+    ("error.target_app_launch", "{app} konnte nicht geöffnet werden: {error}"),
+    (
+        "error.target_app_not_frontmost",
+        "{app} ist nicht in den Vordergrund gekommen, daher wurde die Transkription nicht eingefügt",
+    ),
+    ("key.ctrl", "Strg"),
+    ("key.alt", "Alt"),
+    ("key.shift", "Umschalt"),
+    ("key.space", "Leertaste"),
+];
+
+const ES: Table = &[
+    ("tray.record", "Mantén {shortcut} para grabar"),
+    ("tray.record_toggle", "Pulsa {shortcut} para iniciar o detener la grabación"),
+    ("tray.pause", "Pausar dictado"),
+    ("tray.scratchpad", "Borrador"),
+    ("tray.profile", "Perfil"),
+    ("tray.settings", "Ajustes…"),
+    ("tray.quit", "Salir de VoiceFlow"),
+    ("window.scratchpad", "Borrador"),
+    ("window.switch_button", "Botón de conmutador"),
+    ("error.invalid_shortcut", "Configuración de atajo no válida"),
+    (
+        "error.register_shortcut",
+        "No se pudo registrar el atajo: {error}",
+    ),
+    (
+        "error.template_not_found",
+        "No se encontró la plantilla de prompt «{name}»",
+    ),
+    ("error.app_profile_app", "Elige una app para el perfil"),
+    (
+        "error.no_previous_dictation",
+        "No hay ningún dictado anterior que corregir",
+    ),
+    (
+        "error.confidence_range",
+        "El umbral de confianza debe estar entre 0 y 1",
+    ),
+    (
+        "error.template_name_empty",
+        "El nombre de la plantilla no puede estar vacío",
+    ),
+    (
+        "error.wake_phrase",
+        "La frase de activación debe contener letras o dígitos",
+    ),
+    (
+        "error.no_voice_command",
+        "Ningún comando de voz coincide con «{transcript}»",
+    ),
+    (
+        "error.no_input_device",
+        "No hay ningún dispositivo de entrada disponible",
+    ),
+    ("error.no_captured_audio", "No hay audio grabado"),
+    (
+        "error.clipboard_not_updated",
+        "La transcripción no llegó al portapapeles",
+    ),
+    (
+        "error.clipboard_restore_delay",
+        "El retraso para restaurar el portapapeles debe estar entre 50 y 10000 ms",
+    ),
+    (
+        "error.profile_name_empty",
+        "El nombre del perfil no puede estar vacío ni empezar o terminar con espacios",
+    ),
+    (
+        "error.profile_name_invalid",
+        "«{name}» no es un nombre de perfil válido",
+    ),
+    ("error.profile_exists", "El perfil «{name}» ya existe"),
+    (
+        "error.profile_not_found",
+        "No se encontró el perfil «{name}»",
+    ),
+    (
+        "error.default_profile_delete",
+        "El perfil predeterminado no se puede eliminar",
+    ),
+    (
+        "error.delete_active_profile",
+        "Cambia a otro perfil antes de eliminar este",
+    ),
+    (
+        "error.history_entry_not_found",
+        "No se encontró la entrada del historial {id}",
+    ),
+    (
+        "error.shortcut_capture_permission",
+        "La captura de teclado necesita permiso de Accesibilidad o Monitorización de entrada",
+    ),
+    (
+        "error.shortcut_capture_timeout",
+        "No se pulsó ninguna combinación de teclas",
+    ),
+    (
+        "error.shortcut_key_unsupported",
+        "{key} no se puede usar como atajo",
+    ),
+    (
+        "error.shortcut_needs_modifier",
+        "Los atajos necesitan al menos una tecla modificadora",
+    ),
+    (
+        "error.shortcut_capture_unsupported",
+        "La captura de atajos solo está disponible en macOS",
+    ),
+    (
+        "error.snippet_name_empty",
+        "El nombre del fragmento no puede estar vacío",
+    ),
+    (
+        "error.snippet_trigger_empty",
+        "La frase de activación del fragmento no puede estar vacía",
+    ),
+    ("error.symbol_mapping_empty", "La frase y el símbolo no pueden estar vacíos"),
+    (
+        "error.snippet_trigger_taken",
+        "El fragmento «{name}» ya usa esta frase de activación",
+    ),
+    (
+        "error.snippet_not_found",
+        "No se encontró el fragmento «{name}»",
+    ),
+    (
+        "error.correction_incognito",
+        "No hay ningún dictado que corregir en modo incógnito",
+    ),
+    ("error.correction_not_inserted", "El último dictado no se insertó en ninguna aplicación, así que no hay nada que corregir"),
+    ("auth.history", "mostrar tu historial de dictados"),
+    ("auth.export", "exportar tus dictados"),
+    ("auth.settings", "cambiar el bloqueo del historial"),
+    ("error.authentication_failed", "Error de autenticación"),
+    ("error.authentication_setting", "Activa o desactiva el bloqueo del historial con su propio interruptor"),
+    (
+        "error.authentication_unavailable",
+        "La autenticación del sistema no está disponible en este dispositivo",
+    ),
+    ("a11y.recording", "Grabando"),
+    ("a11y.transcribing", "Transcribiendo"),
+    ("a11y.pasted", "Pegado"),
+    ("a11y.paste_failed", "No se pudo pegar: {error}"),
+    ("a11y.dictation_failed", "El dictado falló: {error}"),
+    (
+        "error.dwell_time",
+        "El tiempo de permanencia debe estar entre 0,3 y 10 segundos",
+    ),
+    ("error.gesture_timing", "La duración del toque debe ser de 0,1 a 1 segundo y el intervalo de doble pulsación de 0,15 a 1,5 segundos"),
+    ("weekly_summary.title", "Tu semana de dictado"),
+    ("weekly_summary.words", "{words} palabras en {dictations} dictados"),
+    ("weekly_summary.top_apps", "Sobre todo en {apps}"),
+    ("weekly_summary.latency", "Latencia media: {latency} ms"),
+    ("error.weekly_summary_schedule", "Elige un día de lunes a domingo y una hora de 0 a 23"),
+    ("error.unknown_input_device", "No hay ningún dispositivo de entrada con el id \"{id}\""),
+    ("error.unknown_feature_flag", "No hay ninguna marca de función llamada \"{name}\""),
+    (
+        "error.paste_elevated_window",
+        "No se puede pegar en una ventana que se ejecuta como administrador. El texto está en el portapapeles.",
+    ),
+    (
+        "error.paste_tool_missing",
+        "Instala {tools} para pegar el texto dictado. El texto está en el portapapeles.",
+    ),
+    ("error.type_tool_missing", "Instala {tools} para escribir el texto dictado."),
+    ("error.unknown_plugin", "No hay ningún plugin instalado llamado \"{id}\""),
+    ("error.unknown_script", "No hay ningún script cargado llamado \"{name}\""),
+    ("error.provider_not_running", "El proveedor \"{name}\" no está en ejecución"),
+    ("error.unknown_provider", "No hay ningún proveedor configurado llamado \"{name}\""),
+    (
+        "error.url_actions_disabled",
+        "Los enlaces de flujos de trabajo están desactivados en los ajustes",
+    ),
+    ("error.unknown_url_action", "Acción de enlace desconocida \"{action}\""),
+    ("error.url_action_declined", "El enlace no tiene permiso para recibir el dictado"),
+    ("error.keychain", "No se pudo acceder al llavero: {error}"),
+    ("error.sync_random", "No hay números aleatorios seguros disponibles"),
+    ("error.sync_key_parameters", "Parámetros de clave no válidos"),
+    ("error.sync_key_invalid", "La clave de sincronización falta o no es válida, vuelve a configurar la sincronización del historial"),
+    ("error.sync_encrypt", "Error al cifrar"),
+    ("error.sync_decrypt", "Error al descifrar, los datos se modificaron o la clave es incorrecta"),
+    ("error.sync_download", "No se pudo descargar {name}: {error}"),
+    ("error.sync_upload", "No se pudo subir {name}: {error}"),
+    ("error.sync_key_info", "Información de clave no válida: {error}"),
+    ("error.sync_passphrase_mismatch", "La frase de contraseña no coincide con la de tus otros dispositivos"),
+    ("error.sync_device_list", "Lista de dispositivos no válida: {error}"),
+    ("error.sync_device_list_busy", "La lista de dispositivos sigue cambiando, vuelve a sincronizar"),
+    ("error.sync_invalid_history", "Historial no válido de {device}: {error}"),
+    ("error.sync_endpoint", "El punto de sincronización debe ser una URL https://, http:// solo se permite para localhost"),
+    ("error.sync_passphrase_short", "Usa una frase de contraseña de al menos 12 caracteres"),
+    ("error.sync_history_disabled", "El historial está desactivado, no hay nada que sincronizar"),
+    ("error.sync_not_configured", "La sincronización del historial no está configurada en este dispositivo"),
+    ("error.voice_server_connection", "Falló la conexión con el servidor de voz: {error}"),
+    ("error.voice_server_message", "Mensaje no válido del servidor de voz: {error}"),
+    ("error.voice_server_closed", "El servidor de voz cerró la conexión"),
+    ("error.voice_server_send", "No se pudo enviar al servidor de voz: {error}"),
+    ("error.voice_server_connect", "No se pudo conectar con el servidor de voz: {error}"),
+    ("error.stream_audio", "No se pudo transmitir el audio: {error}"),
+    ("error.osascript", "No se pudo ejecutar osascript: {error}"),
+    ("error.inline_corrections_unsupported", "Las correcciones en línea no son compatibles con esta plataforma"),
+    ("error.synthetic_copy_unsupported", "La copia simulada solo funciona en macOS"),
+    ("error.keyboard_event", "No se pudo crear el evento de teclado"),
+    ("error.typing_unsupported", "Escribir texto no es compatible con esta plataforma"),
+    ("error.accessibility_insertion_unsupported", "La inserción por accesibilidad solo funciona en macOS"),
+    ("error.key_chord_invalid", "Combinación de teclas no válida «{chord}»"),
+    ("error.unknown_modifier", "Modificador desconocido «{modifier}»"),
+    ("error.unknown_key", "Tecla desconocida «{key}»"),
+    ("error.key_chords_unsupported", "Las combinaciones de teclas solo funcionan en macOS"),
+    ("error.send_paste", "No se pudo enviar Ctrl+V: {error}"),
+    ("error.type_text", "No se pudo escribir el texto: {error}"),
+    ("error.delete_words", "No se pudieron borrar las palabras anteriores: {error}"),
+    ("error.no_display", "No hay ninguna pantalla X11 o Wayland a la que enviar teclas"),
+    ("error.run_tool", "No se pudo ejecutar {tool}: {error}"),
+    ("error.tool_failed", "{tool} falló: {error}"),
+    ("error.import_json", "Exportación JSON no válida: {error}"),
+    ("error.import_no_entries", "No se encontró ninguna lista de entradas en la exportación JSON"),
+    ("error.read_file", "No se pudo leer {path}: {error}"),
+    ("error.import_no_dictations", "No se encontraron dictados con marca de tiempo en este archivo"),
+    ("error.locate_app", "No se pudo localizar la aplicación: {error}"),
+    ("error.home_unset", "HOME no está definido"),
+    ("error.run_key_read", "No se pudo leer la clave Run (error {code})"),
+    ("error.run_key_write", "No se pudo escribir la clave Run (error {code})"),
+    ("error.run_key_update", "No se pudo actualizar la clave Run (error {code})"),
+    ("error.date_range", "Fecha fuera de rango"),
+    ("error.invalid_date", "Fecha no válida «{date}»: {error}"),
+    ("error.copy_digest", "No se pudo copiar el resumen: {error}"),
+    ("error.captions_no_timing", "La transcripción no tiene información de tiempos"),
+    ("error.export_captions", "No se pudieron exportar los subtítulos: {error}"),
+    ("error.data_dir_unavailable", "El directorio de datos de la aplicación no está disponible"),
+    ("error.export", "No se pudo exportar: {error}"),
+    ("error.already_recording", "Ya hay un dictado en curso"),
+    ("error.dictation_blocked", "El dictado está desactivado para la app actual"),
+    ("error.dictation_shortcut_required", "El atajo de dictado no se puede quitar"),
+    ("error.shortcut_in_use", "Este atajo ya lo usa otra acción"),
+    ("error.output_command_empty", "Introduce un comando al que enviar los dictados"),
+    (
+        "error.output_command_timeout",
+        "El tiempo límite del comando debe estar entre 1 y 600 segundos",
+    ),
+    ("error.speech_unavailable", "La síntesis de voz no está disponible: {error}"),
+    ("error.read_back_rate", "La velocidad de lectura debe estar entre 0,5 y 2"),
+    (
+        "error.search_template",
+        "La URL de búsqueda debe empezar por http:// o https:// y contener {text}",
+    ),
+    ("error.target_app_launch", "No se pudo abrir {app}: {error}"),
+    (
+        "error.target_app_not_frontmost",
+        "{app} no pasó al primer plano, así que la transcripción no se pegó",
+    ),
+    ("key.ctrl", "Ctrl"),
+    ("key.alt", "Alt"),
+    ("key.shift", "Mayús"),
+    ("key.space", "Espacio"),
+];
+
+const FR: Table = &[
+    ("tray.record", "Maintenir {shortcut} pour enregistrer"),
+    ("tray.record_toggle", "Appuyer sur {shortcut} pour démarrer ou arrêter l'enregistrement"),
+    ("tray.pause", "Suspendre la dictée"),
+    ("tray.scratchpad", "Brouillon"),
+    ("tray.profile", "Profil"),
+    ("tray.settings", "Réglages…"),
+    ("tray.quit", "Quitter VoiceFlow"),
+    ("window.scratchpad", "Brouillon"),
+    ("window.switch_button", "Bouton contacteur"),
+    (
+        "error.invalid_shortcut",
+        "Configuration de raccourci invalide",
+    ),
+    (
+        "error.register_shortcut",
+        "Impossible d’enregistrer le raccourci : {error}",
+    ),
+    (
+        "error.template_not_found",
+        "Modèle de prompt « {name} » introuvable",
+    ),
+    ("error.app_profile_app", "Choisissez une app pour le profil"),
+    (
+        "error.no_previous_dictation",
+        "Aucune dictée précédente à corriger",
+    ),
+    (
+        "error.confidence_range",
+        "Le seuil de confiance doit être compris entre 0 et 1",
+    ),
+    (
+        "error.template_name_empty",
+        "Le nom du modèle ne peut pas être vide",
+    ),
+    (
+        "error.wake_phrase",
+        "La phrase d’activation doit contenir des lettres ou des chiffres",
+    ),
+    (
+        "error.no_voice_command",
+        "Aucune commande vocale ne correspond à « {transcript} »",
+    ),
+    (
+        "error.no_input_device",
+        "Aucun périphérique d’entrée disponible",
+    ),
+    ("error.no_captured_audio", "Aucun audio enregistré"),
+    (
+        "error.clipboard_not_updated",
+        "La transcription n’a pas atteint le presse-papiers",
+    ),
+    (
+        "error.clipboard_restore_delay",
+        "Le délai de restauration du presse-papiers doit être compris entre 50 et 10000 ms",
+    ),
+    (
+        "error.profile_name_empty",
+        "Le nom du profil ne peut pas être vide ni entouré d’espaces",
+    ),
+    (
+        "error.profile_name_invalid",
+        "« {name} » n’est pas un nom de profil valide",
+    ),
+    ("error.profile_exists", "Le profil « {name} » existe déjà"),
+    ("error.profile_not_found", "Profil « {name} » introuvable"),
+    (
+        "error.default_profile_delete",
+        "Le profil par défaut ne peut pas être supprimé",
+    ),
+    (
+        "error.delete_active_profile",
+        "Passez à un autre profil avant de supprimer celui-ci",
+    ),
+    (
+        "error.history_entry_not_found",
+        "Entrée d’historique {id} introuvable",
+    ),
+    (
+        "error.shortcut_capture_permission",
+        "La capture du clavier nécessite l’autorisation Accessibilité ou Surveillance de l’entrée",
+    ),
+    (
+        "error.shortcut_capture_timeout",
+        "Aucune combinaison de touches n’a été pressée",
+    ),
+    (
+        "error.shortcut_key_unsupported",
+        "{key} ne peut pas servir de raccourci",
+    ),
+    (
+        "error.shortcut_needs_modifier",
+        "Les raccourcis nécessitent au moins une touche de modification",
+    ),
+    (
+        "error.shortcut_capture_unsupported",
+        "La capture de raccourcis n’est prise en charge que sur macOS",
+    ),
+    (
+        "error.snippet_name_empty",
+        "Le nom de l’extrait ne peut pas être vide",
+    ),
+    (
+        "error.snippet_trigger_empty",
+        "La phrase de déclenchement de l’extrait ne peut pas être vide",
+    ),
+    ("error.symbol_mapping_empty", "La phrase et le symbole ne peuvent pas être vides"),
+    (
+        "error.snippet_trigger_taken",
+        "L’extrait « {name} » utilise déjà cette phrase de déclenchement",
+    ),
+    ("error.snippet_not_found", "Extrait « {name} » introuvable"),
+    (
+        "error.correction_incognito",
+        "Aucune dictée à corriger en mode navigation privée",
+    ),
+    ("error.correction_not_inserted", "La dernière dictée n'a été insérée dans aucune application, il n'y a donc rien à corriger"),
+    ("auth.history", "afficher l’historique de vos dictées"),
+    ("auth.export", "exporter vos dictées"),
+    ("auth.settings", "modifier le verrouillage de l’historique"),
+    ("error.authentication_failed", "Échec de l’authentification"),
+    ("error.authentication_setting", "Activez ou désactivez le verrouillage de l’historique avec son propre bouton"),
+    (
+        "error.authentication_unavailable",
+        "L’authentification système n’est pas disponible sur cet appareil",
+    ),
+    ("a11y.recording", "Enregistrement"),
+    ("a11y.transcribing", "Transcription"),
+    ("a11y.pasted", "Collé"),
+    ("a11y.paste_failed", "Impossible de coller : {error}"),
+    ("a11y.dictation_failed", "Échec de la dictée : {error}"),
+    (
+        "error.dwell_time",
+        "Le temps de maintien doit être compris entre 0,3 et 10 secondes",
+    ),
+    ("error.gesture_timing", "La durée d'un appui court doit être de 0,1 à 1 seconde et l'intervalle du double appui de 0,15 à 1,5 seconde"),
+    ("weekly_summary.title", "Votre semaine de dictée"),
+    ("weekly_summary.words", "{words} mots en {dictations} dictées"),
+    ("weekly_summary.top_apps", "Surtout dans {apps}"),
+    ("weekly_summary.latency", "Latence moyenne : {latency} ms"),
+    (
+        "error.weekly_summary_schedule",
+        "Choisissez un jour du lundi au dimanche et une heure de 0 à 23",
+    ),
+    ("error.unknown_input_device", "Aucun périphérique d’entrée avec l’identifiant \"{id}\""),
+    ("error.unknown_feature_flag", "Aucun indicateur de fonctionnalité nommé \"{name}\""),
+    (
+        "error.paste_elevated_window",
+        "Impossible de coller dans une fenêtre exécutée en tant qu’administrateur. Le texte est dans le presse-papiers.",
+    ),
+    (
+        "error.paste_tool_missing",
+        "Installez {tools} pour coller le texte dicté. Le texte est dans le presse-papiers.",
+    ),
+    ("error.type_tool_missing", "Installez {tools} pour saisir le texte dicté."),
+    ("error.unknown_plugin", "Aucun plugin nommé \"{id}\" n’est installé"),
+    ("error.unknown_script", "Aucun script nommé \"{name}\" n’est chargé"),
+    ("error.provider_not_running", "Le fournisseur \"{name}\" n’est pas lancé"),
+    ("error.unknown_provider", "Aucun fournisseur nommé \"{name}\" n’est configuré"),
+    ("error.url_actions_disabled", "Les liens de workflow sont désactivés dans les réglages"),
+    ("error.unknown_url_action", "Action de lien inconnue \"{action}\""),
+    ("error.url_action_declined", "Le lien n’a pas été autorisé à recevoir la dictée"),
+    ("error.keychain", "Impossible d'accéder au trousseau : {error}"),
+    ("error.sync_random", "Les nombres aléatoires sécurisés sont indisponibles"),
+    ("error.sync_key_parameters", "Paramètres de clé non valides"),
+    ("error.sync_key_invalid", "La clé de synchronisation est absente ou non valide, configurez à nouveau la synchronisation de l'historique"),
+    ("error.sync_encrypt", "Échec du chiffrement"),
+    ("error.sync_decrypt", "Échec du déchiffrement, les données ont été modifiées ou la clé est incorrecte"),
+    ("error.sync_download", "Impossible de télécharger {name} : {error}"),
+    ("error.sync_upload", "Impossible d'envoyer {name} : {error}"),
+    ("error.sync_key_info", "Informations de clé non valides : {error}"),
+    ("error.sync_passphrase_mismatch", "La phrase secrète ne correspond pas à celle de vos autres appareils"),
+    ("error.sync_device_list", "Liste d'appareils non valide : {error}"),
+    ("error.sync_device_list_busy", "La liste d'appareils change sans cesse, réessayez la synchronisation"),
+    ("error.sync_invalid_history", "Historique non valide de {device} : {error}"),
+    ("error.sync_endpoint", "Le point de synchronisation doit être une URL https://, http:// n'est autorisé que pour localhost"),
+    ("error.sync_passphrase_short", "Utilisez une phrase secrète d'au moins 12 caractères"),
+    ("error.sync_history_disabled", "L'historique est désactivé, il n'y a rien à synchroniser"),
+    ("error.sync_not_configured", "La synchronisation de l'historique n'est pas configurée sur cet appareil"),
+    ("error.voice_server_connection", "La connexion au serveur vocal a échoué : {error}"),
+    ("error.voice_server_message", "Message du serveur vocal invalide : {error}"),
+    ("error.voice_server_closed", "Le serveur vocal a fermé la connexion"),
+    ("error.voice_server_send", "Échec de l'envoi au serveur vocal : {error}"),
+    ("error.voice_server_connect", "Impossible de se connecter au serveur vocal : {error}"),
+    ("error.stream_audio", "Impossible de diffuser l'audio : {error}"),
+    ("error.osascript", "Impossible d'exécuter osascript : {error}"),
+    ("error.inline_corrections_unsupported", "Les corrections en ligne ne sont pas prises en charge sur cette plateforme"),
+    ("error.synthetic_copy_unsupported", "La copie simulée n'est prise en charge que sur macOS"),
+    ("error.keyboard_event", "Impossible de créer l'événement clavier"),
+    ("error.typing_unsupported", "La saisie de texte n'est pas prise en charge sur cette plateforme"),
+    ("error.accessibility_insertion_unsupported", "L'insertion par l'accessibilité n'est prise en charge que sur macOS"),
+    ("error.key_chord_invalid", "Combinaison de touches invalide « {chord} »"),
+    ("error.unknown_modifier", "Touche de modification inconnue « {modifier} »"),
+    ("error.unknown_key", "Touche inconnue « {key} »"),
+    ("error.key_chords_unsupported", "Les combinaisons de touches ne sont prises en charge que sur macOS"),
+    ("error.send_paste", "Impossible d'envoyer Ctrl+V : {error}"),
+    ("error.type_text", "Impossible de saisir le texte : {error}"),
+    ("error.delete_words", "Impossible de supprimer les mots précédents : {error}"),
+    ("error.no_display", "Aucun affichage X11 ou Wayland auquel envoyer les touches"),
+    ("error.run_tool", "Impossible d'exécuter {tool} : {error}"),
+    ("error.tool_failed", "{tool} a échoué : {error}"),
+    ("error.import_json", "Export JSON invalide : {error}"),
+    ("error.import_no_entries", "Aucune liste d'entrées trouvée dans l'export JSON"),
+    ("error.read_file", "Impossible de lire {path} : {error}"),
+    ("error.import_no_dictations", "Aucune dictée horodatée trouvée dans ce fichier"),
+    ("error.locate_app", "Impossible de localiser l'application : {error}"),
+    ("error.home_unset", "HOME n'est pas défini"),
+    ("error.run_key_read", "Impossible de lire la clé Run (erreur {code})"),
+    ("error.run_key_write", "Impossible d'écrire la clé Run (erreur {code})"),
+    ("error.run_key_update", "Impossible de mettre à jour la clé Run (erreur {code})"),
+    ("error.date_range", "Date hors limites"),
+    ("error.invalid_date", "Date invalide « {date} » : {error}"),
+    ("error.copy_digest", "Impossible de copier le résumé : {error}"),
+    ("error.captions_no_timing", "La transcription ne contient aucune information de minutage"),
+    ("error.export_captions", "Impossible d'exporter les sous-titres : {error}"),
+    ("error.data_dir_unavailable", "Le dossier de données de l'application est indisponible"),
+    ("error.export", "Échec de l'exportation : {error}"),
+    ("error.already_recording", "Une dictée est déjà en cours"),
+    ("error.dictation_blocked", "La dictée est désactivée pour l’app actuelle"),
+    ("error.dictation_shortcut_required", "Le raccourci de dictée ne peut pas être supprimé"),
+    ("error.shortcut_in_use", "Ce raccourci est déjà utilisé par une autre action"),
+    ("error.output_command_empty", "Saisis une commande à laquelle envoyer les dictées"),
+    (
+        "error.output_command_timeout",
+        "Le délai de la commande doit être compris entre 1 et 600 secondes",
+    ),
+    ("error.speech_unavailable", "La synthèse vocale n'est pas disponible : {error}"),
+    ("error.read_back_rate", "La vitesse de lecture doit être comprise entre 0,5 et 2"),
+    (
+        "error.search_template",
+        "L'URL de recherche doit commencer par http:// ou https:// et contenir {text}",
+    ),
+    ("error.target_app_launch", "Impossible d'ouvrir {app} : {error}"),
+    (
+        "error.target_app_not_frontmost",
+        "{app} n'est pas passé au premier plan, la transcription n'a donc pas été collée",
+    ),
+    ("key.ctrl", "Ctrl"),
+    ("key.alt", "Alt"),
+    ("key.shift", "Maj"),
+    ("key.space", "Espace"),
+];
+
+const IT: Table = &[
+    ("tray.record", "Tieni premuto {shortcut} per registrare"),
+    ("tray.record_toggle", "Premi {shortcut} per avviare o fermare la registrazione"),
+    ("tray.pause", "Sospendi dettatura"),
+    ("tray.scratchpad", "Blocco note"),
+    ("tray.profile", "Profilo"),
+    ("tray.settings", "Impostazioni…"),
+    ("tray.quit", "Esci da VoiceFlow"),
+    ("window.scratchpad", "Blocco note"),
+    ("window.switch_button", "Pulsante sensore"),
+    (
+        "error.invalid_shortcut",
+        "Configurazione della scorciatoia non valida",
+    ),
+    (
+        "error.register_shortcut",
+        "Impossibile registrare la scorciatoia: {error}",
+    ),
+    (
+        "error.template_not_found",
+        "Modello di prompt «{name}» non trovato",
+    ),
+    ("error.app_profile_app", "Scegli un'app per il profilo"),
+    (
+        "error.no_previous_dictation",
+        "Nessuna dettatura precedente da correggere",
+    ),
+    (
+        "error.confidence_range",
+        "La soglia di confidenza deve essere compresa tra 0 e 1",
+    ),
+    (
+        "error.template_name_empty",
+        "Il nome del modello non può essere vuoto",
+    ),
+    (
+        "error.wake_phrase",
+        "La frase di attivazione deve contenere lettere o cifre",
+    ),
+    (
+        "error.no_voice_command",
+        "Nessun comando vocale corrisponde a «{transcript}»",
+    ),
+    (
+        "error.no_input_device",
+        "Nessun dispositivo di input disponibile",
+    ),
+    ("error.no_captured_audio", "Nessun audio registrato"),
+    (
+        "error.clipboard_not_updated",
+        "La trascrizione non è arrivata negli appunti",
+    ),
+    (
+        "error.clipboard_restore_delay",
+        "Il ritardo di ripristino degli appunti deve essere compreso tra 50 e 10000 ms",
+    ),
+    (
+        "error.profile_name_empty",
+        "Il nome del profilo non può essere vuoto né iniziare o finire con spazi",
+    ),
+    (
+        "error.profile_name_invalid",
+        "«{name}» non è un nome di profilo valido",
+    ),
+    ("error.profile_exists", "Il profilo «{name}» esiste già"),
+    ("error.profile_not_found", "Profilo «{name}» non trovato"),
+    (
+        "error.default_profile_delete",
+        "Il profilo predefinito non può essere eliminato",
+    ),
+    (
+        "error.delete_active_profile",
+        "Passa a un altro profilo prima di eliminare questo",
+    ),
+    (
+        "error.history_entry_not_found",
+        "Voce della cronologia {id} non trovata",
+    ),
+    (
+        "error.shortcut_capture_permission",
+        "L’acquisizione della tastiera richiede il permesso di Accessibilità o Monitoraggio input",
+    ),
+    (
+        "error.shortcut_capture_timeout",
+        "Non è stata premuta alcuna combinazione di tasti",
+    ),
+    (
+        "error.shortcut_key_unsupported",
+        "{key} non può essere usato come scorciatoia",
+    ),
+    (
+        "error.shortcut_needs_modifier",
+        "Le scorciatoie richiedono almeno un tasto modificatore",
+    ),
+    (
+        "error.shortcut_capture_unsupported",
+        "L’acquisizione delle scorciatoie è supportata solo su macOS",
+    ),
+    (
+        "error.snippet_name_empty",
+        "Il nome dello snippet non può essere vuoto",
+    ),
+    (
+        "error.snippet_trigger_empty",
+        "La frase di attivazione dello snippet non può essere vuota",
+    ),
+    ("error.symbol_mapping_empty", "La frase e il simbolo non possono essere vuoti"),
+    (
+        "error.snippet_trigger_taken",
+        "Lo snippet «{name}» usa già questa frase di attivazione",
+    ),
+    ("error.snippet_not_found", "Snippet «{name}» non trovato"),
+    (
+        "error.correction_incognito",
+        "Non c'è nessuna dettatura da correggere in modalità incognito",
+    ),
+    ("error.correction_not_inserted", "L'ultima dettatura non è stata inserita in nessuna app, quindi non c'è niente da correggere"),
+    ("auth.history", "mostrare la cronologia delle dettature"),
+    ("auth.export", "esportare le tue dettature"),
+    ("auth.settings", "modificare il blocco della cronologia"),
+    ("error.authentication_failed", "Autenticazione non riuscita"),
+    ("error.authentication_setting", "Attiva o disattiva il blocco della cronologia con il suo interruttore"),
+    (
+        "error.authentication_unavailable",
+        "L'autenticazione di sistema non è disponibile su questo dispositivo",
+    ),
+    ("a11y.recording", "Registrazione"),
+    ("a11y.transcribing", "Trascrizione"),
+    ("a11y.pasted", "Incollato"),
+    ("a11y.paste_failed", "Impossibile incollare: {error}"),
+    ("a11y.dictation_failed", "Dettatura non riuscita: {error}"),
+    (
+        "error.dwell_time",
+        "Il tempo di permanenza deve essere compreso tra 0,3 e 10 secondi",
+    ),
+    ("error.gesture_timing", "La durata del tocco deve essere tra 0,1 e 1 secondo e l'intervallo della doppia pressione tra 0,15 e 1,5 secondi"),
+    ("weekly_summary.title", "La tua settimana di dettatura"),
+    ("weekly_summary.words", "{words} parole in {dictations} dettature"),
+    ("weekly_summary.top_apps", "Soprattutto in {apps}"),
+    ("weekly_summary.latency", "Latenza media: {latency} ms"),
+    ("error.weekly_summary_schedule", "Scegli un giorno da lunedì a domenica e un'ora da 0 a 23"),
+    ("error.unknown_input_device", "Nessun dispositivo di input con id \"{id}\""),
+    ("error.unknown_feature_flag", "Nessun flag di funzionalità chiamato \"{name}\""),
+    (
+        "error.paste_elevated_window",
+        "Impossibile incollare in una finestra eseguita come amministratore. Il testo è negli appunti.",
+    ),
+    (
+        "error.paste_tool_missing",
+        "Installa {tools} per incollare il testo dettato. Il testo è negli appunti.",
+    ),
+    ("error.type_tool_missing", "Installa {tools} per digitare il testo dettato."),
+    ("error.unknown_plugin", "Nessun plugin chiamato \"{id}\" è installato"),
+    ("error.unknown_script", "Nessuno script chiamato \"{name}\" è caricato"),
+    ("error.provider_not_running", "Il provider \"{name}\" non è in esecuzione"),
+    ("error.unknown_provider", "Nessun provider chiamato \"{name}\" è configurato"),
+    (
+        "error.url_actions_disabled",
+        "I link dei flussi di lavoro sono disattivati nelle impostazioni",
+    ),
+    ("error.unknown_url_action", "Azione del link sconosciuta \"{action}\""),
+    ("error.url_action_declined", "Il link non è stato autorizzato a ricevere la dettatura"),
+    ("error.keychain", "Impossibile accedere al portachiavi: {error}"),
+    ("error.sync_random", "I numeri casuali sicuri non sono disponibili"),
+    ("error.sync_key_parameters", "Parametri della chiave non validi"),
+    ("error.sync_key_invalid", "La chiave di sincronizzazione manca o non è valida, configura di nuovo la sincronizzazione della cronologia"),
+    ("error.sync_encrypt", "Crittografia non riuscita"),
+    ("error.sync_decrypt", "Decrittografia non riuscita, i dati sono stati modificati o la chiave è errata"),
+    ("error.sync_download", "Impossibile scaricare {name}: {error}"),
+    ("error.sync_upload", "Impossibile caricare {name}: {error}"),
+    ("error.sync_key_info", "Informazioni sulla chiave non valide: {error}"),
+    ("error.sync_passphrase_mismatch", "La passphrase non corrisponde a quella degli altri dispositivi"),
+    ("error.sync_device_list", "Elenco dispositivi non valido: {error}"),
+    ("error.sync_device_list_busy", "L'elenco dei dispositivi continua a cambiare, riprova la sincronizzazione"),
+    ("error.sync_invalid_history", "Cronologia non valida da {device}: {error}"),
+    ("error.sync_endpoint", "L'endpoint di sincronizzazione deve essere un URL https://, http:// è consentito solo per localhost"),
+    ("error.sync_passphrase_short", "Usa una passphrase di almeno 12 caratteri"),
+    ("error.sync_history_disabled", "La cronologia è disattivata, non c'è nulla da sincronizzare"),
+    ("error.sync_not_configured", "La sincronizzazione della cronologia non è configurata su questo dispositivo"),
+    ("error.voice_server_connection", "Connessione al server vocale non riuscita: {error}"),
+    ("error.voice_server_message", "Messaggio del server vocale non valido: {error}"),
+    ("error.voice_server_closed", "Il server vocale ha chiuso la connessione"),
+    ("error.voice_server_send", "Invio al server vocale non riuscito: {error}"),
+    ("error.voice_server_connect", "Impossibile connettersi al server vocale: {error}"),
+    ("error.stream_audio", "Impossibile trasmettere l'audio: {error}"),
+    ("error.osascript", "Impossibile eseguire osascript: {error}"),
+    ("error.inline_corrections_unsupported", "Le correzioni in linea non sono supportate su questa piattaforma"),
+    ("error.synthetic_copy_unsupported", "La copia simulata è supportata solo su macOS"),
+    ("error.keyboard_event", "Impossibile creare l'evento della tastiera"),
+    ("error.typing_unsupported", "La digitazione del testo non è supportata su questa piattaforma"),
+    ("error.accessibility_insertion_unsupported", "L'inserimento tramite accessibilità è supportato solo su macOS"),
+    ("error.key_chord_invalid", "Combinazione di tasti non valida «{chord}»"),
+    ("error.unknown_modifier", "Modificatore sconosciuto «{modifier}»"),
+    ("error.unknown_key", "Tasto sconosciuto «{key}»"),
+    ("error.key_chords_unsupported", "Le combinazioni di tasti sono supportate solo su macOS"),
+    ("error.send_paste", "Impossibile inviare Ctrl+V: {error}"),
+    ("error.type_text", "Impossibile digitare il testo: {error}"),
+    ("error.delete_words", "Impossibile eliminare le parole precedenti: {error}"),
+    ("error.no_display", "Nessun display X11 o Wayland a cui inviare i tasti"),
+    ("error.run_tool", "Impossibile eseguire {tool}: {error}"),
+    ("error.tool_failed", "{tool} non è riuscito: {error}"),
+    ("error.import_json", "Esportazione JSON non valida: {error}"),
+    ("error.import_no_entries", "Nessun elenco di voci trovato nell'esportazione JSON"),
+    ("error.read_file", "Impossibile leggere {path}: {error}"),
+    ("error.import_no_dictations", "Nessuna dettatura con data e ora trovata in questo file"),
+    ("error.locate_app", "Impossibile trovare l'app: {error}"),
+    ("error.home_unset", "HOME non è impostata"),
+    ("error.run_key_read", "Impossibile leggere la chiave Run (errore {code})"),
+    ("error.run_key_write", "Impossibile scrivere la chiave Run (errore {code})"),
+    ("error.run_key_update", "Impossibile aggiornare la chiave Run (errore {code})"),
+    ("error.date_range", "Data fuori intervallo"),
+    ("error.invalid_date", "Data non valida «{date}»: {error}"),
+    ("error.copy_digest", "Impossibile copiare il riepilogo: {error}"),
+    ("error.captions_no_timing", "La trascrizione non contiene informazioni temporali"),
+    ("error.export_captions", "Impossibile esportare i sottotitoli: {error}"),
+    ("error.data_dir_unavailable", "La cartella dei dati dell'app non è disponibile"),
+    ("error.export", "Esportazione non riuscita: {error}"),
+    ("error.already_recording", "È già in corso una dettatura"),
+    ("error.dictation_blocked", "La dettatura è disattivata per l’app attuale"),
+    ("error.dictation_shortcut_required", "La scorciatoia di dettatura non può essere rimossa"),
+    ("error.shortcut_in_use", "Questa scorciatoia è già usata da un’altra azione"),
+    ("error.output_command_empty", "Inserisci un comando a cui inviare le dettature"),
+    ("error.output_command_timeout", "Il timeout del comando deve essere tra 1 e 600 secondi"),
+    ("error.speech_unavailable", "La sintesi vocale non è disponibile: {error}"),
+    ("error.read_back_rate", "La velocità di lettura deve essere tra 0,5 e 2"),
+    (
+        "error.search_template",
+        "L'URL di ricerca deve iniziare con http:// o https:// e contenere {text}",
+    ),
+    ("error.target_app_launch", "Impossibile aprire {app}: {error}"),
+    (
+        "error.target_app_not_frontmost",
+        "{app} non è passato in primo piano, quindi la trascrizione non è stata incollata",
+    ),
+    ("key.ctrl", "Ctrl"),
+    ("key.alt", "Alt"),
+    ("key.shift", "Maiusc"),
+    ("key.space", "Spazio"),
+];
+
+fn table(locale: &str) -> Table {
+    match locale {
+        "de" => DE,
+        "es" => ES,
+        "fr" => FR,
+        "it" => IT,
+        _ => EN,
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocaleInfo {
+    pub locale: String,
+    /// The user's explicit choice, None when following the system
+    pub preferred: Option<String>,
+    pub supported: Vec<String>,
+}
+
+/// Reduce a tag like "fr-CA" or "de_DE.UTF-8" to a supported language code
+fn supported_language(tag: &str) -> Option<&'static str> {
+    let language = tag.split(['-', '_', '.']).next()?.to_lowercase();
+    SUPPORTED_LOCALES
+        .iter()
+        .copied()
+        .find(|supported| *supported == language)
+}
+
+fn current_locale() -> String {
+    let locale = LOCALE
+        .lock()
+        .map(|locale| locale.clone())
+        .unwrap_or_default();
+    if locale.is_empty() {
+        DEFAULT_LOCALE.to_string()
+    } else {
+        locale
+    }
+}
+
+/// Apply the locale from settings, falling back to the OS language
+pub fn init(app: &AppHandle) {
+    let preferred = settings::current(app).locale;
+    let locale = preferred
+        .as_deref()
+        .and_then(supported_language)
+        .or_else(|| {
+            sys_locale::get_locale()
+                .as_deref()
+                .and_then(supported_language)
+        })
+        .unwrap_or(DEFAULT_LOCALE);
+    if let Ok(mut current) = LOCALE.lock() {
+        *current = locale.to_string();
+    }
+}
+
+/// Look up a user-visible string, falling back to English and then to the key
+pub fn t(key: &str) -> String {
+    let lookup = |table: Table| {
+        table
+            .iter()
+            .find(|(candidate, _)| *candidate == key)
+            .map(|(_, text)| text.to_string())
+    };
+    lookup(table(&current_locale()))
+        .or_else(|| lookup(EN))
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// `t` with `{name}` placeholders filled in
+pub fn tf(key: &str, args: &[(&str, &str)]) -> String {
+    args.iter().fold(t(key), |text, (name, value)| {
+        text.replace(&format!("{{{name}}}"), value)
+    })
+}
+
+fn info(app: &AppHandle) -> LocaleInfo {
+    LocaleInfo {
+        locale: current_locale(),
+        preferred: settings::current(app).locale,
+        supported: SUPPORTED_LOCALES
+            .iter()
+            .map(|locale| locale.to_string())
+            .collect(),
+    }
+}
+
+#[tauri::command]
+pub async fn get_locale(app: AppHandle) -> LocaleInfo {
+    info(&app)
+}
+
+/// Choose the language for tray items and messages, or None to follow the system
+#[tauri::command]
+pub async fn set_locale(app: AppHandle, locale: Option<String>) -> Result<LocaleInfo, String> {
+    if let Some(locale) = &locale {
+        if supported_language(locale).is_none() {
+            return Err(format!("Unsupported locale \"{locale}\""));
+        }
+    }
+    settings::update(&app, |settings| settings.locale = locale)?;
+    init(&app);
+    tray::refresh_menu(&app);
+
+    let info = info(&app);
+    let _ = app.emit("locale-changed", &info);
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_locale_translates_every_key() {
+        for locale in SUPPORTED_LOCALES {
+            let keys: Vec<&str> = table(locale).iter().map(|(key, _)| *key).collect();
+            let english: Vec<&str> = EN.iter().map(|(key, _)| *key).collect();
+            assert_eq!(keys, english, "{locale}");
+        }
+    }
+
+    #[test]
+    fn resolves_language_tags() {
+        assert_eq!(supported_language("fr-CA"), Some("fr"));
+        assert_eq!(supported_language("de_DE.UTF-8"), Some("de"));
+        assert_eq!(supported_language("ja-JP"), None);
+    }
+}
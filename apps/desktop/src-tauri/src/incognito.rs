@@ -0,0 +1,61 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter};
+
+// Switched on from the UI or tray, deliberately not persisted across launches
+static TOGGLED: AtomicBool = AtomicBool::new(false);
+// Set when the shortcut starting the next dictation is configured as incognito
+static SHORTCUT: AtomicBool = AtomicBool::new(false);
+// Whether the current (or last) dictation is incognito, decided when it starts
+static SESSION: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncognitoState {
+    /// Incognito switched on for every dictation
+    pub enabled: bool,
+    /// The current dictation won't be stored, either way it was made incognito
+    pub session: bool,
+}
+
+fn state() -> IncognitoState {
+    IncognitoState {
+        enabled: TOGGLED.load(Ordering::SeqCst),
+        session: SESSION.load(Ordering::SeqCst),
+    }
+}
+
+/// Whether the dictation being processed must stay out of history, logs and fixtures
+pub fn is_active() -> bool {
+    SESSION.load(Ordering::SeqCst)
+}
+
+/// Remember whether the shortcut that was just pressed asks for an incognito dictation
+pub fn begin_session(from_incognito_shortcut: bool) {
+    SHORTCUT.store(from_incognito_shortcut, Ordering::SeqCst);
+}
+
+/// Decide whether a new dictation is incognito, from the toggle or the pressed shortcut's flag
+pub fn session_started(app: &AppHandle) {
+    let incognito = TOGGLED.load(Ordering::SeqCst) || SHORTCUT.swap(false, Ordering::SeqCst);
+    SESSION.store(incognito, Ordering::SeqCst);
+    let _ = app.emit("incognito-changed", state());
+}
+
+#[tauri::command]
+pub async fn get_incognito_state() -> IncognitoState {
+    state()
+}
+
+/// Switch incognito on or off for every dictation. Switching it on also covers
+/// the dictation in progress; switching it off never exposes one made incognito.
+#[tauri::command]
+pub async fn set_incognito(app: AppHandle, enabled: bool) -> IncognitoState {
+    TOGGLED.store(enabled, Ordering::SeqCst);
+    if enabled {
+        SESSION.store(true, Ordering::SeqCst);
+    }
+    let state = state();
+    let _ = app.emit("incognito-changed", &state);
+    state
+}
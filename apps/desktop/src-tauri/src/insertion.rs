@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::feature_flags::{self, Flag};
+use crate::{
+    accessibility, append_e2e_log, auto_send, caret_context, char_limit, history, i18n, keyboard,
+    learning, paste_text, settings,
+};
+
+/// Give the target app time to apply the insertion before reading it back
+const VERIFY_DELAY: Duration = Duration::from_millis(100);
+
+// History id of the last dictation put into an app and how many words went in, which
+// can differ from the stored entry once it is redacted
+static LAST_INSERTED: Mutex<Option<(u64, usize)>> = Mutex::new(None);
+
+/// Remember that `words` words of the dictation with `history_id` are in the target app
+pub fn record_inserted(history_id: u64, words: usize) {
+    if let Ok(mut last) = LAST_INSERTED.lock() {
+        *last = Some((history_id, words));
+    }
+}
+
+/// Words the dictation with `history_id` put into the target app, if it was the last one
+pub fn inserted_words(history_id: u64) -> Option<usize> {
+    let last = *LAST_INSERTED.lock().ok()?;
+    last.filter(|(id, _)| *id == history_id)
+        .map(|(_, words)| words)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InsertionStrategy {
+    /// Write the clipboard and send Cmd+V
+    #[default]
+    #[serde(rename = "clipboard")]
+    Clipboard,
+    /// Synthetic Unicode key events: CGEvent on macOS, SendInput on Windows and
+    /// wtype, ydotool or xdotool on Linux
+    #[serde(rename = "cgEvent")]
+    CgEvent,
+    /// Set the focused element's selected text through the Accessibility API
+    #[serde(rename = "ax")]
+    Ax,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InsertionOutcome {
+    pub strategy: InsertionStrategy,
+    pub success: bool,
+    /// Whether the text was read back from the focused element, None when it can't be read
+    pub verified: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InsertionComplete {
+    history_id: Option<u64>,
+    #[serde(flatten)]
+    outcome: InsertionOutcome,
+}
+
+/// The focused element's value, read through the Accessibility API
+#[cfg(target_os = "macos")]
+pub fn focused_value() -> Option<String> {
+    use std::process::Command;
+
+    let script = r#"
+        tell application "System Events"
+            set frontApp to first application process whose frontmost is true
+            set focusedElement to value of attribute "AXFocusedUIElement" of frontApp
+            return value of attribute "AXValue" of focusedElement
+        end tell
+    "#;
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let value = String::from_utf8_lossy(&output.stdout)
+        .trim_end_matches('\n')
+        .to_string();
+    (value != "missing value").then_some(value)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn focused_value() -> Option<String> {
+    None
+}
+
+fn verify(text: &str) -> Option<bool> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    thread::sleep(VERIFY_DELAY);
+    focused_value().map(|value| value.contains(text))
+}
+
+/// Emit `insertion-complete` and record the outcome on the history entry.
+/// Verification reads the target back, so it runs off the caller's thread.
+pub fn report(
+    app: &AppHandle,
+    history_id: Option<u64>,
+    strategy: InsertionStrategy,
+    text: Option<String>,
+    result: &Result<(), String>,
+) {
+    if let (Some(id), Ok(())) = (history_id, result) {
+        match &text {
+            Some(text) => record_inserted(id, text.split_whitespace().count()),
+            // Whatever was on the clipboard went in, so its length is unknown
+            None => {
+                if let Ok(mut last) = LAST_INSERTED.lock() {
+                    *last = None;
+                }
+            }
+        }
+    }
+    let app = app.clone();
+    let error = result.as_ref().err().cloned();
+    thread::spawn(move || {
+        let success = error.is_none();
+        let verified = match (&text, success) {
+            (Some(text), true) => verify(text),
+            _ => None,
+        };
+        let outcome = InsertionOutcome {
+            strategy,
+            success,
+            verified,
+            error,
+        };
+
+        // Only dictations carry a history id; pasting from the picker never sends
+        if let (true, Some(id)) = (success, history_id) {
+            auto_send::pasted(&app, id);
+            if let Some(text) = &text {
+                learning::pasted(&app, text);
+            }
+        }
+
+        append_e2e_log(if success {
+            "insertion-complete"
+        } else {
+            "insertion-failed"
+        });
+        match &outcome.error {
+            None => accessibility::announce_key(&app, "a11y.pasted"),
+            Some(error) => {
+                accessibility::announce(&app, i18n::tf("a11y.paste_failed", &[("error", error)]))
+            }
+        }
+        // Id 0 marks an entry that was never stored because history is disabled
+        if let Some(id) = history_id.filter(|id| *id != 0) {
+            let recorded = outcome.clone();
+            if let Err(e) = history::update_entry(&app, id, |entry| {
+                entry.insertion = Some(recorded);
+                Ok(())
+            }) {
+                eprintln!("[voiceflow] Failed to record insertion: {e}");
+            }
+        }
+        let _ = app.emit(
+            "insertion-complete",
+            InsertionComplete {
+                history_id,
+                outcome,
+            },
+        );
+    });
+}
+
+/// Insert `text` into the frontmost app with the given strategy, or the default one
+/// for this install when none is given
+#[tauri::command]
+pub async fn insert_text(
+    app: AppHandle,
+    text: String,
+    strategy: Option<InsertionStrategy>,
+    history_id: Option<u64>,
+) -> Result<(), String> {
+    let (text, overflow) = prepare(&app, text);
+    let (strategy, result) = match strategy {
+        Some(strategy) => (strategy, insert_with(&app, strategy, &text)),
+        None if settings::current(&app).insert_by_typing => {
+            let strategy = InsertionStrategy::CgEvent;
+            (strategy, insert_with(&app, strategy, &text))
+        }
+        None if feature_flags::is_enabled(&app, Flag::NewPasteStrategy) => {
+            match keyboard::insert_via_accessibility(&text) {
+                Ok(()) => (InsertionStrategy::Ax, Ok(())),
+                // Fields that don't expose their selection through Accessibility
+                Err(_) => (InsertionStrategy::Clipboard, paste_text(&app, Some(&text))),
+            }
+        }
+        None => {
+            let strategy = InsertionStrategy::default();
+            (strategy, insert_with(&app, strategy, &text))
+        }
+    };
+    finish(&app, &result, overflow);
+    report(&app, history_id, strategy, Some(text), &result);
+    result
+}
+
+fn insert_with(app: &AppHandle, strategy: InsertionStrategy, text: &str) -> Result<(), String> {
+    match strategy {
+        InsertionStrategy::Clipboard => paste_text(app, Some(text)),
+        InsertionStrategy::CgEvent => keyboard::type_text(text),
+        InsertionStrategy::Ax => keyboard::insert_via_accessibility(text),
+    }
+}
+
+/// Fit a transcript to the focused field before it goes in: case and spacing from the
+/// text before the caret, then the field's character limit. Returns the text to insert
+/// and what didn't fit.
+pub fn prepare(app: &AppHandle, text: String) -> (String, Option<String>) {
+    char_limit::fit(app, caret_context::adapt(app, text))
+}
+
+/// Leave what didn't fit on the clipboard once the rest is in
+pub fn finish(app: &AppHandle, result: &Result<(), String>, overflow: Option<String>) {
+    if let (Ok(()), Some(overflow)) = (result, overflow) {
+        char_limit::keep_overflow(app, &overflow);
+    }
+}
+
+/// Paste `text`, or type it when the user keeps transcripts off the clipboard.
+/// Without text whatever is on the clipboard is pasted.
+pub fn paste_or_type(
+    app: &AppHandle,
+    text: Option<&str>,
+) -> (InsertionStrategy, Result<(), String>) {
+    match text {
+        Some(text) if settings::current(app).insert_by_typing => {
+            (InsertionStrategy::CgEvent, keyboard::type_text(text))
+        }
+        _ => (InsertionStrategy::Clipboard, paste_text(app, text)),
+    }
+}
+
+/// Type `text` into the frontmost app as key events, without touching the clipboard
+#[tauri::command]
+pub async fn type_text(
+    app: AppHandle,
+    text: String,
+    history_id: Option<u64>,
+) -> Result<(), String> {
+    let (text, overflow) = prepare(&app, text);
+    let typed = text.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || keyboard::type_text(&typed))
+        .await
+        .map_err(|e| e.to_string())?;
+    finish(&app, &result, overflow);
+    report(
+        &app,
+        history_id,
+        InsertionStrategy::CgEvent,
+        Some(text),
+        &result,
+    );
+    result
+}
@@ -0,0 +1,618 @@
+#[cfg(target_os = "macos")]
+fn run_system_events(body: &str) -> Result<(), String> {
+    use std::process::Command;
+
+    let script = format!("tell application \"System Events\"\n{body}\nend tell");
+    let result = Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .map_err(|e| crate::i18n::tf("error.osascript", &[("error", &e.to_string())]))?;
+
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        eprintln!("[voiceflow] Keyboard script failed: {}", stderr);
+        return Err(stderr.to_string());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+pub fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Press Option+Delete `n_words` times, which deletes the previous word
+#[cfg(target_os = "macos")]
+fn delete_words(n_words: usize) -> Result<(), String> {
+    if n_words == 0 {
+        return Ok(());
+    }
+    run_system_events(&format!(
+        "repeat {n_words} times\nkey code 51 using option down\nend repeat"
+    ))
+}
+
+/// Delete the last `n_words` words before the caret and type `replacement` in their place
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+pub fn replace_last_words(n_words: usize, replacement: &str) -> Result<(), String> {
+    delete_words(n_words)?;
+    type_text(replacement)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+pub fn replace_last_words(_n_words: usize, _replacement: &str) -> Result<(), String> {
+    Err(crate::i18n::t("error.inline_corrections_unsupported"))
+}
+
+/// Send Cmd+C to the frontmost app
+#[cfg(target_os = "macos")]
+pub fn press_copy() -> Result<(), String> {
+    run_system_events("keystroke \"c\" using command down")
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn press_copy() -> Result<(), String> {
+    Err(crate::i18n::t("error.synthetic_copy_unsupported"))
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGEventCreateKeyboardEvent(
+        source: *const std::ffi::c_void,
+        virtual_key: u16,
+        key_down: bool,
+    ) -> *mut std::ffi::c_void;
+    fn CGEventKeyboardSetUnicodeString(
+        event: *mut std::ffi::c_void,
+        length: std::ffi::c_ulong,
+        string: *const u16,
+    );
+    fn CGEventPost(tap: u32, event: *mut std::ffi::c_void);
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    fn CFRelease(cf: *const std::ffi::c_void);
+}
+
+/// Type `text` as synthetic Unicode key events, bypassing the clipboard and keyboard layout
+#[cfg(target_os = "macos")]
+pub fn type_text(text: &str) -> Result<(), String> {
+    // kCGHIDEventTap
+    const HID_EVENT_TAP: u32 = 0;
+    // Apps drop the tail of longer strings attached to a single event
+    const MAX_UNITS_PER_EVENT: usize = 20;
+
+    let units: Vec<u16> = text.encode_utf16().collect();
+    for chunk in units.chunks(MAX_UNITS_PER_EVENT) {
+        for key_down in [true, false] {
+            // SAFETY: the event is checked for null, only used here, and released once
+            unsafe {
+                let event = CGEventCreateKeyboardEvent(std::ptr::null(), 0, key_down);
+                if event.is_null() {
+                    return Err(crate::i18n::t("error.keyboard_event"));
+                }
+                CGEventKeyboardSetUnicodeString(event, chunk.len() as _, chunk.as_ptr());
+                CGEventPost(HID_EVENT_TAP, event);
+                CFRelease(event);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+pub fn type_text(_text: &str) -> Result<(), String> {
+    Err(crate::i18n::t("error.typing_unsupported"))
+}
+
+/// Replace the focused element's selection with `text` through the Accessibility API
+#[cfg(target_os = "macos")]
+pub fn insert_via_accessibility(text: &str) -> Result<(), String> {
+    run_system_events(&format!(
+        "set frontApp to first application process whose frontmost is true\n\
+         set focusedElement to value of attribute \"AXFocusedUIElement\" of frontApp\n\
+         set value of attribute \"AXSelectedText\" of focusedElement to {}",
+        applescript_string(text)
+    ))
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn insert_via_accessibility(_text: &str) -> Result<(), String> {
+    Err(crate::i18n::t("error.accessibility_insertion_unsupported"))
+}
+
+/// Press a key chord such as "cmd+shift+t" or "ctrl+return"
+#[cfg(target_os = "macos")]
+pub fn press_chord(chord: &str) -> Result<(), String> {
+    let parts: Vec<String> = chord
+        .split('+')
+        .map(|part| part.trim().to_lowercase())
+        .filter(|part| !part.is_empty())
+        .collect();
+    let (key, modifiers) = parts
+        .split_last()
+        .ok_or_else(|| crate::i18n::tf("error.key_chord_invalid", &[("chord", chord)]))?;
+
+    let mut held = Vec::new();
+    for modifier in modifiers {
+        held.push(match modifier.as_str() {
+            "cmd" | "command" | "super" | "meta" => "command down",
+            "alt" | "option" => "option down",
+            "ctrl" | "control" => "control down",
+            "shift" => "shift down",
+            other => {
+                return Err(crate::i18n::tf(
+                    "error.unknown_modifier",
+                    &[("modifier", other)],
+                ))
+            }
+        });
+    }
+    let using = if held.is_empty() {
+        String::new()
+    } else {
+        format!(" using {{{}}}", held.join(", "))
+    };
+
+    let key_code = match key.as_str() {
+        "return" | "enter" => Some(36),
+        "tab" => Some(48),
+        "space" => Some(49),
+        "delete" | "backspace" => Some(51),
+        "escape" | "esc" => Some(53),
+        "left" => Some(123),
+        "right" => Some(124),
+        "down" => Some(125),
+        "up" => Some(126),
+        _ => None,
+    };
+    let press = match key_code {
+        Some(code) => format!("key code {code}{using}"),
+        None if key.chars().count() == 1 => format!("keystroke {}{using}", applescript_string(key)),
+        None => {
+            return Err(crate::i18n::tf(
+                "error.unknown_key",
+                &[("key", key.as_str())],
+            ))
+        }
+    };
+    run_system_events(&press)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn press_chord(_chord: &str) -> Result<(), String> {
+    Err(crate::i18n::t("error.key_chords_unsupported"))
+}
+
+/// Whether the process behind `process` runs elevated (as administrator)
+#[cfg(target_os = "windows")]
+fn is_elevated(process: windows_sys::Win32::Foundation::HANDLE) -> Option<bool> {
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::Security::{
+        GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY,
+    };
+    use windows_sys::Win32::System::Threading::OpenProcessToken;
+
+    let mut token: HANDLE = std::ptr::null_mut();
+    // SAFETY: `token` is a valid out pointer and is closed below when opened
+    if unsafe { OpenProcessToken(process, TOKEN_QUERY, &mut token) } == 0 {
+        return None;
+    }
+    let mut elevation = TOKEN_ELEVATION { TokenIsElevated: 0 };
+    let mut returned = 0u32;
+    // SAFETY: the buffer is a TOKEN_ELEVATION of the size passed in
+    let ok = unsafe {
+        GetTokenInformation(
+            token,
+            TokenElevation,
+            &mut elevation as *mut TOKEN_ELEVATION as *mut std::ffi::c_void,
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut returned,
+        )
+    };
+    // SAFETY: `token` was opened above
+    unsafe { CloseHandle(token) };
+    (ok != 0).then_some(elevation.TokenIsElevated != 0)
+}
+
+/// Windows silently drops input sent to a window of an elevated process from one
+/// that isn't, so this has to be checked up front
+#[cfg(target_os = "windows")]
+fn foreground_is_elevated_above_us() -> bool {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, GetWindowThreadProcessId,
+    };
+
+    // SAFETY: the pseudo handle of the current process needs no cleanup
+    if is_elevated(unsafe { GetCurrentProcess() }) == Some(true) {
+        return false;
+    }
+    let mut process_id = 0u32;
+    // SAFETY: plain queries on the foreground window, which may be null
+    unsafe { GetWindowThreadProcessId(GetForegroundWindow(), &mut process_id) };
+    if process_id == 0 {
+        return false;
+    }
+    // SAFETY: the handle is checked for null and closed below
+    let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, process_id) };
+    if process.is_null() {
+        // Elevated processes can usually still be queried, so this is something else
+        return false;
+    }
+    let elevated = is_elevated(process);
+    // SAFETY: `process` was opened above
+    unsafe { CloseHandle(process) };
+    elevated == Some(true)
+}
+
+/// Send Ctrl+V to the focused window
+#[cfg(target_os = "windows")]
+pub fn press_paste() -> Result<(), String> {
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{
+        SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYEVENTF_KEYUP, VIRTUAL_KEY,
+        VK_CONTROL, VK_V,
+    };
+
+    if foreground_is_elevated_above_us() {
+        return Err(crate::i18n::t("error.paste_elevated_window"));
+    }
+    let key = |key: VIRTUAL_KEY, flags| INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: key,
+                wScan: 0,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    };
+    let inputs = [
+        key(VK_CONTROL, 0),
+        key(VK_V, 0),
+        key(VK_V, KEYEVENTF_KEYUP),
+        key(VK_CONTROL, KEYEVENTF_KEYUP),
+    ];
+    // SAFETY: `inputs` is a valid array of fully initialized INPUT structs
+    let sent = unsafe {
+        SendInput(
+            inputs.len() as u32,
+            inputs.as_ptr(),
+            std::mem::size_of::<INPUT>() as i32,
+        )
+    };
+    if sent as usize != inputs.len() {
+        return Err(crate::i18n::tf(
+            "error.send_paste",
+            &[("error", &std::io::Error::last_os_error().to_string())],
+        ));
+    }
+    Ok(())
+}
+
+/// Type `text` as synthetic Unicode key events, bypassing the clipboard and keyboard layout
+#[cfg(target_os = "windows")]
+pub fn type_text(text: &str) -> Result<(), String> {
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{
+        SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYEVENTF_KEYUP, KEYEVENTF_UNICODE,
+        VK_RETURN,
+    };
+
+    if foreground_is_elevated_above_us() {
+        return Err(crate::i18n::t("error.paste_elevated_window"));
+    }
+    let key = |key, unit, flags| INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: key,
+                wScan: unit,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    };
+    let mut inputs = Vec::new();
+    for c in text.chars() {
+        match c {
+            '\r' => {}
+            // Editors treat a Unicode line feed as a character, not a new line
+            '\n' => {
+                inputs.push(key(VK_RETURN, 0, 0));
+                inputs.push(key(VK_RETURN, 0, KEYEVENTF_KEYUP));
+            }
+            _ => {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    inputs.push(key(0, *unit, KEYEVENTF_UNICODE));
+                    inputs.push(key(0, *unit, KEYEVENTF_UNICODE | KEYEVENTF_KEYUP));
+                }
+            }
+        }
+    }
+    if inputs.is_empty() {
+        return Ok(());
+    }
+    // SAFETY: `inputs` is a valid array of fully initialized INPUT structs
+    let sent = unsafe {
+        SendInput(
+            inputs.len() as u32,
+            inputs.as_ptr(),
+            std::mem::size_of::<INPUT>() as i32,
+        )
+    };
+    if sent as usize != inputs.len() {
+        return Err(crate::i18n::tf(
+            "error.type_text",
+            &[("error", &std::io::Error::last_os_error().to_string())],
+        ));
+    }
+    Ok(())
+}
+
+/// Press Ctrl+Backspace `n_words` times, which deletes the previous word in most editors
+#[cfg(target_os = "windows")]
+fn delete_words(n_words: usize) -> Result<(), String> {
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{
+        SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYEVENTF_KEYUP, VIRTUAL_KEY,
+        VK_BACK, VK_CONTROL,
+    };
+
+    if n_words == 0 {
+        return Ok(());
+    }
+    if foreground_is_elevated_above_us() {
+        return Err(crate::i18n::t("error.paste_elevated_window"));
+    }
+    let key = |key: VIRTUAL_KEY, flags| INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: key,
+                wScan: 0,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    };
+    let mut inputs = vec![key(VK_CONTROL, 0)];
+    for _ in 0..n_words {
+        inputs.push(key(VK_BACK, 0));
+        inputs.push(key(VK_BACK, KEYEVENTF_KEYUP));
+    }
+    inputs.push(key(VK_CONTROL, KEYEVENTF_KEYUP));
+    // SAFETY: `inputs` is a valid array of fully initialized INPUT structs
+    let sent = unsafe {
+        SendInput(
+            inputs.len() as u32,
+            inputs.as_ptr(),
+            std::mem::size_of::<INPUT>() as i32,
+        )
+    };
+    if sent as usize != inputs.len() {
+        return Err(crate::i18n::tf(
+            "error.delete_words",
+            &[("error", &std::io::Error::last_os_error().to_string())],
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DisplayServer {
+    X11,
+    Wayland,
+}
+
+/// Wayland when the session says so, X11 when there is only an X display
+#[cfg(target_os = "linux")]
+fn display_server(
+    session_type: Option<&str>,
+    wayland_display: Option<&str>,
+    x_display: Option<&str>,
+) -> Option<DisplayServer> {
+    let set = |value: Option<&str>| value.is_some_and(|value| !value.is_empty());
+    match session_type.map(str::to_lowercase).as_deref() {
+        Some("wayland") => Some(DisplayServer::Wayland),
+        Some("x11") => Some(DisplayServer::X11),
+        _ if set(wayland_display) => Some(DisplayServer::Wayland),
+        _ if set(x_display) => Some(DisplayServer::X11),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+type ToolCommand = (&'static str, &'static [&'static str]);
+
+/// Tools that can press Ctrl+V, tried in order. wtype needs the compositor's virtual
+/// keyboard protocol, which GNOME lacks, so ydotool (uinput) and xdotool (XWayland
+/// windows only) follow it on Wayland.
+#[cfg(target_os = "linux")]
+fn paste_commands(server: DisplayServer) -> &'static [ToolCommand] {
+    const WTYPE: ToolCommand = ("wtype", &["-M", "ctrl", "v", "-m", "ctrl"]);
+    // Linux input event codes: 29 is left Ctrl, 47 is V
+    const YDOTOOL: ToolCommand = ("ydotool", &["key", "29:1", "47:1", "47:0", "29:0"]);
+    const XDOTOOL: ToolCommand = ("xdotool", &["key", "--clearmodifiers", "ctrl+v"]);
+    match server {
+        DisplayServer::X11 => &[XDOTOOL],
+        DisplayServer::Wayland => &[WTYPE, YDOTOOL, XDOTOOL],
+    }
+}
+
+/// Tools that can type text read from stdin, in the same order as `paste_commands`
+#[cfg(target_os = "linux")]
+fn type_commands(server: DisplayServer) -> &'static [ToolCommand] {
+    const WTYPE: ToolCommand = ("wtype", &["-"]);
+    const YDOTOOL: ToolCommand = ("ydotool", &["type", "--file", "-"]);
+    const XDOTOOL: ToolCommand = ("xdotool", &["type", "--clearmodifiers", "--file", "-"]);
+    match server {
+        DisplayServer::X11 => &[XDOTOOL],
+        DisplayServer::Wayland => &[WTYPE, YDOTOOL, XDOTOOL],
+    }
+}
+
+/// Tools that can press Ctrl+Backspace, in the same order as `paste_commands`
+#[cfg(target_os = "linux")]
+fn delete_word_commands(server: DisplayServer) -> &'static [ToolCommand] {
+    const WTYPE: ToolCommand = ("wtype", &["-M", "ctrl", "-k", "BackSpace", "-m", "ctrl"]);
+    // Linux input event codes: 29 is left Ctrl, 14 is Backspace
+    const YDOTOOL: ToolCommand = ("ydotool", &["key", "29:1", "14:1", "14:0", "29:0"]);
+    const XDOTOOL: ToolCommand = ("xdotool", &["key", "--clearmodifiers", "ctrl+BackSpace"]);
+    match server {
+        DisplayServer::X11 => &[XDOTOOL],
+        DisplayServer::Wayland => &[WTYPE, YDOTOOL, XDOTOOL],
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn current_display_server() -> Result<DisplayServer, String> {
+    use std::env::var;
+
+    display_server(
+        var("XDG_SESSION_TYPE").ok().as_deref(),
+        var("WAYLAND_DISPLAY").ok().as_deref(),
+        var("DISPLAY").ok().as_deref(),
+    )
+    .ok_or_else(|| crate::i18n::t("error.no_display"))
+}
+
+/// Run the first of `commands` that is installed, with `input` on its stdin, falling
+/// back to `missing_key` naming the tools when none is
+#[cfg(target_os = "linux")]
+fn run_first_tool(commands: &[ToolCommand], input: &str, missing_key: &str) -> Result<(), String> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let mut last_error = None;
+    for (tool, args) in commands {
+        let child = Command::new(tool)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn();
+        let mut child = match child {
+            Ok(child) => child,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => {
+                last_error = Some(crate::i18n::tf(
+                    "error.run_tool",
+                    &[("tool", *tool), ("error", &e.to_string())],
+                ));
+                continue;
+            }
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(input.as_bytes());
+        }
+        match child.wait_with_output() {
+            Ok(output) if output.status.success() => return Ok(()),
+            Ok(output) => {
+                let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+                last_error = Some(crate::i18n::tf(
+                    "error.tool_failed",
+                    &[("tool", *tool), ("error", &stderr)],
+                ));
+            }
+            Err(e) => {
+                last_error = Some(crate::i18n::tf(
+                    "error.run_tool",
+                    &[("tool", *tool), ("error", &e.to_string())],
+                ))
+            }
+        }
+    }
+    Err(last_error.unwrap_or_else(|| {
+        let tools: Vec<&str> = commands.iter().map(|(tool, _)| *tool).collect();
+        crate::i18n::tf(missing_key, &[("tools", &tools.join(", "))])
+    }))
+}
+
+/// Send Ctrl+V to the focused window
+#[cfg(target_os = "linux")]
+pub fn press_paste() -> Result<(), String> {
+    let commands = paste_commands(current_display_server()?);
+    run_first_tool(commands, "", "error.paste_tool_missing")
+}
+
+/// Type `text` into the focused window with whichever typing tool is installed
+#[cfg(target_os = "linux")]
+pub fn type_text(text: &str) -> Result<(), String> {
+    if text.is_empty() {
+        return Ok(());
+    }
+    let commands = type_commands(current_display_server()?);
+    run_first_tool(commands, text, "error.type_tool_missing")
+}
+
+/// Press Ctrl+Backspace `n_words` times, which deletes the previous word in most editors
+#[cfg(target_os = "linux")]
+fn delete_words(n_words: usize) -> Result<(), String> {
+    if n_words == 0 {
+        return Ok(());
+    }
+    let commands = delete_word_commands(current_display_server()?);
+    for _ in 0..n_words {
+        run_first_tool(commands, "", "error.type_tool_missing")?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn replace_last(n_words: usize, replacement: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || replace_last_words(n_words, &replacement))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_display_server() {
+        assert_eq!(
+            display_server(Some("wayland"), None, Some(":0")),
+            Some(DisplayServer::Wayland)
+        );
+        assert_eq!(
+            display_server(Some("tty"), Some("wayland-0"), Some(":0")),
+            Some(DisplayServer::Wayland)
+        );
+        assert_eq!(
+            display_server(None, Some(""), Some(":1")),
+            Some(DisplayServer::X11)
+        );
+        assert_eq!(display_server(None, None, None), None);
+    }
+
+    #[test]
+    fn types_from_stdin_with_every_tool() {
+        for server in [DisplayServer::X11, DisplayServer::Wayland] {
+            let pasting: Vec<&str> = paste_commands(server)
+                .iter()
+                .map(|(tool, _)| *tool)
+                .collect();
+            let typing: Vec<&str> = type_commands(server)
+                .iter()
+                .map(|(tool, _)| *tool)
+                .collect();
+            assert_eq!(typing, pasting);
+        }
+        for (_, args) in type_commands(DisplayServer::Wayland) {
+            assert_eq!(args.last(), Some(&"-"));
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use crate::i18n;
+
+// Secrets such as sync keys and passwords go to the OS credential store instead of
+// the config dir: the login keychain on macOS, Credential Manager on Windows and the
+// Secret Service (through secret-tool) on Linux.
+
+const SERVICE: &str = "VoiceFlow";
+
+fn failed(error: impl std::fmt::Display) -> String {
+    i18n::tf("error.keychain", &[("error", &error.to_string())])
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::ffi::c_void;
+    use std::ptr;
+
+    use super::{failed, SERVICE};
+
+    #[link(name = "Security", kind = "framework")]
+    extern "C" {
+        fn SecKeychainAddGenericPassword(
+            keychain: *const c_void,
+            service_length: u32,
+            service: *const u8,
+            account_length: u32,
+            account: *const u8,
+            password_length: u32,
+            password: *const c_void,
+            item: *mut *const c_void,
+        ) -> i32;
+        fn SecKeychainFindGenericPassword(
+            keychain: *const c_void,
+            service_length: u32,
+            service: *const u8,
+            account_length: u32,
+            account: *const u8,
+            password_length: *mut u32,
+            password: *mut *mut c_void,
+            item: *mut *const c_void,
+        ) -> i32;
+        fn SecKeychainItemModifyAttributesAndData(
+            item: *const c_void,
+            attributes: *const c_void,
+            length: u32,
+            data: *const c_void,
+        ) -> i32;
+        fn SecKeychainItemFreeContent(attributes: *const c_void, data: *mut c_void) -> i32;
+        fn SecKeychainItemDelete(item: *const c_void) -> i32;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFRelease(cf: *const c_void);
+    }
+
+    // errSecItemNotFound
+    const ITEM_NOT_FOUND: i32 = -25300;
+
+    /// The item for `account` and its secret, None when there is none
+    fn find(account: &str) -> Result<Option<(*const c_void, Vec<u8>)>, String> {
+        let mut length = 0;
+        let mut data = ptr::null_mut();
+        let mut item = ptr::null();
+        // SAFETY: the strings are passed with their lengths, and the returned data is
+        // copied before it is freed; the caller releases the item
+        unsafe {
+            let status = SecKeychainFindGenericPassword(
+                ptr::null(),
+                SERVICE.len() as u32,
+                SERVICE.as_ptr(),
+                account.len() as u32,
+                account.as_ptr(),
+                &mut length,
+                &mut data,
+                &mut item,
+            );
+            match status {
+                0 => {
+                    let secret =
+                        std::slice::from_raw_parts(data as *const u8, length as usize).to_vec();
+                    SecKeychainItemFreeContent(ptr::null(), data);
+                    Ok(Some((item, secret)))
+                }
+                ITEM_NOT_FOUND => Ok(None),
+                status => Err(failed(format!("OSStatus {status}"))),
+            }
+        }
+    }
+
+    pub fn set(account: &str, secret: &str) -> Result<(), String> {
+        // SAFETY: the item from `find` is released after use, the secret outlives the calls
+        let status = unsafe {
+            match find(account)? {
+                Some((item, _)) => {
+                    let status = SecKeychainItemModifyAttributesAndData(
+                        item,
+                        ptr::null(),
+                        secret.len() as u32,
+                        secret.as_ptr() as *const c_void,
+                    );
+                    CFRelease(item);
+                    status
+                }
+                None => SecKeychainAddGenericPassword(
+                    ptr::null(),
+                    SERVICE.len() as u32,
+                    SERVICE.as_ptr(),
+                    account.len() as u32,
+                    account.as_ptr(),
+                    secret.len() as u32,
+                    secret.as_ptr() as *const c_void,
+                    ptr::null_mut(),
+                ),
+            }
+        };
+        match status {
+            0 => Ok(()),
+            status => Err(failed(format!("OSStatus {status}"))),
+        }
+    }
+
+    pub fn get(account: &str) -> Result<Option<String>, String> {
+        let Some((item, secret)) = find(account)? else {
+            return Ok(None);
+        };
+        // SAFETY: `find` handed over ownership of the item
+        unsafe { CFRelease(item) };
+        String::from_utf8(secret).map(Some).map_err(failed)
+    }
+
+    pub fn delete(account: &str) -> Result<(), String> {
+        let Some((item, _)) = find(account)? else {
+            return Ok(());
+        };
+        // SAFETY: `find` handed over ownership of the item
+        let status = unsafe {
+            let status = SecKeychainItemDelete(item);
+            CFRelease(item);
+            status
+        };
+        match status {
+            0 => Ok(()),
+            status => Err(failed(format!("OSStatus {status}"))),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::ptr;
+    use windows_sys::Win32::Foundation::{GetLastError, ERROR_NOT_FOUND};
+    use windows_sys::Win32::Security::Credentials::{
+        CredDeleteW, CredFree, CredReadW, CredWriteW, CREDENTIALW, CRED_PERSIST_LOCAL_MACHINE,
+        CRED_TYPE_GENERIC,
+    };
+
+    use super::{failed, SERVICE};
+
+    fn target(account: &str) -> Vec<u16> {
+        format!("{SERVICE}/{account}")
+            .encode_utf16()
+            .chain(Some(0))
+            .collect()
+    }
+
+    fn last_error() -> String {
+        // SAFETY: reads the calling thread's last error code
+        failed(format!("error {}", unsafe { GetLastError() }))
+    }
+
+    pub fn set(account: &str, secret: &str) -> Result<(), String> {
+        let mut target = target(account);
+        let mut blob = secret.as_bytes().to_vec();
+        // SAFETY: zeroed is a valid CREDENTIALW, and the buffers it points to outlive the call
+        let written = unsafe {
+            let mut credential: CREDENTIALW = std::mem::zeroed();
+            credential.Type = CRED_TYPE_GENERIC;
+            credential.TargetName = target.as_mut_ptr();
+            credential.CredentialBlobSize = blob.len() as u32;
+            credential.CredentialBlob = blob.as_mut_ptr();
+            credential.Persist = CRED_PERSIST_LOCAL_MACHINE;
+            CredWriteW(&credential, 0)
+        };
+        if written == 0 {
+            return Err(last_error());
+        }
+        Ok(())
+    }
+
+    pub fn get(account: &str) -> Result<Option<String>, String> {
+        let target = target(account);
+        let mut credential: *mut CREDENTIALW = ptr::null_mut();
+        // SAFETY: the credential is copied out of and then freed with CredFree
+        unsafe {
+            if CredReadW(target.as_ptr(), CRED_TYPE_GENERIC, 0, &mut credential) == 0 {
+                if GetLastError() == ERROR_NOT_FOUND {
+                    return Ok(None);
+                }
+                return Err(last_error());
+            }
+            let blob = std::slice::from_raw_parts(
+                (*credential).CredentialBlob,
+                (*credential).CredentialBlobSize as usize,
+            )
+            .to_vec();
+            CredFree(credential as *const _);
+            String::from_utf8(blob).map(Some).map_err(failed)
+        }
+    }
+
+    pub fn delete(account: &str) -> Result<(), String> {
+        let target = target(account);
+        // SAFETY: plain call with a NUL-terminated target name
+        unsafe {
+            if CredDeleteW(target.as_ptr(), CRED_TYPE_GENERIC, 0) == 0
+                && GetLastError() != ERROR_NOT_FOUND
+            {
+                return Err(last_error());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    use super::{failed, SERVICE};
+
+    fn secret_tool(args: &[&str], account: &str) -> Command {
+        let mut command = Command::new("secret-tool");
+        command
+            .args(args)
+            .args(["service", SERVICE, "account", account]);
+        command
+    }
+
+    pub fn set(account: &str, secret: &str) -> Result<(), String> {
+        let label = format!("--label={SERVICE} {account}");
+        // The secret goes through stdin so it never shows up in the process list
+        let mut child = secret_tool(&["store", &label], account)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(failed)?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(secret.as_bytes()).map_err(failed)?;
+        }
+        let output = child.wait_with_output().map_err(failed)?;
+        if !output.status.success() {
+            return Err(failed(String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        Ok(())
+    }
+
+    pub fn get(account: &str) -> Result<Option<String>, String> {
+        let output = secret_tool(&["lookup"], account)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(failed)?;
+        // lookup exits with 1 and prints nothing when there is no such secret
+        if !output.status.success() || output.stdout.is_empty() {
+            return Ok(None);
+        }
+        String::from_utf8(output.stdout).map(Some).map_err(failed)
+    }
+
+    pub fn delete(account: &str) -> Result<(), String> {
+        secret_tool(&["clear"], account)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(failed)?;
+        Ok(())
+    }
+}
+
+/// Store `secret` for `account`, replacing what was there
+pub fn set(account: &str, secret: &str) -> Result<(), String> {
+    platform::set(account, secret)
+}
+
+pub fn get(account: &str) -> Result<Option<String>, String> {
+    platform::get(account)
+}
+
+pub fn delete(account: &str) -> Result<(), String> {
+    platform::delete(account)
+}
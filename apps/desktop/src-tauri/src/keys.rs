@@ -0,0 +1,253 @@
+use std::str::FromStr;
+use tauri_plugin_global_shortcut::Code;
+
+use crate::i18n;
+
+/// Keys other than letters, digits and F-keys: their code, the label shown for them,
+/// and lowercase names accepted besides the W3C code name (e.g. "Semicolon")
+const NAMED_KEYS: &[(Code, &str, &[&str])] = &[
+    (Code::Space, "Space", &["space"]),
+    (Code::Enter, "Enter", &["enter", "return"]),
+    (Code::Tab, "Tab", &["tab"]),
+    (Code::Backspace, "Backspace", &["backspace"]),
+    (Code::Escape, "Esc", &["esc", "escape"]),
+    (Code::Delete, "Delete", &["delete", "del", "forwarddelete"]),
+    (Code::Insert, "Insert", &["insert", "ins"]),
+    (Code::Home, "Home", &["home"]),
+    (Code::End, "End", &["end"]),
+    (Code::PageUp, "Page Up", &["pageup", "pgup"]),
+    (Code::PageDown, "Page Down", &["pagedown", "pgdn"]),
+    (Code::ArrowUp, "\u{2191}", &["up", "arrowup", "\u{2191}"]),
+    (
+        Code::ArrowDown,
+        "\u{2193}",
+        &["down", "arrowdown", "\u{2193}"],
+    ),
+    (
+        Code::ArrowLeft,
+        "\u{2190}",
+        &["left", "arrowleft", "\u{2190}"],
+    ),
+    (
+        Code::ArrowRight,
+        "\u{2192}",
+        &["right", "arrowright", "\u{2192}"],
+    ),
+    (Code::CapsLock, "Caps Lock", &["capslock"]),
+    (Code::NumLock, "Num Lock", &["numlock"]),
+    (Code::ScrollLock, "Scroll Lock", &["scrolllock"]),
+    (Code::PrintScreen, "Print Screen", &["printscreen", "prtsc"]),
+    (Code::Pause, "Pause", &["pause"]),
+    (Code::Semicolon, ";", &[";", "semicolon"]),
+    (Code::Quote, "'", &["'", "quote"]),
+    (Code::Comma, ",", &[",", "comma"]),
+    (Code::Period, ".", &[".", "period"]),
+    (Code::Slash, "/", &["/", "slash"]),
+    (Code::Backslash, "\\", &["\\", "backslash"]),
+    (Code::Backquote, "`", &["`", "backquote", "backtick"]),
+    (Code::Minus, "-", &["-", "minus"]),
+    (Code::Equal, "=", &["=", "equal"]),
+    (Code::BracketLeft, "[", &["[", "bracketleft"]),
+    (Code::BracketRight, "]", &["]", "bracketright"]),
+    // Keys that only exist on some layouts, e.g. the key left of Z on ISO keyboards
+    (
+        Code::IntlBackslash,
+        "\u{00A7}",
+        &["intlbackslash", "\u{00A7}"],
+    ),
+    (Code::IntlRo, "\u{308D}", &["intlro", "\u{308D}"]),
+    (Code::IntlYen, "\u{00A5}", &["intlyen", "\u{00A5}"]),
+    (Code::Numpad0, "Num 0", &["num0", "numpad0"]),
+    (Code::Numpad1, "Num 1", &["num1", "numpad1"]),
+    (Code::Numpad2, "Num 2", &["num2", "numpad2"]),
+    (Code::Numpad3, "Num 3", &["num3", "numpad3"]),
+    (Code::Numpad4, "Num 4", &["num4", "numpad4"]),
+    (Code::Numpad5, "Num 5", &["num5", "numpad5"]),
+    (Code::Numpad6, "Num 6", &["num6", "numpad6"]),
+    (Code::Numpad7, "Num 7", &["num7", "numpad7"]),
+    (Code::Numpad8, "Num 8", &["num8", "numpad8"]),
+    (Code::Numpad9, "Num 9", &["num9", "numpad9"]),
+    (Code::NumpadAdd, "Num +", &["numadd", "numpadadd", "num+"]),
+    (
+        Code::NumpadSubtract,
+        "Num -",
+        &["numsubtract", "numpadsubtract", "num-"],
+    ),
+    (
+        Code::NumpadMultiply,
+        "Num *",
+        &["nummultiply", "numpadmultiply", "num*"],
+    ),
+    (
+        Code::NumpadDivide,
+        "Num /",
+        &["numdivide", "numpaddivide", "num/"],
+    ),
+    (
+        Code::NumpadDecimal,
+        "Num .",
+        &["numdecimal", "numpaddecimal", "num."],
+    ),
+    (
+        Code::NumpadEqual,
+        "Num =",
+        &["numequal", "numpadequal", "num="],
+    ),
+    (Code::NumpadEnter, "Num Enter", &["numenter", "numpadenter"]),
+    (
+        Code::MediaPlayPause,
+        "Play/Pause",
+        &["mediaplaypause", "playpause", "play/pause"],
+    ),
+    (Code::MediaStop, "Stop", &["mediastop", "stop"]),
+    (
+        Code::MediaTrackNext,
+        "Next Track",
+        &["mediatracknext", "nexttrack"],
+    ),
+    (
+        Code::MediaTrackPrevious,
+        "Previous Track",
+        &["mediatrackprevious", "previoustrack"],
+    ),
+    (
+        Code::MediaFastForward,
+        "Fast Forward",
+        &["mediafastforward", "fastforward"],
+    ),
+    (Code::MediaRewind, "Rewind", &["mediarewind", "rewind"]),
+    (
+        Code::AudioVolumeUp,
+        "Volume Up",
+        &["audiovolumeup", "volumeup"],
+    ),
+    (
+        Code::AudioVolumeDown,
+        "Volume Down",
+        &["audiovolumedown", "volumedown"],
+    ),
+    (
+        Code::AudioVolumeMute,
+        "Mute",
+        &["audiovolumemute", "volumemute", "mute"],
+    ),
+];
+
+/// F1 through F24
+const FUNCTION_KEYS: [Code; 24] = [
+    Code::F1,
+    Code::F2,
+    Code::F3,
+    Code::F4,
+    Code::F5,
+    Code::F6,
+    Code::F7,
+    Code::F8,
+    Code::F9,
+    Code::F10,
+    Code::F11,
+    Code::F12,
+    Code::F13,
+    Code::F14,
+    Code::F15,
+    Code::F16,
+    Code::F17,
+    Code::F18,
+    Code::F19,
+    Code::F20,
+    Code::F21,
+    Code::F22,
+    Code::F23,
+    Code::F24,
+];
+
+/// Physical key for a key name in a shortcut. Accepts single letters and digits ("A",
+/// "7"), F1 through F24, the names and symbols in `NAMED_KEYS`, and W3C code names as
+/// browsers report them in `KeyboardEvent.code` ("KeyA", "Semicolon", "Numpad1").
+/// Names describe key positions on a US layout, whatever the active layout prints.
+pub fn parse(name: &str) -> Option<Code> {
+    let name = name.trim();
+    // Labels such as "Page Up" are accepted too
+    let lower: String = name
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let mut chars = lower.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if c.is_ascii_lowercase() {
+            return Code::from_str(&format!("Key{}", c.to_ascii_uppercase())).ok();
+        }
+        if c.is_ascii_digit() {
+            return Code::from_str(&format!("Digit{c}")).ok();
+        }
+    }
+    if let Some(number) = lower
+        .strip_prefix('f')
+        .and_then(|n| n.parse::<usize>().ok())
+    {
+        return number
+            .checked_sub(1)
+            .and_then(|index| FUNCTION_KEYS.get(index))
+            .copied();
+    }
+    NAMED_KEYS
+        .iter()
+        .find(|(_, _, names)| names.contains(&lower.as_str()))
+        .map(|(code, _, _)| *code)
+        .or_else(|| Code::from_str(name).ok())
+}
+
+pub fn is_function_key(code: Code) -> bool {
+    FUNCTION_KEYS.contains(&code)
+}
+
+/// How a key is shown to the user, e.g. "A", "F13", ";" or "Num 1"
+pub fn label(code: Code) -> String {
+    if code == Code::Space {
+        return i18n::t("key.space");
+    }
+    if let Some((_, label, _)) = NAMED_KEYS.iter().find(|(named, _, _)| *named == code) {
+        return label.to_string();
+    }
+    let name = code.to_string();
+    ["Key", "Digit"]
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix))
+        .unwrap_or(&name)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_short_names_symbols_and_code_names() {
+        assert_eq!(parse("a"), Some(Code::KeyA));
+        assert_eq!(parse("KeyA"), Some(Code::KeyA));
+        assert_eq!(parse("7"), Some(Code::Digit7));
+        assert_eq!(parse(";"), Some(Code::Semicolon));
+        assert_eq!(parse("Semicolon"), Some(Code::Semicolon));
+        assert_eq!(parse("f13"), Some(Code::F13));
+        assert_eq!(parse("F24"), Some(Code::F24));
+        assert_eq!(parse("PageUp"), Some(Code::PageUp));
+        assert_eq!(parse("Numpad1"), Some(Code::Numpad1));
+        assert_eq!(parse("IntlBackslash"), Some(Code::IntlBackslash));
+        assert_eq!(parse("MediaPlayPause"), Some(Code::MediaPlayPause));
+        assert_eq!(parse("F25"), None);
+        assert_eq!(parse("F0"), None);
+        assert_eq!(parse("Foo"), None);
+    }
+
+    #[test]
+    fn labels_round_trip_through_parse() {
+        let named = NAMED_KEYS.iter().map(|(code, _, _)| *code);
+        let others = [Code::KeyQ, Code::Digit0, Code::F20];
+        for code in named.chain(others).filter(|code| *code != Code::Space) {
+            assert_eq!(parse(&label(code)), Some(code));
+        }
+        assert_eq!(label(Code::ArrowUp), "\u{2191}");
+        assert_eq!(label(Code::F13), "F13");
+    }
+}
@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::active_app::{self, ActiveApp};
+use crate::settings;
+
+// Dictating into Overleaf or a TeX editor, "integral from zero to one of x squared dx"
+// should become $\int_0^1 x^2 \, dx$. Post-processing uses a LaTeX template there,
+// spoken notation is written as LaTeX even without it, and the result is previewed
+// before it is pasted.
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LatexSettings {
+    pub enabled: bool,
+    /// App names, bundle identifiers or window title fragments (for Overleaf in a browser)
+    pub apps: Vec<String>,
+    /// Post-processing template used while a TeX editor is frontmost, empty to keep the usual one
+    pub template: String,
+    /// Show the LaTeX and wait for confirmation before pasting it
+    pub preview: bool,
+}
+
+impl Default for LatexSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            apps: [
+                "Overleaf",
+                "TeXShop",
+                "TeXstudio",
+                "Texmaker",
+                "TeXworks",
+                "LyX",
+            ]
+            .map(str::to_string)
+            .to_vec(),
+            template: "latex math".to_string(),
+            preview: true,
+        }
+    }
+}
+
+impl LatexSettings {
+    fn matches(&self, app: &ActiveApp) -> bool {
+        let title = app.window_title.to_lowercase();
+        self.enabled
+            && self.apps.iter().any(|target| {
+                let target = target.trim();
+                !target.is_empty()
+                    && (target.eq_ignore_ascii_case(&app.name)
+                        || target.eq_ignore_ascii_case(&app.bundle_id)
+                        || title.contains(&target.to_lowercase()))
+            })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LatexPreview {
+    latex: String,
+    /// Name of the TeX editor it is pasted into
+    app: String,
+}
+
+/// Whether dictations go into a TeX editor right now
+pub fn is_target(app: &AppHandle) -> bool {
+    let latex = settings::current(app).latex;
+    latex.enabled && active_app::frontmost_app().is_some_and(|frontmost| latex.matches(&frontmost))
+}
+
+/// Post-processing template for math dictated into a TeX editor, None elsewhere
+pub fn template(app: &AppHandle) -> Option<String> {
+    let template = settings::current(app).latex.template;
+    (!template.trim().is_empty() && is_target(app)).then_some(template)
+}
+
+/// Send `text` to the webview as `latex-preview` when it goes into a TeX editor.
+/// Returns whether pasting should wait until the user confirms the preview.
+pub fn preview(app: &AppHandle, text: &str) -> bool {
+    let latex = settings::current(app).latex;
+    let Some(frontmost) = active_app::frontmost_app().filter(|frontmost| latex.matches(frontmost))
+    else {
+        return false;
+    };
+    let _ = app.emit(
+        "latex-preview",
+        LatexPreview {
+            latex: text.to_string(),
+            app: frontmost.name,
+        },
+    );
+    latex.preview
+}
+
+#[tauri::command]
+pub async fn get_latex_settings(app: AppHandle) -> LatexSettings {
+    settings::current(&app).latex
+}
+
+#[tauri::command]
+pub async fn set_latex_settings(app: AppHandle, latex: LatexSettings) -> Result<(), String> {
+    settings::update(&app, |settings| settings.latex = latex)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_tex_editors_by_window_title() {
+        let latex = LatexSettings {
+            enabled: true,
+            ..LatexSettings::default()
+        };
+        let overleaf = ActiveApp {
+            name: "Google Chrome".to_string(),
+            bundle_id: "com.google.Chrome".to_string(),
+            window_title: "thesis - Online LaTeX Editor Overleaf".to_string(),
+        };
+        assert!(latex.matches(&overleaf));
+        let docs = ActiveApp {
+            window_title: "Notes - Google Docs".to_string(),
+            ..overleaf.clone()
+        };
+        assert!(!latex.matches(&docs));
+        let disabled = LatexSettings::default();
+        assert!(!disabled.matches(&overleaf));
+    }
+}
@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::text_diff::{self, TextEdit};
+use crate::vocabulary::{self, LearnedWord};
+use crate::{incognito, insertion, settings};
+
+/// How long after pasting the target is read back, giving the user time to fix words
+const OBSERVE_AFTER: Duration = Duration::from_secs(30);
+/// A substitution is suggested once it has been observed this many times
+const SUGGEST_AFTER: u32 = 3;
+
+// Bumped by every paste, so only the latest one is observed
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Replacement {
+    pub heard: String,
+    pub written: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CorrectionLearningSettings {
+    /// Read pasted text back from the target app and the clipboard to spot corrections
+    pub enabled: bool,
+    /// Substitutions seen so far, with how often
+    pub observed: Vec<LearnedWord>,
+    /// Suggestions the user turned down
+    pub dismissed: Vec<Replacement>,
+}
+
+/// Substitutions between the pasted text and what was later observed. Rewritten
+/// or vanished text yields nothing, so unrelated field contents aren't mined.
+fn substitutions(pasted: &str, observed: &str) -> Vec<TextEdit> {
+    let edits = text_diff::word_diff(pasted, observed);
+    let removed: usize = edits
+        .iter()
+        .map(|edit| edit.removed.split_whitespace().count())
+        .sum();
+    if removed * 2 > pasted.split_whitespace().count() {
+        return Vec::new();
+    }
+    edits
+        .into_iter()
+        .filter(|edit| {
+            edit.removed.split_whitespace().count() == 1
+                && edit.inserted.split_whitespace().count() == 1
+        })
+        .collect()
+}
+
+/// Frequent substitutions that aren't learned or dismissed yet, most frequent first
+fn suggestions(learning: &CorrectionLearningSettings, learned: &[LearnedWord]) -> Vec<LearnedWord> {
+    let mut suggested: Vec<LearnedWord> = learning
+        .observed
+        .iter()
+        .filter(|observed| observed.count >= SUGGEST_AFTER)
+        .filter(|observed| {
+            !learning.dismissed.iter().any(|dismissed| {
+                dismissed.heard == observed.heard && dismissed.written == observed.written
+            })
+        })
+        .filter(|observed| {
+            vocabulary::correction(learned, &observed.heard) != Some(observed.written.as_str())
+        })
+        .cloned()
+        .collect();
+    suggested.sort_by_key(|suggestion| std::cmp::Reverse(suggestion.count));
+    suggested
+}
+
+fn observe(app: &AppHandle, pasted: &str) {
+    let observed = [insertion::focused_value(), app.clipboard().read_text().ok()];
+    let edits: Vec<TextEdit> = observed
+        .iter()
+        .flatten()
+        .filter(|text| text.as_str() != pasted)
+        .flat_map(|text| substitutions(pasted, text))
+        .collect();
+    if edits.is_empty() {
+        return;
+    }
+    if let Err(e) = settings::update(app, |settings| {
+        vocabulary::count_fixes(&mut settings.correction_learning.observed, &edits)
+    }) {
+        eprintln!("[voiceflow] Failed to save observed corrections: {e}");
+    }
+}
+
+/// Look at the target again a while after a dictation was pasted into it
+pub fn pasted(app: &AppHandle, text: &str) {
+    if incognito::is_active() || !settings::current(app).correction_learning.enabled {
+        return;
+    }
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let app = app.clone();
+    let pasted = text.to_string();
+    thread::spawn(move || {
+        thread::sleep(OBSERVE_AFTER);
+        if GENERATION.load(Ordering::SeqCst) == generation {
+            observe(&app, &pasted);
+        }
+    });
+}
+
+/// Turning learning off also forgets everything observed
+#[tauri::command]
+pub async fn set_correction_learning(app: AppHandle, enabled: bool) -> Result<(), String> {
+    settings::update(&app, |settings| {
+        settings.correction_learning.enabled = enabled;
+        if !enabled {
+            settings.correction_learning.observed.clear();
+        }
+    })?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_suggested_replacements(app: AppHandle) -> Vec<LearnedWord> {
+    let settings = settings::current(&app);
+    suggestions(&settings.correction_learning, &settings.vocabulary.learned)
+}
+
+/// Add a suggestion to the vocabulary, so it is corrected in future dictations
+#[tauri::command]
+pub async fn accept_suggested_replacement(
+    app: AppHandle,
+    replacement: Replacement,
+) -> Result<(), String> {
+    settings::update(&app, |settings| {
+        let learning = &mut settings.correction_learning;
+        learning.observed.retain(|observed| {
+            observed.heard != replacement.heard || observed.written != replacement.written
+        });
+        vocabulary::add(
+            &mut settings.vocabulary.learned,
+            &replacement.heard,
+            &replacement.written,
+        );
+    })?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn dismiss_suggested_replacement(
+    app: AppHandle,
+    replacement: Replacement,
+) -> Result<(), String> {
+    settings::update(&app, |settings| {
+        let learning = &mut settings.correction_learning;
+        learning.observed.retain(|observed| {
+            observed.heard != replacement.heard || observed.written != replacement.written
+        });
+        if !learning.dismissed.contains(&replacement) {
+            learning.dismissed.push(replacement);
+        }
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mines_substitutions_from_observed_text() {
+        let pasted = "please ask jon about the cooper netties rollout";
+        let field = "Hi all, please ask John about the cooper netties rollout.\nThanks";
+        let edits = substitutions(pasted, field);
+        let pairs: Vec<(&str, &str)> = edits
+            .iter()
+            .map(|edit| (edit.removed.as_str(), edit.inserted.as_str()))
+            .collect();
+        assert_eq!(pairs, vec![("jon", "John")]);
+
+        assert!(substitutions(pasted, "something else entirely").is_empty());
+    }
+}
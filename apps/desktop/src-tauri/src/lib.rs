@@ -1,14 +1,11 @@
 use serde::{Deserialize, Serialize};
-use std::fs::{self, OpenOptions};
+use std::fs::OpenOptions;
 use std::io::Write;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tauri::{
-    image::Image,
-    menu::{Menu, MenuItem},
-    tray::TrayIconBuilder,
     AppHandle, Emitter, Manager, PhysicalPosition, Position, WebviewUrl, WebviewWindowBuilder,
 };
 use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
@@ -17,12 +14,124 @@ use tauri_plugin_shell::{
     ShellExt,
 };
 
+mod accessibility;
+mod active_app;
+mod app_profiles;
+mod app_rules;
+mod app_stats;
+mod auth;
+mod audio;
+mod audio_file;
+mod auto_send;
+mod batch;
+mod benchmark;
+mod browser;
+mod calendar;
+mod captions;
+mod caret_context;
+mod char_limit;
+mod chunking;
+mod clipboard;
+mod command_output;
+mod continuous;
+mod demo;
+mod devices;
+mod diagnostics;
+mod digest;
+mod email;
+mod evaluation;
+mod feature_flags;
+mod form_fill;
+mod gestures;
+mod grammar;
+mod history;
+mod history_db;
+mod history_import;
+mod history_picker;
+mod history_sync;
+mod housekeeping;
+mod i18n;
+mod incognito;
+mod insertion;
+mod keyboard;
+mod keychain;
+mod keys;
+mod latex;
+mod learning;
+mod login_item;
+mod markdown;
+mod meeting;
+mod microphone;
+mod normalize;
+mod notation;
+mod partials;
+mod pipeline;
+mod plugins;
+mod policy;
+mod postprocess;
+mod profiles;
+mod project;
+mod prompts;
+mod providers;
+mod redaction;
+mod replay;
+mod result_panel;
+mod scratchpad;
+mod screen_share;
+mod scripting;
+mod search;
+mod selection;
+mod session;
+mod settings;
+mod shortcut_capture;
+mod shortcuts;
+mod shutdown;
+mod snippets;
+mod speech;
+mod spelling;
+mod startup;
+mod switch_access;
+mod supervisor;
+mod symbols;
+mod sync;
+mod target_app;
+mod text_diff;
+mod transcription;
+mod tray;
+#[cfg(target_os = "macos")]
+mod url_scheme;
+mod vocabulary;
+mod voice_commands;
+mod wake_word;
+mod watchdog;
+mod weekly_summary;
+
+use batch::BatchJobStore;
+use feature_flags::FeatureFlagManager;
+use history::HistoryStore;
+use history_sync::HistorySync;
+use plugins::PluginManager;
+use profiles::ProfileManager;
+use prompts::PromptTemplateManager;
+use scratchpad::Scratchpad;
+use scripting::ScriptManager;
+use settings::{BubblePlacement, ReopenBehavior, SettingsManager};
+use shortcuts::ShortcutAction;
+use snippets::SnippetManager;
+use supervisor::ServerStatus;
+use symbols::SymbolManager;
+use sync::SyncManager;
+use tray::TrayState;
+use voice_commands::VoiceCommandManager;
+
 #[cfg(target_os = "macos")]
 use tauri_nspanel::WebviewWindowExt as NSPanelWebviewWindowExt;
 #[cfg(target_os = "macos")]
 use tauri_nspanel::objc2::{runtime::NSObjectProtocol, ClassType, Message};
 
 static IS_RECORDING: AtomicBool = AtomicBool::new(false);
+// Set while a text field in one of VoiceFlow's own windows has focus
+static SHORTCUTS_SUSPENDED: AtomicBool = AtomicBool::new(false);
 
 fn append_e2e_log(event: &str) {
     let Ok(path) = std::env::var("VOICEFLOW_E2E_LOG") else {
@@ -68,10 +177,33 @@ fn setup_macos_panel(window: &tauri::WebviewWindow) {
     );
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// How a dictation shortcut records
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShortcutMode {
+    /// Record while the shortcut is held down
+    #[default]
+    Hold,
+    /// One press starts recording, the next one stops it
+    Toggle,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ShortcutConfig {
     pub modifiers: Vec<String>,
     pub key: String,
+    /// Post-processing prompt template used for dictations started with this shortcut
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_template: Option<String>,
+    /// Dictations started with this shortcut are pasted but never stored
+    #[serde(default)]
+    pub incognito: bool,
+    #[serde(default)]
+    pub mode: ShortcutMode,
+    /// App brought to the front, and launched if needed, before the transcript is
+    /// pasted: a bundle identifier or app name on macOS, an executable elsewhere
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_app: Option<String>,
 }
 
 impl Default for ShortcutConfig {
@@ -79,6 +211,10 @@ impl Default for ShortcutConfig {
         Self {
             modifiers: vec!["Alt".to_string()],
             key: "Space".to_string(),
+            prompt_template: None,
+            incognito: false,
+            mode: ShortcutMode::Hold,
+            target_app: None,
         }
     }
 }
@@ -96,117 +232,97 @@ impl ShortcutConfig {
             }
         }
 
-        let code = match self.key.to_lowercase().as_str() {
-            "space" => Code::Space,
-            "a" => Code::KeyA,
-            "b" => Code::KeyB,
-            "c" => Code::KeyC,
-            "d" => Code::KeyD,
-            "e" => Code::KeyE,
-            "f" => Code::KeyF,
-            "g" => Code::KeyG,
-            "h" => Code::KeyH,
-            "i" => Code::KeyI,
-            "j" => Code::KeyJ,
-            "k" => Code::KeyK,
-            "l" => Code::KeyL,
-            "m" => Code::KeyM,
-            "n" => Code::KeyN,
-            "o" => Code::KeyO,
-            "p" => Code::KeyP,
-            "q" => Code::KeyQ,
-            "r" => Code::KeyR,
-            "s" => Code::KeyS,
-            "t" => Code::KeyT,
-            "u" => Code::KeyU,
-            "v" => Code::KeyV,
-            "w" => Code::KeyW,
-            "x" => Code::KeyX,
-            "y" => Code::KeyY,
-            "z" => Code::KeyZ,
-            "1" => Code::Digit1,
-            "2" => Code::Digit2,
-            "3" => Code::Digit3,
-            "4" => Code::Digit4,
-            "5" => Code::Digit5,
-            "6" => Code::Digit6,
-            "7" => Code::Digit7,
-            "8" => Code::Digit8,
-            "9" => Code::Digit9,
-            "0" => Code::Digit0,
-            "f1" => Code::F1,
-            "f2" => Code::F2,
-            "f3" => Code::F3,
-            "f4" => Code::F4,
-            "f5" => Code::F5,
-            "f6" => Code::F6,
-            "f7" => Code::F7,
-            "f8" => Code::F8,
-            "f9" => Code::F9,
-            "f10" => Code::F10,
-            "f11" => Code::F11,
-            "f12" => Code::F12,
-            _ => return None,
-        };
+        let code = keys::parse(&self.key)?;
 
         Some(Shortcut::new(if mods.is_empty() { None } else { Some(mods) }, code))
     }
 
+    #[cfg(target_os = "macos")]
     fn display_string(&self) -> String {
         let mut parts = Vec::new();
         for m in &self.modifiers {
             match m.to_lowercase().as_str() {
-                "alt" | "option" => parts.push("\u{2325}"),
-                "ctrl" | "control" => parts.push("\u{2303}"),
-                "shift" => parts.push("\u{21E7}"),
-                "super" | "cmd" | "command" | "meta" => parts.push("\u{2318}"),
+                "alt" | "option" => parts.push("\u{2325}".to_string()),
+                "ctrl" | "control" => parts.push("\u{2303}".to_string()),
+                "shift" => parts.push("\u{21E7}".to_string()),
+                "super" | "cmd" | "command" | "meta" => parts.push("\u{2318}".to_string()),
                 _ => {}
             }
         }
-        parts.push(&self.key);
+        parts.push(self.key_name());
         parts.join(" ")
     }
-}
 
-pub struct ShortcutManager {
-    config: ShortcutConfig,
-    config_path: PathBuf,
+    /// Modifier names as printed on Windows and Linux keyboards, e.g. "Ctrl+Alt+Space"
+    #[cfg(not(target_os = "macos"))]
+    fn display_string(&self) -> String {
+        let super_name = if cfg!(target_os = "windows") { "Win" } else { "Super" };
+        let mut parts = Vec::new();
+        for m in &self.modifiers {
+            match m.to_lowercase().as_str() {
+                "ctrl" | "control" => parts.push(i18n::t("key.ctrl")),
+                "alt" | "option" => parts.push(i18n::t("key.alt")),
+                "shift" => parts.push(i18n::t("key.shift")),
+                "super" | "cmd" | "command" | "meta" => parts.push(super_name.to_string()),
+                _ => {}
+            }
+        }
+        parts.push(self.key_name());
+        parts.join("+")
+    }
+
+    fn key_name(&self) -> String {
+        keys::parse(&self.key).map_or_else(|| self.key.to_uppercase(), keys::label)
+    }
 }
 
 #[derive(Default)]
 struct ServerManager {
     child: Option<CommandChild>,
+    /// Whether the sidecar should be running, so the supervisor restarts it
+    wanted: bool,
+    status: ServerStatus,
+    started_at: Option<Instant>,
+    /// Automatic restarts since the app started
+    restarts: u32,
+    /// Restarts since the sidecar was last healthy, which sets the backoff
+    failed_restarts: u32,
+    /// The sidecar couldn't bind its port, so the supervisor's restart picks another
+    port_taken: bool,
 }
 
-impl ShortcutManager {
-    fn new(config_dir: PathBuf) -> Self {
-        let config_path = config_dir.join("shortcut.json");
-        let config = Self::load_config(&config_path).unwrap_or_default();
-        Self { config, config_path }
-    }
-
-    fn load_config(path: &PathBuf) -> Option<ShortcutConfig> {
-        let content = fs::read_to_string(path).ok()?;
-        serde_json::from_str(&content).ok()
-    }
+/// Where the webview reaches the sidecar
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ServerEndpoint {
+    port: u16,
+    http_url: String,
+    ws_url: String,
+}
 
-    fn save_config(&self) -> Result<(), String> {
-        if let Some(parent) = self.config_path.parent() {
-            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+impl ServerManager {
+    /// Keep the current port while it is free, so clients that already know it keep
+    /// working, and let the OS pick one when another instance or app holds it. The
+    /// port can still be taken before the sidecar binds it; the sidecar then exits
+    /// and is restarted on another one.
+    fn pick_port(&self) -> Result<u16, String> {
+        let port = transcription::server_port();
+        if !self.port_taken && std::net::TcpListener::bind(("127.0.0.1", port)).is_ok() {
+            return Ok(port);
         }
-        let content = serde_json::to_string_pretty(&self.config).map_err(|e| e.to_string())?;
-        fs::write(&self.config_path, content).map_err(|e| e.to_string())?;
-        Ok(())
-    }
-
-    fn get_config(&self) -> ShortcutConfig {
-        self.config.clone()
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0))
+            .map_err(|e| format!("Failed to find a free port for the sidecar: {e}"))?;
+        let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+        eprintln!("[voiceflow] Sidecar port {} is taken, using {port}", transcription::server_port());
+        Ok(port)
     }
+}
 
-    fn set_config(&mut self, config: ShortcutConfig) -> Result<(), String> {
-        self.config = config;
-        self.save_config()
-    }
+/// Whether the sidecar's log line reports that its port was already in use
+fn is_port_conflict(line: &str) -> bool {
+    let line = line.to_lowercase();
+    line.contains("address already in use")
+        || line.contains("only one usage of each socket address")
 }
 
 fn ensure_sidecar_running(app: &AppHandle) -> Result<(), String> {
@@ -215,21 +331,28 @@ fn ensure_sidecar_running(app: &AppHandle) -> Result<(), String> {
         .lock()
         .map_err(|e| e.to_string())?;
 
+    server_manager.wanted = true;
     if server_manager.child.is_some() {
         return Ok(());
     }
 
+    let port = server_manager.pick_port()?;
     let (mut rx, child) = app
         .shell()
         .sidecar("voiceflow-server")
         .map_err(|e| format!("Failed to prepare sidecar: {e}"))?
+        .args(["--port", &port.to_string()])
         .spawn()
         .map_err(|e| format!("Failed to spawn sidecar: {e}"))?;
 
     let pid = child.pid();
+    transcription::set_server_port(port);
+    server_manager.port_taken = false;
     server_manager.child = Some(child);
+    server_manager.started_at = Some(Instant::now());
     drop(server_manager);
     append_e2e_log("server-started");
+    supervisor::set_status(app, ServerStatus::Starting);
 
     let app_handle = app.clone();
     tauri::async_runtime::spawn(async move {
@@ -239,10 +362,13 @@ fn ensure_sidecar_running(app: &AppHandle) -> Result<(), String> {
                     eprintln!("[voiceflow-server:{pid}] {}", String::from_utf8_lossy(&line));
                 }
                 CommandEvent::Stderr(line) => {
-                    eprintln!(
-                        "[voiceflow-server:{pid}][stderr] {}",
-                        String::from_utf8_lossy(&line)
-                    );
+                    let line = String::from_utf8_lossy(&line);
+                    eprintln!("[voiceflow-server:{pid}][stderr] {line}");
+                    if is_port_conflict(&line) {
+                        if let Ok(mut manager) = app_handle.state::<Mutex<ServerManager>>().lock() {
+                            manager.port_taken = true;
+                        }
+                    }
                 }
                 CommandEvent::Error(error) => {
                     eprintln!("[voiceflow-server:{pid}][error] {error}");
@@ -256,12 +382,20 @@ fn ensure_sidecar_running(app: &AppHandle) -> Result<(), String> {
         }
 
         let server_state = app_handle.state::<Mutex<ServerManager>>();
-        if let Ok(mut manager) = server_state.lock() {
-            let tracked_pid = manager.child.as_ref().map(CommandChild::pid);
-            if tracked_pid == Some(pid) {
-                manager.child = None;
+        let crashed = match server_state.lock() {
+            Ok(mut manager) => {
+                let tracked_pid = manager.child.as_ref().map(CommandChild::pid);
+                if tracked_pid == Some(pid) {
+                    manager.child = None;
+                }
+                // Killed on purpose by stop_sidecar or the supervisor otherwise
+                tracked_pid == Some(pid) && manager.wanted
             }
+            Err(_) => false,
         };
+        if crashed {
+            supervisor::set_status(&app_handle, ServerStatus::Crashed);
+        }
     });
 
     Ok(())
@@ -273,8 +407,10 @@ fn stop_sidecar(app: &AppHandle) -> Result<(), String> {
         let mut manager = server_state
             .lock()
             .map_err(|e| e.to_string())?;
+        manager.wanted = false;
         manager.child.take()
     };
+    supervisor::set_status(app, ServerStatus::Stopped);
 
     if let Some(child) = child {
         if let Err(error) = child.kill() {
@@ -287,25 +423,27 @@ fn stop_sidecar(app: &AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// Place the bubble on the screen with the mouse pointer, which is usually the one
+/// being typed on, falling back to the bubble's own screen
 fn position_bubble(app: &AppHandle) {
     let Some(window) = app.get_webview_window("main") else {
         return;
     };
-    let monitor = window
-        .current_monitor()
+    let monitor = app
+        .cursor_position()
         .ok()
-        .flatten()
+        .and_then(|cursor| app.monitor_from_point(cursor.x, cursor.y).ok().flatten())
+        .or_else(|| window.current_monitor().ok().flatten())
         .or_else(|| window.primary_monitor().ok().flatten());
 
     let (Some(monitor), Ok(size)) = (monitor, window.outer_size()) else {
         return;
     };
 
-    let screen = monitor.size();
-    let screen_pos = monitor.position();
+    // The work area leaves out the menu bar, Dock and taskbar
     let margin = 24;
-    let x = screen_pos.x + ((screen.width as i32 - size.width as i32) / 2);
-    let y = screen_pos.y + screen.height as i32 - size.height as i32 - margin;
+    let placement = settings::current(app).bubble_placement;
+    let (x, y) = placement.origin(*monitor.work_area(), size, margin);
     let _ = window.set_position(Position::Physical(PhysicalPosition::new(x, y)));
 }
 
@@ -318,6 +456,10 @@ fn focus_and_bring_to_front(window: &tauri::WebviewWindow) {
 }
 
 fn show_main_window(app: &AppHandle) {
+    if screen_share::should_hide_overlay(app) {
+        append_e2e_log("bubble-suppressed");
+        return;
+    }
     if let Some(window) = app.get_webview_window("main") {
         position_bubble(app);
         let _ = window.show();
@@ -325,6 +467,98 @@ fn show_main_window(app: &AppHandle) {
     }
 }
 
+/// Begin a dictation, returning false if one is already running or an app rule forbids it
+fn start_recording(app: &AppHandle) -> bool {
+    if tray::is_paused() {
+        append_e2e_log("recording-paused");
+        return false;
+    }
+    if app_rules::blocks_dictation(app) {
+        append_e2e_log("recording-blocked-by-rule");
+        return false;
+    }
+    if IS_RECORDING.swap(true, Ordering::SeqCst) {
+        return false;
+    }
+    // A transcript still being read back would end up in the recording
+    speech::stop(app);
+    incognito::session_started(app);
+    session::started();
+    active_app::capture_in_background();
+    browser::capture_in_background(app);
+    selection::capture_in_background(app);
+    audio::start(app);
+    tray::set_state(app, TrayState::Recording);
+    let _ = app.emit("recording-start", ());
+    accessibility::announce_key(app, "a11y.recording");
+    show_main_window(app);
+    true
+}
+
+fn stop_recording(app: &AppHandle) {
+    if IS_RECORDING.swap(false, Ordering::SeqCst) {
+        session::stopped();
+        audio::stop();
+        tray::set_state(app, TrayState::Processing);
+        let _ = app.emit("recording-stop", ());
+        accessibility::announce_key(app, "a11y.transcribing");
+    }
+}
+
+/// Drop the running dictation without transcribing it
+fn cancel_recording(app: &AppHandle) {
+    if IS_RECORDING.swap(false, Ordering::SeqCst) {
+        session::stopped();
+        audio::discard();
+        tray::set_state(app, TrayState::Idle);
+        result_panel::dictation_cancelled();
+        #[cfg(target_os = "macos")]
+        url_scheme::dictation_cancelled();
+        let _ = app.emit("recording-cancel", ());
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.hide();
+        }
+    }
+}
+
+/// Dock icon click, as configured by the reopen behavior setting
+#[cfg(target_os = "macos")]
+fn handle_reopen(app: &AppHandle, has_visible_windows: bool) {
+    match settings::current(app).reopen_behavior {
+        ReopenBehavior::OpenMainApp => {
+            if !has_visible_windows {
+                show_or_create_main_app(app);
+            }
+        }
+        ReopenBehavior::ShowBubble => show_main_window(app),
+        ReopenBehavior::ToggleRecording => {
+            if IS_RECORDING.load(Ordering::SeqCst) {
+                stop_recording(app);
+            } else if start_recording(app) {
+                append_e2e_log("reopen-recording-start");
+            }
+        }
+    }
+}
+
+#[tauri::command]
+async fn set_reopen_behavior(app: AppHandle, behavior: ReopenBehavior) -> Result<(), String> {
+    settings::update(&app, |settings| settings.reopen_behavior = behavior)?;
+    Ok(())
+}
+
+#[tauri::command]
+async fn set_bubble_placement(app: AppHandle, placement: BubblePlacement) -> Result<(), String> {
+    settings::update(&app, |settings| settings.bubble_placement = placement)?;
+    let visible = app
+        .get_webview_window("main")
+        .is_some_and(|window| window.is_visible().unwrap_or(false));
+    if visible {
+        position_bubble(&app);
+    }
+    Ok(())
+}
+
 #[tauri::command]
 async fn show_bubble(app: AppHandle) {
     show_main_window(&app);
@@ -348,8 +582,54 @@ async fn resize_main_window(app: AppHandle, width: f64, height: f64, centered: b
     }
 }
 
+/// Paste the clipboard into the frontmost app, first placing `text` on it when given
 #[tauri::command]
-async fn paste_from_clipboard() -> Result<(), String> {
+async fn paste_from_clipboard(
+    app: AppHandle,
+    text: Option<String>,
+    history_id: Option<u64>,
+) -> Result<(), String> {
+    let (text, overflow) = match text {
+        Some(text) => {
+            let (text, overflow) = insertion::prepare(&app, text);
+            (Some(text), overflow)
+        }
+        None => (None, None),
+    };
+    let (strategy, result) = insertion::paste_or_type(&app, text.as_deref());
+    insertion::finish(&app, &result, overflow);
+    insertion::report(&app, history_id, strategy, text, &result);
+    result
+}
+
+fn paste_text(app: &AppHandle, text: Option<&str>) -> Result<(), String> {
+    let Some(text) = text else {
+        return press_paste();
+    };
+    let restore = settings::current(app).restore_clipboard;
+    if restore.enabled {
+        clipboard::preserve(app);
+    }
+    let result = if let Some(html) = email::html_for(text) {
+        clipboard::write_html_verified(app, &html, text)
+    } else if let Some(rich) = markdown::rich_for(text) {
+        clipboard::write_rich_verified(app, &rich.html, &rich.rtf, text)
+    } else {
+        clipboard::write_verified(app, text)
+    }
+    .and_then(|()| press_paste());
+    if restore.enabled {
+        match &result {
+            Ok(()) => clipboard::restore_later(app, text, restore.delay()),
+            // The error tells the user the transcript is on the clipboard, so leave it
+            Err(_) => clipboard::discard(),
+        }
+    }
+    result
+}
+
+/// Send the platform's paste shortcut to the frontmost app
+fn press_paste() -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
         use std::process::Command;
@@ -376,30 +656,55 @@ async fn paste_from_clipboard() -> Result<(), String> {
             return Err(stderr.to_string());
         }
     }
+
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
+    {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        if let Err(e) = keyboard::press_paste() {
+            eprintln!("[voiceflow] Paste failed: {}", e);
+            return Err(e);
+        }
+    }
     Ok(())
 }
 
 #[tauri::command]
 async fn get_current_shortcut(app: AppHandle) -> Result<ShortcutConfig, String> {
-    let state = app.state::<Mutex<ShortcutManager>>();
-    let manager = state.lock().map_err(|e| e.to_string())?;
-    Ok(manager.get_config())
+    Ok(current_shortcut_config(&app))
+}
+
+/// Display string for a shortcut in the platform's and locale's conventions
+#[tauri::command]
+async fn format_shortcut(modifiers: Vec<String>, key: String) -> String {
+    ShortcutConfig {
+        modifiers,
+        key,
+        prompt_template: None,
+        incognito: false,
+        mode: ShortcutMode::Hold,
+        target_app: None,
+    }
+    .display_string()
 }
 
 #[tauri::command]
 async fn set_shortcut(app: AppHandle, modifiers: Vec<String>, key: String) -> Result<(), String> {
-    let new_config = ShortcutConfig { modifiers, key };
+    let current_config = current_shortcut_config(&app);
+    let new_config = ShortcutConfig {
+        modifiers,
+        key,
+        prompt_template: current_config.prompt_template.clone(),
+        incognito: current_config.incognito,
+        mode: current_config.mode,
+        target_app: current_config.target_app.clone(),
+    };
 
     // Validate the shortcut can be created
     let new_shortcut = new_config.to_shortcut()
-        .ok_or_else(|| "Invalid shortcut configuration".to_string())?;
+        .ok_or_else(|| i18n::t("error.invalid_shortcut"))?;
 
     // Get current shortcut to unregister
-    let current_shortcut = {
-        let state = app.state::<Mutex<ShortcutManager>>();
-        let manager = state.lock().map_err(|e| e.to_string())?;
-        manager.get_config().to_shortcut()
-    };
+    let current_shortcut = current_config.to_shortcut();
 
     // Unregister old shortcut
     if let Some(old_shortcut) = current_shortcut {
@@ -412,24 +717,154 @@ async fn set_shortcut(app: AppHandle, modifiers: Vec<String>, key: String) -> Re
         if let Some(old_shortcut) = current_shortcut {
             let _ = app.global_shortcut().register(old_shortcut);
         }
-        return Err(format!("Failed to register shortcut: {}", e));
+        return Err(i18n::tf("error.register_shortcut", &[("error", &e.to_string())]));
+    }
+    // Registering proved the shortcut is free; it comes back when shortcuts resume
+    if shortcuts_suspended() {
+        let _ = app.global_shortcut().unregister(new_shortcut);
     }
 
     // Save new config
-    {
-        let state = app.state::<Mutex<ShortcutManager>>();
-        let mut manager = state.lock().map_err(|e| e.to_string())?;
-        manager.set_config(new_config.clone())?;
-    }
+    update_dictation_shortcut(&app, |config| *config = new_config)?;
 
     // Update tray menu text
-    update_tray_menu_text(&app, &new_config);
+    tray::refresh_menu(&app);
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn set_shortcut_prompt_template(app: AppHandle, template: Option<String>) -> Result<(), String> {
+    if let Some(name) = &template {
+        prompts::get_template(&app, name)
+            .ok_or_else(|| i18n::tf("error.template_not_found", &[("name", name)]))?;
+    }
+
+    update_dictation_shortcut(&app, |config| config.prompt_template = template)
+}
+
+#[tauri::command]
+async fn set_shortcut_incognito(app: AppHandle, incognito: bool) -> Result<(), String> {
+    update_dictation_shortcut(&app, |config| config.incognito = incognito)
+}
+
+#[tauri::command]
+async fn set_shortcut_target_app(app: AppHandle, target_app: Option<String>) -> Result<(), String> {
+    let target_app = target_app
+        .map(|target| target.trim().to_string())
+        .filter(|target| !target.is_empty());
+    update_dictation_shortcut(&app, |config| config.target_app = target_app)
+}
+
+#[tauri::command]
+async fn set_shortcut_mode(app: AppHandle, mode: ShortcutMode) -> Result<(), String> {
+    update_dictation_shortcut(&app, |config| config.mode = mode)?;
+    tray::refresh_menu(&app);
+    let _ = app.emit("shortcut-mode-changed", mode);
+    Ok(())
+}
+
+/// Config of whichever dictation shortcut was pressed
+fn pressed_shortcut_config(app: &AppHandle, shortcut: &Shortcut) -> Option<ShortcutConfig> {
+    [
+        Some(current_shortcut_config(app)),
+        voice_commands::command_shortcut_config(app),
+        settings::current(app).dictate_and_send.shortcut,
+        shortcuts::config_for(app, ShortcutAction::Search),
+    ]
+    .into_iter()
+    .flatten()
+    .find(|config| config.to_shortcut().as_ref() == Some(shortcut))
+}
 
+/// Hold or toggle, from the config of whichever dictation shortcut was pressed
+fn shortcut_mode(app: &AppHandle, shortcut: &Shortcut) -> ShortcutMode {
+    pressed_shortcut_config(app, shortcut).map_or(ShortcutMode::Hold, |config| config.mode)
+}
+
+/// Prompt template selected by the shortcut that triggered the current dictation
+fn active_prompt_template(app: &AppHandle) -> Option<String> {
+    current_shortcut_config(app).prompt_template
+}
+
+fn current_shortcut_config(app: &AppHandle) -> ShortcutConfig {
+    shortcuts::stored(app, ShortcutAction::Dictate).unwrap_or_default()
+}
+
+fn update_dictation_shortcut(
+    app: &AppHandle,
+    apply: impl FnOnce(&mut ShortcutConfig),
+) -> Result<(), String> {
+    settings::update(app, |settings| {
+        apply(settings.shortcuts.entry(ShortcutAction::Dictate).or_default())
+    })?;
     Ok(())
 }
 
-/// Placeholder for dynamic tray menu updates (not supported in Tauri 2.x)
-fn update_tray_menu_text(_app: &AppHandle, _config: &ShortcutConfig) {}
+/// Register the named shortcuts of the active profile and the feature shortcuts
+fn register_shortcuts(app: &AppHandle) {
+    let _ = app.global_shortcut().unregister_all();
+    if shortcuts_suspended() {
+        return;
+    }
+
+    let shortcut = current_shortcut_config(app).to_shortcut()
+        .unwrap_or_else(|| Shortcut::new(Some(Modifiers::ALT), Code::Space));
+    if let Err(e) = app.global_shortcut().register(shortcut) {
+        eprintln!("[voiceflow] Failed to register shortcut: {}", e);
+    }
+    if let Some(command_shortcut) = voice_commands::command_shortcut(app) {
+        if let Err(e) = app.global_shortcut().register(command_shortcut) {
+            eprintln!("[voiceflow] Failed to register command mode shortcut: {}", e);
+        }
+    }
+    if let Some(send_shortcut) = auto_send::shortcut(app) {
+        if let Err(e) = app.global_shortcut().register(send_shortcut) {
+            eprintln!("[voiceflow] Failed to register dictate and send shortcut: {}", e);
+        }
+    }
+    if let Some(cancel_shortcut) = shortcuts::shortcut(app, ShortcutAction::Cancel) {
+        if let Err(e) = app.global_shortcut().register(cancel_shortcut) {
+            eprintln!("[voiceflow] Failed to register cancel shortcut: {}", e);
+        }
+    }
+    if let Some(search_shortcut) = shortcuts::shortcut(app, ShortcutAction::Search) {
+        if let Err(e) = app.global_shortcut().register(search_shortcut) {
+            eprintln!("[voiceflow] Failed to register search shortcut: {}", e);
+        }
+    }
+    if let Some(picker_shortcut) = history_picker::shortcut(app) {
+        if let Err(e) = app.global_shortcut().register(picker_shortcut) {
+            eprintln!("[voiceflow] Failed to register history picker shortcut: {}", e);
+        }
+    }
+}
+
+fn shortcuts_suspended() -> bool {
+    SHORTCUTS_SUSPENDED.load(Ordering::SeqCst)
+}
+
+/// Release the global shortcuts while the user types in one of our own text fields,
+/// so Alt+Space and the like reach the field instead of starting a dictation
+#[tauri::command]
+async fn suspend_shortcuts(app: AppHandle) {
+    if !SHORTCUTS_SUSPENDED.swap(true, Ordering::SeqCst) {
+        let _ = app.global_shortcut().unregister_all();
+        append_e2e_log("shortcuts-suspended");
+    }
+}
+
+#[tauri::command]
+async fn resume_shortcuts(app: AppHandle) {
+    resume_suspended_shortcuts(&app);
+}
+
+fn resume_suspended_shortcuts(app: &AppHandle) {
+    if SHORTCUTS_SUSPENDED.swap(false, Ordering::SeqCst) {
+        register_shortcuts(app);
+        append_e2e_log("shortcuts-resumed");
+    }
+}
 
 fn show_or_create_main_app(app: &AppHandle) {
     if let Some(window) = app.get_webview_window("main-app") {
@@ -464,37 +899,20 @@ async fn stop_server(app: AppHandle) -> Result<(), String> {
     stop_sidecar(&app)
 }
 
-fn setup_tray(app: &AppHandle, shortcut_display: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let quit_item = MenuItem::with_id(app, "quit", "Quit VoiceFlow", true, Some("CmdOrCtrl+Q"))?;
-    let record_text = format!("Hold {} to Record", shortcut_display);
-    let record_item = MenuItem::with_id(app, "record", &record_text, true, None::<&str>)?;
-    let settings_item = MenuItem::with_id(app, "settings", "Settings...", true, Some("CmdOrCtrl+,"))?;
-
-    let menu = Menu::with_items(app, &[&record_item, &settings_item, &quit_item])?;
-
-    let icon_bytes = include_bytes!("../icons/tray-icon.png");
-    let icon = Image::from_bytes(icon_bytes)?;
-
-    let _tray = TrayIconBuilder::with_id("main")
-        .icon(icon)
-        .menu(&menu)
-        .show_menu_on_left_click(true)
-        .on_menu_event(|app, event| match event.id.as_ref() {
-            "quit" => app.exit(0),
-            "record" => show_main_window(app),
-            "settings" => {
-                // Open the main app window (which has Settings tab)
-                show_or_create_main_app(app);
-            }
-            _ => {}
-        })
-        .build(app)?;
-
-    Ok(())
+/// The sidecar's address, which changes when its port was taken at spawn time
+#[tauri::command]
+async fn get_server_endpoint() -> ServerEndpoint {
+    ServerEndpoint {
+        port: transcription::server_port(),
+        http_url: transcription::server_http_url(),
+        ws_url: transcription::server_ws_url(),
+    }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    demo::init();
+
     let mut builder = tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_clipboard_manager::init());
@@ -505,21 +923,46 @@ pub fn run() {
     }
 
     builder.setup(|app| {
-            let config_dir = app.path().app_config_dir()
+            // Before anything reads or writes user data
+            let permission_report = diagnostics::check_permissions(app.handle());
+            let mut startup_checks = vec![startup::permissions(&permission_report)];
+            app.manage(Mutex::new(permission_report));
+
+            let config_dir = demo::config_dir(app.handle())
                 .unwrap_or_else(|_| PathBuf::from("."));
-            let shortcut_manager = ShortcutManager::new(config_dir);
-            let shortcut_config = shortcut_manager.get_config();
-            let shortcut_display = shortcut_config.display_string();
+            let profile_manager = ProfileManager::new(config_dir.clone());
+            let profile_dir = profile_manager.active_dir();
 
-            app.manage(Mutex::new(shortcut_manager));
+            app.manage(Mutex::new(profile_manager));
             app.manage(Mutex::new(ServerManager::default()));
+            app.manage(Mutex::new(SyncManager::new(config_dir.clone())));
+            app.manage(Mutex::new(SnippetManager::new(config_dir.clone())));
+            app.manage(Mutex::new(SymbolManager::new(config_dir.clone())));
+            app.manage(Mutex::new(FeatureFlagManager::new(config_dir.clone())));
+            app.manage(Mutex::new(PluginManager::new(config_dir.clone())));
+            app.manage(Mutex::new(ScriptManager::new(config_dir.clone())));
+            app.manage(Mutex::new(PromptTemplateManager::new(config_dir)));
+            app.manage(Mutex::new(SettingsManager::new(profile_dir.clone())));
+            app.manage(Mutex::new(VoiceCommandManager::new(profile_dir)));
+            let data_dir = demo::data_dir(app.handle())
+                .unwrap_or_else(|_| PathBuf::from("."));
+            startup_checks.push(startup::history(&data_dir));
+            app.manage(Mutex::new(HistoryStore::new(data_dir.clone())?));
+            app.manage(Mutex::new(HistorySync::new(data_dir.clone())));
+            app.manage(Mutex::new(BatchJobStore::new(data_dir.clone())));
+            app.manage(Mutex::new(Scratchpad::new(data_dir)));
+            startup::finish(app.handle(), startup_checks);
             append_e2e_log("app-started");
 
             if let Err(e) = ensure_sidecar_running(app.handle()) {
                 eprintln!("[voiceflow] Failed to start sidecar: {e}");
             }
+            if let Err(e) = batch::resume(app.handle()) {
+                eprintln!("[voiceflow] Failed to resume batch jobs: {e}");
+            }
 
-            if let Err(e) = setup_tray(app.handle(), &shortcut_display) {
+            i18n::init(app.handle());
+            if let Err(e) = tray::setup(app.handle()) {
                 eprintln!("[voiceflow] Failed to setup tray: {}", e);
             }
 
@@ -528,26 +971,87 @@ pub fn run() {
                 setup_macos_panel(&window);
             }
 
+            screen_share::start_monitor(app.handle().clone());
+            accessibility::start_monitor(app.handle().clone());
+            weekly_summary::start_scheduler(app.handle().clone());
+            devices::start_monitor(app.handle().clone());
+            housekeeping::start(app.handle().clone());
+            feature_flags::start_refresh(app.handle().clone());
+            scripting::start_monitor(app.handle().clone());
+            meeting::start_monitor(app.handle().clone());
+            app_rules::start_monitor(app.handle().clone());
+            wake_word::start_listener(app.handle().clone());
+            switch_access::start(app.handle().clone());
+            watchdog::start(app.handle().clone());
+            supervisor::start(app.handle().clone());
+            providers::start(app.handle().clone());
+            login_item::sync(app.handle());
+            sync::start(app.handle().clone());
+
             let app_handle = app.handle().clone();
-            let shortcut = shortcut_config.to_shortcut()
-                .unwrap_or_else(|| Shortcut::new(Some(Modifiers::ALT), Code::Space));
 
             let shortcut_plugin = tauri_plugin_global_shortcut::Builder::new()
-                .with_handler(move |_app, _shortcut, event| {
+                .with_handler(move |_app, shortcut, event| {
+                    if history_picker::shortcut(&app_handle).is_some_and(|picker| picker == *shortcut) {
+                        if event.state() == ShortcutState::Pressed {
+                            history_picker::toggle(&app_handle);
+                        }
+                        return;
+                    }
+                    let action = shortcuts::action_for(&app_handle, shortcut);
+                    if let Some(action) = action {
+                        shortcuts::emit(&app_handle, action, event.state());
+                    }
+                    if action == Some(ShortcutAction::Cancel) {
+                        if event.state() == ShortcutState::Pressed {
+                            append_e2e_log("cancel-shortcut-pressed");
+                            cancel_recording(&app_handle);
+                        }
+                        return;
+                    }
                     match event.state() {
                         ShortcutState::Pressed => {
-                            if !IS_RECORDING.load(Ordering::SeqCst) {
-                                IS_RECORDING.store(true, Ordering::SeqCst);
+                            let mode = shortcut_mode(&app_handle, shortcut);
+                            if continuous::is_active() {
                                 append_e2e_log("shortcut-pressed");
-                                let _ = app_handle.emit("recording-start", ());
-                                show_main_window(&app_handle);
+                                continuous::stop(&app_handle);
+                            } else if IS_RECORDING.load(Ordering::SeqCst) {
+                                // The second press of a toggle ends the dictation
+                                if mode == ShortcutMode::Toggle {
+                                    append_e2e_log("shortcut-pressed");
+                                    stop_recording(&app_handle);
+                                }
+                            } else {
+                                append_e2e_log("shortcut-pressed");
+                                // Taps are how toggle mode works, so gestures need hold mode
+                                if mode == ShortcutMode::Hold && gestures::pressed(&app_handle) {
+                                    return;
+                                }
+                                voice_commands::begin_session(action == Some(ShortcutAction::CommandMode));
+                                search::begin_session(action == Some(ShortcutAction::Search));
+                                let pressed = pressed_shortcut_config(&app_handle, shortcut);
+                                incognito::begin_session(
+                                    pressed.as_ref().is_some_and(|config| config.incognito),
+                                );
+                                target_app::begin_session(
+                                    pressed.and_then(|config| config.target_app),
+                                );
+                                auto_send::begin_session(
+                                    auto_send::shortcut(&app_handle).is_some_and(|send| send == *shortcut),
+                                );
+                                start_recording(&app_handle);
                             }
                         }
                         ShortcutState::Released => {
-                            if IS_RECORDING.load(Ordering::SeqCst) {
-                                IS_RECORDING.store(false, Ordering::SeqCst);
+                            if IS_RECORDING.load(Ordering::SeqCst)
+                                && shortcut_mode(&app_handle, shortcut) == ShortcutMode::Hold
+                            {
                                 append_e2e_log("shortcut-released");
-                                let _ = app_handle.emit("recording-stop", ());
+                                if gestures::released(&app_handle) {
+                                    cancel_recording(&app_handle);
+                                } else {
+                                    stop_recording(&app_handle);
+                                }
                             }
                         }
                     }
@@ -556,12 +1060,13 @@ pub fn run() {
 
             if let Err(e) = app.handle().plugin(shortcut_plugin) {
                 eprintln!("[voiceflow] Failed to setup global shortcut plugin: {}", e);
-            } else if let Err(e) = app.global_shortcut().register(shortcut) {
-                eprintln!("[voiceflow] Failed to register shortcut: {}", e);
+            } else {
+                register_shortcuts(app.handle());
             }
 
             Ok(())
         })
+        .on_page_load(session::on_page_load)
         .on_window_event(|window, event| {
             // Handle dock click on macOS - show main app when all windows are closed
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
@@ -571,6 +1076,15 @@ pub fn run() {
                     let _ = window.hide();
                 }
             }
+            if let tauri::WindowEvent::Focused(false) = event {
+                // The picker is transient, like Spotlight
+                if window.label() == history_picker::WINDOW_LABEL {
+                    let _ = window.hide();
+                }
+                // In case the webview never reports the blur, dictation must stay
+                // reachable once the user is in another app
+                resume_suspended_shortcuts(window.app_handle());
+            }
         })
         .invoke_handler(tauri::generate_handler![
             show_bubble,
@@ -578,10 +1092,189 @@ pub fn run() {
             resize_main_window,
             paste_from_clipboard,
             get_current_shortcut,
+            format_shortcut,
             set_shortcut,
+            set_shortcut_prompt_template,
+            set_shortcut_incognito,
+            set_shortcut_mode,
+            set_shortcut_target_app,
+            suspend_shortcuts,
+            resume_shortcuts,
+            gestures::set_shortcut_gestures,
+            auto_send::set_dictate_and_send,
+            auto_send::cancel_auto_send,
+            incognito::get_incognito_state,
+            incognito::set_incognito,
             show_main_app,
+            set_reopen_behavior,
+            set_bubble_placement,
             ensure_server_running,
+            supervisor::get_server_status,
             stop_server,
+            get_server_endpoint,
+            providers::list_providers,
+            providers::set_providers,
+            providers::restart_provider,
+            replay::set_fixture_recording,
+            replay::attach_fixture_audio,
+            replay::replay_fixture,
+            scratchpad::show_scratchpad,
+            scratchpad::get_scratchpad,
+            scratchpad::edit_scratchpad_entry,
+            scratchpad::clear_scratchpad,
+            scratchpad::copy_scratchpad,
+            scratchpad::insert_scratchpad,
+            scratchpad::set_output_target,
+            command_output::set_output_command,
+            speech::speak_text,
+            speech::stop_speaking,
+            speech::list_voices,
+            speech::set_read_back,
+            search::set_search_template,
+            clipboard::set_clipboard_restore,
+            screen_share::get_screen_sharing_state,
+            screen_share::set_hide_overlay_while_sharing,
+            meeting::set_offer_meeting_transcription,
+            meeting::set_name_meetings_from_calendar,
+            meeting::transcribe_meeting_recording,
+            batch::start_batch_transcription,
+            batch::list_batch_jobs,
+            batch::resume_pending_jobs,
+            benchmark::run_benchmark,
+            evaluation::run_evaluation,
+            history::get_history,
+            history::delete_history_entry,
+            history::clear_history,
+            history::get_history_entry,
+            history::get_alternatives,
+            history::choose_alternative,
+            history::edit_history_entry,
+            history::merge_speakers,
+            history::export_history_entry,
+            digest::copy_daily_digest,
+            history_picker::show_history_picker,
+            history_picker::hide_history_picker,
+            history_picker::search_history,
+            history_picker::pick_history_entry,
+            history_picker::set_history_picker_shortcut,
+            shortcuts::get_shortcuts,
+            shortcuts::set_named_shortcut,
+            login_item::get_launch_at_login,
+            login_item::set_launch_at_login,
+            result_panel::start_quick_dictation,
+            result_panel::get_result_panel_entry,
+            result_panel::hide_result_panel,
+            result_panel::copy_result,
+            result_panel::paste_result,
+            result_panel::open_result_in_editor,
+            history_import::import_history,
+            snippets::list_snippets,
+            snippets::save_snippet,
+            snippets::delete_snippet,
+            snippets::fill_snippet,
+            form_fill::skip_template_field,
+            form_fill::cancel_template_fill,
+            app_rules::list_app_rules,
+            app_rules::save_app_rule,
+            app_rules::delete_app_rule,
+            active_app::get_active_app,
+            app_profiles::list_app_profiles,
+            app_profiles::set_app_profile,
+            project::get_current_project,
+            project::set_current_project,
+            project::set_project_rules,
+            project::get_project_stats,
+            app_stats::get_app_stats,
+            audio::set_native_capture,
+            audio::get_captured_audio,
+            audio::transcribe_captured_audio,
+            weekly_summary::get_weekly_summary,
+            weekly_summary::set_weekly_summary,
+            devices::list_audio_input_devices,
+            devices::set_audio_input_device,
+            housekeeping::run_housekeeping,
+            feature_flags::list_feature_flags,
+            feature_flags::set_flag_override,
+            plugins::list_plugins,
+            plugins::enable_plugin,
+            scripting::list_scripts,
+            scripting::reload_scripts,
+            scripting::set_script_enabled,
+            project::export_project,
+            history_sync::get_history_sync_status,
+            history_sync::configure_history_sync,
+            history_sync::disable_history_sync,
+            history_sync::sync_now,
+            i18n::get_locale,
+            i18n::set_locale,
+            insertion::insert_text,
+            insertion::type_text,
+            captions::export_captions,
+            continuous::start_continuous_dictation,
+            continuous::stop_continuous_dictation,
+            diagnostics::get_diagnostics,
+            startup::get_startup_report,
+            pipeline::record_transcription,
+            pipeline::set_low_confidence_threshold,
+            policy::get_locked_settings,
+            keyboard::replace_last,
+            profiles::list_profiles,
+            profiles::switch_profile,
+            profiles::create_profile,
+            profiles::delete_profile,
+            prompts::list_prompt_templates,
+            prompts::save_prompt_template,
+            postprocess::set_post_processing,
+            browser::set_browser_context,
+            grammar::set_grammar_check,
+            grammar::apply_suggestion,
+            grammar::dismiss_suggestion,
+            redaction::set_redaction,
+            auth::set_require_authentication,
+            auth::lock_history,
+            demo::is_demo_mode,
+            vocabulary::set_learn_from_edits,
+            learning::set_correction_learning,
+            learning::get_suggested_replacements,
+            learning::accept_suggested_replacement,
+            learning::dismiss_suggested_replacement,
+            switch_access::press_switch_button,
+            switch_access::set_switch_access,
+            accessibility::get_accessibility_prefs,
+            email::get_email_settings,
+            email::set_email_settings,
+            latex::get_latex_settings,
+            latex::set_latex_settings,
+            selection::capture_selected_text,
+            selection::set_capture_selection_context,
+            session::get_session_state,
+            session::get_latency_metrics,
+            session::report_partial_transcript,
+            settings::get_settings,
+            settings::update_settings,
+            settings::validate_settings,
+            shortcut_capture::capture_next_shortcut,
+            shutdown::quit_app,
+            shutdown::relaunch_app,
+            voice_commands::list_voice_commands,
+            voice_commands::save_voice_commands,
+            voice_commands::preview_voice_command,
+            voice_commands::set_command_mode_shortcut,
+            wake_word::get_wake_word_state,
+            wake_word::set_wake_word,
+            watchdog::webview_heartbeat,
+            spelling::set_spelling_mode,
+            spelling::toggle_spelling_mode,
+            sync::get_sync_status,
+            sync::set_sync_folder,
+            sync::sync_config_now,
+            symbols::list_symbols,
+            symbols::get_custom_symbols,
+            symbols::save_custom_symbols,
+            tray::get_dictation_paused,
+            tray::set_dictation_paused,
+            #[cfg(target_os = "macos")]
+            url_scheme::answer_url_last_request,
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
@@ -589,14 +1282,27 @@ pub fn run() {
             // Handle macOS dock click (reopen event)
             #[cfg(target_os = "macos")]
             if let tauri::RunEvent::Reopen { has_visible_windows, .. } = event {
-                if !has_visible_windows {
-                    show_or_create_main_app(app_handle);
+                handle_reopen(app_handle, has_visible_windows);
+            }
+
+            // voiceflow:// links, e.g. from Alfred or Raycast workflows
+            #[cfg(target_os = "macos")]
+            if let tauri::RunEvent::Opened { urls } = &event {
+                url_scheme::handle_urls(app_handle, urls);
+            }
+
+            if let tauri::RunEvent::ExitRequested { api, .. } = &event {
+                if !shutdown::is_complete() {
+                    api.prevent_exit();
+                    shutdown::request_quit(app_handle);
                 }
             }
 
             if let tauri::RunEvent::Exit = event {
                 append_e2e_log("app-exit");
                 let _ = stop_sidecar(app_handle);
+                providers::stop_all();
+                demo::wipe();
             }
         });
 }
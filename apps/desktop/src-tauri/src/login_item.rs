@@ -0,0 +1,260 @@
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use crate::{i18n, settings};
+
+fn executable() -> Result<PathBuf, String> {
+    std::env::current_exe().map_err(|e| i18n::tf("error.locate_app", &[("error", &e.to_string())]))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn home_dir() -> Result<PathBuf, String> {
+    std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .ok_or_else(|| i18n::t("error.home_unset"))
+}
+
+/// A LaunchAgent in ~/Library/LaunchAgents, named after the bundle identifier
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn escape_xml(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+
+    fn agent_path(identifier: &str) -> Result<PathBuf, String> {
+        Ok(super::home_dir()?
+            .join("Library/LaunchAgents")
+            .join(format!("{identifier}.plist")))
+    }
+
+    fn agent_plist(identifier: &str, executable: &Path) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+            escape_xml(identifier),
+            escape_xml(&executable.to_string_lossy())
+        )
+    }
+
+    pub fn is_enabled(identifier: &str) -> Result<bool, String> {
+        Ok(agent_path(identifier)?.exists())
+    }
+
+    pub fn enable(identifier: &str, executable: &Path) -> Result<(), String> {
+        let path = agent_path(identifier)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::write(&path, agent_plist(identifier, executable)).map_err(|e| e.to_string())
+    }
+
+    pub fn disable(identifier: &str) -> Result<(), String> {
+        let path = agent_path(identifier)?;
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+/// A value under HKCU\Software\Microsoft\Windows\CurrentVersion\Run
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::path::Path;
+    use std::ptr;
+    use windows_sys::Win32::Foundation::{ERROR_FILE_NOT_FOUND, ERROR_SUCCESS};
+    use windows_sys::Win32::System::Registry::{
+        RegDeleteKeyValueW, RegGetValueW, RegSetKeyValueW, HKEY_CURRENT_USER, REG_SZ, RRF_RT_REG_SZ,
+    };
+
+    const RUN_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
+
+    fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain(Some(0)).collect()
+    }
+
+    fn run_key_error(key: &str, code: u32) -> String {
+        crate::i18n::tf(key, &[("code", &code.to_string())])
+    }
+
+    pub fn is_enabled(identifier: &str) -> Result<bool, String> {
+        let key = wide(RUN_KEY);
+        let name = wide(identifier);
+        // SAFETY: both strings are NUL-terminated and outlive the call; passing no
+        // buffer only asks whether the value exists
+        let status = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                key.as_ptr(),
+                name.as_ptr(),
+                RRF_RT_REG_SZ,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+            )
+        };
+        match status {
+            ERROR_SUCCESS => Ok(true),
+            ERROR_FILE_NOT_FOUND => Ok(false),
+            error => Err(run_key_error("error.run_key_read", error)),
+        }
+    }
+
+    pub fn enable(identifier: &str, executable: &Path) -> Result<(), String> {
+        let key = wide(RUN_KEY);
+        let name = wide(identifier);
+        let command = wide(&format!("\"{}\"", executable.display()));
+        // SAFETY: the strings are NUL-terminated and outlive the call, and the size
+        // covers the command including its terminator
+        let status = unsafe {
+            RegSetKeyValueW(
+                HKEY_CURRENT_USER,
+                key.as_ptr(),
+                name.as_ptr(),
+                REG_SZ,
+                command.as_ptr().cast(),
+                (command.len() * 2) as u32,
+            )
+        };
+        if status != ERROR_SUCCESS {
+            return Err(run_key_error("error.run_key_write", status));
+        }
+        Ok(())
+    }
+
+    pub fn disable(identifier: &str) -> Result<(), String> {
+        let key = wide(RUN_KEY);
+        let name = wide(identifier);
+        // SAFETY: both strings are NUL-terminated and outlive the call
+        let status = unsafe { RegDeleteKeyValueW(HKEY_CURRENT_USER, key.as_ptr(), name.as_ptr()) };
+        match status {
+            ERROR_SUCCESS | ERROR_FILE_NOT_FOUND => Ok(()),
+            error => Err(run_key_error("error.run_key_update", error)),
+        }
+    }
+}
+
+/// A desktop entry in the XDG autostart directory
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn autostart_dir() -> Result<PathBuf, String> {
+        match std::env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+            Some(config) => Ok(PathBuf::from(config).join("autostart")),
+            None => Ok(super::home_dir()?.join(".config/autostart")),
+        }
+    }
+
+    fn entry_path(identifier: &str) -> Result<PathBuf, String> {
+        Ok(autostart_dir()?.join(format!("{identifier}.desktop")))
+    }
+
+    /// Quote a path for an Exec line, escaping what the desktop entry spec reserves
+    fn quote_exec(path: &str) -> String {
+        let mut quoted = String::from("\"");
+        for c in path.chars() {
+            if matches!(c, '"' | '`' | '$' | '\\') {
+                quoted.push('\\');
+            }
+            quoted.push(c);
+        }
+        quoted.push('"');
+        quoted
+    }
+
+    pub(super) fn desktop_entry(executable: &Path) -> String {
+        format!(
+            "[Desktop Entry]\nType=Application\nName=VoiceFlow\nExec={}\nTerminal=false\nX-GNOME-Autostart-enabled=true\n",
+            quote_exec(&executable.to_string_lossy())
+        )
+    }
+
+    pub fn is_enabled(identifier: &str) -> Result<bool, String> {
+        Ok(entry_path(identifier)?.exists())
+    }
+
+    pub fn enable(identifier: &str, executable: &Path) -> Result<(), String> {
+        let path = entry_path(identifier)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::write(&path, desktop_entry(executable)).map_err(|e| e.to_string())
+    }
+
+    pub fn disable(identifier: &str) -> Result<(), String> {
+        let path = entry_path(identifier)?;
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+fn identifier(app: &AppHandle) -> String {
+    app.config().identifier.clone()
+}
+
+/// Add or remove the login entry
+pub fn apply(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    let identifier = identifier(app);
+    if enabled {
+        platform::enable(&identifier, &executable()?)
+    } else {
+        platform::disable(&identifier)
+    }
+}
+
+/// Make the OS match the setting, rewriting the entry so it points at this copy of
+/// the app after an update or a move
+pub fn sync(app: &AppHandle) {
+    let enabled = settings::current(app).launch_at_login;
+    if let Err(e) = apply(app, enabled) {
+        eprintln!("[voiceflow] Failed to update launch at login: {e}");
+    }
+}
+
+/// Whether the OS will start VoiceFlow at login
+#[tauri::command]
+pub async fn get_launch_at_login(app: AppHandle) -> Result<bool, String> {
+    platform::is_enabled(&identifier(&app))
+}
+
+#[tauri::command]
+pub async fn set_launch_at_login(app: AppHandle, enabled: bool) -> Result<(), String> {
+    apply(&app, enabled)?;
+    settings::update(&app, |settings| settings.launch_at_login = enabled)?;
+    Ok(())
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn quotes_the_executable_in_the_desktop_entry() {
+        let entry = platform::desktop_entry(Path::new("/opt/Voice Flow/$bin"));
+        assert!(entry.starts_with("[Desktop Entry]\n"));
+        assert!(entry.contains("Exec=\"/opt/Voice Flow/\\$bin\"\n"));
+    }
+}
@@ -0,0 +1,362 @@
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+use crate::email::escape_html;
+use crate::settings;
+
+// Post-processing templates often answer in Markdown. Pasting that as-is leaves
+// asterisks and dashes in Pages, Word or Notion, so headings, lists and bold, italic
+// and code spans are also converted to HTML and RTF. The Markdown stays the plain
+// text version, which is what terminals and code editors paste.
+
+const MAX_HEADING_LEVEL: usize = 6;
+
+// Plain text of the last converted transcript and its formatted versions, picked up
+// when it is pasted
+static RICH_TEXT: Mutex<Option<(String, RichText)>> = Mutex::new(None);
+
+/// Formatted versions of a Markdown transcript
+#[derive(Debug, Clone, PartialEq)]
+pub struct RichText {
+    pub html: String,
+    pub rtf: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Style {
+    bold: bool,
+    italic: bool,
+    code: bool,
+}
+
+impl Style {
+    fn is_plain(&self) -> bool {
+        *self == Style::default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Block {
+    Heading(usize, String),
+    /// Numbered from `start`, or bulleted when None
+    List {
+        start: Option<u32>,
+        items: Vec<String>,
+    },
+    /// Lines kept apart by line breaks
+    Paragraph(Vec<String>),
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let text = line[level..].strip_prefix(' ')?;
+    (1..=MAX_HEADING_LEVEL)
+        .contains(&level)
+        .then(|| (level, text.trim()))
+}
+
+fn bullet(line: &str) -> Option<&str> {
+    ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|marker| line.strip_prefix(marker))
+        .map(str::trim)
+}
+
+fn numbered(line: &str) -> Option<(u32, &str)> {
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let rest = line[digits..]
+        .strip_prefix(". ")
+        .or_else(|| line[digits..].strip_prefix(") "))?;
+    Some((line[..digits].parse().ok()?, rest.trim()))
+}
+
+fn parse_blocks(text: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    for line in text.lines().map(str::trim) {
+        let last = blocks.last_mut();
+        if line.is_empty() {
+            // A blank line ends a paragraph, a list only ends where other text starts
+            if let Some(Block::Paragraph(_)) = last {
+                blocks.push(Block::Paragraph(Vec::new()));
+            }
+        } else if let Some((level, text)) = heading(line) {
+            blocks.push(Block::Heading(level, text.to_string()));
+        } else if let Some(item) = bullet(line) {
+            match last {
+                Some(Block::List { start: None, items }) => items.push(item.to_string()),
+                _ => blocks.push(Block::List {
+                    start: None,
+                    items: vec![item.to_string()],
+                }),
+            }
+        } else if let Some((number, item)) = numbered(line) {
+            match last {
+                Some(Block::List {
+                    start: Some(_),
+                    items,
+                }) => items.push(item.to_string()),
+                _ => blocks.push(Block::List {
+                    start: Some(number),
+                    items: vec![item.to_string()],
+                }),
+            }
+        } else {
+            match last {
+                Some(Block::Paragraph(lines)) => lines.push(line.to_string()),
+                _ => blocks.push(Block::Paragraph(vec![line.to_string()])),
+            }
+        }
+    }
+    blocks.retain(|block| !matches!(block, Block::Paragraph(lines) if lines.is_empty()));
+    blocks
+}
+
+/// `text` split into runs of the same style. A marker only opens a span when it is
+/// closed later on, and underscores inside words such as snake_case stay literal.
+fn parse_inline(text: &str) -> Vec<(Style, String)> {
+    let mut runs = Vec::new();
+    let mut style = Style::default();
+    let mut current = String::new();
+    let mut previous: Option<char> = None;
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let marker = if style.code {
+            rest.starts_with('`').then_some("`")
+        } else if rest.starts_with("**") || rest.starts_with("__") {
+            Some(&rest[..2])
+        } else if matches!(c, '*' | '_' | '`') {
+            Some(&rest[..1])
+        } else {
+            None
+        };
+        if let Some(marker) = marker {
+            let open = match marker {
+                "**" | "__" => style.bold,
+                "`" => style.code,
+                _ => style.italic,
+            };
+            let after = &rest[marker.len()..];
+            let toggles = open
+                || (after.contains(marker)
+                    && !after.starts_with(char::is_whitespace)
+                    && !(marker.starts_with('_') && previous.is_some_and(char::is_alphanumeric)));
+            if toggles {
+                if !current.is_empty() {
+                    runs.push((style, std::mem::take(&mut current)));
+                }
+                match marker {
+                    "**" | "__" => style.bold = !open,
+                    "`" => style.code = !open,
+                    _ => style.italic = !open,
+                }
+                rest = after;
+                previous = marker.chars().last();
+                continue;
+            }
+        }
+        current.push(c);
+        previous = Some(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    if !current.is_empty() {
+        runs.push((style, current));
+    }
+    runs
+}
+
+fn inline_html(text: &str) -> String {
+    parse_inline(text)
+        .into_iter()
+        .map(|(style, text)| {
+            let mut html = escape_html(&text);
+            if style.code {
+                html = format!("<code>{html}</code>");
+            }
+            if style.italic {
+                html = format!("<em>{html}</em>");
+            }
+            if style.bold {
+                html = format!("<strong>{html}</strong>");
+            }
+            html
+        })
+        .collect()
+}
+
+fn to_html(blocks: &[Block]) -> String {
+    blocks
+        .iter()
+        .map(|block| match block {
+            Block::Heading(level, text) => format!("<h{level}>{}</h{level}>", inline_html(text)),
+            Block::List { start, items } => {
+                let items: String = items
+                    .iter()
+                    .map(|item| format!("<li>{}</li>", inline_html(item)))
+                    .collect();
+                match start {
+                    None => format!("<ul>{items}</ul>"),
+                    Some(1) => format!("<ol>{items}</ol>"),
+                    Some(start) => format!("<ol start=\"{start}\">{items}</ol>"),
+                }
+            }
+            Block::Paragraph(lines) => {
+                let lines: Vec<String> = lines.iter().map(|line| inline_html(line)).collect();
+                format!("<p>{}</p>", lines.join("<br>"))
+            }
+        })
+        .collect()
+}
+
+/// RTF escapes for braces and backslashes, and `\uN?` for anything outside ASCII
+fn escape_rtf(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c if c.is_ascii() => escaped.push(c),
+            c => {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    escaped.push_str(&format!("\\u{}?", *unit as i16));
+                }
+            }
+        }
+    }
+    escaped
+}
+
+fn inline_rtf(text: &str) -> String {
+    parse_inline(text)
+        .into_iter()
+        .map(|(style, text)| {
+            if style.is_plain() {
+                return escape_rtf(&text);
+            }
+            let mut controls = String::new();
+            if style.bold {
+                controls.push_str("\\b");
+            }
+            if style.italic {
+                controls.push_str("\\i");
+            }
+            if style.code {
+                controls.push_str("\\f1");
+            }
+            format!("{{{controls} {}}}", escape_rtf(&text))
+        })
+        .collect()
+}
+
+fn to_rtf(blocks: &[Block]) -> String {
+    let mut rtf = String::from(
+        "{\\rtf1\\ansi\\deff0{\\fonttbl{\\f0\\fswiss Helvetica;}{\\f1\\fmodern Courier;}}\\fs24\n",
+    );
+    for block in blocks {
+        match block {
+            Block::Heading(level, text) => {
+                // Half-points, from 18 pt for a top-level heading down to the body size
+                let size = 36 - 4 * (level - 1).min(3);
+                rtf.push_str(&format!(
+                    "{{\\pard\\sa120{{\\b\\fs{size} {}}}\\par}}\n",
+                    inline_rtf(text)
+                ));
+            }
+            Block::List { start, items } => {
+                for (index, item) in items.iter().enumerate() {
+                    let marker = match start {
+                        None => "\\bullet".to_string(),
+                        Some(start) => format!("{}.", *start as usize + index),
+                    };
+                    rtf.push_str(&format!(
+                        "{{\\pard\\li360\\fi-360 {marker}\\tab {}\\par}}\n",
+                        inline_rtf(item)
+                    ));
+                }
+            }
+            Block::Paragraph(lines) => {
+                let lines: Vec<String> = lines.iter().map(|line| inline_rtf(line)).collect();
+                rtf.push_str(&format!(
+                    "{{\\pard\\sa120 {}\\par}}\n",
+                    lines.join("\\line ")
+                ));
+            }
+        }
+    }
+    rtf.push('}');
+    rtf
+}
+
+/// HTML and RTF for `text`, None when it has no Markdown formatting worth keeping
+fn convert(text: &str) -> Option<RichText> {
+    let blocks = parse_blocks(text);
+    let formatted = blocks.iter().any(|block| match block {
+        Block::Heading(..) | Block::List { .. } => true,
+        Block::Paragraph(lines) => lines.iter().any(|line| {
+            parse_inline(line)
+                .iter()
+                .any(|(style, _)| !style.is_plain())
+        }),
+    });
+    formatted.then(|| RichText {
+        html: to_html(&blocks),
+        rtf: to_rtf(&blocks),
+    })
+}
+
+/// Convert post-processed `text` when rich text pasting is on, so pasting it inserts
+/// the formatted version
+pub fn remember(app: &AppHandle, text: &str) {
+    let rich = settings::current(app)
+        .markdown_rich_text
+        .then(|| convert(text))
+        .flatten();
+    if let Ok(mut last) = RICH_TEXT.lock() {
+        *last = rich.map(|rich| (text.to_string(), rich));
+    }
+}
+
+/// Formatted versions of `text` when it is the last converted transcript
+pub fn rich_for(text: &str) -> Option<RichText> {
+    let rich = RICH_TEXT.lock().ok()?;
+    rich.as_ref()
+        .filter(|(plain, _)| plain == text)
+        .map(|(_, rich)| rich.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_lists_headings_and_emphasis() {
+        let rich = convert("## Plan\n\nShip **today**:\n- fix *the* bug\n- run `cargo test`\n\n3. review\n4. merge")
+            .unwrap();
+        assert_eq!(
+            rich.html,
+            "<h2>Plan</h2><p>Ship <strong>today</strong>:</p>\
+             <ul><li>fix <em>the</em> bug</li><li>run <code>cargo test</code></li></ul>\
+             <ol start=\"3\"><li>review</li><li>merge</li></ol>"
+        );
+        assert!(rich.rtf.starts_with("{\\rtf1"));
+        assert!(rich
+            .rtf
+            .contains("{\\pard\\li360\\fi-360 \\bullet\\tab fix {\\i the} bug\\par}"));
+        assert!(rich.rtf.contains("4.\\tab merge"));
+    }
+
+    #[test]
+    fn leaves_plain_text_alone() {
+        assert_eq!(convert("Just a sentence. Nothing else."), None);
+        assert_eq!(convert("rename snake_case_name to 2 * 3"), None);
+        assert_eq!(convert("a lone ** pair"), None);
+    }
+
+    #[test]
+    fn escapes_rtf() {
+        assert_eq!(escape_rtf("{a}\\ café"), "\\{a\\}\\\\ caf\\u233?");
+        assert_eq!(escape_rtf("🎙"), "\\u-10180?\\u-8295?");
+    }
+}
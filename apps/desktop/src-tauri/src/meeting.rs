@@ -0,0 +1,247 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter};
+
+use crate::active_app::{self, ActiveApp};
+use crate::history::{self, HistoryEntry};
+use crate::transcription::{self, TranscriptionOptions};
+use crate::{append_e2e_log, settings, IS_RECORDING};
+use crate::{audio_file, calendar, chunking};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// History tag applied to transcripts started from a meeting offer
+pub const MEETING_HISTORY_TAG: &str = "meeting";
+
+struct MeetingApp {
+    name: &'static str,
+    bundle_ids: &'static [&'static str],
+    /// Required window title prefix, for meetings hosted in a browser tab
+    title_prefix: Option<&'static str>,
+}
+
+const BROWSER_BUNDLE_IDS: &[&str] = &[
+    "com.google.Chrome",
+    "com.apple.Safari",
+    "company.thebrowser.Browser",
+    "com.microsoft.edgemac",
+    "com.brave.Browser",
+    "org.mozilla.firefox",
+];
+
+const MEETING_APPS: &[MeetingApp] = &[
+    MeetingApp {
+        name: "Zoom",
+        bundle_ids: &["us.zoom.xos"],
+        title_prefix: None,
+    },
+    MeetingApp {
+        name: "Microsoft Teams",
+        bundle_ids: &["com.microsoft.teams", "com.microsoft.teams2"],
+        title_prefix: None,
+    },
+    MeetingApp {
+        name: "Webex",
+        bundle_ids: &["com.webex.meetingmanager", "Cisco-Systems.Spark"],
+        title_prefix: None,
+    },
+    MeetingApp {
+        name: "Google Meet",
+        bundle_ids: BROWSER_BUNDLE_IDS,
+        title_prefix: Some("Meet - "),
+    },
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeetingOffer {
+    pub meeting_app: String,
+    pub active_app: ActiveApp,
+    pub history_tag: String,
+}
+
+fn match_meeting_app(app: &ActiveApp) -> Option<&'static MeetingApp> {
+    MEETING_APPS.iter().find(|meeting| {
+        meeting.bundle_ids.contains(&app.bundle_id.as_str())
+            && meeting
+                .title_prefix
+                .is_none_or(|prefix| app.window_title.starts_with(prefix))
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn microphone_in_use() -> bool {
+    use std::ffi::c_void;
+
+    #[repr(C)]
+    struct AudioObjectPropertyAddress {
+        selector: u32,
+        scope: u32,
+        element: u32,
+    }
+
+    #[link(name = "CoreAudio", kind = "framework")]
+    extern "C" {
+        fn AudioObjectGetPropertyData(
+            object_id: u32,
+            address: *const AudioObjectPropertyAddress,
+            qualifier_size: u32,
+            qualifier: *const c_void,
+            data_size: *mut u32,
+            data: *mut c_void,
+        ) -> i32;
+    }
+
+    const SYSTEM_OBJECT: u32 = 1;
+    const SCOPE_GLOBAL: u32 = u32::from_be_bytes(*b"glob");
+    const DEFAULT_INPUT_DEVICE: u32 = u32::from_be_bytes(*b"dIn ");
+    const IS_RUNNING_SOMEWHERE: u32 = u32::from_be_bytes(*b"gone");
+
+    let query = |object_id: u32, selector: u32| -> Option<u32> {
+        let address = AudioObjectPropertyAddress {
+            selector,
+            scope: SCOPE_GLOBAL,
+            element: 0,
+        };
+        let mut value: u32 = 0;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        // SAFETY: both properties are plain UInt32 values and `size` matches the buffer
+        let status = unsafe {
+            AudioObjectGetPropertyData(
+                object_id,
+                &address,
+                0,
+                std::ptr::null(),
+                &mut size,
+                &mut value as *mut u32 as *mut c_void,
+            )
+        };
+        (status == 0).then_some(value)
+    };
+
+    query(SYSTEM_OBJECT, DEFAULT_INPUT_DEVICE)
+        .filter(|&device| device != 0)
+        .and_then(|device| query(device, IS_RUNNING_SOMEWHERE))
+        .is_some_and(|running| running != 0)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn microphone_in_use() -> bool {
+    false
+}
+
+fn detect_meeting() -> Option<MeetingOffer> {
+    // Our own dictation holds the microphone while recording
+    if IS_RECORDING.load(Ordering::SeqCst) || !microphone_in_use() {
+        return None;
+    }
+
+    let app = active_app::frontmost_app()?;
+    let meeting = match_meeting_app(&app)?;
+    Some(MeetingOffer {
+        meeting_app: meeting.name.to_string(),
+        active_app: app,
+        history_tag: MEETING_HISTORY_TAG.to_string(),
+    })
+}
+
+pub fn start_monitor(app: AppHandle) {
+    thread::spawn(move || {
+        let mut offered_for: Option<String> = None;
+        loop {
+            thread::sleep(POLL_INTERVAL);
+
+            if !settings::current(&app).offer_meeting_transcription {
+                offered_for = None;
+                continue;
+            }
+
+            let Some(offer) = detect_meeting() else {
+                offered_for = None;
+                continue;
+            };
+
+            // Offer once per meeting rather than on every poll
+            if offered_for.as_deref() == Some(offer.meeting_app.as_str()) {
+                continue;
+            }
+            offered_for = Some(offer.meeting_app.clone());
+            append_e2e_log("meeting-detected");
+            let _ = app.emit("meeting-detected", offer);
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn set_offer_meeting_transcription(app: AppHandle, enabled: bool) -> Result<(), String> {
+    settings::update(&app, |settings| {
+        settings.offer_meeting_transcription = enabled
+    })?;
+    Ok(())
+}
+
+/// Transcribe a recorded meeting with speaker labels and store it under the meeting tag,
+/// named after the calendar event it belongs to when that is enabled
+#[tauri::command]
+pub async fn transcribe_meeting_recording(
+    app: AppHandle,
+    path: PathBuf,
+    tag: Option<String>,
+) -> Result<HistoryEntry, String> {
+    let settings = settings::current(&app);
+    let name_from_calendar = settings.name_meetings_from_calendar;
+    let alternatives = settings.alternative_count;
+    let (transcript, title) = tauri::async_runtime::spawn_blocking(move || {
+        let samples = audio_file::read_pcm16_mono(&path)?;
+        let title = if name_from_calendar {
+            recording_window(&path, samples.len())
+                .and_then(|(start_ago, end_ago)| calendar::event_title(start_ago, end_ago))
+        } else {
+            None
+        };
+        let options = TranscriptionOptions {
+            diarize: true,
+            word_timestamps: true,
+            alternatives,
+        };
+        chunking::transcribe_long(&samples, &options).map(|transcript| (transcript, title))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let mut transcript = transcript;
+    transcription::label_speakers(&mut transcript.segments);
+    let tag = tag.unwrap_or_else(|| MEETING_HISTORY_TAG.to_string());
+    let entry = history::add_entry(&app, transcript, Some(tag))?;
+    match title {
+        Some(title) if entry.id != 0 => history::update_entry(&app, entry.id, |entry| {
+            entry.title = Some(title);
+            Ok(())
+        }),
+        _ => Ok(entry),
+    }
+}
+
+/// When the recording was made, in seconds ago, from the file's modification time
+/// (when recording stopped) and its length
+fn recording_window(path: &Path, samples: usize) -> Option<(u64, u64)> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    let end_ago = SystemTime::now()
+        .duration_since(modified)
+        .unwrap_or_default()
+        .as_secs();
+    let duration = samples as u64 / transcription::SAMPLE_RATE as u64;
+    Some((end_ago + duration, end_ago))
+}
+
+#[tauri::command]
+pub async fn set_name_meetings_from_calendar(app: AppHandle, enabled: bool) -> Result<(), String> {
+    settings::update(&app, |settings| {
+        settings.name_meetings_from_calendar = enabled
+    })?;
+    Ok(())
+}
@@ -0,0 +1,221 @@
+use cpal::traits::{DeviceTrait, StreamTrait};
+use cpal::{FromSample, SampleFormat, SizedSample, StreamError, StreamInstant};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::devices;
+
+const FRAME_MS: u32 = 30;
+pub const FRAME_DURATION: Duration = Duration::from_millis(FRAME_MS as u64);
+/// RMS above which a frame counts as speech
+pub const SPEECH_THRESHOLD: f32 = 0.02;
+/// Dropped frames in one dictation before the webview is warned
+const DROPPED_FRAMES_WARNING: u64 = 10;
+
+// Capture problems since the current dictation started
+static SESSION_FRAMES: AtomicU64 = AtomicU64::new(0);
+static SESSION_DROPPED: AtomicU64 = AtomicU64::new(0);
+static SESSION_XRUNS: AtomicU64 = AtomicU64::new(0);
+static SESSION_STALLS: AtomicU64 = AtomicU64::new(0);
+static SESSION_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// Health of the Rust-side capture during one dictation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureStats {
+    /// Frames delivered to listeners
+    pub frames: u64,
+    /// Frames missing between callbacks, judged from the device capture timestamps
+    pub dropped_frames: u64,
+    /// Buffer overruns and underruns reported by the driver
+    pub xruns: u64,
+    /// Waits for audio that timed out with nothing captured
+    pub stalls: u64,
+}
+
+impl CaptureStats {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+pub fn reset_session_stats() {
+    for counter in [
+        &SESSION_FRAMES,
+        &SESSION_DROPPED,
+        &SESSION_XRUNS,
+        &SESSION_STALLS,
+    ] {
+        counter.store(0, Ordering::SeqCst);
+    }
+    SESSION_WARNED.store(false, Ordering::SeqCst);
+}
+
+pub fn session_stats() -> CaptureStats {
+    CaptureStats {
+        frames: SESSION_FRAMES.load(Ordering::SeqCst),
+        dropped_frames: SESSION_DROPPED.load(Ordering::SeqCst),
+        xruns: SESSION_XRUNS.load(Ordering::SeqCst),
+        stalls: SESSION_STALLS.load(Ordering::SeqCst),
+    }
+}
+
+/// Frames lost between two callbacks, given when the previous buffer was captured and how long it was
+fn dropped_frames(previous: StreamInstant, previous_len: Duration, capture: StreamInstant) -> u64 {
+    let Some(gap) = capture.duration_since(&previous) else {
+        return 0;
+    };
+    // Allow a frame of scheduling jitter before counting anything as lost
+    let missing = gap.saturating_sub(previous_len);
+    if missing <= FRAME_DURATION {
+        return 0;
+    }
+    (missing.as_millis() / u128::from(FRAME_MS)) as u64
+}
+
+/// Selected input device delivering mono frames of `FRAME_DURATION` at the device rate.
+/// The stream is not `Send`, so it must stay on the thread that opened it.
+pub struct Microphone {
+    _stream: cpal::Stream,
+    receiver: Receiver<Vec<f32>>,
+    app: AppHandle,
+    pending: Vec<f32>,
+    frame_len: usize,
+    pub sample_rate: u32,
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    sender: Sender<Vec<f32>>,
+) -> Result<cpal::Stream, String>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let channels = usize::from(config.channels.max(1));
+    let sample_rate = config.sample_rate.max(1);
+    let mut previous: Option<(StreamInstant, Duration)> = None;
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], info: &cpal::InputCallbackInfo| {
+                let capture = info.timestamp().capture;
+                let len = Duration::from_secs_f64(
+                    (data.len() / channels) as f64 / f64::from(sample_rate),
+                );
+                if let Some((previous, previous_len)) = previous {
+                    let dropped = dropped_frames(previous, previous_len, capture);
+                    SESSION_DROPPED.fetch_add(dropped, Ordering::SeqCst);
+                }
+                previous = Some((capture, len));
+
+                let mono = data
+                    .chunks(channels)
+                    .map(|frame| {
+                        frame
+                            .iter()
+                            .map(|sample| sample.to_sample::<f32>())
+                            .sum::<f32>()
+                            / frame.len() as f32
+                    })
+                    .collect();
+                let _ = sender.send(mono);
+            },
+            |e| {
+                if matches!(e, StreamError::BufferUnderrun) {
+                    SESSION_XRUNS.fetch_add(1, Ordering::SeqCst);
+                }
+                eprintln!("[voiceflow] Microphone input error: {e}");
+            },
+            None,
+        )
+        .map_err(|e| format!("Failed to open microphone: {e}"))
+}
+
+impl Microphone {
+    pub fn open(app: &AppHandle) -> Result<Self, String> {
+        let device = devices::input_device(app)?;
+        let supported = device
+            .default_input_config()
+            .map_err(|e| format!("Failed to read input config: {e}"))?;
+        let config = supported.config();
+
+        let (sender, receiver) = mpsc::channel();
+        let stream = match supported.sample_format() {
+            SampleFormat::F32 => build_stream::<f32>(&device, &config, sender)?,
+            SampleFormat::I16 => build_stream::<i16>(&device, &config, sender)?,
+            SampleFormat::U16 => build_stream::<u16>(&device, &config, sender)?,
+            other => return Err(format!("Unsupported sample format {other}")),
+        };
+        stream
+            .play()
+            .map_err(|e| format!("Failed to start microphone: {e}"))?;
+
+        Ok(Self {
+            _stream: stream,
+            receiver,
+            app: app.clone(),
+            pending: Vec::new(),
+            frame_len: (config.sample_rate * FRAME_MS / 1000).max(1) as usize,
+            sample_rate: config.sample_rate,
+        })
+    }
+
+    /// Complete frames captured since the last call, waiting up to `timeout` for audio
+    pub fn frames(&mut self, timeout: Duration) -> Vec<Vec<f32>> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(chunk) => self.pending.extend(chunk),
+            Err(RecvTimeoutError::Timeout) => {
+                SESSION_STALLS.fetch_add(1, Ordering::SeqCst);
+            }
+            Err(RecvTimeoutError::Disconnected) => {}
+        }
+        while let Ok(chunk) = self.receiver.try_recv() {
+            self.pending.extend(chunk);
+        }
+
+        let complete = self.pending.len() / self.frame_len * self.frame_len;
+        let frames: Vec<Vec<f32>> = self
+            .pending
+            .drain(..complete)
+            .collect::<Vec<_>>()
+            .chunks(self.frame_len)
+            .map(<[f32]>::to_vec)
+            .collect();
+        SESSION_FRAMES.fetch_add(frames.len() as u64, Ordering::SeqCst);
+        self.warn_on_drops();
+        frames
+    }
+
+    /// Tell the webview once per dictation that capture is losing audio
+    fn warn_on_drops(&self) {
+        let stats = session_stats();
+        if stats.dropped_frames < DROPPED_FRAMES_WARNING
+            || SESSION_WARNED.swap(true, Ordering::SeqCst)
+        {
+            return;
+        }
+        eprintln!(
+            "[voiceflow] Microphone dropped {} frames this dictation",
+            stats.dropped_frames
+        );
+        let _ = self.app.emit("capture-drops-warning", stats);
+    }
+
+    /// Drop audio buffered while the caller was busy
+    pub fn discard_pending(&mut self) {
+        while self.receiver.try_recv().is_ok() {}
+        self.pending.clear();
+    }
+}
+
+pub fn rms(frame: &[f32]) -> f32 {
+    if frame.is_empty() {
+        return 0.0;
+    }
+    (frame.iter().map(|sample| sample * sample).sum::<f32>() / frame.len() as f32).sqrt()
+}
@@ -0,0 +1,200 @@
+const NARROW_NO_BREAK_SPACE: char = '\u{202F}';
+const NO_BREAK_SPACE: char = '\u{00A0}';
+
+/// Punctuation that never takes a plain space before it
+const CLOSING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']', '}', '…', '%'];
+
+struct LanguageRules {
+    codes: &'static [&'static str],
+    /// Punctuation that must be preceded by a specific (non-breaking) space
+    space_before: &'static [(char, char)],
+    /// Punctuation that must be followed by a specific (non-breaking) space
+    space_after: &'static [(char, char)],
+    /// Drop spaces between ideographs, which the model sometimes inserts
+    join_cjk: bool,
+    capitalize_sentences: bool,
+}
+
+const DEFAULT_RULES: LanguageRules = LanguageRules {
+    codes: &["en"],
+    space_before: &[],
+    space_after: &[],
+    join_cjk: false,
+    capitalize_sentences: true,
+};
+
+const LANGUAGE_RULES: &[LanguageRules] = &[
+    LanguageRules {
+        codes: &["fr"],
+        space_before: &[
+            (';', NARROW_NO_BREAK_SPACE),
+            ('!', NARROW_NO_BREAK_SPACE),
+            ('?', NARROW_NO_BREAK_SPACE),
+            (':', NO_BREAK_SPACE),
+            ('»', NO_BREAK_SPACE),
+        ],
+        space_after: &[('«', NO_BREAK_SPACE)],
+        join_cjk: false,
+        capitalize_sentences: true,
+    },
+    LanguageRules {
+        codes: &["zh", "ja"],
+        space_before: &[],
+        space_after: &[],
+        join_cjk: true,
+        capitalize_sentences: false,
+    },
+    LanguageRules {
+        codes: &["de", "es", "it", "pt", "nl"],
+        ..DEFAULT_RULES
+    },
+];
+
+fn rules_for(language: Option<&str>) -> &'static LanguageRules {
+    let Some(language) = language else {
+        return &DEFAULT_RULES;
+    };
+    // Accept region-qualified tags such as "fr-CA" or "zh_Hans"
+    let base = language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    LANGUAGE_RULES
+        .iter()
+        .find(|rules| rules.codes.contains(&base.as_str()))
+        .unwrap_or(&DEFAULT_RULES)
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3000}'..='\u{303F}'   // CJK punctuation
+        | '\u{3040}'..='\u{30FF}' // Hiragana and Katakana
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{FF00}'..='\u{FFEF}' // Full-width forms
+    )
+}
+
+fn is_space(c: char) -> bool {
+    c.is_whitespace() || c == NO_BREAK_SPACE || c == NARROW_NO_BREAK_SPACE
+}
+
+/// Apply the spacing and capitalization rules for the transcript's language
+pub fn normalize(text: &str, language: Option<&str>) -> String {
+    let rules = rules_for(language);
+    // Runs of spaces and tabs become one space, but dictated line breaks stay
+    let collapsed = text
+        .split('\n')
+        .map(|line| {
+            line.split([' ', '\t'])
+                .filter(|word| !word.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let chars: Vec<char> = collapsed.chars().collect();
+    let mut output: Vec<char> = Vec::with_capacity(chars.len());
+
+    for (index, &c) in chars.iter().enumerate() {
+        if c == ' ' {
+            let previous = output.last().copied();
+            let next = chars.get(index + 1).copied();
+            let before_closing = next.is_some_and(|next| {
+                CLOSING_PUNCTUATION.contains(&next)
+                    || rules.space_before.iter().any(|(p, _)| *p == next)
+            });
+            // A non-breaking space may already have been emitted after opening punctuation
+            let after_space = previous.is_some_and(is_space);
+            let cjk_join =
+                rules.join_cjk && previous.is_some_and(is_cjk) && next.is_some_and(is_cjk);
+            if before_closing || after_space || cjk_join {
+                continue;
+            }
+            output.push(c);
+            continue;
+        }
+
+        if let Some((_, space)) = rules.space_before.iter().find(|(p, _)| *p == c) {
+            if output.last().is_some_and(|previous| !is_space(*previous)) {
+                output.push(*space);
+            }
+        }
+        output.push(c);
+        if let Some((_, space)) = rules.space_after.iter().find(|(p, _)| *p == c) {
+            if index + 1 < chars.len() {
+                output.push(*space);
+            }
+        }
+    }
+
+    if rules.capitalize_sentences {
+        capitalize_sentences(&mut output);
+    }
+    output.into_iter().collect()
+}
+
+fn capitalize_sentences(chars: &mut [char]) {
+    let mut sentence_start = true;
+    for index in 0..chars.len() {
+        let c = chars[index];
+        if sentence_start && c.is_alphabetic() {
+            if c.is_lowercase() {
+                let mut upper = c.to_uppercase();
+                if let (Some(single), None) = (upper.next(), upper.next()) {
+                    chars[index] = single;
+                }
+            }
+            sentence_start = false;
+        } else if matches!(c, '.' | '!' | '?') {
+            // Only a following space starts a new sentence, so "gmail.com" stays intact
+            sentence_start = chars.get(index + 1).is_some_and(|next| is_space(*next));
+        } else if !is_space(c) && !matches!(c, '"' | '\'' | '«' | '(' | '¿' | '¡') {
+            sentence_start = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn english_removes_space_before_punctuation() {
+        assert_eq!(
+            normalize("hello , world . how are you ?", Some("en")),
+            "Hello, world. How are you?"
+        );
+        assert_eq!(
+            normalize("visit gmail.com today", None),
+            "Visit gmail.com today"
+        );
+    }
+
+    #[test]
+    fn keeps_line_breaks() {
+        assert_eq!(
+            normalize("dear sam ,\n\nthanks  for\tcoming .\n", Some("en")),
+            "Dear sam,\n\nthanks for coming.\n"
+        );
+    }
+
+    #[test]
+    fn french_uses_non_breaking_spaces() {
+        assert_eq!(
+            normalize("il a dit « bonjour » : vraiment ?", Some("fr-FR")),
+            "Il a dit «\u{a0}bonjour\u{a0}»\u{a0}: vraiment\u{202f}?"
+        );
+    }
+
+    #[test]
+    fn cjk_joins_ideographs_without_spaces() {
+        assert_eq!(
+            normalize("今日 は 良い 天気 です 。", Some("ja")),
+            "今日は良い天気です。"
+        );
+        assert_eq!(normalize("使用 Rust 编程", Some("zh")), "使用 Rust 编程");
+    }
+}
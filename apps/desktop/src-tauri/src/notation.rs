@@ -0,0 +1,692 @@
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::{app_profiles, latex, settings};
+
+// Scientific dictation: "ten to the minus three" becomes 10⁻³, "alpha sub i" αᵢ and
+// "five micrograms per milliliter" 5 µg/mL, or the same in LaTeX for papers written
+// in TeX. Only spoken English is understood. Numbers stay spelled out unless they are
+// part of such an expression.
+
+/// How spoken notation is written out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NotationStyle {
+    #[default]
+    Off,
+    /// Superscripts, subscripts and symbols, like 10⁻³ µg/mL
+    Unicode,
+    /// Inline math, like $10^{-3}\,\mu\mathrm{g}/\mathrm{mL}$
+    Latex,
+}
+
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', ')'];
+
+const CARDINALS: &[&str] = &[
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+const TENS: &[&str] = &[
+    "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+/// Ordinals as exponents, "to the fifth"; "second" is "squared" in speech
+const ORDINALS: &[(&str, u32)] = &[
+    ("third", 3),
+    ("fourth", 4),
+    ("fifth", 5),
+    ("sixth", 6),
+    ("seventh", 7),
+    ("eighth", 8),
+    ("ninth", 9),
+    ("tenth", 10),
+    ("eleventh", 11),
+    ("twelfth", 12),
+];
+
+const GREEK: &[(&str, &str)] = &[
+    ("alpha", "α"),
+    ("beta", "β"),
+    ("gamma", "γ"),
+    ("delta", "δ"),
+    ("epsilon", "ε"),
+    ("zeta", "ζ"),
+    ("eta", "η"),
+    ("theta", "θ"),
+    ("iota", "ι"),
+    ("kappa", "κ"),
+    ("lambda", "λ"),
+    ("mu", "μ"),
+    ("nu", "ν"),
+    ("xi", "ξ"),
+    ("pi", "π"),
+    ("rho", "ρ"),
+    ("sigma", "σ"),
+    ("tau", "τ"),
+    ("phi", "φ"),
+    ("chi", "χ"),
+    ("psi", "ψ"),
+    ("omega", "ω"),
+];
+
+const SUPERSCRIPTS: &[(char, char)] = &[
+    ('0', '⁰'),
+    ('1', '¹'),
+    ('2', '²'),
+    ('3', '³'),
+    ('4', '⁴'),
+    ('5', '⁵'),
+    ('6', '⁶'),
+    ('7', '⁷'),
+    ('8', '⁸'),
+    ('9', '⁹'),
+    ('-', '⁻'),
+    ('+', '⁺'),
+    ('i', 'ⁱ'),
+    ('n', 'ⁿ'),
+];
+
+const SUBSCRIPTS: &[(char, char)] = &[
+    ('0', '₀'),
+    ('1', '₁'),
+    ('2', '₂'),
+    ('3', '₃'),
+    ('4', '₄'),
+    ('5', '₅'),
+    ('6', '₆'),
+    ('7', '₇'),
+    ('8', '₈'),
+    ('9', '₉'),
+    ('a', 'ₐ'),
+    ('e', 'ₑ'),
+    ('h', 'ₕ'),
+    ('i', 'ᵢ'),
+    ('j', 'ⱼ'),
+    ('k', 'ₖ'),
+    ('l', 'ₗ'),
+    ('m', 'ₘ'),
+    ('n', 'ₙ'),
+    ('o', 'ₒ'),
+    ('p', 'ₚ'),
+    ('r', 'ᵣ'),
+    ('s', 'ₛ'),
+    ('t', 'ₜ'),
+    ('u', 'ᵤ'),
+    ('v', 'ᵥ'),
+    ('x', 'ₓ'),
+    ('β', 'ᵦ'),
+    ('γ', 'ᵧ'),
+    ('ρ', 'ᵨ'),
+    ('φ', 'ᵩ'),
+    ('χ', 'ᵪ'),
+];
+
+/// SI prefixes as (spoken, Unicode, LaTeX)
+const UNIT_PREFIXES: &[(&str, &str, &str)] = &[
+    ("pico", "p", "p"),
+    ("nano", "n", "n"),
+    ("micro", "µ", "\\mu"),
+    ("milli", "m", "m"),
+    ("centi", "c", "c"),
+    ("kilo", "k", "k"),
+    ("mega", "M", "M"),
+    ("giga", "G", "G"),
+    ("", "", ""),
+];
+
+/// Units in the singular, with their symbols
+const UNITS: &[(&str, &str)] = &[
+    ("gram", "g"),
+    ("liter", "L"),
+    ("litre", "L"),
+    ("meter", "m"),
+    ("metre", "m"),
+    ("second", "s"),
+    ("mole", "mol"),
+    ("molar", "M"),
+    ("hertz", "Hz"),
+    ("volt", "V"),
+    ("watt", "W"),
+    ("joule", "J"),
+    ("newton", "N"),
+    ("ampere", "A"),
+    ("amp", "A"),
+    ("pascal", "Pa"),
+    ("kelvin", "K"),
+];
+
+/// A word with the punctuation and whitespace that follow it
+#[derive(Debug)]
+struct Word<'a> {
+    core: &'a str,
+    punctuation: &'a str,
+    space: &'a str,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    Text(String),
+    /// A spoken number, written as digits only inside an expression
+    Number {
+        digits: String,
+        spoken: String,
+    },
+    Math {
+        unicode: String,
+        latex: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Piece {
+    kind: Kind,
+    punctuation: String,
+    space: String,
+}
+
+fn split_words(text: &str) -> (&str, Vec<Word<'_>>) {
+    let mut rest = text.trim_start();
+    let leading = &text[..text.len() - rest.len()];
+    let mut words = Vec::new();
+    while !rest.is_empty() {
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let (word, after) = rest.split_at(end);
+        let space = &after[..after.len() - after.trim_start().len()];
+        let core = word.trim_end_matches(TRAILING_PUNCTUATION);
+        words.push(Word {
+            core,
+            punctuation: &word[core.len()..],
+            space,
+        });
+        rest = &after[space.len()..];
+    }
+    (leading, words)
+}
+
+/// Lowercase words of the first `count` words, None when punctuation splits them
+fn phrase(words: &[Word], count: usize) -> Option<Vec<String>> {
+    if words.len() < count
+        || words[..count - 1]
+            .iter()
+            .any(|word| !word.punctuation.is_empty())
+    {
+        return None;
+    }
+    Some(
+        words[..count]
+            .iter()
+            .map(|word| word.core.to_lowercase())
+            .collect(),
+    )
+}
+
+fn cardinal(word: &str) -> Option<u32> {
+    CARDINALS
+        .iter()
+        .position(|name| *name == word)
+        .map(|value| value as u32)
+}
+
+/// Digits, or number words up to ninety-nine, as (digits, words used)
+fn number(words: &[Word]) -> Option<(String, usize)> {
+    let first = words.first()?;
+    let is_digits = first.core.chars().any(|c| c.is_ascii_digit())
+        && first
+            .core
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '.' || c == ',');
+    if is_digits {
+        return Some((first.core.to_string(), 1));
+    }
+    let lower = first.core.to_lowercase();
+    if let Some(value) = cardinal(&lower) {
+        return Some((value.to_string(), 1));
+    }
+    // "twenty-five" or "twenty five"
+    let (tens, units, used) = match lower.split_once('-') {
+        Some((tens, units)) => (tens, Some(units.to_string()), 1),
+        None => (
+            lower.as_str(),
+            phrase(words, 2).map(|words| words[1].clone()),
+            2,
+        ),
+    };
+    let tens = TENS.iter().position(|name| *name == tens)? as u32 * 10 + 20;
+    match units.as_deref().and_then(cardinal) {
+        Some(units) if (1..10).contains(&units) => Some(((tens + units).to_string(), used)),
+        _ if used == 1 => None,
+        _ => Some((tens.to_string(), 1)),
+    }
+}
+
+fn greek(word: &str) -> Option<(String, String)> {
+    let lower = word.to_lowercase();
+    GREEK
+        .iter()
+        .find(|(name, _)| *name == lower)
+        .map(|(name, letter)| (letter.to_string(), format!("\\{name}")))
+}
+
+fn is_letter(word: &str) -> bool {
+    let mut chars = word.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic()) && chars.next().is_none()
+}
+
+/// "squared", "cubed" or "to the [power of] [minus] three", as (exponent, words used)
+fn exponent(words: &[Word]) -> Option<(String, usize)> {
+    match words.first()?.core.to_lowercase().as_str() {
+        "squared" => return Some(("2".to_string(), 1)),
+        "cubed" => return Some(("3".to_string(), 1)),
+        _ => {}
+    }
+    if phrase(words, 2)? != ["to", "the"] {
+        return None;
+    }
+    let mut used = 2;
+    if phrase(words, used + 2).is_some_and(|words| words[used..] == ["power", "of"]) {
+        used += 2;
+    }
+    let mut sign = "";
+    if let Some(words) = phrase(words, used + 1) {
+        match words[used].as_str() {
+            "minus" | "negative" => sign = "-",
+            "plus" | "positive" => sign = "+",
+            _ => {}
+        }
+    }
+    if !sign.is_empty() {
+        used += 1;
+    }
+    phrase(words, used + 1)?;
+    let value = &words[used];
+    let lower = value.core.to_lowercase();
+    // "to the 6th"
+    let numbered = ["st", "nd", "rd", "th"]
+        .iter()
+        .find_map(|suffix| lower.strip_suffix(suffix))
+        .filter(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()));
+    let (value, count) =
+        if let Some((_, ordinal)) = ORDINALS.iter().find(|(name, _)| *name == lower) {
+            (ordinal.to_string(), 1)
+        } else if let Some(digits) = numbered {
+            (digits.to_string(), 1)
+        } else if is_letter(value.core) {
+            (value.core.to_string(), 1)
+        } else {
+            number(&words[used..])?
+        };
+    used += count;
+    // "to the third power"
+    if phrase(words, used + 1).is_some_and(|words| words[used] == "power") {
+        used += 1;
+    }
+    Some((format!("{sign}{value}"), used))
+}
+
+fn superscript(exponent: &str) -> String {
+    let mapped: Option<String> = exponent
+        .chars()
+        .map(|c| {
+            SUPERSCRIPTS
+                .iter()
+                .find(|(from, _)| *from == c)
+                .map(|(_, to)| *to)
+        })
+        .collect();
+    mapped.unwrap_or_else(|| format!("^({exponent})"))
+}
+
+fn subscript(index: &str) -> String {
+    let mapped: Option<String> = index
+        .chars()
+        .map(|c| {
+            SUBSCRIPTS
+                .iter()
+                .find(|(from, _)| *from == c)
+                .map(|(_, to)| *to)
+        })
+        .collect();
+    mapped.unwrap_or_else(|| format!("_{index}"))
+}
+
+/// One unit like "micrograms" or "square meters", as (Unicode, LaTeX, words used)
+fn unit_part(words: &[Word]) -> Option<(String, String, usize)> {
+    let first = phrase(words, 1)?.remove(0);
+    if matches!(first.as_str(), "degree" | "degrees") {
+        let scale = phrase(words, 2)?.remove(1);
+        let symbol = match scale.as_str() {
+            "celsius" => "C",
+            "fahrenheit" => "F",
+            _ => return None,
+        };
+        return Some((
+            format!("°{symbol}"),
+            format!("{{}}^{{\\circ}}\\mathrm{{{symbol}}}"),
+            2,
+        ));
+    }
+    let (power, mut used) = match first.as_str() {
+        "square" => (Some("2"), 1),
+        "cubic" => (Some("3"), 1),
+        _ => (None, 0),
+    };
+    let word = phrase(words, used + 1)?.remove(used);
+    let (prefix, symbol) = UNIT_PREFIXES.iter().find_map(|prefix| {
+        let rest = word.strip_prefix(prefix.0)?;
+        let singular = rest.strip_suffix('s').unwrap_or(rest);
+        UNITS
+            .iter()
+            .find(|(name, _)| *name == rest || *name == singular)
+            .map(|(_, symbol)| (prefix, *symbol))
+    })?;
+    used += 1;
+    let power = match power {
+        Some(power) => Some(power),
+        None => match phrase(words, used + 1)
+            .map(|words| words[used].clone())
+            .as_deref()
+        {
+            Some("squared") => {
+                used += 1;
+                Some("2")
+            }
+            Some("cubed") => {
+                used += 1;
+                Some("3")
+            }
+            _ => None,
+        },
+    };
+    let mut unicode = format!("{}{symbol}", prefix.1);
+    let mut latex = if prefix.2.starts_with('\\') {
+        format!("{}\\mathrm{{{symbol}}}", prefix.2)
+    } else {
+        format!("\\mathrm{{{}{symbol}}}", prefix.2)
+    };
+    if let Some(power) = power {
+        unicode.push_str(&superscript(power));
+        latex.push_str(&format!("^{{{power}}}"));
+    }
+    Some((unicode, latex, used))
+}
+
+/// Units joined by "per", like "micrograms per milliliter"
+fn unit(words: &[Word]) -> Option<(String, String, usize)> {
+    let (mut unicode, mut latex, mut used) = unit_part(words)?;
+    while phrase(words, used + 1).is_some_and(|words| words[used] == "per") {
+        let Some((next_unicode, next_latex, next_used)) = unit_part(&words[used + 1..]) else {
+            break;
+        };
+        unicode = format!("{unicode}/{next_unicode}");
+        latex = format!("{latex}/{next_latex}");
+        used += 1 + next_used;
+    }
+    Some((unicode, latex, used))
+}
+
+/// The piece as the base of an exponent or subscript, as (Unicode, LaTeX)
+fn as_base(piece: &Piece) -> Option<(String, String)> {
+    if !piece.punctuation.is_empty() {
+        return None;
+    }
+    match &piece.kind {
+        Kind::Number { digits, .. } => Some((digits.clone(), digits.clone())),
+        Kind::Math { unicode, latex } => Some((unicode.clone(), latex.clone())),
+        // Not "I squared the numbers"
+        Kind::Text(text) if is_letter(text) && text != "I" => Some((text.clone(), text.clone())),
+        Kind::Text(_) => None,
+    }
+}
+
+fn is_numeric(piece: &Piece) -> bool {
+    piece.punctuation.is_empty()
+        && match &piece.kind {
+            Kind::Number { .. } => true,
+            Kind::Math { unicode, .. } => unicode.starts_with(|c: char| c.is_ascii_digit()),
+            Kind::Text(_) => false,
+        }
+}
+
+/// Turn a spoken number into digits now that it belongs to an expression
+fn to_math(piece: &mut Piece) {
+    if let Kind::Number { digits, .. } = &piece.kind {
+        piece.kind = Kind::Math {
+            unicode: digits.clone(),
+            latex: digits.clone(),
+        };
+    }
+}
+
+fn piece(kind: Kind, words: &[Word]) -> Piece {
+    let last = words.last().expect("a piece covers at least one word");
+    Piece {
+        kind,
+        punctuation: last.punctuation.to_string(),
+        space: last.space.to_string(),
+    }
+}
+
+fn parse(words: &[Word]) -> Vec<Piece> {
+    let mut pieces: Vec<Piece> = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        let rest = &words[i..];
+        let lower = rest[0].core.to_lowercase();
+        let base = pieces.last().and_then(as_base);
+
+        if let (Some((unicode, latex)), Some((power, used))) = (&base, exponent(rest)) {
+            pieces.pop();
+            let kind = Kind::Math {
+                unicode: format!("{unicode}{}", superscript(&power)),
+                latex: format!("{latex}^{{{power}}}"),
+            };
+            pieces.push(piece(kind, &rest[..used]));
+            i += used;
+            continue;
+        }
+        if let (Some((unicode, latex)), true) = (&base, lower == "sub") {
+            let index = rest.get(1).and_then(|word| {
+                if let Some(letter) = greek(word.core) {
+                    return Some((letter, 1));
+                }
+                if is_letter(word.core) {
+                    return Some(((word.core.to_string(), word.core.to_string()), 1));
+                }
+                number(&rest[1..]).map(|(digits, used)| ((digits.clone(), digits), used))
+            });
+            if let Some(((index_unicode, index_latex), used)) =
+                index.filter(|_| rest[0].punctuation.is_empty())
+            {
+                pieces.pop();
+                let kind = Kind::Math {
+                    unicode: format!("{unicode}{}", subscript(&index_unicode)),
+                    latex: format!("{latex}_{{{index_latex}}}"),
+                };
+                pieces.push(piece(kind, &rest[..1 + used]));
+                i += 1 + used;
+                continue;
+            }
+        }
+        if pieces.last().is_some_and(is_numeric) {
+            if let Some((unicode, latex, used)) = unit(rest) {
+                to_math(pieces.last_mut().expect("checked above"));
+                let kind = Kind::Math {
+                    unicode,
+                    latex: format!("\\,{latex}"),
+                };
+                pieces.push(piece(kind, &rest[..used]));
+                i += used;
+                continue;
+            }
+            // "three times ten to the minus four"
+            let scaled = rest[0].punctuation.is_empty()
+                && number(&rest[1..])
+                    .is_some_and(|(_, used)| exponent(&rest[1 + used..]).is_some());
+            if lower == "times" && scaled {
+                to_math(pieces.last_mut().expect("checked above"));
+                let kind = Kind::Math {
+                    unicode: "×".to_string(),
+                    latex: "\\times".to_string(),
+                };
+                pieces.push(piece(kind, &rest[..1]));
+                i += 1;
+                continue;
+            }
+        }
+        if let Some((digits, used)) = number(rest) {
+            let spoken = rest[..used]
+                .iter()
+                .enumerate()
+                .map(|(index, word)| {
+                    if index + 1 == used {
+                        word.core.to_string()
+                    } else {
+                        format!("{}{}{}", word.core, word.punctuation, word.space)
+                    }
+                })
+                .collect();
+            pieces.push(piece(Kind::Number { digits, spoken }, &rest[..used]));
+            i += used;
+            continue;
+        }
+        // "capital delta"
+        if lower == "capital" {
+            if let Some((letter, name)) = rest.get(1).and_then(|word| greek(word.core)) {
+                if rest[0].punctuation.is_empty() {
+                    // \delta to \Delta
+                    let latex = format!("\\{}{}", name[1..2].to_uppercase(), &name[2..]);
+                    let kind = Kind::Math {
+                        unicode: letter.to_uppercase(),
+                        latex,
+                    };
+                    pieces.push(piece(kind, &rest[..2]));
+                    i += 2;
+                    continue;
+                }
+            }
+        }
+        if let Some((unicode, latex)) = greek(rest[0].core) {
+            pieces.push(piece(Kind::Math { unicode, latex }, &rest[..1]));
+            i += 1;
+            continue;
+        }
+        pieces.push(piece(Kind::Text(rest[0].core.to_string()), &rest[..1]));
+        i += 1;
+    }
+    pieces
+}
+
+fn render(leading: &str, pieces: &[Piece], style: NotationStyle) -> String {
+    let mut output = leading.to_string();
+    let mut math: Vec<&str> = Vec::new();
+    for (index, piece) in pieces.iter().enumerate() {
+        match (&piece.kind, style) {
+            (Kind::Math { latex, .. }, NotationStyle::Latex) => {
+                math.push(latex);
+                let next_is_math = pieces
+                    .get(index + 1)
+                    .is_some_and(|next| matches!(next.kind, Kind::Math { .. }));
+                // A run of expressions separated only by spaces is one formula
+                if next_is_math && piece.punctuation.is_empty() {
+                    continue;
+                }
+                output.push('$');
+                output.push_str(&math.join(" "));
+                output.push('$');
+                math.clear();
+            }
+            (Kind::Math { unicode, .. }, _) => output.push_str(unicode),
+            (Kind::Number { spoken, .. }, _) => output.push_str(spoken),
+            (Kind::Text(text), _) => output.push_str(text),
+        }
+        output.push_str(&piece.punctuation);
+        output.push_str(&piece.space);
+    }
+    output
+}
+
+/// `text` with spoken notation written out in `style`
+pub fn convert(text: &str, style: NotationStyle) -> String {
+    if style == NotationStyle::Off {
+        return text.to_string();
+    }
+    let (leading, words) = split_words(text);
+    render(leading, &parse(&words), style)
+}
+
+/// The style for the app being dictated into: its profile's, LaTeX in a TeX editor,
+/// or the global one
+pub fn style(app: &AppHandle) -> NotationStyle {
+    app_profiles::current(app)
+        .and_then(|profile| profile.notation)
+        .or_else(|| latex::is_target(app).then_some(NotationStyle::Latex))
+        .unwrap_or_else(|| settings::current(app).scientific_notation)
+}
+
+/// Write out spoken notation in an English transcript, in the style for the app
+pub fn apply(app: &AppHandle, text: &str, language: Option<&str>) -> String {
+    let english = language.is_none_or(|language| language.to_lowercase().starts_with("en"));
+    if !english {
+        return text.to_string();
+    }
+    convert(text, style(app))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_notation_as_unicode() {
+        let unicode = |text| convert(text, NotationStyle::Unicode);
+        assert_eq!(
+            unicode("a rate of three times ten to the minus four per day."),
+            "a rate of 3 × 10⁻⁴ per day."
+        );
+        assert_eq!(
+            unicode("dilute to five micrograms per milliliter"),
+            "dilute to 5 µg/mL"
+        );
+        assert_eq!(unicode("let alpha sub i be x squared"), "let αᵢ be x²");
+        assert_eq!(unicode("at 9.81 meters per second squared"), "at 9.81 m/s²");
+        assert_eq!(
+            unicode("kept at twenty-five degrees celsius"),
+            "kept at 25 °C"
+        );
+        assert_eq!(unicode("capital delta"), "Δ");
+        assert_eq!(unicode("2.5 times 10 to the 6th"), "2.5 × 10⁶");
+        assert_eq!(
+            unicode("I have two cats and one dog"),
+            "I have two cats and one dog"
+        );
+    }
+
+    #[test]
+    fn writes_notation_as_latex() {
+        let latex = |text| convert(text, NotationStyle::Latex);
+        assert_eq!(latex("ten to the minus three, then"), "$10^{-3}$, then");
+        assert_eq!(
+            latex("add five micrograms per milliliter"),
+            "add $5 \\,\\mu\\mathrm{g}/\\mathrm{mL}$"
+        );
+        assert_eq!(latex("alpha sub i"), "$\\alpha_{i}$");
+        assert_eq!(latex("capital delta"), "$\\Delta$");
+    }
+}
@@ -0,0 +1,164 @@
+use serde::Serialize;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TrySendError};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::transcription::{LiveSession, TranscriptionOptions};
+use crate::{screen_share, session};
+
+// In streaming mode, natively captured audio also goes to the sidecar while the user
+// is still speaking, over a live session of its own, and the hypotheses it sends back
+// reach the bubble as `transcript-partial` events. Neither side waits on the other:
+// a connection that can't keep up with the audio loses its partials instead of
+// stalling capture, and hypotheses arriving faster than the bubble should repaint
+// replace each other.
+
+// Chunks waiting to be sent, 3 s of audio at the capture's 100 ms chunks
+const AUDIO_QUEUE: usize = 30;
+// Fastest the bubble is repainted
+const MIN_EMIT_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TranscriptPartial {
+    text: String,
+    /// Counts up from 0 within a dictation
+    sequence: u64,
+    /// Newer hypotheses that replaced others before they were emitted
+    replaced: u64,
+}
+
+/// Holds the newest hypothesis until the next emit is due
+#[derive(Debug, Default)]
+struct Coalescer {
+    latest: Option<String>,
+    replaced: u64,
+    emitted: Option<(Instant, String)>,
+}
+
+impl Coalescer {
+    fn push(&mut self, text: String) {
+        let unchanged = self
+            .emitted
+            .as_ref()
+            .is_some_and(|(_, emitted)| *emitted == text);
+        if unchanged {
+            self.latest = None;
+            return;
+        }
+        if self.latest.replace(text).is_some() {
+            self.replaced += 1;
+        }
+    }
+
+    /// The hypothesis to emit at `now` and how many it replaced, None while nothing
+    /// new arrived or the last emit was too recent
+    fn take(&mut self, now: Instant) -> Option<(String, u64)> {
+        let too_soon = self
+            .emitted
+            .as_ref()
+            .is_some_and(|(at, _)| now.duration_since(*at) < MIN_EMIT_INTERVAL);
+        if too_soon {
+            return None;
+        }
+        let text = self.latest.take()?;
+        self.emitted = Some((now, text.clone()));
+        Some((text, std::mem::take(&mut self.replaced)))
+    }
+}
+
+fn relay(app: &AppHandle, audio: Receiver<Vec<i16>>) -> Result<(), String> {
+    let mut live = LiveSession::open(&TranscriptionOptions::default())?;
+    let mut partials = Coalescer::default();
+    let mut sequence = 0;
+    loop {
+        match audio.recv_timeout(MIN_EMIT_INTERVAL) {
+            Ok(samples) => live.send_audio(&samples)?,
+            Err(RecvTimeoutError::Timeout) => {}
+            // The capture finished; the final transcript takes it from here
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        while let Some(text) = live.poll_partial()? {
+            partials.push(text);
+        }
+        if let Some((text, replaced)) = partials.take(Instant::now()) {
+            session::set_partial(text.clone());
+            // Previews would put the dictation on screen while it is being broadcast
+            if screen_share::should_hide_overlay(app) {
+                continue;
+            }
+            let _ = app.emit(
+                "transcript-partial",
+                TranscriptPartial {
+                    text,
+                    sequence,
+                    replaced,
+                },
+            );
+            sequence += 1;
+        }
+    }
+    live.close();
+    Ok(())
+}
+
+/// Feeds one dictation's audio to a live session; dropping it ends the session
+pub struct Bridge {
+    audio: Option<SyncSender<Vec<i16>>>,
+}
+
+impl Bridge {
+    pub fn start(app: &AppHandle) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(AUDIO_QUEUE);
+        let app = app.clone();
+        thread::spawn(move || {
+            if let Err(e) = relay(&app, receiver) {
+                eprintln!("[voiceflow] Live transcription stopped: {e}");
+            }
+        });
+        Self {
+            audio: Some(sender),
+        }
+    }
+
+    /// Queue 16 kHz PCM without ever blocking the capture
+    pub fn send(&mut self, samples: Vec<i16>) {
+        let Some(sender) = &self.audio else {
+            return;
+        };
+        match sender.try_send(samples) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                eprintln!("[voiceflow] Live transcription fell behind, no more partials");
+                self.audio = None;
+            }
+            Err(TrySendError::Disconnected(_)) => self.audio = None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emits_only_the_newest_hypothesis_when_due() {
+        let start = Instant::now();
+        let mut partials = Coalescer::default();
+        assert_eq!(partials.take(start), None);
+        partials.push("hel".to_string());
+        partials.push("hello".to_string());
+        assert_eq!(partials.take(start), Some(("hello".to_string(), 1)));
+
+        partials.push("hello wor".to_string());
+        assert_eq!(partials.take(start + MIN_EMIT_INTERVAL / 2), None);
+        assert_eq!(
+            partials.take(start + MIN_EMIT_INTERVAL),
+            Some(("hello wor".to_string(), 0))
+        );
+
+        partials.push("hello wor".to_string());
+        assert_eq!(partials.take(start + MIN_EMIT_INTERVAL * 3), None);
+    }
+}
@@ -0,0 +1,366 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter};
+
+use crate::active_app::ActiveApp;
+use crate::grammar::Suggestion;
+use crate::history::{self, HistoryEntry};
+use crate::plugins::{self, PluginOutcome};
+use crate::postprocess::{self, PromptContext};
+use crate::scripting::{self, Hook};
+use crate::snippets::{self, Snippet};
+use crate::transcription::Transcript;
+use crate::tray::TrayState;
+use crate::{
+    accessibility, active_app, active_prompt_template, app_profiles, auto_send, browser,
+    command_output, continuous, email, form_fill, grammar, i18n, incognito, insertion, keyboard,
+    latex, markdown, normalize, notation, redaction, replay, result_panel, scratchpad, search,
+    selection, session, settings, shutdown, speech, spelling, symbols, target_app, tray,
+    vocabulary, voice_commands,
+};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedTranscription {
+    pub entry: HistoryEntry,
+    /// Ask the user before pasting because confidence fell below the configured threshold
+    pub requires_confirmation: bool,
+    /// The transcript was already consumed (spoken command, scratchpad), so nothing should be pasted
+    pub handled: bool,
+    /// Frontmost app when the dictation started
+    pub active_app: Option<ActiveApp>,
+}
+
+const CORRECTION_PREFIXES: &[&str] = &["correct that to ", "correct that: ", "correct that, "];
+
+/// Extract the replacement from a "correct that to …" utterance
+fn parse_correction(text: &str) -> Option<&str> {
+    let trimmed = text.trim();
+    CORRECTION_PREFIXES.iter().find_map(|prefix| {
+        let head = trimmed.get(..prefix.len())?;
+        if !head.eq_ignore_ascii_case(prefix) {
+            return None;
+        }
+        let replacement = trimmed[prefix.len()..].trim().trim_end_matches('.');
+        (!replacement.is_empty()).then_some(replacement)
+    })
+}
+
+/// Swap the previous dictation in the target app for the spoken replacement
+async fn apply_correction(app: &AppHandle, replacement: String) -> Result<HistoryEntry, String> {
+    // Incognito dictations aren't in history, so the last entry is the wrong one to replace
+    if incognito::is_active() {
+        return Err(i18n::t("error.correction_incognito"));
+    }
+    let previous =
+        history::last_entry(app).ok_or_else(|| i18n::t("error.no_previous_dictation"))?;
+    // The stored text may be redacted or edited, so count what was actually inserted
+    let n_words = insertion::inserted_words(previous.id)
+        .ok_or_else(|| i18n::t("error.correction_not_inserted"))?;
+
+    let typed = replacement.clone();
+    tauri::async_runtime::spawn_blocking(move || keyboard::replace_last_words(n_words, &typed))
+        .await
+        .map_err(|e| e.to_string())??;
+    insertion::record_inserted(previous.id, replacement.split_whitespace().count());
+
+    history::update_entry(app, previous.id, |entry| {
+        entry.text = redaction::redact(app, &replacement);
+        Ok(())
+    })
+}
+
+fn requires_confirmation(confidence: Option<f32>, threshold: Option<f32>) -> bool {
+    matches!((confidence, threshold), (Some(confidence), Some(threshold)) if confidence < threshold)
+}
+
+/// Run the LLM cleanup stage, keeping the original text if it fails
+async fn post_process(app: &AppHandle, transcript: &Transcript) -> Option<String> {
+    let app = app.clone();
+    let text = transcript.text.clone();
+    let mut context = PromptContext {
+        language: transcript.language.clone(),
+        selection: selection::take(),
+        tab: None,
+    };
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let frontmost = active_app::frontmost_app();
+        context.tab = browser::active_tab(&app, frontmost.as_ref());
+        let template = active_prompt_template(&app)
+            .or_else(|| app_profiles::current(&app)?.prompt_template)
+            .or_else(|| browser::template(&app, context.tab.as_ref()))
+            .or_else(|| latex::template(&app))
+            .or_else(|| email::template(&app));
+        postprocess::process(&app, &text, template.as_deref(), &context)
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|result| result);
+
+    match result {
+        Ok(cleaned) => cleaned,
+        Err(error) => {
+            eprintln!("[voiceflow] Post-processing failed: {error}");
+            None
+        }
+    }
+}
+
+/// The text stages: scripts, then spelling mode or normalization followed by post-processing
+pub async fn transform(app: &AppHandle, mut transcript: Transcript) -> Transcript {
+    let language = transcript.language.clone();
+    if let Some(text) = scripting::run_text_hook(
+        app,
+        Hook::PostTranscription,
+        &transcript.text,
+        language.as_deref(),
+    ) {
+        replace_text(&mut transcript, text);
+    }
+    if let Some(spelled) = spelling::apply(&transcript.text) {
+        transcript.text = spelled;
+        // Timings no longer line up with the literal characters
+        transcript.words.clear();
+        transcript.segments.clear();
+    } else {
+        transcript.text = normalize::normalize(&transcript.text, transcript.language.as_deref());
+        transcript.text = vocabulary::apply(app, &transcript.text);
+        transcript.text = symbols::apply(app, &transcript.text, language.as_deref());
+        transcript.text = notation::apply(app, &transcript.text, language.as_deref());
+        if let Some(cleaned) = post_process(app, &transcript).await {
+            markdown::remember(app, &cleaned);
+            transcript.text = cleaned;
+        }
+    }
+    transcript
+}
+
+/// Replace the text of a transcript whose timings no longer line up with it
+fn replace_text(transcript: &mut Transcript, text: String) {
+    transcript.text = text;
+    transcript.words.clear();
+    transcript.segments.clear();
+}
+
+/// Everything a regular dictation goes through before it is stored: the text
+/// stages, email layout, plugins, pre-output scripts and the grammar check
+async fn dictation_text(
+    app: &AppHandle,
+    transcript: Transcript,
+) -> (Transcript, Vec<Suggestion>, PluginOutcome) {
+    let mut transcript = transform(app, transcript).await;
+    if let Some(formatted) = email::format(app, &transcript.text) {
+        replace_text(&mut transcript, formatted);
+    }
+    let plugged = plugins::run(app, &transcript).await;
+    if let Some(text) = &plugged.text {
+        replace_text(&mut transcript, text.clone());
+    }
+    let language = transcript.language.clone();
+    if let Some(text) =
+        scripting::run_text_hook(app, Hook::PreOutput, &transcript.text, language.as_deref())
+    {
+        replace_text(&mut transcript, text);
+    }
+    let suggestions = grammar::check(app, &transcript.text, language.as_deref()).await;
+    (transcript, suggestions, plugged)
+}
+
+/// A trigger phrase replaced by its snippet, kept as spoken when fill-ins are still pending
+fn snippet_transcript(mut transcript: Transcript, snippet: &Snippet, fill_in: bool) -> Transcript {
+    if !fill_in {
+        transcript.text = snippets::render(&snippet.body, &HashMap::new());
+    }
+    transcript.words.clear();
+    transcript.segments.clear();
+    transcript.alternatives.clear();
+    transcript
+}
+
+/// Store a dictation result received by the webview and broadcast it to all windows.
+/// The returned entry's text is what should be inserted into the target app.
+#[tauri::command]
+pub async fn record_transcription(
+    app: AppHandle,
+    transcript: Transcript,
+    tag: Option<String>,
+) -> Result<RecordedTranscription, String> {
+    let _session = shutdown::SessionGuard;
+    let received_ms = session::now_ms();
+    replay::transcript_received(&transcript);
+    let recorded = record(&app, transcript, tag).await;
+    #[cfg(target_os = "macos")]
+    let recorded = crate::url_scheme::dictation_finished(recorded);
+    let recorded = result_panel::dictation_finished(&app, recorded);
+    if let Err(error) = &recorded {
+        accessibility::announce(&app, i18n::tf("a11y.dictation_failed", &[("error", error)]));
+    }
+    session::finished(received_ms);
+    tray::set_state(&app, TrayState::Idle);
+    replay::session_finished(&app, &recorded);
+    recorded
+}
+
+async fn record(
+    app: &AppHandle,
+    transcript: Transcript,
+    tag: Option<String>,
+) -> Result<RecordedTranscription, String> {
+    let recorded = dispatch(app, transcript, tag).await;
+    // Continuous dictation listens for the next segment however this one was handled
+    continuous::segment_finished(app);
+    recorded
+}
+
+/// Hand the transcript to whatever the session was for, storing it in history
+async fn dispatch(
+    app: &AppHandle,
+    transcript: Transcript,
+    tag: Option<String>,
+) -> Result<RecordedTranscription, String> {
+    let send = auto_send::take_session();
+    let is_search = search::take_session();
+    let target_app = target_app::take_session();
+    if let Some(replacement) = parse_correction(&transcript.text) {
+        let entry = apply_correction(app, replacement.to_string()).await?;
+        let recorded = RecordedTranscription {
+            entry,
+            requires_confirmation: false,
+            handled: true,
+            active_app: active_app::at_start(),
+        };
+        let _ = app.emit("correction-applied", &recorded);
+        return Ok(recorded);
+    }
+
+    if voice_commands::take_session() {
+        voice_commands::handle(app, &transcript.text)?;
+        let tag = Some(voice_commands::COMMAND_HISTORY_TAG.to_string());
+        let entry = history::add_dictation(app, transcript, tag)?;
+        return Ok(RecordedTranscription {
+            entry,
+            requires_confirmation: false,
+            handled: true,
+            active_app: active_app::at_start(),
+        });
+    }
+
+    if is_search {
+        search::open(app, &transcript.text)?;
+        let tag = Some(search::SEARCH_HISTORY_TAG.to_string());
+        let entry = history::add_dictation(app, transcript, tag)?;
+        return Ok(RecordedTranscription {
+            entry,
+            requires_confirmation: false,
+            handled: true,
+            active_app: active_app::at_start(),
+        });
+    }
+
+    if form_fill::is_active() {
+        let transcript = transform(app, transcript).await;
+        let tag = Some(snippets::SNIPPET_HISTORY_TAG.to_string());
+        let entry = history::add_dictation(app, transcript, tag)?;
+        form_fill::field_dictated(app, entry.text.clone())?;
+        return Ok(RecordedTranscription {
+            entry,
+            requires_confirmation: false,
+            handled: true,
+            active_app: active_app::at_start(),
+        });
+    }
+
+    let (transcript, tag, suggestions, plugged) =
+        match snippets::find_triggered(app, &transcript.text) {
+            Some(snippet) => {
+                let fill_in = snippets::request_fill_ins(app, &snippet);
+                let transcript = snippet_transcript(transcript, &snippet, fill_in);
+                let tag = Some(snippets::SNIPPET_HISTORY_TAG.to_string());
+                let fields = !fill_in && form_fill::has_fields(&transcript.text);
+                if fields {
+                    form_fill::start(app, &transcript.text)?;
+                }
+                if fill_in || fields {
+                    // Inserted by fill_snippet once the user has provided the values,
+                    // or field by field as they are dictated
+                    let entry = history::add_dictation(app, transcript, tag)?;
+                    return Ok(RecordedTranscription {
+                        entry,
+                        requires_confirmation: false,
+                        handled: true,
+                        active_app: active_app::at_start(),
+                    });
+                }
+                (transcript, tag, Vec::new(), PluginOutcome::default())
+            }
+            None => {
+                let (transcript, suggestions, plugged) = dictation_text(app, transcript).await;
+                (transcript, tag, suggestions, plugged)
+            }
+        };
+    let entry = history::add_dictation(app, transcript, tag)?;
+    let entry = grammar::attach(app, entry, suggestions)?;
+    let entry = plugins::attach(app, entry, plugged.metadata)?;
+    let recorded = if let Some(veto) = plugged.veto {
+        let _ = app.emit("paste-vetoed", &veto);
+        RecordedTranscription {
+            entry,
+            requires_confirmation: false,
+            handled: true,
+            active_app: active_app::at_start(),
+        }
+    } else if scratchpad::is_target(app) {
+        scratchpad::append(app, entry.text.clone())?;
+        RecordedTranscription {
+            entry,
+            requires_confirmation: false,
+            handled: true,
+            active_app: active_app::at_start(),
+        }
+    } else if command_output::is_target(app) {
+        command_output::send(app, &entry);
+        RecordedTranscription {
+            entry,
+            requires_confirmation: false,
+            handled: true,
+            active_app: active_app::at_start(),
+        }
+    } else {
+        if let Some(target) = &target_app {
+            target_app::activate(target)?;
+        }
+        let threshold = settings::current(app).low_confidence_threshold;
+        if send {
+            auto_send::send_after_paste(entry.id);
+        }
+        // Hearing the transcript only helps if the paste waits for a go-ahead
+        let read_back = speech::read_back(app, &entry.text);
+        let confirm_paste = app_profiles::current(app).is_some_and(|profile| profile.confirm_paste);
+        let latex_preview = latex::preview(app, &entry.text);
+        RecordedTranscription {
+            requires_confirmation: read_back
+                || confirm_paste
+                || latex_preview
+                || requires_confirmation(entry.confidence, threshold),
+            handled: false,
+            active_app: active_app::at_start(),
+            entry,
+        }
+    };
+    let _ = app.emit("transcription-recorded", &recorded);
+    Ok(recorded)
+}
+
+#[tauri::command]
+pub async fn set_low_confidence_threshold(
+    app: AppHandle,
+    threshold: Option<f32>,
+) -> Result<(), String> {
+    if threshold.is_some_and(|value| !(0.0..=1.0).contains(&value)) {
+        return Err(i18n::t("error.confidence_range"));
+    }
+    settings::update(&app, |settings| {
+        settings.low_confidence_threshold = threshold
+    })?;
+    Ok(())
+}
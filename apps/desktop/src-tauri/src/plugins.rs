@@ -0,0 +1,417 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use wasmtime::{Config, Engine, Instance, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use crate::history::{self, HistoryEntry};
+use crate::transcription::Transcript;
+use crate::{active_app, i18n, settings};
+
+const MANIFEST: &str = "manifest.json";
+const MODULE: &str = "plugin.wasm";
+/// Roughly a few hundred milliseconds of work per dictation
+const FUEL_PER_CALL: u64 = 500_000_000;
+const MAX_MEMORY_BYTES: usize = 32 * 1024 * 1024;
+const MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Capability {
+    /// Replace the transcript text
+    Transform,
+    /// Keep the transcript from being pasted
+    Veto,
+    /// Attach key-value pairs to the history entry
+    Metadata,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginManifest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub version: String,
+    pub capabilities: Vec<Capability>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInfo {
+    /// Directory name, what enable_plugin takes
+    pub id: String,
+    pub manifest: Option<PluginManifest>,
+    pub enabled: bool,
+    /// Why the plugin can't be loaded
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PluginInput<'a> {
+    text: &'a str,
+    language: Option<&'a str>,
+    app: Option<&'a str>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct PluginOutput {
+    text: Option<String>,
+    veto: Option<String>,
+    metadata: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Veto {
+    pub plugin: String,
+    pub reason: String,
+}
+
+/// What the enabled plugins made of a transcript
+#[derive(Debug, Clone, Default)]
+pub struct PluginOutcome {
+    /// Replacement text, after all transforming plugins ran
+    pub text: Option<String>,
+    /// The first plugin that vetoed pasting
+    pub veto: Option<Veto>,
+    pub metadata: BTreeMap<String, String>,
+}
+
+struct LoadedPlugin {
+    id: String,
+    manifest: PluginManifest,
+    module: Module,
+}
+
+/// Transcript processors installed by the user as WebAssembly modules. Each plugin
+/// is a directory in `plugins/` of the config dir holding a `manifest.json` and a
+/// `plugin.wasm`. The module may not import anything, so it has no access to files,
+/// the network or the clock, and it must export:
+///
+/// - `memory`
+/// - `alloc(len: i32) -> i32`, returning a buffer for the input
+/// - `process(ptr: i32, len: i32) -> i64`, taking the input JSON and returning
+///   the output JSON's pointer in the high and its length in the low 32 bits
+///
+/// The input is `{"text", "language", "app"}`; the output may set `text` to
+/// replace the transcript, `veto` to a reason for not pasting it, and `metadata`
+/// to string pairs stored with the history entry. Each field is only honored when
+/// the manifest declares the matching capability.
+pub struct PluginManager {
+    engine: Engine,
+    dir: PathBuf,
+    plugins: Vec<LoadedPlugin>,
+    /// Plugin directories that failed to load, with the reason
+    failed: Vec<(String, String)>,
+}
+
+fn engine() -> Engine {
+    let mut config = Config::new();
+    config.consume_fuel(true);
+    Engine::new(&config).unwrap_or_default()
+}
+
+fn load(engine: &Engine, dir: &Path) -> Result<(PluginManifest, Module), String> {
+    let manifest = fs::read_to_string(dir.join(MANIFEST))
+        .map_err(|e| format!("Can't read {MANIFEST}: {e}"))?;
+    let manifest: PluginManifest =
+        serde_json::from_str(&manifest).map_err(|e| format!("Invalid {MANIFEST}: {e}"))?;
+    let module = Module::from_file(engine, dir.join(MODULE))
+        .map_err(|e| format!("Can't load {MODULE}: {e:#}"))?;
+    if let Some(import) = module.imports().next() {
+        return Err(format!(
+            "Imports {}::{}, but plugins get no host functions",
+            import.module(),
+            import.name()
+        ));
+    }
+    Ok((manifest, module))
+}
+
+impl PluginManager {
+    pub fn new(config_dir: PathBuf) -> Self {
+        let mut manager = Self {
+            engine: engine(),
+            dir: config_dir.join("plugins"),
+            plugins: Vec::new(),
+            failed: Vec::new(),
+        };
+        manager.scan();
+        manager
+    }
+
+    /// Load every plugin directory again, picking up installed and removed ones
+    pub fn scan(&mut self) {
+        self.plugins.clear();
+        self.failed.clear();
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let mut dirs: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+        dirs.sort();
+        for dir in dirs {
+            let Some(id) = dir.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            match load(&self.engine, &dir) {
+                Ok((manifest, module)) => self.plugins.push(LoadedPlugin {
+                    id: id.to_string(),
+                    manifest,
+                    module,
+                }),
+                Err(e) => {
+                    eprintln!("[voiceflow] Plugin {id} not loaded: {e}");
+                    self.failed.push((id.to_string(), e));
+                }
+            }
+        }
+    }
+
+    pub fn list(&self, enabled: &[String]) -> Vec<PluginInfo> {
+        let loaded = self.plugins.iter().map(|plugin| PluginInfo {
+            id: plugin.id.clone(),
+            manifest: Some(plugin.manifest.clone()),
+            enabled: enabled.contains(&plugin.id),
+            error: None,
+        });
+        let failed = self.failed.iter().map(|(id, error)| PluginInfo {
+            id: id.clone(),
+            manifest: None,
+            enabled: false,
+            error: Some(error.clone()),
+        });
+        let mut plugins: Vec<PluginInfo> = loaded.chain(failed).collect();
+        plugins.sort_by(|a, b| a.id.cmp(&b.id));
+        plugins
+    }
+
+    fn is_loaded(&self, id: &str) -> bool {
+        self.plugins.iter().any(|plugin| plugin.id == id)
+    }
+
+    /// Run the enabled plugins in directory order, each seeing the text the
+    /// previous one produced. A plugin that fails is skipped.
+    fn run(
+        &self,
+        enabled: &[String],
+        text: &str,
+        language: Option<&str>,
+        app: Option<&str>,
+    ) -> PluginOutcome {
+        let mut outcome = PluginOutcome::default();
+        for plugin in self
+            .plugins
+            .iter()
+            .filter(|plugin| enabled.contains(&plugin.id))
+        {
+            let input = PluginInput {
+                text: outcome.text.as_deref().unwrap_or(text),
+                language,
+                app,
+            };
+            let output = match call(&self.engine, &plugin.module, &input) {
+                Ok(output) => output,
+                Err(e) => {
+                    eprintln!("[voiceflow] Plugin {} failed: {e}", plugin.id);
+                    continue;
+                }
+            };
+            let allows = |capability| plugin.manifest.capabilities.contains(&capability);
+            if let Some(text) = output.text.filter(|_| allows(Capability::Transform)) {
+                outcome.text = Some(text);
+            }
+            if outcome.veto.is_none() && allows(Capability::Veto) {
+                outcome.veto = output.veto.map(|reason| Veto {
+                    plugin: plugin.manifest.name.clone(),
+                    reason,
+                });
+            }
+            if allows(Capability::Metadata) {
+                outcome.metadata.extend(output.metadata);
+            }
+        }
+        outcome
+    }
+}
+
+/// Run one plugin in a fresh, fuel- and memory-limited instance
+fn call(engine: &Engine, module: &Module, input: &PluginInput) -> Result<PluginOutput, String> {
+    let limits = StoreLimitsBuilder::new()
+        .memory_size(MAX_MEMORY_BYTES)
+        .instances(1)
+        .build();
+    let mut store: Store<StoreLimits> = Store::new(engine, limits);
+    store.limiter(|limits| limits);
+    store.set_fuel(FUEL_PER_CALL).map_err(|e| e.to_string())?;
+
+    let instance = Instance::new(&mut store, module, &[]).map_err(|e| format!("{e:#}"))?;
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .ok_or("No exported memory")?;
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&mut store, "alloc")
+        .map_err(|e| format!("{e:#}"))?;
+    let process = instance
+        .get_typed_func::<(i32, i32), i64>(&mut store, "process")
+        .map_err(|e| format!("{e:#}"))?;
+
+    let input = serde_json::to_vec(input).map_err(|e| e.to_string())?;
+    let len = i32::try_from(input.len()).map_err(|e| e.to_string())?;
+    let ptr = alloc.call(&mut store, len).map_err(|e| format!("{e:#}"))?;
+    memory
+        .write(&mut store, ptr as u32 as usize, &input)
+        .map_err(|e| e.to_string())?;
+    let packed = process
+        .call(&mut store, (ptr, len))
+        .map_err(|e| format!("{e:#}"))? as u64;
+
+    let (output_ptr, output_len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+    if output_len > MAX_OUTPUT_BYTES {
+        return Err(format!("Output of {output_len} bytes is too large"));
+    }
+    let mut output = vec![0; output_len];
+    memory
+        .read(&store, output_ptr, &mut output)
+        .map_err(|e| e.to_string())?;
+    serde_json::from_slice(&output).map_err(|e| format!("Invalid output: {e}"))
+}
+
+/// Pass a dictation through the enabled plugins
+pub async fn run(app: &AppHandle, transcript: &Transcript) -> PluginOutcome {
+    let enabled = settings::current(app).enabled_plugins;
+    if enabled.is_empty() {
+        return PluginOutcome::default();
+    }
+    let app = app.clone();
+    let text = transcript.text.clone();
+    let language = transcript.language.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let frontmost = active_app::frontmost_app().map(|frontmost| frontmost.name);
+        let state = app.state::<Mutex<PluginManager>>();
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        Ok::<_, String>(manager.run(&enabled, &text, language.as_deref(), frontmost.as_deref()))
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|result| result);
+    result.unwrap_or_else(|e| {
+        eprintln!("[voiceflow] Plugins failed: {e}");
+        PluginOutcome::default()
+    })
+}
+
+/// Store the metadata plugins added with the entry
+pub fn attach(
+    app: &AppHandle,
+    mut entry: HistoryEntry,
+    metadata: BTreeMap<String, String>,
+) -> Result<HistoryEntry, String> {
+    if metadata.is_empty() {
+        return Ok(entry);
+    }
+    if entry.id != 0 {
+        let stored = metadata.clone();
+        history::update_entry(app, entry.id, |entry| {
+            entry.metadata.extend(stored);
+            Ok(())
+        })?;
+    }
+    entry.metadata.extend(metadata);
+    Ok(entry)
+}
+
+#[tauri::command]
+pub async fn list_plugins(app: AppHandle) -> Result<Vec<PluginInfo>, String> {
+    let enabled = settings::current(&app).enabled_plugins;
+    let state = app.state::<Mutex<PluginManager>>();
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.scan();
+    Ok(manager.list(&enabled))
+}
+
+#[tauri::command]
+pub async fn enable_plugin(
+    app: AppHandle,
+    id: String,
+    enabled: bool,
+) -> Result<Vec<PluginInfo>, String> {
+    let state = app.state::<Mutex<PluginManager>>();
+    let manager = state.lock().map_err(|e| e.to_string())?;
+    if enabled && !manager.is_loaded(&id) {
+        return Err(i18n::tf("error.unknown_plugin", &[("id", &id)]));
+    }
+    let updated = settings::update(&app, |settings| {
+        settings.enabled_plugins.retain(|plugin| plugin != &id);
+        if enabled {
+            settings.enabled_plugins.push(id.clone());
+        }
+    })?;
+    Ok(manager.list(&updated.enabled_plugins))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Answers every call with a fixed output placed at offset 1024
+    const PLUGIN: &str = r#"
+        (module
+            (memory (export "memory") 1)
+            (data (i32.const 1024) "{\"text\":\"plugged\",\"veto\":\"private\",\"metadata\":{\"k\":\"v\"}}")
+            (func (export "alloc") (param i32) (result i32) i32.const 0)
+            (func (export "process") (param i32 i32) (result i64)
+                i64.const 4398046511160))
+    "#;
+
+    fn install(dir: &Path, id: &str, capabilities: &str, wat: &str) {
+        let plugin = dir.join("plugins").join(id);
+        fs::create_dir_all(&plugin).unwrap();
+        let manifest = format!(r#"{{"name": "{id}", "capabilities": {capabilities}}}"#);
+        fs::write(plugin.join(MANIFEST), manifest).unwrap();
+        fs::write(plugin.join(MODULE), wat::parse_str(wat).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn runs_plugins_within_their_capabilities() {
+        let dir = std::env::temp_dir().join(format!(
+            "voiceflow-plugins-test-{}",
+            crate::session::now_ms()
+        ));
+        install(&dir, "a-transform", r#"["transform", "metadata"]"#, PLUGIN);
+        install(&dir, "b-veto", r#"["veto"]"#, PLUGIN);
+        install(
+            &dir,
+            "c-imports",
+            "[]",
+            r#"(module (import "env" "now" (func)))"#,
+        );
+        let manager = PluginManager::new(dir.clone());
+        let plugins = manager.list(&[]);
+        assert_eq!(plugins.len(), 3);
+        assert!(plugins[2].error.is_some());
+
+        let enabled = ["a-transform".to_string()];
+        let outcome = manager.run(&enabled, "hello", None, None);
+        assert_eq!(outcome.text.as_deref(), Some("plugged"));
+        assert!(outcome.veto.is_none());
+        assert_eq!(outcome.metadata.get("k").map(String::as_str), Some("v"));
+
+        let enabled = ["b-veto".to_string()];
+        let outcome = manager.run(&enabled, "hello", None, None);
+        assert!(outcome.text.is_none());
+        assert_eq!(outcome.veto.unwrap().reason, "private");
+        assert!(outcome.metadata.is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::settings::{SettingOverride, SettingsManager};
+
+/// Read-only policy files, checked in order, that admins deploy to lock settings
+#[cfg(target_os = "macos")]
+const POLICY_PATHS: &[&str] = &[
+    "/Library/Managed Preferences/com.voiceflow.app.json",
+    "/Library/Application Support/VoiceFlow/policy.json",
+];
+#[cfg(target_os = "windows")]
+const POLICY_PATHS: &[&str] = &["C:\\ProgramData\\VoiceFlow\\policy.json"];
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const POLICY_PATHS: &[&str] = &["/etc/voiceflow/policy.json"];
+
+/// `{"locked": {"postProcessing.enabled": false, "lowConfidenceThreshold": 0.5}}`
+#[derive(Debug, Default, Deserialize)]
+struct PolicyFile {
+    #[serde(default)]
+    locked: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockedSetting {
+    /// Dotted settings key, e.g. "postProcessing.enabled"
+    pub key: String,
+    pub value: Value,
+    /// Policy file that locked it
+    pub source: String,
+}
+
+impl LockedSetting {
+    pub fn to_override(&self) -> SettingOverride {
+        SettingOverride {
+            source: format!("policy:{}", self.key),
+            path: self.key.split('.').map(str::to_string).collect(),
+            candidates: vec![self.value.clone()],
+        }
+    }
+}
+
+fn read_policy(path: &Path) -> Option<Vec<LockedSetting>> {
+    let content = fs::read_to_string(path).ok()?;
+    let policy: PolicyFile = match serde_json::from_str(&content) {
+        Ok(policy) => policy,
+        Err(e) => {
+            // Logged so admins notice; a broken file locks nothing
+            eprintln!(
+                "[voiceflow] Ignoring unreadable policy {}: {e}",
+                path.display()
+            );
+            return None;
+        }
+    };
+    let source = path.display().to_string();
+    Some(
+        policy
+            .locked
+            .into_iter()
+            .map(|(key, value)| LockedSetting {
+                key,
+                value,
+                source: source.clone(),
+            })
+            .collect(),
+    )
+}
+
+/// Settings locked by the first policy file present on this machine
+pub fn load() -> Vec<LockedSetting> {
+    POLICY_PATHS
+        .iter()
+        .find_map(|path| read_policy(Path::new(path)))
+        .unwrap_or_default()
+}
+
+/// Settings the UI should show as managed and read-only
+#[tauri::command]
+pub async fn get_locked_settings(app: AppHandle) -> Result<Vec<LockedSetting>, String> {
+    let state = app.state::<Mutex<SettingsManager>>();
+    let manager = state.lock().map_err(|e| e.to_string())?;
+    Ok(manager.locked())
+}
@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+use crate::browser::BrowserTab;
+use crate::{active_app, i18n, prompts, providers, settings};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PostProcessingSettings {
+    pub enabled: bool,
+    /// OpenAI-compatible chat completions endpoint, local by default (Ollama)
+    pub endpoint: String,
+    pub model: String,
+    /// Template used when the triggering shortcut doesn't select one
+    pub default_template: String,
+}
+
+impl Default for PostProcessingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "http://127.0.0.1:11434/v1/chat/completions".to_string(),
+            model: "llama3.2".to_string(),
+            default_template: prompts::DEFAULT_TEMPLATE.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatMessage {
+    content: String,
+}
+
+/// Context available to prompt templates
+#[derive(Debug, Clone, Default)]
+pub struct PromptContext {
+    pub language: Option<String>,
+    pub selection: Option<String>,
+    /// Active browser tab, when browser context is enabled
+    pub tab: Option<BrowserTab>,
+}
+
+pub fn complete(
+    settings: &PostProcessingSettings,
+    system: &str,
+    text: &str,
+) -> Result<String, String> {
+    let body = serde_json::json!({
+        "model": settings.model,
+        "temperature": 0,
+        "messages": [
+            { "role": "system", "content": system },
+            { "role": "user", "content": text },
+        ],
+    });
+
+    let response: ChatResponse = ureq::post(&settings.endpoint)
+        .send_json(&body)
+        .map_err(|e| format!("Post-processing request failed: {e}"))?
+        .body_mut()
+        .read_json()
+        .map_err(|e| format!("Invalid post-processing response: {e}"))?;
+
+    response
+        .choices
+        .into_iter()
+        .next()
+        .map(|choice| choice.message.content.trim().to_string())
+        .filter(|content| !content.is_empty())
+        .ok_or_else(|| "Post-processing returned no text".to_string())
+}
+
+/// Run the LLM cleanup stage with the given (or default) template.
+/// Returns `None` when post-processing is disabled or the template is empty.
+pub fn process(
+    app: &AppHandle,
+    text: &str,
+    template_name: Option<&str>,
+    context: &PromptContext,
+) -> Result<Option<String>, String> {
+    process_with(
+        app,
+        &settings::current(app).post_processing,
+        text,
+        template_name,
+        context,
+    )
+}
+
+/// Same as `process` with explicit settings, e.g. another profile's
+pub fn process_with(
+    app: &AppHandle,
+    settings: &PostProcessingSettings,
+    text: &str,
+    template_name: Option<&str>,
+    context: &PromptContext,
+) -> Result<Option<String>, String> {
+    if !settings.enabled {
+        return Ok(None);
+    }
+
+    let name = template_name.unwrap_or(&settings.default_template);
+    let template = prompts::get_template(app, name)
+        .ok_or_else(|| i18n::tf("error.template_not_found", &[("name", name)]))?;
+    if template.template.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let active = active_app::frontmost_app();
+    let variables = HashMap::from([
+        ("app", active.map(|app| app.name).unwrap_or_default()),
+        (
+            "language",
+            context.language.clone().unwrap_or_else(|| "en".to_string()),
+        ),
+        ("selection", context.selection.clone().unwrap_or_default()),
+        (
+            "url",
+            context
+                .tab
+                .as_ref()
+                .map(|tab| tab.url.clone())
+                .unwrap_or_default(),
+        ),
+        (
+            "page",
+            context
+                .tab
+                .as_ref()
+                .map(|tab| tab.title.clone())
+                .unwrap_or_default(),
+        ),
+        ("transcript", text.to_string()),
+    ]);
+    let system = prompts::render(&template.template, &variables);
+    if let Some(result) = providers::process(text, &system, context.language.as_deref()) {
+        return result.map(Some);
+    }
+    complete(settings, &system, text).map(Some)
+}
+
+#[tauri::command]
+pub async fn set_post_processing(
+    app: AppHandle,
+    post_processing: PostProcessingSettings,
+) -> Result<(), String> {
+    settings::update(&app, |settings| settings.post_processing = post_processing)?;
+    Ok(())
+}
@@ -0,0 +1,233 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::settings::SettingsManager;
+use crate::voice_commands::VoiceCommandManager;
+use crate::{i18n, register_shortcuts, tray, wake_word};
+
+/// Profile whose files live directly in the config dir, as before profiles existed
+pub const DEFAULT_PROFILE: &str = "Default";
+pub const MENU_ID_PREFIX: &str = "profile:";
+
+/// Files that make up a profile, copied when a new profile is created
+pub const PROFILE_FILES: &[&str] = &["settings.json", "voice_commands.json"];
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ProfileState {
+    active: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileList {
+    pub profiles: Vec<String>,
+    pub active: String,
+}
+
+pub struct ProfileManager {
+    config_dir: PathBuf,
+    active: String,
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() || trimmed != name {
+        return Err(i18n::t("error.profile_name_empty"));
+    }
+    if name.starts_with('.') || name.contains(['/', '\\', ':']) {
+        return Err(i18n::tf("error.profile_name_invalid", &[("name", name)]));
+    }
+    Ok(())
+}
+
+impl ProfileManager {
+    pub fn new(config_dir: PathBuf) -> Self {
+        let state = Self::load_state(&config_dir.join("profiles.json")).unwrap_or_default();
+        let mut manager = Self {
+            config_dir,
+            active: DEFAULT_PROFILE.to_string(),
+        };
+        if let Some(active) = state.active {
+            if manager.exists(&active) {
+                manager.active = active;
+            }
+        }
+        manager
+    }
+
+    fn load_state(path: &PathBuf) -> Option<ProfileState> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn save_state(&self) -> Result<(), String> {
+        fs::create_dir_all(&self.config_dir).map_err(|e| e.to_string())?;
+        let state = ProfileState {
+            active: Some(self.active.clone()),
+        };
+        let content = serde_json::to_string_pretty(&state).map_err(|e| e.to_string())?;
+        fs::write(self.config_dir.join("profiles.json"), content).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    pub fn profile_dir(&self, name: &str) -> PathBuf {
+        if name == DEFAULT_PROFILE {
+            self.config_dir.clone()
+        } else {
+            self.config_dir.join("profiles").join(name)
+        }
+    }
+
+    pub fn active_dir(&self) -> PathBuf {
+        self.profile_dir(&self.active)
+    }
+
+    fn exists(&self, name: &str) -> bool {
+        name == DEFAULT_PROFILE || self.profile_dir(name).is_dir()
+    }
+
+    pub fn list(&self) -> ProfileList {
+        let mut profiles: Vec<String> = fs::read_dir(self.config_dir.join("profiles"))
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.path().is_dir())
+                    .filter_map(|entry| entry.file_name().into_string().ok())
+                    .filter(|name| validate_name(name).is_ok() && name != DEFAULT_PROFILE)
+                    .collect()
+            })
+            .unwrap_or_default();
+        profiles.sort();
+        profiles.insert(0, DEFAULT_PROFILE.to_string());
+        ProfileList {
+            profiles,
+            active: self.active.clone(),
+        }
+    }
+
+    fn create(&self, name: &str) -> Result<(), String> {
+        validate_name(name)?;
+        if self.exists(name) {
+            return Err(i18n::tf("error.profile_exists", &[("name", name)]));
+        }
+        let dir = self.profile_dir(name);
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create profile: {e}"))?;
+
+        // New profiles start as a copy of the active one
+        let source = self.active_dir();
+        for file in PROFILE_FILES {
+            let from = source.join(file);
+            if from.is_file() {
+                fs::copy(&from, dir.join(file))
+                    .map_err(|e| format!("Failed to copy {file}: {e}"))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn delete(&self, name: &str) -> Result<(), String> {
+        if name == DEFAULT_PROFILE {
+            return Err(i18n::t("error.default_profile_delete"));
+        }
+        if name == self.active {
+            return Err(i18n::t("error.delete_active_profile"));
+        }
+        validate_name(name)?;
+        if !self.exists(name) {
+            return Err(i18n::tf("error.profile_not_found", &[("name", name)]));
+        }
+        fs::remove_dir_all(self.profile_dir(name))
+            .map_err(|e| format!("Failed to delete profile: {e}"))
+    }
+
+    fn set_active(&mut self, name: &str) -> Result<PathBuf, String> {
+        if !self.exists(name) {
+            return Err(i18n::tf("error.profile_not_found", &[("name", name)]));
+        }
+        self.active = name.to_string();
+        self.save_state()?;
+        Ok(self.active_dir())
+    }
+}
+
+pub fn list(app: &AppHandle) -> ProfileList {
+    let state = app.state::<Mutex<ProfileManager>>();
+    let list = state.lock().map(|manager| manager.list());
+    list.unwrap_or_else(|_| ProfileList {
+        profiles: vec![DEFAULT_PROFILE.to_string()],
+        active: DEFAULT_PROFILE.to_string(),
+    })
+}
+
+/// Load another profile's config files and apply them to the running app
+pub fn switch(app: &AppHandle, name: &str) -> Result<ProfileList, String> {
+    {
+        let state = app.state::<Mutex<ProfileManager>>();
+        let mut manager = state.lock().map_err(|e| e.to_string())?;
+        manager.set_active(name)?;
+    }
+    reload(app)?;
+
+    let profiles = list(app);
+    let _ = app.emit("profile-changed", &profiles);
+    Ok(profiles)
+}
+
+pub fn active_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let state = app.state::<Mutex<ProfileManager>>();
+    let manager = state.lock().map_err(|e| e.to_string())?;
+    Ok(manager.active_dir())
+}
+
+/// Re-read the active profile's config files, e.g. after they changed on disk
+pub fn reload(app: &AppHandle) -> Result<(), String> {
+    let dir = active_dir(app)?;
+    *app.state::<Mutex<SettingsManager>>()
+        .lock()
+        .map_err(|e| e.to_string())? = SettingsManager::new(dir.clone());
+    *app.state::<Mutex<VoiceCommandManager>>()
+        .lock()
+        .map_err(|e| e.to_string())? = VoiceCommandManager::new(dir);
+
+    register_shortcuts(app);
+    i18n::init(app);
+    tray::refresh_menu(app);
+    // A disabled wake word is noticed by the running listener itself
+    wake_word::start_listener(app.clone());
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_profiles(app: AppHandle) -> ProfileList {
+    list(&app)
+}
+
+#[tauri::command]
+pub async fn switch_profile(app: AppHandle, name: String) -> Result<ProfileList, String> {
+    switch(&app, &name)
+}
+
+#[tauri::command]
+pub async fn create_profile(app: AppHandle, name: String) -> Result<ProfileList, String> {
+    {
+        let state = app.state::<Mutex<ProfileManager>>();
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.create(&name)?;
+    }
+    tray::refresh_menu(&app);
+    Ok(list(&app))
+}
+
+#[tauri::command]
+pub async fn delete_profile(app: AppHandle, name: String) -> Result<ProfileList, String> {
+    {
+        let state = app.state::<Mutex<ProfileManager>>();
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.delete(&name)?;
+    }
+    tray::refresh_menu(&app);
+    Ok(list(&app))
+}
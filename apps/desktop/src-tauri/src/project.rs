@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::active_app::ActiveApp;
+use crate::history::{HistoryEntry, HistoryStore};
+use crate::{auth, demo, i18n, settings};
+
+/// Plain-text file in the app data dir holding the current project, so time trackers
+/// and scripts can set it without going through the app
+const PROJECT_FILE: &str = "current_project";
+
+/// Tags dictations with a project while the frontmost window title contains some text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectRule {
+    /// Matched case-insensitively against the focused window title
+    pub title_contains: String,
+    pub project: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectStats {
+    pub project: String,
+    pub entries: usize,
+    pub words: usize,
+}
+
+fn project_file(app: &AppHandle) -> Option<PathBuf> {
+    demo::data_dir(app).ok().map(|dir| dir.join(PROJECT_FILE))
+}
+
+/// Project set explicitly through the file or set_current_project
+fn explicit(app: &AppHandle) -> Option<String> {
+    let content = fs::read_to_string(project_file(app)?).ok()?;
+    let project = content.trim();
+    (!project.is_empty()).then(|| project.to_string())
+}
+
+/// An explicitly set project wins; otherwise the first rule matching the window title
+fn resolve(
+    explicit: Option<String>,
+    rules: &[ProjectRule],
+    window_title: Option<&str>,
+) -> Option<String> {
+    explicit.or_else(|| {
+        let title = window_title?.to_lowercase();
+        rules
+            .iter()
+            .find(|rule| {
+                !rule.title_contains.is_empty()
+                    && title.contains(&rule.title_contains.to_lowercase())
+            })
+            .map(|rule| rule.project.clone())
+    })
+}
+
+pub fn current(app: &AppHandle, frontmost: Option<&ActiveApp>) -> Option<String> {
+    let rules = settings::current(app).project_rules;
+    resolve(
+        explicit(app),
+        &rules,
+        frontmost.map(|active| active.window_title.as_str()),
+    )
+}
+
+/// Word and entry counts per project, most words first
+fn stats(entries: &[HistoryEntry]) -> Vec<ProjectStats> {
+    let mut stats: Vec<ProjectStats> = Vec::new();
+    for entry in entries {
+        let Some(project) = &entry.project else {
+            continue;
+        };
+        let words = entry.text.split_whitespace().count();
+        match stats.iter_mut().find(|stats| stats.project == *project) {
+            Some(stats) => {
+                stats.entries += 1;
+                stats.words += words;
+            }
+            None => stats.push(ProjectStats {
+                project: project.clone(),
+                entries: 1,
+                words,
+            }),
+        }
+    }
+    stats.sort_by(|a, b| b.words.cmp(&a.words).then(a.project.cmp(&b.project)));
+    stats
+}
+
+#[tauri::command]
+pub async fn get_current_project(app: AppHandle) -> Option<String> {
+    explicit(&app)
+}
+
+/// Set or clear (None) the project new dictations are tagged with
+#[tauri::command]
+pub async fn set_current_project(app: AppHandle, project: Option<String>) -> Result<(), String> {
+    let path = project_file(&app).ok_or_else(|| i18n::t("error.data_dir_unavailable"))?;
+    let project = project
+        .map(|project| project.trim().to_string())
+        .filter(|project| !project.is_empty());
+    match &project {
+        Some(project) => {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            fs::write(&path, project).map_err(|e| e.to_string())?;
+        }
+        None => match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.to_string()),
+            _ => {}
+        },
+    }
+    let _ = app.emit("project-changed", &project);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_project_rules(app: AppHandle, rules: Vec<ProjectRule>) -> Result<(), String> {
+    settings::update(&app, |settings| settings.project_rules = rules)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_project_stats(app: AppHandle) -> Result<Vec<ProjectStats>, String> {
+    let state = app.state::<Mutex<HistoryStore>>();
+    let store = state.lock().map_err(|e| e.to_string())?;
+    Ok(stats(store.all()))
+}
+
+/// Write every transcript tagged with the project to a text file, oldest first
+#[tauri::command]
+pub async fn export_project(app: AppHandle, project: String, path: PathBuf) -> Result<(), String> {
+    auth::unlock(&app, "auth.export").await?;
+    let text = {
+        let state = app.state::<Mutex<HistoryStore>>();
+        let store = state.lock().map_err(|e| e.to_string())?;
+        store
+            .all()
+            .iter()
+            .filter(|entry| entry.project.as_deref() == Some(project.as_str()))
+            .map(HistoryEntry::formatted_text)
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    };
+    fs::write(&path, text).map_err(|e| i18n::tf("error.export", &[("error", &e.to_string())]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explicit_project_wins_over_title_rules() {
+        let rules = vec![ProjectRule {
+            title_contains: "ACME".to_string(),
+            project: "Acme redesign".to_string(),
+        }];
+        assert_eq!(
+            resolve(None, &rules, Some("Figma – acme homepage")),
+            Some("Acme redesign".to_string())
+        );
+        assert_eq!(resolve(None, &rules, Some("Inbox")), None);
+        assert_eq!(
+            resolve(Some("Internal".to_string()), &rules, Some("acme")),
+            Some("Internal".to_string())
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::i18n;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTemplate {
+    pub name: String,
+    /// System prompt for the cleanup model; supports {app}, {language}, {selection}, {url}, {page},
+    /// {transcript}.
+    /// An empty template skips post-processing entirely.
+    pub template: String,
+}
+
+pub const DEFAULT_TEMPLATE: &str = "cleanup";
+
+fn builtin_templates() -> Vec<PromptTemplate> {
+    vec![
+        PromptTemplate {
+            name: DEFAULT_TEMPLATE.to_string(),
+            template: "You clean up dictated text typed into {app}. Fix punctuation, \
+                capitalization and obvious recognition errors without changing the meaning \
+                or the language ({language}). Reply with the cleaned text only."
+                .to_string(),
+        },
+        PromptTemplate {
+            name: "formalize email".to_string(),
+            template: "Rewrite the dictated text as a polished, formal email body for {app}. \
+                Keep the language ({language}) and every fact. Reply with the email text only."
+                .to_string(),
+        },
+        PromptTemplate {
+            name: "latex math".to_string(),
+            template: "The dictated text goes into a LaTeX document in {app}. Write spoken \
+                math as LaTeX, e.g. \"integral from zero to one of x squared dx\" as \
+                $\\int_0^1 x^2 \\, dx$, using $...$ inline and \\[...\\] for whole \
+                equations. Keep the prose in its language ({language}) and escape characters \
+                LaTeX treats specially. Reply with the LaTeX only."
+                .to_string(),
+        },
+        PromptTemplate {
+            name: "verbatim".to_string(),
+            template: String::new(),
+        },
+    ]
+}
+
+pub struct PromptTemplateManager {
+    templates: Vec<PromptTemplate>,
+    templates_path: PathBuf,
+}
+
+impl PromptTemplateManager {
+    pub fn new(config_dir: PathBuf) -> Self {
+        let templates_path = config_dir.join("prompt_templates.json");
+        let templates = Self::load_templates(&templates_path).unwrap_or_else(builtin_templates);
+        Self {
+            templates,
+            templates_path,
+        }
+    }
+
+    fn load_templates(path: &PathBuf) -> Option<Vec<PromptTemplate>> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn save_templates(&self) -> Result<(), String> {
+        if let Some(parent) = self.templates_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string_pretty(&self.templates).map_err(|e| e.to_string())?;
+        fs::write(&self.templates_path, content).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    pub fn list(&self) -> Vec<PromptTemplate> {
+        self.templates.clone()
+    }
+
+    pub fn get(&self, name: &str) -> Option<PromptTemplate> {
+        self.templates
+            .iter()
+            .find(|template| template.name == name)
+            .cloned()
+    }
+
+    pub fn save(&mut self, template: PromptTemplate) -> Result<(), String> {
+        match self
+            .templates
+            .iter_mut()
+            .find(|existing| existing.name == template.name)
+        {
+            Some(existing) => *existing = template,
+            None => self.templates.push(template),
+        }
+        self.save_templates()
+    }
+}
+
+/// Substitute `{name}` placeholders; unknown placeholders are left untouched
+pub fn render(template: &str, variables: &HashMap<&str, String>) -> String {
+    let mut rendered = template.to_string();
+    for (name, value) in variables {
+        rendered = rendered.replace(&format!("{{{name}}}"), value);
+    }
+    rendered
+}
+
+pub fn get_template(app: &AppHandle, name: &str) -> Option<PromptTemplate> {
+    let state = app.state::<Mutex<PromptTemplateManager>>();
+    let manager = state.lock().ok()?;
+    manager.get(name)
+}
+
+#[tauri::command]
+pub async fn list_prompt_templates(app: AppHandle) -> Result<Vec<PromptTemplate>, String> {
+    let state = app.state::<Mutex<PromptTemplateManager>>();
+    let manager = state.lock().map_err(|e| e.to_string())?;
+    Ok(manager.list())
+}
+
+#[tauri::command]
+pub async fn save_prompt_template(
+    app: AppHandle,
+    name: String,
+    template: String,
+) -> Result<(), String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(i18n::t("error.template_name_empty"));
+    }
+    let state = app.state::<Mutex<PromptTemplateManager>>();
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.save(PromptTemplate { name, template })
+}
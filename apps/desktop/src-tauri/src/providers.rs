@@ -0,0 +1,591 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::supervisor::{self, ServerStatus};
+use crate::transcription::{Transcript, TranscriptionOptions, SAMPLE_RATE};
+use crate::{i18n, settings};
+
+// Provider protocol
+//
+// A provider is an executable VoiceFlow spawns with the configured arguments. It reads
+// requests from stdin and writes responses to stdout, one JSON object per line; stderr
+// is copied to VoiceFlow's log.
+//
+//   request:  {"id": 1, "method": "transcribe", "params": {...}}
+//   response: {"id": 1, "result": ...} or {"id": 1, "error": {"message": "..."}}
+//
+// Methods:
+//   initialize   params {"protocolVersion": 1}, sent once after spawning. The result
+//                must echo {"protocolVersion": 1}.
+//   transcribe   params {"sampleRate": 16000, "audio": base64 of 16-bit little-endian
+//                mono PCM, "diarize", "wordTimestamps", "alternatives"}. The result is
+//                a transcript as the sidecar sends it: {"text", "segments", ...}.
+//   process      params {"text", "prompt", "language"}, where prompt is the rendered
+//                post-processing template. The result is {"text": "..."}.
+//   shutdown     no params and no response; the provider should exit. It is killed
+//                if it is still running after a second.
+//
+// Lines that aren't a response to the pending request are ignored, so providers can
+// print their own notifications.
+
+pub const PROTOCOL_VERSION: u32 = 1;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const INITIALIZE_TIMEOUT: Duration = Duration::from_secs(10);
+// Matches the sidecar's read timeout for long recordings
+const TRANSCRIBE_TIMEOUT: Duration = Duration::from_secs(300);
+const PROCESS_TIMEOUT: Duration = Duration::from_secs(60);
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProviderKind {
+    /// Replaces the sidecar for recordings transcribed from Rust
+    #[default]
+    Transcription,
+    /// Replaces the chat completions endpoint used for post-processing
+    PostProcessing,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ProviderConfig {
+    /// Unique, shown in the UI and the log
+    pub name: String,
+    pub kind: ProviderKind,
+    /// Executable path, or a name looked up on PATH
+    pub command: String,
+    pub args: Vec<String>,
+    /// The first enabled provider of each kind is used
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderInfo {
+    pub name: String,
+    pub kind: ProviderKind,
+    pub enabled: bool,
+    pub status: ServerStatus,
+    /// Automatic restarts since the app started
+    pub restarts: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderStatusEvent {
+    pub name: String,
+    pub status: ServerStatus,
+    pub restarts: u32,
+}
+
+struct ProviderProcess {
+    child: Child,
+    stdin: ChildStdin,
+    /// Lines from stdout, read on a separate thread so requests can time out
+    lines: Receiver<String>,
+    next_id: u64,
+}
+
+struct Provider {
+    config: ProviderConfig,
+    process: Option<Arc<Mutex<ProviderProcess>>>,
+    status: ServerStatus,
+    restarts: u32,
+    /// Restarts since the provider last stayed up, for the backoff
+    failed_restarts: u32,
+    /// Earliest time the next spawn may happen
+    retry_at: Option<Instant>,
+}
+
+static PROVIDERS: Mutex<Vec<Provider>> = Mutex::new(Vec::new());
+
+#[derive(Deserialize)]
+struct Response {
+    id: Option<u64>,
+    result: Option<Value>,
+    error: Option<ResponseError>,
+}
+
+#[derive(Deserialize)]
+struct ResponseError {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct ProcessResult {
+    text: String,
+}
+
+impl ProviderProcess {
+    fn spawn(config: &ProviderConfig) -> Result<Self, String> {
+        let mut child = Command::new(&config.command)
+            .args(&config.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to start provider {}: {e}", config.name))?;
+        let (Some(stdin), Some(stdout), Some(stderr)) =
+            (child.stdin.take(), child.stdout.take(), child.stderr.take())
+        else {
+            let _ = child.kill();
+            return Err(format!("Provider {} has no stdio", config.name));
+        };
+
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        let name = config.name.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                eprintln!("[voiceflow] [{name}] {line}");
+            }
+        });
+
+        let mut process = Self {
+            child,
+            stdin,
+            lines,
+            next_id: 0,
+        };
+        let initialized = process.call(
+            "initialize",
+            serde_json::json!({ "protocolVersion": PROTOCOL_VERSION }),
+            INITIALIZE_TIMEOUT,
+        );
+        let version = match initialized {
+            Ok(result) => result.get("protocolVersion").and_then(Value::as_u64),
+            Err(e) => {
+                process.kill();
+                return Err(e);
+            }
+        };
+        if version != Some(u64::from(PROTOCOL_VERSION)) {
+            process.kill();
+            return Err(format!(
+                "Provider {} doesn't speak protocol version {PROTOCOL_VERSION}",
+                config.name
+            ));
+        }
+        Ok(process)
+    }
+
+    /// Send a request and wait for its response. A provider that doesn't answer in
+    /// time is killed, since it may still answer later and confuse the next request.
+    fn call(&mut self, method: &str, params: Value, timeout: Duration) -> Result<Value, String> {
+        self.next_id += 1;
+        let id = self.next_id;
+        let request = serde_json::json!({ "id": id, "method": method, "params": params });
+        writeln!(self.stdin, "{request}")
+            .and_then(|_| self.stdin.flush())
+            .map_err(|e| format!("Failed to send {method} to provider: {e}"))?;
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let line = match self.lines.recv_timeout(remaining) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => {
+                    self.kill();
+                    return Err(format!("Provider didn't answer {method} in time"));
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(format!("Provider exited while handling {method}"));
+                }
+            };
+            let Ok(response) = serde_json::from_str::<Response>(&line) else {
+                continue;
+            };
+            if response.id != Some(id) {
+                continue;
+            }
+            if let Some(error) = response.error {
+                return Err(error.message);
+            }
+            return Ok(response.result.unwrap_or(Value::Null));
+        }
+    }
+
+    fn is_running(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    fn kill(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+
+    /// Ask the provider to exit, killing it if it doesn't
+    fn shutdown(&mut self) {
+        let _ = writeln!(
+            self.stdin,
+            "{}",
+            serde_json::json!({ "method": "shutdown" })
+        );
+        let _ = self.stdin.flush();
+        let deadline = Instant::now() + SHUTDOWN_GRACE;
+        while Instant::now() < deadline {
+            if !self.is_running() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        self.kill();
+    }
+}
+
+fn emit_status(app: &AppHandle, provider: &Provider) {
+    let event = ProviderStatusEvent {
+        name: provider.config.name.clone(),
+        status: provider.status,
+        restarts: provider.restarts,
+    };
+    let _ = app.emit("provider-status", event);
+}
+
+fn set_status(app: &AppHandle, provider: &mut Provider, status: ServerStatus) {
+    if provider.status != status {
+        provider.status = status;
+        emit_status(app, provider);
+    }
+}
+
+fn stop(provider: &mut Provider) {
+    if let Some(process) = provider.process.take() {
+        if let Ok(mut process) = process.lock() {
+            process.shutdown();
+        }
+    }
+}
+
+/// Bring the running providers in line with the registry in settings, stopping
+/// removed or changed ones and noticing ones that exited
+fn sync(app: &AppHandle, configs: &[ProviderConfig]) {
+    let Ok(mut providers) = PROVIDERS.lock() else {
+        return;
+    };
+    providers.retain_mut(|provider| {
+        let keep = configs.contains(&provider.config);
+        if !keep {
+            stop(provider);
+        }
+        keep
+    });
+    for config in configs {
+        if !providers.iter().any(|provider| &provider.config == config) {
+            providers.push(Provider {
+                config: config.clone(),
+                process: None,
+                status: ServerStatus::Stopped,
+                restarts: 0,
+                failed_restarts: 0,
+                retry_at: None,
+            });
+        }
+    }
+
+    for provider in providers.iter_mut() {
+        if !provider.config.enabled {
+            stop(provider);
+            set_status(app, provider, ServerStatus::Stopped);
+            continue;
+        }
+        let Some(process) = provider.process.clone() else {
+            continue;
+        };
+        // Busy with a request, so it is running
+        let Ok(mut process) = process.try_lock() else {
+            continue;
+        };
+        if process.is_running() {
+            provider.failed_restarts = 0;
+            continue;
+        }
+        drop(process);
+        eprintln!("[voiceflow] Provider {} exited", provider.config.name);
+        provider.process = None;
+        provider.retry_at = Some(Instant::now() + supervisor::backoff(provider.failed_restarts));
+        set_status(app, provider, ServerStatus::Crashed);
+    }
+}
+
+/// Spawn enabled providers that aren't running once their backoff has passed
+fn spawn_pending(app: &AppHandle) {
+    let pending: Vec<ProviderConfig> = {
+        let Ok(mut providers) = PROVIDERS.lock() else {
+            return;
+        };
+        let now = Instant::now();
+        providers
+            .iter_mut()
+            .filter(|provider| provider.config.enabled && provider.process.is_none())
+            .filter(|provider| provider.retry_at.is_none_or(|at| at <= now))
+            .map(|provider| {
+                let status = if provider.status == ServerStatus::Stopped {
+                    ServerStatus::Starting
+                } else {
+                    ServerStatus::Restarting
+                };
+                set_status(app, provider, status);
+                provider.config.clone()
+            })
+            .collect()
+    };
+
+    // Spawned without the lock, the handshake can take a while
+    for config in pending {
+        let spawned = ProviderProcess::spawn(&config);
+        let Ok(mut providers) = PROVIDERS.lock() else {
+            return;
+        };
+        let Some(provider) = providers.iter_mut().find(|p| p.config == config) else {
+            // Removed from settings in the meantime
+            if let Ok(mut process) = spawned {
+                process.shutdown();
+            }
+            continue;
+        };
+        if provider.status == ServerStatus::Restarting {
+            provider.restarts += 1;
+        }
+        match spawned {
+            Ok(process) => {
+                eprintln!("[voiceflow] Provider {} started", config.name);
+                provider.process = Some(Arc::new(Mutex::new(process)));
+                provider.retry_at = None;
+                set_status(app, provider, ServerStatus::Healthy);
+            }
+            Err(e) => {
+                eprintln!("[voiceflow] {e}");
+                provider.retry_at =
+                    Some(Instant::now() + supervisor::backoff(provider.failed_restarts));
+                provider.failed_restarts += 1;
+                set_status(app, provider, ServerStatus::Crashed);
+            }
+        }
+    }
+}
+
+/// Keep the providers from settings running, restarting them with backoff when they exit
+pub fn start(app: AppHandle) {
+    thread::spawn(move || loop {
+        sync(&app, &settings::current(&app).providers);
+        spawn_pending(&app);
+        thread::sleep(POLL_INTERVAL);
+    });
+}
+
+/// Stop every provider, e.g. when the app exits
+pub fn stop_all() {
+    if let Ok(mut providers) = PROVIDERS.lock() {
+        providers.iter_mut().for_each(stop);
+    }
+}
+
+/// Running process of the first enabled provider of `kind`. Err names a provider that
+/// is enabled but not running.
+fn active(kind: ProviderKind) -> Option<Result<Arc<Mutex<ProviderProcess>>, String>> {
+    let providers = PROVIDERS.lock().ok()?;
+    let provider = providers
+        .iter()
+        .find(|provider| provider.config.enabled && provider.config.kind == kind)?;
+    Some(
+        provider
+            .process
+            .clone()
+            .ok_or_else(|| provider.config.name.clone()),
+    )
+}
+
+fn call(
+    process: &Mutex<ProviderProcess>,
+    method: &str,
+    params: Value,
+    timeout: Duration,
+) -> Result<Value, String> {
+    let mut process = process.lock().map_err(|e| e.to_string())?;
+    process.call(method, params, timeout)
+}
+
+/// Transcribe with the active transcription provider, None when there is none so the
+/// sidecar is used
+pub fn transcribe(
+    samples: &[i16],
+    options: &TranscriptionOptions,
+) -> Option<Result<Transcript, String>> {
+    let process = match active(ProviderKind::Transcription)? {
+        Ok(process) => process,
+        Err(name) => {
+            eprintln!("[voiceflow] Provider {name} isn't running, using the sidecar");
+            return None;
+        }
+    };
+    let audio: Vec<u8> = samples
+        .iter()
+        .flat_map(|sample| sample.to_le_bytes())
+        .collect();
+    let mut params = match serde_json::to_value(options) {
+        Ok(params) => params,
+        Err(e) => return Some(Err(e.to_string())),
+    };
+    params["sampleRate"] = SAMPLE_RATE.into();
+    params["audio"] = BASE64.encode(audio).into();
+    let result = call(&process, "transcribe", params, TRANSCRIBE_TIMEOUT).and_then(|result| {
+        serde_json::from_value(result).map_err(|e| format!("Invalid provider transcript: {e}"))
+    });
+    Some(result)
+}
+
+/// Post-process with the active post-processing provider, None when there is none
+pub fn process(text: &str, prompt: &str, language: Option<&str>) -> Option<Result<String, String>> {
+    let process = match active(ProviderKind::PostProcessing)? {
+        Ok(process) => process,
+        Err(name) => {
+            return Some(Err(i18n::tf(
+                "error.provider_not_running",
+                &[("name", &name)],
+            )));
+        }
+    };
+    let params = serde_json::json!({ "text": text, "prompt": prompt, "language": language });
+    let result = call(&process, "process", params, PROCESS_TIMEOUT).and_then(|result| {
+        serde_json::from_value::<ProcessResult>(result)
+            .map(|result| result.text)
+            .map_err(|e| format!("Invalid provider result: {e}"))
+    });
+    Some(result)
+}
+
+/// Problems with the provider registry, for settings validation
+pub fn invalid_providers(providers: &[ProviderConfig]) -> Vec<String> {
+    let mut errors = Vec::new();
+    for (index, provider) in providers.iter().enumerate() {
+        if provider.name.trim().is_empty() {
+            errors.push(format!("Provider {} has no name", index + 1));
+        } else if providers[..index].iter().any(|p| p.name == provider.name) {
+            errors.push(format!("Provider name {} is used twice", provider.name));
+        }
+        if provider.command.trim().is_empty() {
+            errors.push(format!("Provider {} has no command", provider.name));
+        }
+    }
+    errors
+}
+
+#[tauri::command]
+pub async fn list_providers() -> Result<Vec<ProviderInfo>, String> {
+    let providers = PROVIDERS.lock().map_err(|e| e.to_string())?;
+    Ok(providers
+        .iter()
+        .map(|provider| ProviderInfo {
+            name: provider.config.name.clone(),
+            kind: provider.config.kind,
+            enabled: provider.config.enabled,
+            status: provider.status,
+            restarts: provider.restarts,
+        })
+        .collect())
+}
+
+/// Replace the provider registry and start or stop providers to match
+#[tauri::command]
+pub async fn set_providers(app: AppHandle, providers: Vec<ProviderConfig>) -> Result<(), String> {
+    if let Some(error) = invalid_providers(&providers).into_iter().next() {
+        return Err(error);
+    }
+    settings::update(&app, |settings| settings.providers = providers.clone())?;
+    sync(&app, &providers);
+    spawn_pending(&app);
+    Ok(())
+}
+
+/// Stop a provider and start it again straight away, e.g. after updating it
+#[tauri::command]
+pub async fn restart_provider(app: AppHandle, name: String) -> Result<(), String> {
+    {
+        let mut providers = PROVIDERS.lock().map_err(|e| e.to_string())?;
+        let provider = providers
+            .iter_mut()
+            .find(|provider| provider.config.name == name)
+            .ok_or_else(|| i18n::tf("error.unknown_provider", &[("name", &name)]))?;
+        stop(provider);
+        provider.retry_at = None;
+        provider.failed_restarts = 0;
+        set_status(&app, provider, ServerStatus::Stopped);
+    }
+    spawn_pending(&app);
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn shell_provider(script: &str) -> ProviderConfig {
+        ProviderConfig {
+            name: "test".to_string(),
+            kind: ProviderKind::PostProcessing,
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            enabled: true,
+        }
+    }
+
+    // Answers initialize, then echoes a fixed result for request 2 after some chatter
+    const ECHO: &str = r#"read line; echo '{"id":1,"result":{"protocolVersion":1}}'
+read line; echo 'loading'; echo '{"id":2,"result":{"text":"done"}}'; read line"#;
+
+    #[test]
+    fn handshakes_and_matches_responses_by_id() {
+        let mut process = ProviderProcess::spawn(&shell_provider(ECHO)).unwrap();
+        let result = process
+            .call("process", serde_json::json!({}), Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(result["text"], "done");
+        process.shutdown();
+        assert!(!process.is_running());
+    }
+
+    #[test]
+    fn rejects_other_protocol_versions() {
+        let script = r#"read line; echo '{"id":1,"result":{"protocolVersion":99}}'"#;
+        assert!(ProviderProcess::spawn(&shell_provider(script)).is_err());
+    }
+
+    #[test]
+    fn reports_provider_errors() {
+        let script = r#"read line; echo '{"id":1,"result":{"protocolVersion":1}}'
+read line; echo '{"id":2,"error":{"message":"no model"}}'"#;
+        let mut process = ProviderProcess::spawn(&shell_provider(script)).unwrap();
+        let error = process.call("transcribe", Value::Null, Duration::from_secs(5));
+        assert_eq!(error.unwrap_err(), "no model");
+    }
+
+    #[test]
+    fn validates_names_and_commands() {
+        let provider = shell_provider("true");
+        assert!(invalid_providers(std::slice::from_ref(&provider)).is_empty());
+        let unnamed = ProviderConfig {
+            name: String::new(),
+            command: String::new(),
+            ..provider.clone()
+        };
+        assert_eq!(invalid_providers(&[unnamed]).len(), 2);
+        assert_eq!(invalid_providers(&[provider.clone(), provider]).len(), 1);
+    }
+}
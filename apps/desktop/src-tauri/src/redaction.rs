@@ -0,0 +1,168 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use tauri::AppHandle;
+
+use crate::settings;
+use crate::transcription::Transcript;
+
+static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b[a-z0-9._%+-]+@[a-z0-9.-]+\.[a-z]{2,}\b").expect("valid email pattern")
+});
+static SSN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b\d{3}[- ]\d{2}[- ]\d{4}\b").expect("valid SSN pattern"));
+static CARD_NUMBER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b\d(?:[ -]?\d){12,18}\b").expect("valid card number pattern"));
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RedactionSettings {
+    /// Mask sensitive data in everything stored or sent elsewhere; pasting is unaffected
+    pub enabled: bool,
+    pub credit_cards: bool,
+    pub ssns: bool,
+    pub emails: bool,
+    /// Extra regular expressions whose matches are masked
+    pub patterns: Vec<String>,
+}
+
+impl Default for RedactionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            credit_cards: true,
+            ssns: true,
+            emails: true,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+/// Custom patterns that don't compile, with the reason
+pub fn invalid_patterns(settings: &RedactionSettings) -> Vec<String> {
+    settings
+        .patterns
+        .iter()
+        .filter_map(|pattern| Regex::new(pattern).err().map(|e| format!("{pattern}: {e}")))
+        .collect()
+}
+
+/// Luhn checksum, which every payment card number satisfies
+fn passes_luhn(number: &str) -> bool {
+    let digits: Vec<u32> = number.chars().filter_map(|c| c.to_digit(10)).collect();
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(index, digit)| match (index % 2, digit * 2) {
+            (1, doubled) if doubled > 9 => doubled - 9,
+            (1, doubled) => doubled,
+            _ => *digit,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+fn mask_matches(text: &str, pattern: &Regex, mask: &str, keep: impl Fn(&str) -> bool) -> String {
+    pattern
+        .replace_all(text, |captures: &regex::Captures| {
+            let found = &captures[0];
+            if keep(found) {
+                mask.to_string()
+            } else {
+                found.to_string()
+            }
+        })
+        .into_owned()
+}
+
+/// Replace sensitive spans with a placeholder naming what was removed
+fn redact_text(text: &str, settings: &RedactionSettings) -> String {
+    let mut text = text.to_string();
+    if settings.emails {
+        text = mask_matches(&text, &EMAIL, "[email]", |_| true);
+    }
+    if settings.credit_cards {
+        text = mask_matches(&text, &CARD_NUMBER, "[card number]", passes_luhn);
+    }
+    if settings.ssns {
+        text = mask_matches(&text, &SSN, "[SSN]", |_| true);
+    }
+    for pattern in &settings.patterns {
+        // Invalid patterns are rejected when saved, and skipped if they come in some other way
+        if let Ok(pattern) = Regex::new(pattern) {
+            text = mask_matches(&text, &pattern, "[redacted]", |_| true);
+        }
+    }
+    text
+}
+
+/// Text as it may be stored or sent elsewhere
+pub fn redact(app: &AppHandle, text: &str) -> String {
+    let settings = settings::current(app).redaction;
+    if !settings.enabled {
+        return text.to_string();
+    }
+    redact_text(text, &settings)
+}
+
+/// Redact a transcript before it is stored. Word timings and alternatives can't be
+/// redacted reliably, so they are dropped whenever something was masked.
+pub fn redact_transcript(app: &AppHandle, mut transcript: Transcript) -> Transcript {
+    let settings = settings::current(app).redaction;
+    if !settings.enabled {
+        return transcript;
+    }
+    let redacted = redact_text(&transcript.text, &settings);
+    if redacted == transcript.text {
+        return transcript;
+    }
+    transcript.text = redacted;
+    transcript.words.clear();
+    transcript.alternatives.clear();
+    for segment in &mut transcript.segments {
+        segment.text = redact_text(&segment.text, &settings);
+    }
+    transcript
+}
+
+#[tauri::command]
+pub async fn set_redaction(app: AppHandle, redaction: RedactionSettings) -> Result<(), String> {
+    if let Some(error) = invalid_patterns(&redaction).into_iter().next() {
+        return Err(format!("Invalid redaction pattern {error}"));
+    }
+    settings::update(&app, |settings| settings.redaction = redaction)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_built_in_detectors_and_custom_patterns() {
+        let settings = RedactionSettings {
+            enabled: true,
+            patterns: vec![r"(?i)project \w+".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            redact_text(
+                "Mail ada@example.com the card 4111 1111 1111 1111, SSN 078-05-1120, about Project Falcon.",
+                &settings
+            ),
+            "Mail [email] the card [card number], SSN [SSN], about [redacted]."
+        );
+        // Order numbers failing the Luhn check are left alone
+        assert_eq!(
+            redact_text("Order 1234 5678 9012 3456 shipped", &settings),
+            "Order 1234 5678 9012 3456 shipped"
+        );
+
+        let invalid = RedactionSettings {
+            patterns: vec!["(unclosed".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(invalid_patterns(&invalid).len(), 1);
+    }
+}
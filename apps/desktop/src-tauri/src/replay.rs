@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+use crate::audio_file::resample;
+use crate::pipeline::{self, RecordedTranscription};
+use crate::session::now_ms;
+use crate::transcription::{self, Transcript, TranscriptionOptions, SAMPLE_RATE};
+use crate::{demo, incognito};
+
+const FIXTURE_VERSION: u32 = 1;
+
+// Opt-in for debugging, deliberately not persisted across launches
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static CURRENT: Mutex<Option<Fixture>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FixtureEvent {
+    /// Milliseconds since the dictation started
+    pub offset_ms: u64,
+    pub name: String,
+    #[serde(default)]
+    pub payload: Value,
+}
+
+/// Everything needed to reproduce one dictation, written as a single JSON file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Fixture {
+    pub version: u32,
+    pub created_ms: u64,
+    /// Raw audio as captured, when the recorder supplied it
+    #[serde(default)]
+    pub sample_rate: u32,
+    #[serde(default)]
+    pub audio: Vec<i16>,
+    /// What the sidecar returned for the audio
+    pub transcript: Option<Transcript>,
+    /// Text the pipeline produced for insertion
+    pub output: Option<String>,
+    pub events: Vec<FixtureEvent>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayResult {
+    pub recorded_output: Option<String>,
+    /// Fresh sidecar transcription of the fixture audio
+    pub retranscribed: Option<String>,
+    pub replayed_output: String,
+    pub matches: bool,
+}
+
+fn with_current(f: impl FnOnce(&mut Fixture)) {
+    if let Ok(mut current) = CURRENT.lock() {
+        if let Some(fixture) = current.as_mut() {
+            f(fixture);
+        }
+    }
+}
+
+pub fn event(name: &str, payload: impl Serialize) {
+    with_current(|fixture| {
+        fixture.events.push(FixtureEvent {
+            offset_ms: now_ms().saturating_sub(fixture.created_ms),
+            name: name.to_string(),
+            payload: serde_json::to_value(payload).unwrap_or_default(),
+        });
+    });
+}
+
+/// Start a fixture for the new dictation if recording is switched on
+pub fn session_started() {
+    // Incognito dictations leave no audio or transcript behind
+    let enabled = ENABLED.load(Ordering::SeqCst) && !incognito::is_active();
+    let fixture = enabled.then(|| Fixture {
+        version: FIXTURE_VERSION,
+        created_ms: now_ms(),
+        sample_rate: 0,
+        audio: Vec::new(),
+        transcript: None,
+        output: None,
+        events: Vec::new(),
+    });
+    if let Ok(mut current) = CURRENT.lock() {
+        *current = fixture;
+    }
+    event("recording-start", ());
+}
+
+pub fn transcript_received(transcript: &Transcript) {
+    event("sidecar-response", transcript);
+    with_current(|fixture| fixture.transcript = Some(transcript.clone()));
+}
+
+/// Close the fixture once the pipeline is done and write it next to the history
+pub fn session_finished(app: &AppHandle, result: &Result<RecordedTranscription, String>) {
+    match result {
+        Ok(recorded) => event("transcription-recorded", recorded),
+        Err(error) => event("pipeline-error", error),
+    }
+    let Some(mut fixture) = CURRENT.lock().ok().and_then(|mut current| current.take()) else {
+        return;
+    };
+    fixture.output = result
+        .as_ref()
+        .ok()
+        .map(|recorded| recorded.entry.text.clone());
+
+    match write_fixture(app, &fixture) {
+        Ok(path) => {
+            eprintln!("[voiceflow] Saved session fixture to {}", path.display());
+            let _ = app.emit("fixture-saved", path);
+        }
+        Err(error) => eprintln!("[voiceflow] Failed to save session fixture: {error}"),
+    }
+}
+
+fn write_fixture(app: &AppHandle, fixture: &Fixture) -> Result<PathBuf, String> {
+    let dir = demo::data_dir(app)?.join("fixtures");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("session-{}.json", fixture.created_ms));
+    let content = serde_json::to_string_pretty(fixture).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+#[tauri::command]
+pub async fn set_fixture_recording(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+/// Raw audio of the running dictation, sent by the webview that captured it
+#[tauri::command]
+pub async fn attach_fixture_audio(samples: Vec<i16>, sample_rate: u32) {
+    event(
+        "audio",
+        serde_json::json!({ "samples": samples.len(), "sampleRate": sample_rate }),
+    );
+    with_current(|fixture| {
+        fixture.sample_rate = sample_rate;
+        fixture.audio = samples;
+    });
+}
+
+/// Re-run a saved fixture: transcribe its audio again when present, then pass the
+/// transcript through the text stages and compare with what was recorded
+#[tauri::command]
+pub async fn replay_fixture(app: AppHandle, path: PathBuf) -> Result<ReplayResult, String> {
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read fixture: {e}"))?;
+    let fixture: Fixture =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid fixture: {e}"))?;
+
+    let retranscribed = if fixture.audio.is_empty() {
+        None
+    } else {
+        let audio = fixture.audio.clone();
+        let sample_rate = fixture.sample_rate;
+        let transcript = tauri::async_runtime::spawn_blocking(move || {
+            let samples: Vec<i16> = if sample_rate == SAMPLE_RATE || sample_rate == 0 {
+                audio
+            } else {
+                let floats: Vec<f32> = audio
+                    .iter()
+                    .map(|sample| f32::from(*sample) / f32::from(i16::MAX))
+                    .collect();
+                resample(&floats, sample_rate, SAMPLE_RATE)
+                    .into_iter()
+                    .map(|sample| (sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16)
+                    .collect()
+            };
+            transcription::transcribe(&samples, &TranscriptionOptions::default())
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+        Some(transcript)
+    };
+
+    let source = retranscribed
+        .clone()
+        .or(fixture.transcript)
+        .ok_or_else(|| "Fixture has neither audio nor a transcript".to_string())?;
+    let replayed = pipeline::transform(&app, source).await;
+    Ok(ReplayResult {
+        matches: fixture.output.as_deref() == Some(replayed.text.as_str()),
+        recorded_output: fixture.output,
+        retranscribed: retranscribed.map(|transcript| transcript.text),
+        replayed_output: replayed.text,
+    })
+}
@@ -0,0 +1,206 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::history::HistoryEntry;
+use crate::pipeline::RecordedTranscription;
+use crate::{
+    append_e2e_log, history_picker, i18n, insertion, scratchpad, screen_share, start_recording,
+};
+
+#[cfg(target_os = "macos")]
+use tauri_nspanel::objc2::{runtime::NSObjectProtocol, ClassType, Message};
+#[cfg(target_os = "macos")]
+use tauri_nspanel::{ManagerExt, WebviewWindowExt};
+
+pub const WINDOW_LABEL: &str = "result-panel";
+const WIDTH: f64 = 480.0;
+const HEIGHT: f64 = 240.0;
+
+// Like the history picker, the panel takes keyboard input without activating
+// VoiceFlow, so "Paste" goes to whatever app is frontmost
+#[cfg(target_os = "macos")]
+tauri_nspanel::panel!(ResultPanel {
+    config: {
+        can_become_key_window: true,
+        can_become_main_window: false,
+        is_floating_panel: true,
+    }
+});
+
+// The running dictation should end up in the panel instead of being pasted
+static SESSION: AtomicBool = AtomicBool::new(false);
+// Transcript shown in the panel
+static RESULT: Mutex<Option<HistoryEntry>> = Mutex::new(None);
+
+/// Send the running dictation's result to the panel
+pub fn begin_session() {
+    SESSION.store(true, Ordering::SeqCst);
+}
+
+pub fn dictation_cancelled() {
+    SESSION.store(false, Ordering::SeqCst);
+}
+
+/// Show the result of a dictation started for the panel, marking it handled so
+/// the webview doesn't paste it
+pub fn dictation_finished(
+    app: &AppHandle,
+    recorded: Result<RecordedTranscription, String>,
+) -> Result<RecordedTranscription, String> {
+    if !SESSION.swap(false, Ordering::SeqCst) {
+        return recorded;
+    }
+    let mut recorded = recorded?;
+    // Spoken commands and the like were already acted on
+    if recorded.handled {
+        return Ok(recorded);
+    }
+    recorded.handled = true;
+    if let Ok(mut result) = RESULT.lock() {
+        *result = Some(recorded.entry.clone());
+    }
+    let handle = app.clone();
+    let entry = recorded.entry.clone();
+    let _ = app.run_on_main_thread(move || show(&handle, entry));
+    Ok(recorded)
+}
+
+#[cfg(target_os = "macos")]
+fn setup_panel(window: &tauri::WebviewWindow) {
+    use tauri_nspanel::objc2_app_kit::{NSWindowCollectionBehavior, NSWindowStyleMask};
+
+    let Ok(panel) = window.to_panel::<ResultPanel<_>>() else {
+        return;
+    };
+    panel.set_level(25);
+    panel.set_style_mask(NSWindowStyleMask::NonactivatingPanel);
+    panel.set_collection_behavior(
+        NSWindowCollectionBehavior::CanJoinAllSpaces
+            | NSWindowCollectionBehavior::Transient
+            | NSWindowCollectionBehavior::FullScreenAuxiliary,
+    );
+}
+
+fn show(app: &AppHandle, entry: HistoryEntry) {
+    if screen_share::should_hide_overlay(app) {
+        append_e2e_log("result-panel-suppressed");
+        return;
+    }
+    let window = match app.get_webview_window(WINDOW_LABEL) {
+        Some(window) => window,
+        None => {
+            let builder = WebviewWindowBuilder::new(
+                app,
+                WINDOW_LABEL,
+                WebviewUrl::App("/result-panel".into()),
+            )
+            .title("VoiceFlow")
+            .inner_size(WIDTH, HEIGHT)
+            .resizable(false)
+            .decorations(false)
+            .transparent(true)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .visible(false);
+            match builder.build() {
+                Ok(window) => {
+                    #[cfg(target_os = "macos")]
+                    setup_panel(&window);
+                    window
+                }
+                Err(e) => {
+                    eprintln!("[voiceflow] Failed to create result panel: {e}");
+                    return;
+                }
+            }
+        }
+    };
+
+    history_picker::position(app, &window);
+    let _ = app.emit_to(WINDOW_LABEL, "result-panel-opened", entry);
+
+    #[cfg(target_os = "macos")]
+    if let Ok(panel) = app.get_webview_panel(WINDOW_LABEL) {
+        panel.show_and_make_key();
+        return;
+    }
+    let _ = window.show();
+    let _ = window.set_focus();
+}
+
+fn hide(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        let _ = window.hide();
+    }
+}
+
+fn result_text() -> Result<String, String> {
+    RESULT
+        .lock()
+        .map_err(|e| e.to_string())?
+        .as_ref()
+        .map(|entry| entry.text.clone())
+        .ok_or_else(|| i18n::t("error.no_previous_dictation"))
+}
+
+/// Start a dictation whose transcript is shown in the result panel instead of pasted
+#[tauri::command]
+pub async fn start_quick_dictation(app: AppHandle) -> Result<(), String> {
+    begin_session();
+    if !start_recording(&app) {
+        dictation_cancelled();
+        return Err(i18n::t("error.dictation_blocked"));
+    }
+    Ok(())
+}
+
+/// The transcript the panel shows, for when it loads after the opened event
+#[tauri::command]
+pub async fn get_result_panel_entry() -> Result<Option<HistoryEntry>, String> {
+    Ok(RESULT.lock().map_err(|e| e.to_string())?.clone())
+}
+
+#[tauri::command]
+pub async fn hide_result_panel(app: AppHandle) {
+    hide(&app);
+}
+
+#[tauri::command]
+pub async fn copy_result(app: AppHandle) -> Result<(), String> {
+    let text = result_text()?;
+    app.clipboard()
+        .write_text(text)
+        .map_err(|e| format!("Failed to copy transcript: {e}"))?;
+    hide(&app);
+    Ok(())
+}
+
+/// Close the panel and paste the transcript into the frontmost app
+#[tauri::command]
+pub async fn paste_result(app: AppHandle) -> Result<(), String> {
+    let text = result_text()?;
+    let id = RESULT
+        .lock()
+        .ok()
+        .and_then(|result| result.as_ref().map(|entry| entry.id));
+    hide(&app);
+    // Give the frontmost app time to become key again before pasting
+    thread::sleep(Duration::from_millis(150));
+    let (strategy, result) = insertion::paste_or_type(&app, Some(&text));
+    insertion::report(&app, id, strategy, Some(text), &result);
+    result
+}
+
+/// Move the transcript to the scratchpad to keep editing it there
+#[tauri::command]
+pub async fn open_result_in_editor(app: AppHandle) -> Result<(), String> {
+    let text = result_text()?;
+    scratchpad::append(&app, text)?;
+    hide(&app);
+    scratchpad::show(&app);
+    Ok(())
+}
@@ -0,0 +1,233 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::{
+    append_e2e_log, focus_and_bring_to_front, i18n, incognito, paste_from_clipboard, screen_share,
+    settings,
+};
+
+pub const WINDOW_LABEL: &str = "scratchpad";
+
+/// Where finished dictations go
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OutputTarget {
+    /// Paste into the frontmost app
+    #[default]
+    Paste,
+    /// Collect in the scratchpad window for editing before inserting
+    Scratchpad,
+    /// Pipe into a shell command's stdin, see command_output.rs
+    Command,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScratchpadEntry {
+    pub id: u64,
+    pub timestamp_ms: u64,
+    pub text: String,
+    /// Dictated while incognito, so it is never written to disk
+    #[serde(skip)]
+    pub incognito: bool,
+}
+
+pub struct Scratchpad {
+    entries: Vec<ScratchpadEntry>,
+    scratchpad_path: PathBuf,
+}
+
+impl Scratchpad {
+    pub fn new(data_dir: PathBuf) -> Self {
+        let scratchpad_path = data_dir.join("scratchpad.json");
+        let entries = Self::load_entries(&scratchpad_path).unwrap_or_default();
+        Self {
+            entries,
+            scratchpad_path,
+        }
+    }
+
+    fn load_entries(path: &PathBuf) -> Option<Vec<ScratchpadEntry>> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn save_entries(&self) -> Result<(), String> {
+        if let Some(parent) = self.scratchpad_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let stored: Vec<&ScratchpadEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| !entry.incognito)
+            .collect();
+        let content = serde_json::to_string_pretty(&stored).map_err(|e| e.to_string())?;
+        fs::write(&self.scratchpad_path, content).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    fn append(&mut self, text: String, incognito: bool) -> Result<ScratchpadEntry, String> {
+        let id = self.entries.iter().map(|entry| entry.id).max().unwrap_or(0) + 1;
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or_default();
+        let entry = ScratchpadEntry {
+            id,
+            timestamp_ms,
+            text,
+            incognito,
+        };
+        self.entries.push(entry.clone());
+        self.save_entries()?;
+        Ok(entry)
+    }
+
+    fn edit(&mut self, id: u64, text: Option<String>) -> Result<(), String> {
+        let index = self
+            .entries
+            .iter()
+            .position(|entry| entry.id == id)
+            .ok_or_else(|| format!("Scratchpad entry {id} not found"))?;
+        match text {
+            Some(text) => self.entries[index].text = text,
+            None => {
+                self.entries.remove(index);
+            }
+        }
+        self.save_entries()
+    }
+
+    fn clear(&mut self) -> Result<(), String> {
+        self.entries.clear();
+        self.save_entries()
+    }
+
+    pub fn entries(&self) -> Vec<ScratchpadEntry> {
+        self.entries.clone()
+    }
+
+    /// Everything collected so far as a single block of text
+    pub fn joined_text(&self) -> String {
+        self.entries
+            .iter()
+            .map(|entry| entry.text.trim())
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+fn with_scratchpad<T>(
+    app: &AppHandle,
+    apply: impl FnOnce(&mut Scratchpad) -> Result<T, String>,
+) -> Result<T, String> {
+    let state = app.state::<Mutex<Scratchpad>>();
+    let mut scratchpad = state.lock().map_err(|e| e.to_string())?;
+    let result = apply(&mut scratchpad)?;
+    let _ = app.emit("scratchpad-updated", scratchpad.entries());
+    Ok(result)
+}
+
+pub fn is_target(app: &AppHandle) -> bool {
+    settings::current(app).output_target == OutputTarget::Scratchpad
+}
+
+pub fn append(app: &AppHandle, text: String) -> Result<ScratchpadEntry, String> {
+    with_scratchpad(app, |scratchpad| {
+        scratchpad.append(text, incognito::is_active())
+    })
+}
+
+fn get_scratchpad_text(app: &AppHandle) -> Result<String, String> {
+    let state = app.state::<Mutex<Scratchpad>>();
+    let scratchpad = state.lock().map_err(|e| e.to_string())?;
+    Ok(scratchpad.joined_text())
+}
+
+pub fn show(app: &AppHandle) {
+    if screen_share::should_hide_overlay(app) {
+        append_e2e_log("scratchpad-suppressed");
+        return;
+    }
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        focus_and_bring_to_front(&window);
+        return;
+    }
+
+    let builder =
+        WebviewWindowBuilder::new(app, WINDOW_LABEL, WebviewUrl::App("/scratchpad".into()))
+            .title(i18n::t("window.scratchpad"))
+            .inner_size(420.0, 560.0)
+            .center()
+            .focused(true);
+
+    if let Ok(window) = builder.build() {
+        focus_and_bring_to_front(&window);
+    }
+}
+
+#[tauri::command]
+pub async fn show_scratchpad(app: AppHandle) {
+    show(&app);
+}
+
+#[tauri::command]
+pub async fn get_scratchpad(app: AppHandle) -> Result<Vec<ScratchpadEntry>, String> {
+    let state = app.state::<Mutex<Scratchpad>>();
+    let scratchpad = state.lock().map_err(|e| e.to_string())?;
+    Ok(scratchpad.entries())
+}
+
+/// Replace an entry's text, or remove the entry when `text` is null
+#[tauri::command]
+pub async fn edit_scratchpad_entry(
+    app: AppHandle,
+    id: u64,
+    text: Option<String>,
+) -> Result<(), String> {
+    with_scratchpad(&app, |scratchpad| scratchpad.edit(id, text))
+}
+
+#[tauri::command]
+pub async fn clear_scratchpad(app: AppHandle) -> Result<(), String> {
+    with_scratchpad(&app, Scratchpad::clear)
+}
+
+#[tauri::command]
+pub async fn copy_scratchpad(app: AppHandle) -> Result<String, String> {
+    let text = get_scratchpad_text(&app)?;
+    app.clipboard()
+        .write_text(text.clone())
+        .map_err(|e| format!("Failed to copy scratchpad: {e}"))?;
+    Ok(text)
+}
+
+/// Hide the scratchpad and paste its contents into the app that was frontmost before it
+#[tauri::command]
+pub async fn insert_scratchpad(app: AppHandle, clear: bool) -> Result<(), String> {
+    let text = get_scratchpad_text(&app)?;
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        let _ = window.hide();
+    }
+    // Give the previous app time to become key again before pasting
+    thread::sleep(Duration::from_millis(150));
+    paste_from_clipboard(app.clone(), Some(text), None).await?;
+
+    if clear {
+        with_scratchpad(&app, Scratchpad::clear)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_output_target(app: AppHandle, target: OutputTarget) -> Result<(), String> {
+    settings::update(&app, |settings| settings.output_target = target)?;
+    Ok(())
+}
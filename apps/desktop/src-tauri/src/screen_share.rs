@@ -0,0 +1,148 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{append_e2e_log, history_picker, result_panel, scratchpad, settings, switch_access};
+
+static SCREEN_SHARING: AtomicBool = AtomicBool::new(false);
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Listing every process is slower than the window server check, so it only runs
+/// on every few polls
+#[cfg(target_os = "macos")]
+const PROCESS_SCAN_EVERY: u32 = 5;
+
+/// Windows that may show dictated text and must not be broadcast
+const OVERLAY_WINDOW_LABELS: &[&str] = &[
+    "main",
+    result_panel::WINDOW_LABEL,
+    history_picker::WINDOW_LABEL,
+    scratchpad::WINDOW_LABEL,
+    switch_access::WINDOW_LABEL,
+];
+
+// Helper processes that only run while the screen is captured or shared
+#[cfg(target_os = "macos")]
+const CAPTURE_PROCESSES: &[&str] = &["CptHost", "screensharingd", "screencaptureui"];
+
+// Loads CoreGraphics so the private call below can be looked up
+#[cfg(target_os = "macos")]
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {}
+
+/// Private CoreGraphics call, true while any process streams the display contents.
+/// It is looked up at runtime since a macOS update may drop it.
+#[cfg(target_os = "macos")]
+fn screen_watcher_present() -> Option<bool> {
+    use std::sync::OnceLock;
+
+    static FUNCTION: OnceLock<Option<extern "C" fn() -> bool>> = OnceLock::new();
+    let function = FUNCTION.get_or_init(|| {
+        // SAFETY: dlsym with a NUL-terminated name; a found symbol has this signature
+        unsafe {
+            let symbol = libc::dlsym(libc::RTLD_DEFAULT, c"CGSIsScreenWatcherPresent".as_ptr());
+            (!symbol.is_null())
+                .then(|| std::mem::transmute::<*mut libc::c_void, extern "C" fn() -> bool>(symbol))
+        }
+    });
+    function.map(|present| present())
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenSharingState {
+    pub active: bool,
+    /// Whether the overlay is hidden and transcript previews should be suppressed
+    pub hide_overlay: bool,
+}
+
+#[cfg(target_os = "macos")]
+fn capture_process_running() -> bool {
+    use std::process::Command;
+
+    let Ok(output) = Command::new("ps").args(["-axco", "comm="]).output() else {
+        return false;
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .any(|name| CAPTURE_PROCESSES.contains(&name.trim()))
+}
+
+/// Whether the screen is shared, None when this poll can't tell and the last answer stands
+#[cfg(target_os = "macos")]
+fn detect_screen_sharing(poll: u32) -> Option<bool> {
+    if screen_watcher_present() == Some(true) {
+        return Some(true);
+    }
+    if poll.is_multiple_of(PROCESS_SCAN_EVERY) {
+        return Some(capture_process_running());
+    }
+    None
+}
+
+#[cfg(not(target_os = "macos"))]
+fn detect_screen_sharing(_poll: u32) -> Option<bool> {
+    Some(false)
+}
+
+pub fn state(app: &AppHandle) -> ScreenSharingState {
+    let active = SCREEN_SHARING.load(Ordering::SeqCst);
+    ScreenSharingState {
+        active,
+        hide_overlay: active && settings::current(app).hide_overlay_while_sharing,
+    }
+}
+
+pub fn should_hide_overlay(app: &AppHandle) -> bool {
+    state(app).hide_overlay
+}
+
+fn hide_overlay_windows(app: &AppHandle) {
+    for label in OVERLAY_WINDOW_LABELS {
+        if let Some(window) = app.get_webview_window(label) {
+            let _ = window.hide();
+        }
+    }
+}
+
+fn apply_state(app: &AppHandle) {
+    let state = state(app);
+    if state.hide_overlay {
+        hide_overlay_windows(app);
+    }
+    let _ = app.emit("screen-sharing-changed", state);
+}
+
+pub fn start_monitor(app: AppHandle) {
+    thread::spawn(move || {
+        for poll in 0u32.. {
+            if let Some(active) = detect_screen_sharing(poll) {
+                if SCREEN_SHARING.swap(active, Ordering::SeqCst) != active {
+                    append_e2e_log(if active {
+                        "screen-sharing-started"
+                    } else {
+                        "screen-sharing-stopped"
+                    });
+                    apply_state(&app);
+                }
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_screen_sharing_state(app: AppHandle) -> ScreenSharingState {
+    state(&app)
+}
+
+#[tauri::command]
+pub async fn set_hide_overlay_while_sharing(app: AppHandle, enabled: bool) -> Result<(), String> {
+    settings::update(&app, |settings| {
+        settings.hide_overlay_while_sharing = enabled
+    })?;
+    apply_state(&app);
+    Ok(())
+}
@@ -0,0 +1,444 @@
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{CallFnOptions, Dynamic, Engine, Map, Scope, AST};
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::active_app::{self, ActiveApp};
+use crate::{i18n, settings};
+
+const EXTENSION: &str = "rhai";
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Keeps a runaway loop from stalling a dictation
+const MAX_OPERATIONS: u64 = 200_000;
+const MAX_STRING_BYTES: usize = 1024 * 1024;
+
+/// Points in a dictation where scripts can step in, each a function the script may define
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Hook {
+    /// `fn post_transcription(text, ctx)`, on the raw transcript before cleanup
+    PostTranscription,
+    /// `fn pre_output(text, ctx)`, on the final text right before it is inserted
+    PreOutput,
+    /// `fn on_app_change(ctx)`, when another app comes to the front
+    OnAppChange,
+}
+
+impl Hook {
+    const ALL: [Hook; 3] = [Hook::PostTranscription, Hook::PreOutput, Hook::OnAppChange];
+
+    fn function(self) -> &'static str {
+        match self {
+            Hook::PostTranscription => "post_transcription",
+            Hook::PreOutput => "pre_output",
+            Hook::OnAppChange => "on_app_change",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptInfo {
+    /// File name, what set_script_enabled takes
+    pub name: String,
+    pub hooks: Vec<Hook>,
+    pub enabled: bool,
+    /// Why the script doesn't compile
+    pub error: Option<String>,
+}
+
+/// Sent for each `emit(name, payload)` a script calls
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScriptEvent {
+    script: String,
+    name: String,
+    payload: String,
+}
+
+struct LoadedScript {
+    name: String,
+    ast: AST,
+    hooks: Vec<Hook>,
+}
+
+/// Rhai scripts from `scripts/` in the config dir. Scripts get no file, network or
+/// module access; besides Rhai's built-in functions they can only call `log(message)`
+/// and `emit(name, payload)`, which reaches the webview as a script-event.
+pub struct ScriptManager {
+    engine: Engine,
+    dir: PathBuf,
+    scripts: Vec<LoadedScript>,
+    failed: Vec<(String, String)>,
+    /// Events emitted by the script that is running, sent once it returns
+    emitted: Arc<Mutex<Vec<(String, String)>>>,
+}
+
+fn engine(emitted: Arc<Mutex<Vec<(String, String)>>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_module_resolver(DummyModuleResolver::new());
+    engine.disable_symbol("eval");
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_string_size(MAX_STRING_BYTES);
+    engine.set_max_array_size(10_000);
+    engine.set_max_map_size(10_000);
+    engine.set_max_call_levels(32);
+    engine.on_print(|message| eprintln!("[voiceflow] Script: {message}"));
+    engine.on_debug(|message, _, _| eprintln!("[voiceflow] Script: {message}"));
+    engine.register_fn("log", |message: &str| {
+        eprintln!("[voiceflow] Script: {message}")
+    });
+    engine.register_fn("emit", move |name: &str, payload: &str| {
+        if let Ok(mut emitted) = emitted.lock() {
+            emitted.push((name.to_string(), payload.to_string()));
+        }
+    });
+    engine
+}
+
+fn context(app: Option<&ActiveApp>, language: Option<&str>) -> Map {
+    let mut context = Map::new();
+    let field = |value: Option<&str>| value.map_or(Dynamic::UNIT, |value| value.into());
+    context.insert("app".into(), field(app.map(|app| app.name.as_str())));
+    context.insert(
+        "bundleId".into(),
+        field(app.map(|app| app.bundle_id.as_str())),
+    );
+    context.insert("language".into(), field(language));
+    context
+}
+
+impl ScriptManager {
+    pub fn new(config_dir: PathBuf) -> Self {
+        let emitted = Arc::new(Mutex::new(Vec::new()));
+        let mut manager = Self {
+            engine: engine(emitted.clone()),
+            dir: config_dir.join("scripts"),
+            scripts: Vec::new(),
+            failed: Vec::new(),
+            emitted,
+        };
+        manager.reload();
+        manager
+    }
+
+    /// Compile every script in the directory again, picking up edits
+    pub fn reload(&mut self) {
+        self.scripts.clear();
+        self.failed.clear();
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == EXTENSION)
+            })
+            .collect();
+        paths.sort();
+        for path in paths {
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            match self.engine.compile_file(path.clone()) {
+                Ok(ast) => {
+                    let hooks = Hook::ALL
+                        .into_iter()
+                        .filter(|hook| ast.iter_functions().any(|f| f.name == hook.function()))
+                        .collect();
+                    self.scripts.push(LoadedScript {
+                        name: name.to_string(),
+                        ast,
+                        hooks,
+                    });
+                }
+                Err(e) => {
+                    eprintln!("[voiceflow] Script {name} not loaded: {e}");
+                    self.failed.push((name.to_string(), e.to_string()));
+                }
+            }
+        }
+    }
+
+    pub fn list(&self, enabled: &[String]) -> Vec<ScriptInfo> {
+        let loaded = self.scripts.iter().map(|script| ScriptInfo {
+            name: script.name.clone(),
+            hooks: script.hooks.clone(),
+            enabled: enabled.contains(&script.name),
+            error: None,
+        });
+        let failed = self.failed.iter().map(|(name, error)| ScriptInfo {
+            name: name.clone(),
+            hooks: Vec::new(),
+            enabled: false,
+            error: Some(error.clone()),
+        });
+        let mut scripts: Vec<ScriptInfo> = loaded.chain(failed).collect();
+        scripts.sort_by(|a, b| a.name.cmp(&b.name));
+        scripts
+    }
+
+    fn is_loaded(&self, name: &str) -> bool {
+        self.scripts.iter().any(|script| script.name == name)
+    }
+
+    fn with_hook<'a>(
+        &'a self,
+        enabled: &'a [String],
+        hook: Hook,
+    ) -> impl Iterator<Item = &'a LoadedScript> {
+        self.scripts
+            .iter()
+            .filter(move |script| enabled.contains(&script.name) && script.hooks.contains(&hook))
+    }
+
+    fn call(
+        &self,
+        script: &LoadedScript,
+        hook: Hook,
+        args: impl rhai::FuncArgs,
+    ) -> Option<Dynamic> {
+        let options = CallFnOptions::new().eval_ast(false);
+        let result = self.engine.call_fn_with_options::<Dynamic>(
+            options,
+            &mut Scope::new(),
+            &script.ast,
+            hook.function(),
+            args,
+        );
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                eprintln!(
+                    "[voiceflow] Script {} failed in {}: {e}",
+                    script.name,
+                    hook.function()
+                );
+                None
+            }
+        }
+    }
+
+    /// Pass `text` through each enabled script defining `hook`. A script returning
+    /// `()` leaves the text as it is; one that fails is skipped.
+    fn transform(
+        &self,
+        enabled: &[String],
+        hook: Hook,
+        text: &str,
+        context: &Map,
+    ) -> Option<String> {
+        let mut transformed: Option<String> = None;
+        for script in self.with_hook(enabled, hook) {
+            let current = transformed.as_deref().unwrap_or(text).to_string();
+            let Some(value) = self.call(script, hook, (current, context.clone())) else {
+                continue;
+            };
+            if value.is_unit() {
+                continue;
+            }
+            match value.into_string() {
+                Ok(text) => transformed = Some(text),
+                Err(kind) => eprintln!(
+                    "[voiceflow] Script {} returned {kind} from {}, expected a string",
+                    script.name,
+                    hook.function()
+                ),
+            }
+        }
+        transformed
+    }
+
+    fn take_emitted(&self) -> Vec<(String, String)> {
+        self.emitted
+            .lock()
+            .map(|mut emitted| std::mem::take(&mut *emitted))
+            .unwrap_or_default()
+    }
+
+    fn has_hook(&self, enabled: &[String], hook: Hook) -> bool {
+        self.with_hook(enabled, hook).next().is_some()
+    }
+}
+
+fn send_emitted(app: &AppHandle, script: &str, emitted: Vec<(String, String)>) {
+    for (name, payload) in emitted {
+        let _ = app.emit(
+            "script-event",
+            ScriptEvent {
+                script: script.to_string(),
+                name,
+                payload,
+            },
+        );
+    }
+}
+
+/// Run a text hook of the enabled scripts, returning the new text if any script changed it
+pub fn run_text_hook(
+    app: &AppHandle,
+    hook: Hook,
+    text: &str,
+    language: Option<&str>,
+) -> Option<String> {
+    let enabled = settings::current(app).enabled_scripts;
+    if enabled.is_empty() {
+        return None;
+    }
+    let state = app.state::<Mutex<ScriptManager>>();
+    let manager = state.lock().ok()?;
+    if !manager.has_hook(&enabled, hook) {
+        return None;
+    }
+    let context = context(active_app::frontmost_app().as_ref(), language);
+    let transformed = manager.transform(&enabled, hook, text, &context);
+    send_emitted(app, hook.function(), manager.take_emitted());
+    transformed.filter(|transformed| transformed != text)
+}
+
+fn app_changed(app: &AppHandle, enabled: &[String], current: Option<&ActiveApp>) {
+    let state = app.state::<Mutex<ScriptManager>>();
+    let Ok(manager) = state.lock() else {
+        return;
+    };
+    let context = context(current, None);
+    for script in manager.with_hook(enabled, Hook::OnAppChange) {
+        manager.call(script, Hook::OnAppChange, (context.clone(),));
+        send_emitted(app, &script.name, manager.take_emitted());
+    }
+}
+
+/// Call on_app_change hooks while an enabled script defines one
+pub fn start_monitor(app: AppHandle) {
+    thread::spawn(move || {
+        let mut frontmost: Option<ActiveApp> = None;
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let enabled = settings::current(&app).enabled_scripts;
+            let watching = app
+                .state::<Mutex<ScriptManager>>()
+                .lock()
+                .is_ok_and(|manager| manager.has_hook(&enabled, Hook::OnAppChange));
+            if !watching {
+                frontmost = None;
+                continue;
+            }
+            let current = active_app::frontmost_app();
+            if current.as_ref().map(|app| &app.bundle_id)
+                == frontmost.as_ref().map(|app| &app.bundle_id)
+            {
+                continue;
+            }
+            app_changed(&app, &enabled, current.as_ref());
+            frontmost = current;
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn list_scripts(app: AppHandle) -> Result<Vec<ScriptInfo>, String> {
+    let enabled = settings::current(&app).enabled_scripts;
+    let state = app.state::<Mutex<ScriptManager>>();
+    let manager = state.lock().map_err(|e| e.to_string())?;
+    Ok(manager.list(&enabled))
+}
+
+/// Recompile all scripts, e.g. after editing one
+#[tauri::command]
+pub async fn reload_scripts(app: AppHandle) -> Result<Vec<ScriptInfo>, String> {
+    let enabled = settings::current(&app).enabled_scripts;
+    let state = app.state::<Mutex<ScriptManager>>();
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.reload();
+    Ok(manager.list(&enabled))
+}
+
+#[tauri::command]
+pub async fn set_script_enabled(
+    app: AppHandle,
+    name: String,
+    enabled: bool,
+) -> Result<Vec<ScriptInfo>, String> {
+    let state = app.state::<Mutex<ScriptManager>>();
+    let manager = state.lock().map_err(|e| e.to_string())?;
+    if enabled && !manager.is_loaded(&name) {
+        return Err(i18n::tf("error.unknown_script", &[("name", &name)]));
+    }
+    let updated = settings::update(&app, |settings| {
+        settings.enabled_scripts.retain(|script| script != &name);
+        if enabled {
+            settings.enabled_scripts.push(name.clone());
+        }
+    })?;
+    Ok(manager.list(&updated.enabled_scripts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_hooks_of_enabled_scripts() {
+        let dir = std::env::temp_dir().join(format!(
+            "voiceflow-scripting-test-{}",
+            crate::session::now_ms()
+        ));
+        let scripts = dir.join("scripts");
+        fs::create_dir_all(&scripts).unwrap();
+        fs::write(
+            scripts.join("a.rhai"),
+            r#"
+                fn pre_output(text, ctx) {
+                    if ctx.app == "Slack" { emit("slack", text); return text.to_lower(); }
+                }
+            "#,
+        )
+        .unwrap();
+        fs::write(
+            scripts.join("b.rhai"),
+            "fn pre_output(text, ctx) { text + \"!\" } fn on_app_change(ctx) {}",
+        )
+        .unwrap();
+        fs::write(
+            scripts.join("c.rhai"),
+            "fn pre_output(text, ctx) { loop {} }",
+        )
+        .unwrap();
+        fs::write(scripts.join("d.rhai"), "fn broken(").unwrap();
+
+        let manager = ScriptManager::new(dir.clone());
+        let listed = manager.list(&[]);
+        assert_eq!(listed.len(), 4);
+        assert_eq!(listed[1].hooks, vec![Hook::PreOutput, Hook::OnAppChange]);
+        assert!(listed[3].error.is_some());
+
+        let slack = ActiveApp {
+            name: "Slack".to_string(),
+            bundle_id: "com.tinyspeck.slackmacgap".to_string(),
+            window_title: String::new(),
+        };
+        let context = context(Some(&slack), Some("en"));
+        let enabled: Vec<String> = ["a.rhai", "b.rhai", "c.rhai"].map(String::from).to_vec();
+        assert_eq!(
+            manager.transform(&enabled, Hook::PreOutput, "Hello", &context),
+            Some("hello!".to_string())
+        );
+        assert_eq!(
+            manager.take_emitted(),
+            vec![("slack".to_string(), "Hello".to_string())]
+        );
+        assert_eq!(
+            manager.transform(&enabled, Hook::PostTranscription, "Hello", &context),
+            None
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter};
+
+use crate::voice_commands::{encode_url_component, open_url, TEXT_PLACEHOLDER};
+use crate::{i18n, settings};
+
+pub const SEARCH_HISTORY_TAG: &str = "search";
+
+// The running dictation was started with the search shortcut
+static SESSION: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SearchSettings {
+    /// URL opened for a search dictation, with {text} replaced by the URL-encoded
+    /// transcript, e.g. "https://devdocs.io/#q={text}"
+    pub template: String,
+}
+
+impl Default for SearchSettings {
+    fn default() -> Self {
+        Self {
+            template: "https://www.google.com/search?q={text}".to_string(),
+        }
+    }
+}
+
+impl SearchSettings {
+    pub fn is_valid(&self) -> bool {
+        let template = &self.template;
+        (template.starts_with("http://") || template.starts_with("https://"))
+            && template.contains(TEXT_PLACEHOLDER)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchOpened {
+    query: String,
+    url: String,
+}
+
+fn search_url(template: &str, query: &str) -> String {
+    template.replace(TEXT_PLACEHOLDER, &encode_url_component(query))
+}
+
+pub fn begin_session(is_search: bool) {
+    SESSION.store(is_search, Ordering::SeqCst);
+}
+
+/// Consume the search flag for the dictation that just finished
+pub fn take_session() -> bool {
+    SESSION.swap(false, Ordering::SeqCst)
+}
+
+/// Open the search template for a transcript in the default browser
+pub fn open(app: &AppHandle, query: &str) -> Result<(), String> {
+    let query = query.trim().trim_end_matches(['.', '?', '!']).trim();
+    if query.is_empty() {
+        return Ok(());
+    }
+    let url = search_url(&settings::current(app).search.template, query);
+    open_url(&url)?;
+    let _ = app.emit(
+        "search-opened",
+        SearchOpened {
+            query: query.to_string(),
+            url,
+        },
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_search_template(app: AppHandle, template: String) -> Result<(), String> {
+    let search = SearchSettings { template };
+    if !search.is_valid() {
+        return Err(i18n::t("error.search_template"));
+    }
+    settings::update(&app, |settings| settings.search = search)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_in_the_encoded_query() {
+        assert_eq!(
+            search_url("https://devdocs.io/#q={text}", "rust vec & slice"),
+            "https://devdocs.io/#q=rust+vec+%26+slice"
+        );
+        assert!(SearchSettings::default().is_valid());
+        let no_placeholder = SearchSettings {
+            template: "https://wiki.example.com/search".to_string(),
+        };
+        assert!(!no_placeholder.is_valid());
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::{keyboard, settings};
+
+// Selection captured when the current dictation started
+static CAPTURED_SELECTION: Mutex<Option<String>> = Mutex::new(None);
+
+const COPY_SETTLE_DELAY: Duration = Duration::from_millis(150);
+
+/// Read the focused element's selected text through the Accessibility API
+#[cfg(target_os = "macos")]
+fn selected_text_via_accessibility() -> Option<String> {
+    use std::process::Command;
+
+    let script = r#"
+        tell application "System Events"
+            set frontApp to first application process whose frontmost is true
+            set focusedElement to value of attribute "AXFocusedUIElement" of frontApp
+            return value of attribute "AXSelectedText" of focusedElement
+        end tell
+    "#;
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout)
+        .trim_end_matches('\n')
+        .to_string();
+    (!text.is_empty() && text != "missing value").then_some(text)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn selected_text_via_accessibility() -> Option<String> {
+    None
+}
+
+/// Copy the selection with Cmd+C, then put the user's clipboard back
+fn selected_text_via_copy(app: &AppHandle) -> Result<Option<String>, String> {
+    let clipboard = app.clipboard();
+    let previous = clipboard.read_text().ok();
+
+    keyboard::press_copy()?;
+    thread::sleep(COPY_SETTLE_DELAY);
+    let copied = clipboard.read_text().ok();
+
+    if let Some(previous) = &previous {
+        let _ = clipboard.write_text(previous.clone());
+    }
+
+    // An unchanged clipboard means nothing was selected
+    Ok(copied.filter(|text| !text.is_empty() && Some(text) != previous.as_ref()))
+}
+
+pub fn capture(app: &AppHandle) -> Result<Option<String>, String> {
+    let selection = match selected_text_via_accessibility() {
+        Some(text) => Some(text),
+        None => selected_text_via_copy(app)?,
+    };
+    if let Ok(mut captured) = CAPTURED_SELECTION.lock() {
+        captured.clone_from(&selection);
+    }
+    Ok(selection)
+}
+
+/// Capture the selection off the shortcut thread when the setting is on
+pub fn capture_in_background(app: &AppHandle) {
+    if let Ok(mut captured) = CAPTURED_SELECTION.lock() {
+        *captured = None;
+    }
+    if !settings::current(app).capture_selection_context {
+        return;
+    }
+
+    let app = app.clone();
+    thread::spawn(move || {
+        if let Err(error) = capture(&app) {
+            eprintln!("[voiceflow] Failed to capture selection: {error}");
+        }
+    });
+}
+
+/// Hand the captured selection to the pipeline, clearing it for the next dictation
+pub fn take() -> Option<String> {
+    CAPTURED_SELECTION.lock().ok()?.take()
+}
+
+#[tauri::command]
+pub async fn capture_selected_text(app: AppHandle) -> Result<Option<String>, String> {
+    tauri::async_runtime::spawn_blocking(move || capture(&app))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn set_capture_selection_context(app: AppHandle, enabled: bool) -> Result<(), String> {
+    settings::update(&app, |settings| {
+        settings.capture_selection_context = enabled
+    })?;
+    Ok(())
+}
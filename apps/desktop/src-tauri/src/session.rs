@@ -0,0 +1,150 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::webview::{PageLoadEvent, PageLoadPayload};
+use tauri::{Emitter, Manager, Webview};
+
+use crate::microphone::{self, CaptureStats};
+use crate::{continuous, incognito, replay, voice_commands, IS_RECORDING};
+
+static STARTED_AT_MS: AtomicU64 = AtomicU64::new(0);
+static STOPPED_AT_MS: AtomicU64 = AtomicU64::new(0);
+static LAST_METRICS: Mutex<Option<LatencyMetrics>> = Mutex::new(None);
+// Latest partial transcript of the running dictation, from the webview or partials.rs
+static PARTIAL: Mutex<Option<String>> = Mutex::new(None);
+
+/// Snapshot of the dictation in progress, re-sent to webviews that reload mid-session
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionState {
+    pub recording: bool,
+    pub elapsed_ms: u64,
+    pub command_mode: bool,
+    pub continuous: bool,
+    /// The dictation won't be stored in history
+    pub incognito: bool,
+    pub partial: Option<String>,
+}
+
+/// Timings of the last finished dictation
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyMetrics {
+    pub recording_ms: u64,
+    /// From releasing the shortcut until the webview handed over the transcript
+    pub transcription_ms: u64,
+    /// Normalization, post-processing and storing
+    pub processing_ms: u64,
+    pub capture: CaptureStats,
+}
+
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+pub fn started() {
+    STARTED_AT_MS.store(now_ms(), Ordering::SeqCst);
+    if let Ok(mut partial) = PARTIAL.lock() {
+        *partial = None;
+    }
+    microphone::reset_session_stats();
+    replay::session_started();
+}
+
+pub fn stopped() {
+    STOPPED_AT_MS.store(now_ms(), Ordering::SeqCst);
+    replay::event("recording-stop", ());
+}
+
+/// Time since the last dictation stopped, while its transcript is being stored
+pub fn latency_ms() -> Option<u64> {
+    let stopped = STOPPED_AT_MS.load(Ordering::SeqCst);
+    if IS_RECORDING.load(Ordering::SeqCst) || stopped < STARTED_AT_MS.load(Ordering::SeqCst) {
+        return None;
+    }
+    Some(now_ms().saturating_sub(stopped))
+}
+
+/// Length of the last dictation's recording, once it has stopped
+pub fn recording_ms() -> Option<u64> {
+    let started = STARTED_AT_MS.load(Ordering::SeqCst);
+    let stopped = STOPPED_AT_MS.load(Ordering::SeqCst);
+    if IS_RECORDING.load(Ordering::SeqCst) || started == 0 || stopped < started {
+        return None;
+    }
+    Some(stopped - started)
+}
+
+/// Record timings once the pipeline finished with a transcript received at `received_ms`
+pub fn finished(received_ms: u64) {
+    let started = STARTED_AT_MS.load(Ordering::SeqCst);
+    let stopped = STOPPED_AT_MS.load(Ordering::SeqCst).max(started);
+    let metrics = LatencyMetrics {
+        recording_ms: stopped - started,
+        transcription_ms: received_ms.saturating_sub(stopped),
+        processing_ms: now_ms().saturating_sub(received_ms),
+        capture: microphone::session_stats(),
+    };
+    if let Ok(mut last) = LAST_METRICS.lock() {
+        *last = Some(metrics);
+    }
+}
+
+pub fn state() -> SessionState {
+    let recording = IS_RECORDING.load(Ordering::SeqCst);
+    let elapsed_ms = if recording {
+        now_ms().saturating_sub(STARTED_AT_MS.load(Ordering::SeqCst))
+    } else {
+        0
+    };
+    SessionState {
+        recording,
+        elapsed_ms,
+        command_mode: recording && voice_commands::is_command_session(),
+        continuous: continuous::is_active(),
+        incognito: recording && incognito::is_active(),
+        partial: recording
+            .then(|| PARTIAL.lock().ok().and_then(|partial| partial.clone()))
+            .flatten(),
+    }
+}
+
+/// Page load hook: a webview that (re)loaded while recording gets the running session
+/// so its UI doesn't fall out of sync with the Rust side
+pub fn on_page_load(webview: &Webview, payload: &PageLoadPayload<'_>) {
+    if payload.event() != PageLoadEvent::Finished || !IS_RECORDING.load(Ordering::SeqCst) {
+        return;
+    }
+    let _ = webview
+        .app_handle()
+        .emit_to(webview.label(), "session-state", state());
+}
+
+#[tauri::command]
+pub async fn get_session_state() -> SessionState {
+    state()
+}
+
+#[tauri::command]
+pub async fn get_latency_metrics() -> Option<LatencyMetrics> {
+    LAST_METRICS.lock().ok().and_then(|last| last.clone())
+}
+
+/// Keep the latest partial transcript so a reloaded window can show it again
+pub fn set_partial(text: String) {
+    if !IS_RECORDING.load(Ordering::SeqCst) {
+        return;
+    }
+    if let Ok(mut partial) = PARTIAL.lock() {
+        *partial = Some(text);
+    }
+}
+
+#[tauri::command]
+pub async fn report_partial_transcript(text: String) {
+    set_partial(text);
+}
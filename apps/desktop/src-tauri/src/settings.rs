@@ -0,0 +1,829 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, PhysicalRect, PhysicalSize};
+
+use crate::app_profiles::AppProfile;
+use crate::app_rules::AppRule;
+use crate::auto_send::DictateAndSendSettings;
+use crate::browser::BrowserContextSettings;
+use crate::char_limit::CharacterLimitMode;
+use crate::clipboard::ClipboardRestoreSettings;
+use crate::command_output::CommandOutputSettings;
+use crate::email::EmailSettings;
+use crate::gestures::ShortcutGestureSettings;
+use crate::latex::LatexSettings;
+use crate::learning::CorrectionLearningSettings;
+use crate::notation::NotationStyle;
+use crate::policy::{self, LockedSetting};
+use crate::postprocess::PostProcessingSettings;
+use crate::project::ProjectRule;
+use crate::providers::{self, ProviderConfig};
+use crate::redaction::{self, RedactionSettings};
+use crate::scratchpad::OutputTarget;
+use crate::search::SearchSettings;
+use crate::shortcuts::{self, ShortcutAction};
+use crate::speech::ReadBackSettings;
+use crate::switch_access::{self, SwitchAccessSettings};
+use crate::transcription;
+use crate::vocabulary::VocabularySettings;
+use crate::wake_word::{self, WakeWordSettings};
+use crate::weekly_summary::WeeklySummarySettings;
+use crate::{i18n, login_item, register_shortcuts, sync, tray, ShortcutConfig};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AppSettings {
+    /// Global shortcuts by what they do; the dictation shortcut falls back to Alt+Space
+    pub shortcuts: BTreeMap<ShortcutAction, ShortcutConfig>,
+    /// Hide the overlay windows and transcript previews while the screen is being shared
+    pub hide_overlay_while_sharing: bool,
+    /// Offer meeting transcription when a meeting app is frontmost and using the microphone
+    pub offer_meeting_transcription: bool,
+    /// Name meeting transcripts after the calendar event they were recorded during
+    pub name_meetings_from_calendar: bool,
+    /// Confirm before pasting when overall transcript confidence is below this value
+    pub low_confidence_threshold: Option<f32>,
+    /// Alternative hypotheses kept with dictations and meetings to pick from later
+    pub alternative_count: u32,
+    pub post_processing: PostProcessingSettings,
+    /// Pass the frontmost app's selected text to post-processing as {selection}
+    pub capture_selection_context: bool,
+    /// Read the active browser tab for post-processing ({url}, {page}) and history
+    pub browser_context: BrowserContextSettings,
+    /// Check transcripts for grammar and style problems with the post-processing model
+    pub grammar_check: bool,
+    /// Words learned from corrections to history entries
+    pub vocabulary: VocabularySettings,
+    /// Spot corrections made to pasted dictations and suggest vocabulary entries
+    pub correction_learning: CorrectionLearningSettings,
+    pub redaction: RedactionSettings,
+    /// Ask for Touch ID or the account password before showing or exporting history
+    pub require_authentication: bool,
+    pub wake_word: WakeWordSettings,
+    /// On-screen button for switch and dwell users that toggles recording
+    pub switch_access: SwitchAccessSettings,
+    /// Record dictations in the backend instead of the webview
+    pub native_capture: bool,
+    /// Microphone recorded from by the backend, None for the OS default
+    pub input_device: Option<String>,
+    pub output_target: OutputTarget,
+    /// Don't keep transcripts in history, typically enforced by a managed policy
+    pub disable_history: bool,
+    /// Language for tray items and messages, None to follow the system
+    pub locale: Option<String>,
+    pub reopen_behavior: ReopenBehavior,
+    /// Quit straight away, finishing the active dictation, instead of asking first
+    pub quit_without_confirmation: bool,
+    /// Opens the history picker overlay, None to leave it unbound
+    pub history_picker_shortcut: Option<ShortcutConfig>,
+    /// Tap and double-press handling on the record shortcut
+    pub shortcut_gestures: ShortcutGestureSettings,
+    /// Separate shortcut that presses Return after pasting, for chat apps
+    pub dictate_and_send: DictateAndSendSettings,
+    /// Per-app standby and "never trigger" rules
+    pub app_rules: Vec<AppRule>,
+    /// Window title rules that tag dictations with a project when none is set explicitly
+    pub project_rules: Vec<ProjectRule>,
+    /// Email layout and signature for dictations into mail apps
+    pub email: EmailSettings,
+    /// LaTeX output and preview for math dictated into TeX editors
+    pub latex: LatexSettings,
+    /// Scheduled notification summing up the past week of dictation
+    pub weekly_summary: WeeklySummarySettings,
+    /// JSON file of remotely rolled out feature flags, None to use only local ones
+    pub feature_flags_url: Option<String>,
+    /// Transcript plugins that run on every dictation, by directory name
+    pub enabled_plugins: Vec<String>,
+    /// Rhai scripts whose hooks run, by file name
+    pub enabled_scripts: Vec<String>,
+    /// External transcription and post-processing engines, see providers.rs
+    pub providers: Vec<ProviderConfig>,
+    /// Let voiceflow:// links start dictations and receive transcripts, for workflow apps
+    pub url_actions_enabled: bool,
+    /// Schemes of further apps voiceflow:// links may send transcripts to, besides the
+    /// built-in workflow apps
+    pub url_callback_schemes: Vec<String>,
+    /// Start VoiceFlow when the user logs in, see login_item.rs
+    pub launch_at_login: bool,
+    /// Command transcripts are piped into when the output target is `command`
+    pub output_command: CommandOutputSettings,
+    /// Speak transcripts aloud before they are pasted, for eyes-free use
+    pub read_back: ReadBackSettings,
+    /// Where the search shortcut sends what was said
+    pub search: SearchSettings,
+    /// Type transcripts as key events instead of pasting them, leaving the clipboard alone
+    pub insert_by_typing: bool,
+    /// Put the previous clipboard contents back after a transcript is pasted
+    pub restore_clipboard: ClipboardRestoreSettings,
+    /// Capitalize and space transcripts to fit the text left of the caret, see
+    /// caret_context.rs
+    pub fit_to_caret_context: bool,
+    /// Per-app overrides by app name or bundle identifier, see app_profiles.rs
+    pub app_profiles: BTreeMap<String, AppProfile>,
+    /// Warn or truncate when a transcript is longer than the field has room for
+    pub character_limit: CharacterLimitMode,
+    /// Paste Markdown from post-processing as formatted text, see markdown.rs
+    pub markdown_rich_text: bool,
+    /// Turn spoken names like "thumbs up emoji" into the emoji or symbol, see symbols.rs
+    pub spoken_symbols: bool,
+    /// Write spoken powers, subscripts, Greek letters and units as notation, see
+    /// notation.rs. App profiles can choose another style.
+    pub scientific_notation: NotationStyle,
+    /// Where on the cursor's screen the bubble appears
+    pub bubble_placement: BubblePlacement,
+}
+
+/// What clicking the dock icon does
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReopenBehavior {
+    /// Open the main app window if nothing is visible
+    #[default]
+    OpenMainApp,
+    ShowBubble,
+    /// Start a dictation, or finish the running one
+    ToggleRecording,
+}
+
+/// Edge of the screen the bubble is shown at
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BubblePlacement {
+    #[default]
+    BottomCenter,
+    BottomRight,
+    TopCenter,
+}
+
+impl BubblePlacement {
+    /// Top left corner for a bubble of `size` inside the screen `area`, `margin` away
+    /// from its edges
+    pub fn origin(
+        self,
+        area: PhysicalRect<i32, u32>,
+        size: PhysicalSize<u32>,
+        margin: i32,
+    ) -> (i32, i32) {
+        let (x, y) = (area.position.x, area.position.y);
+        let (width, height) = (area.size.width as i32, area.size.height as i32);
+        let (bubble_width, bubble_height) = (size.width as i32, size.height as i32);
+        let center = x + (width - bubble_width) / 2;
+        let bottom = y + height - bubble_height - margin;
+        match self {
+            BubblePlacement::BottomCenter => (center, bottom),
+            BubblePlacement::BottomRight => (x + width - bubble_width - margin, bottom),
+            BubblePlacement::TopCenter => (center, y + margin),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsError {
+    /// JSON key of the offending setting, empty when the whole file is unreadable
+    pub field: String,
+    pub message: String,
+}
+
+impl SettingsError {
+    fn new(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl AppSettings {
+    /// Checks serde can't express, such as value ranges
+    pub fn validate(&self) -> Vec<SettingsError> {
+        let mut errors = Vec::new();
+        if let Some(threshold) = self.low_confidence_threshold {
+            if !(0.0..=1.0).contains(&threshold) {
+                errors.push(SettingsError::new(
+                    "lowConfidenceThreshold",
+                    "Must be between 0 and 1",
+                ));
+            }
+        }
+        if self.alternative_count > transcription::MAX_ALTERNATIVES {
+            errors.push(SettingsError::new(
+                "alternativeCount",
+                format!("Must be at most {}", transcription::MAX_ALTERNATIVES),
+            ));
+        }
+        let endpoint = &self.post_processing.endpoint;
+        if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+            errors.push(SettingsError::new(
+                "postProcessing.endpoint",
+                "Must be an http:// or https:// URL",
+            ));
+        }
+        if self.post_processing.model.trim().is_empty() {
+            errors.push(SettingsError::new(
+                "postProcessing.model",
+                "Must not be empty",
+            ));
+        }
+        if !self.wake_word.phrase.chars().any(char::is_alphanumeric) {
+            errors.push(SettingsError::new(
+                "wakeWord.phrase",
+                "Must contain letters or digits",
+            ));
+        }
+        if !self.shortcut_gestures.is_valid() {
+            errors.push(SettingsError::new(
+                "shortcutGestures",
+                "tapMs must be between 100 and 1000, doublePressMs between 150 and 1500",
+            ));
+        }
+        let dwell_ms = self.switch_access.dwell_ms;
+        if !(switch_access::MIN_DWELL_MS..=switch_access::MAX_DWELL_MS).contains(&dwell_ms) {
+            errors.push(SettingsError::new(
+                "switchAccess.dwellMs",
+                "Must be between 300 and 10000",
+            ));
+        }
+        if !self.weekly_summary.is_valid() {
+            errors.push(SettingsError::new(
+                "weeklySummary",
+                "weekday must be between 0 and 6, hour between 0 and 23",
+            ));
+        }
+        if !self.output_command.is_valid() {
+            errors.push(SettingsError::new(
+                "outputCommand.timeoutSecs",
+                "Must be between 1 and 600",
+            ));
+        }
+        if !self.read_back.is_valid() {
+            errors.push(SettingsError::new(
+                "readBack.rate",
+                "Must be between 0.5 and 2",
+            ));
+        }
+        if !self.search.is_valid() {
+            errors.push(SettingsError::new(
+                "search.template",
+                "Must be an http:// or https:// URL containing {text}",
+            ));
+        }
+        if !self.restore_clipboard.is_valid() {
+            errors.push(SettingsError::new(
+                "restoreClipboard.delayMs",
+                "Must be between 50 and 10000",
+            ));
+        }
+        if let Some(url) = &self.feature_flags_url {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                errors.push(SettingsError::new(
+                    "featureFlagsUrl",
+                    "Must be an http:// or https:// URL",
+                ));
+            }
+        }
+        for error in redaction::invalid_patterns(&self.redaction) {
+            errors.push(SettingsError::new("redaction.patterns", error));
+        }
+        for error in providers::invalid_providers(&self.providers) {
+            errors.push(SettingsError::new("providers", error));
+        }
+        errors
+    }
+}
+
+/// Bumped whenever settings.json changes shape; `migrate` upgrades older files
+pub const SETTINGS_VERSION: u64 = 1;
+/// Top-level field holding the version settings.json was written with
+const VERSION_FIELD: &str = "version";
+/// Where shortcuts were kept before version 1
+const LEGACY_SHORTCUT_FILE: &str = "shortcut.json";
+
+fn read_object(content: &str) -> Result<Map<String, Value>, SettingsError> {
+    match serde_json::from_str::<Value>(content) {
+        Ok(Value::Object(object)) => Ok(object),
+        Ok(_) => Err(SettingsError::new("", "Settings must be a JSON object")),
+        Err(e) => Err(SettingsError::new("", format!("Invalid JSON: {e}"))),
+    }
+}
+
+/// Bring a settings object written by an older version up to date. `legacy_shortcuts`
+/// is the content of shortcut.json, if there is one. Returns whether anything was
+/// carried over, so the caller knows to save the result.
+fn migrate(object: &mut Map<String, Value>, legacy_shortcuts: Option<&str>) -> bool {
+    let version = object
+        .get(VERSION_FIELD)
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+    let mut migrated = false;
+    if version < 1 && !object.contains_key("shortcuts") {
+        let shortcuts = legacy_shortcuts
+            .and_then(shortcuts::parse_stored)
+            .and_then(|shortcuts| serde_json::to_value(shortcuts).ok());
+        if let Some(shortcuts) = shortcuts {
+            object.insert("shortcuts".to_string(), shortcuts);
+            migrated = true;
+        }
+    }
+    object.insert(VERSION_FIELD.to_string(), SETTINGS_VERSION.into());
+    migrated
+}
+
+/// Parse settings one top-level field at a time so a single bad value only resets
+/// that field, collecting an error for everything that was rejected
+fn parse_object(mut object: Map<String, Value>) -> (AppSettings, Vec<SettingsError>) {
+    object.remove(VERSION_FIELD);
+    let mut merged = match serde_json::to_value(AppSettings::default()) {
+        Ok(Value::Object(defaults)) => defaults,
+        _ => Map::new(),
+    };
+    let mut errors = Vec::new();
+    for (field, value) in object {
+        if !merged.contains_key(&field) {
+            errors.push(SettingsError::new(&field, "Unknown setting"));
+            continue;
+        }
+        let previous = merged.insert(field.clone(), value);
+        if let Err(e) = serde_json::from_value::<AppSettings>(Value::Object(merged.clone())) {
+            errors.push(SettingsError::new(&field, e.to_string()));
+            if let Some(previous) = previous {
+                merged.insert(field, previous);
+            }
+        }
+    }
+
+    let settings: AppSettings = serde_json::from_value(Value::Object(merged)).unwrap_or_default();
+    errors.extend(settings.validate());
+    (settings, errors)
+}
+
+/// Prefix of environment variables that override settings, e.g.
+/// `VOICEFLOW_LOW_CONFIDENCE_THRESHOLD=0.6` or `VOICEFLOW_POST_PROCESSING__MODEL=qwen3`
+const ENV_PREFIX: &str = "VOICEFLOW_";
+/// Nested settings are addressed with a double underscore
+const ENV_NESTING: &str = "__";
+/// Variables with the prefix that configure something other than settings
+const NON_SETTING_VARS: &[&str] = &[
+    "VOICEFLOW_E2E_LOG",
+    "VOICEFLOW_TEXT",
+    // Read by the sidecar for its default port
+    "VOICEFLOW_PORT",
+    // Launches in demo mode, see demo.rs
+    "VOICEFLOW_DEMO",
+];
+
+/// A value layered over settings.json by the environment or a managed policy
+#[derive(Debug, Clone)]
+pub struct SettingOverride {
+    /// Environment variable or policy key, used when reporting errors
+    pub source: String,
+    pub path: Vec<String>,
+    /// Values tried in order until one deserializes
+    pub candidates: Vec<Value>,
+}
+
+fn camel_case(segment: &str) -> String {
+    let mut result = String::new();
+    for (index, word) in segment
+        .split('_')
+        .filter(|word| !word.is_empty())
+        .enumerate()
+    {
+        let word = word.to_lowercase();
+        if index == 0 {
+            result.push_str(&word);
+        } else {
+            let mut chars = word.chars();
+            if let Some(first) = chars.next() {
+                result.extend(first.to_uppercase());
+                result.push_str(chars.as_str());
+            }
+        }
+    }
+    result
+}
+
+fn env_overrides(vars: impl Iterator<Item = (String, String)>) -> Vec<SettingOverride> {
+    let mut overrides: Vec<SettingOverride> = vars
+        .filter(|(var, _)| !NON_SETTING_VARS.contains(&var.as_str()))
+        .filter_map(|(var, raw)| {
+            let name = var.strip_prefix(ENV_PREFIX)?;
+            let path = name.split(ENV_NESTING).map(camel_case).collect();
+            // Values are JSON when they parse as such, so "0.6", "true" and "null" work,
+            // with the raw text as a fallback for string settings
+            let mut candidates: Vec<Value> = serde_json::from_str(&raw).into_iter().collect();
+            candidates.push(Value::String(raw));
+            Some(SettingOverride {
+                source: var,
+                path,
+                candidates,
+            })
+        })
+        .collect();
+    // Deterministic precedence when a nested override also sets its parent
+    overrides.sort_by(|a, b| {
+        a.path
+            .len()
+            .cmp(&b.path.len())
+            .then(a.source.cmp(&b.source))
+    });
+    overrides
+}
+
+fn set_path(target: &mut Value, path: &[String], value: Value) -> bool {
+    let Some((last, parents)) = path.split_last() else {
+        return false;
+    };
+    let mut current = target;
+    for key in parents {
+        match current.get_mut(key) {
+            Some(next) if next.is_object() => current = next,
+            _ => return false,
+        }
+    }
+    match current.as_object_mut() {
+        Some(object) if object.contains_key(last) => {
+            object.insert(last.clone(), value);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Layer overrides over the file settings, later overrides winning over earlier ones
+fn apply_overrides(
+    base: &AppSettings,
+    overrides: &[SettingOverride],
+) -> (AppSettings, Vec<SettingsError>) {
+    let mut merged = serde_json::to_value(base).unwrap_or_default();
+    let mut errors = Vec::new();
+
+    for entry in overrides {
+        let mut last_error = None;
+        let mut applied = false;
+        for value in entry.candidates.iter().cloned() {
+            let mut candidate = merged.clone();
+            if !set_path(&mut candidate, &entry.path, value) {
+                last_error = Some("Unknown setting".to_string());
+                break;
+            }
+            match serde_json::from_value::<AppSettings>(candidate.clone()) {
+                Ok(_) => {
+                    merged = candidate;
+                    applied = true;
+                    break;
+                }
+                Err(e) => last_error = Some(e.to_string()),
+            }
+        }
+        if !applied {
+            let message = last_error.unwrap_or_default();
+            errors.push(SettingsError::new(&entry.source, message));
+        }
+    }
+
+    let settings: AppSettings = serde_json::from_value(merged).unwrap_or_else(|_| base.clone());
+    errors.extend(settings.validate().into_iter().filter(|error| {
+        !base
+            .validate()
+            .iter()
+            .any(|known| known.field == error.field)
+    }));
+    (settings, errors)
+}
+
+/// Top-level settings that are only read at launch, so changing them needs `relaunch_app`.
+/// These shortcuts are registered with the OS at startup and by their own setters, which
+/// `update_settings` doesn't go through.
+const RESTART_REQUIRED_FIELDS: &[&str] = &["historyPickerShortcut", "dictateAndSend"];
+
+fn restart_required(before: &AppSettings, after: &AppSettings) -> bool {
+    let (Ok(Value::Object(before)), Ok(Value::Object(after))) =
+        (serde_json::to_value(before), serde_json::to_value(after))
+    else {
+        return false;
+    };
+    RESTART_REQUIRED_FIELDS
+        .iter()
+        .any(|field| before.get(*field) != after.get(*field))
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsUpdate {
+    pub settings: AppSettings,
+    /// The change only takes effect after `relaunch_app`
+    pub restart_required: bool,
+}
+
+pub struct SettingsManager {
+    /// Effective settings, including environment overrides
+    settings: AppSettings,
+    /// What settings.json holds, the only part that is ever saved
+    file_settings: AppSettings,
+    /// Environment overrides followed by managed policy, so policy has the last word
+    overrides: Vec<SettingOverride>,
+    locked: Vec<LockedSetting>,
+    settings_path: PathBuf,
+}
+
+impl SettingsManager {
+    pub fn new(config_dir: PathBuf) -> Self {
+        let settings_path = config_dir.join("settings.json");
+        let (file_settings, mut errors, migrated) = Self::load_settings(&settings_path);
+        let locked = policy::load();
+        let mut overrides = env_overrides(std::env::vars());
+        overrides.extend(locked.iter().map(LockedSetting::to_override));
+        let (settings, env_errors) = apply_overrides(&file_settings, &overrides);
+        errors.extend(env_errors);
+        for error in &errors {
+            eprintln!(
+                "[voiceflow] Invalid setting {}: {}",
+                error.field, error.message
+            );
+        }
+        let manager = Self {
+            settings,
+            file_settings,
+            overrides,
+            locked,
+            settings_path,
+        };
+        if migrated {
+            manager.finish_migration(&config_dir);
+        }
+        manager
+    }
+
+    /// Settings from settings.json, migrated to the current version, and whether
+    /// anything was migrated
+    fn load_settings(path: &Path) -> (AppSettings, Vec<SettingsError>, bool) {
+        let mut object = match fs::read_to_string(path) {
+            Ok(content) => match read_object(&content) {
+                Ok(object) => object,
+                Err(error) => return (AppSettings::default(), vec![error], false),
+            },
+            Err(_) => Map::new(),
+        };
+        let legacy_shortcuts = path
+            .parent()
+            .and_then(|dir| fs::read_to_string(dir.join(LEGACY_SHORTCUT_FILE)).ok());
+        let migrated = migrate(&mut object, legacy_shortcuts.as_deref());
+        let (settings, errors) = parse_object(object);
+        (settings, errors, migrated)
+    }
+
+    /// Save what was migrated and retire the files it came from, keeping them as
+    /// *.migrated
+    fn finish_migration(&self, config_dir: &Path) {
+        if let Err(e) = self.save_settings() {
+            eprintln!("[voiceflow] Failed to save migrated settings: {e}");
+            return;
+        }
+        let legacy = config_dir.join(LEGACY_SHORTCUT_FILE);
+        if legacy.exists() {
+            if let Err(e) = fs::rename(&legacy, legacy.with_extension("json.migrated")) {
+                eprintln!("[voiceflow] Failed to retire {LEGACY_SHORTCUT_FILE}: {e}");
+            }
+        }
+    }
+
+    fn save_settings(&self) -> Result<(), String> {
+        let mut value = serde_json::to_value(&self.file_settings).map_err(|e| e.to_string())?;
+        if let Value::Object(object) = &mut value {
+            object.insert(VERSION_FIELD.to_string(), SETTINGS_VERSION.into());
+        }
+        let content = serde_json::to_string_pretty(&value).map_err(|e| e.to_string())?;
+        sync::write_atomic(&self.settings_path, content.as_bytes())
+    }
+
+    pub fn get(&self) -> AppSettings {
+        self.settings.clone()
+    }
+
+    pub fn settings_path(&self) -> &Path {
+        &self.settings_path
+    }
+
+    /// Everything rejected in settings.json and the overrides as they are now
+    pub fn file_errors(&self) -> Vec<SettingsError> {
+        let (file_settings, mut errors, _) = Self::load_settings(&self.settings_path);
+        errors.extend(apply_overrides(&file_settings, &self.overrides).1);
+        errors
+    }
+
+    pub fn locked(&self) -> Vec<LockedSetting> {
+        self.locked.clone()
+    }
+
+    pub fn update(&mut self, apply: impl FnOnce(&mut AppSettings)) -> Result<AppSettings, String> {
+        // Changes go to the file; environment and policy overrides keep winning
+        apply(&mut self.file_settings);
+        self.save_settings()?;
+        self.settings = apply_overrides(&self.file_settings, &self.overrides).0;
+        Ok(self.settings.clone())
+    }
+}
+
+/// Snapshot of the persisted settings, falling back to defaults if the lock is poisoned
+pub fn current(app: &AppHandle) -> AppSettings {
+    let state = app.state::<Mutex<SettingsManager>>();
+    let settings = state.lock().map(|manager| manager.get());
+    settings.unwrap_or_default()
+}
+
+pub fn update(
+    app: &AppHandle,
+    apply: impl FnOnce(&mut AppSettings),
+) -> Result<AppSettings, String> {
+    let updated = {
+        let state = app.state::<Mutex<SettingsManager>>();
+        let mut manager = state.lock().map_err(|e| e.to_string())?;
+        manager.update(apply)?
+    };
+    let _ = app.emit("settings-changed", &updated);
+    Ok(updated)
+}
+
+#[tauri::command]
+pub async fn get_settings(app: AppHandle) -> AppSettings {
+    current(&app)
+}
+
+/// Replace the saved settings, rejecting values that fail validation
+#[tauri::command]
+pub async fn update_settings(
+    app: AppHandle,
+    settings: AppSettings,
+) -> Result<SettingsUpdate, String> {
+    if let Some(error) = settings.validate().into_iter().next() {
+        return Err(format!("{}: {}", error.field, error.message));
+    }
+    let before = current(&app);
+    // Only set_require_authentication may switch the lock, after authenticating
+    if settings.require_authentication != before.require_authentication {
+        return Err(i18n::t("error.authentication_setting"));
+    }
+    let updated = update(&app, |current| *current = settings)?;
+    // Apply the parts that individual setters normally take care of
+    if before.locale != updated.locale {
+        i18n::init(&app);
+        tray::refresh_menu(&app);
+    }
+    if before.shortcuts != updated.shortcuts {
+        register_shortcuts(&app);
+        tray::refresh_menu(&app);
+    }
+    if before.launch_at_login != updated.launch_at_login {
+        login_item::apply(&app, updated.launch_at_login)?;
+    }
+    wake_word::start_listener(app.clone());
+    switch_access::start(app.clone());
+    Ok(SettingsUpdate {
+        restart_required: restart_required(&before, &updated),
+        settings: updated,
+    })
+}
+
+/// Re-read settings.json and report every field that is rejected or out of range
+#[tauri::command]
+pub async fn validate_settings(app: AppHandle) -> Result<Vec<SettingsError>, String> {
+    let state = app.state::<Mutex<SettingsManager>>();
+    let manager = state.lock().map_err(|e| e.to_string())?;
+    Ok(manager.file_errors())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tauri::PhysicalPosition;
+
+    fn parse_settings(content: &str) -> (AppSettings, Vec<SettingsError>) {
+        match read_object(content) {
+            Ok(object) => parse_object(object),
+            Err(error) => (AppSettings::default(), vec![error]),
+        }
+    }
+
+    #[test]
+    fn keeps_valid_fields_when_one_is_invalid() {
+        let (settings, errors) = parse_settings(
+            r#"{"offerMeetingTranscription": true, "lowConfidenceThreshold": "high"}"#,
+        );
+        assert!(settings.offer_meeting_transcription);
+        assert_eq!(settings.low_confidence_threshold, None);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "lowConfidenceThreshold");
+    }
+
+    #[test]
+    fn reports_unknown_and_out_of_range_fields() {
+        let (_, errors) = parse_settings(r#"{"hideOverlay": true, "lowConfidenceThreshold": 2.0}"#);
+        let fields: Vec<&str> = errors.iter().map(|error| error.field.as_str()).collect();
+        assert_eq!(fields, ["hideOverlay", "lowConfidenceThreshold"]);
+    }
+
+    #[test]
+    fn only_shortcuts_registered_at_launch_need_a_restart() {
+        let before = AppSettings::default();
+        let (picker, _) =
+            parse_settings(r#"{"historyPickerShortcut": {"modifiers": ["Alt"], "key": "H"}}"#);
+        let (sharing, _) = parse_settings(r#"{"hideOverlayWhileSharing": true}"#);
+        assert!(restart_required(&before, &picker));
+        assert!(!restart_required(&before, &sharing));
+    }
+
+    #[test]
+    fn moves_shortcuts_out_of_shortcut_json() {
+        let mut object = read_object(r#"{"grammarCheck": true}"#).unwrap();
+        let legacy = r#"{"modifiers": ["Alt", "Shift"], "key": "D"}"#;
+        assert!(migrate(&mut object, Some(legacy)));
+        assert_eq!(object[VERSION_FIELD], SETTINGS_VERSION);
+
+        let (settings, errors) = parse_object(object.clone());
+        assert!(errors.is_empty());
+        assert!(settings.grammar_check);
+        assert_eq!(settings.shortcuts[&ShortcutAction::Dictate].key, "D");
+
+        // Files already at the current version are left alone
+        assert!(!migrate(
+            &mut object,
+            Some(r#"{"modifiers": [], "key": "X"}"#)
+        ));
+        assert_eq!(object["shortcuts"]["dictate"]["key"], "D");
+    }
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<SettingOverride> {
+        env_overrides(
+            pairs
+                .iter()
+                .map(|(var, value)| (var.to_string(), value.to_string())),
+        )
+    }
+
+    #[test]
+    fn environment_overrides_file_values() {
+        let (file, _) = parse_settings(r#"{"lowConfidenceThreshold": 0.3}"#);
+        let overrides = vars(&[
+            ("VOICEFLOW_LOW_CONFIDENCE_THRESHOLD", "0.6"),
+            ("VOICEFLOW_POST_PROCESSING__MODEL", "42"),
+            ("VOICEFLOW_E2E_LOG", "/tmp/e2e.log"),
+            ("VOICEFLOW_PORT", "9000"),
+            ("VOICEFLOW_DEMO", "1"),
+        ]);
+        let (settings, errors) = apply_overrides(&file, &overrides);
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(settings.low_confidence_threshold, Some(0.6));
+        assert_eq!(settings.post_processing.model, "42");
+    }
+
+    #[test]
+    fn reports_invalid_environment_overrides() {
+        let overrides = vars(&[
+            ("VOICEFLOW_NOT_A_SETTING", "1"),
+            ("VOICEFLOW_HIDE_OVERLAY_WHILE_SHARING", "sometimes"),
+        ]);
+        let (settings, errors) = apply_overrides(&AppSettings::default(), &overrides);
+        assert!(!settings.hide_overlay_while_sharing);
+        let fields: Vec<&str> = errors.iter().map(|error| error.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            [
+                "VOICEFLOW_HIDE_OVERLAY_WHILE_SHARING",
+                "VOICEFLOW_NOT_A_SETTING"
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_unparseable_file() {
+        let (_, errors) = parse_settings("{");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "");
+    }
+
+    #[test]
+    fn places_the_bubble_inside_the_work_area() {
+        let area = PhysicalRect {
+            position: PhysicalPosition::new(1920, 25),
+            size: PhysicalSize::new(1600, 1000),
+        };
+        let bubble = PhysicalSize::new(200, 60);
+        assert_eq!(
+            BubblePlacement::BottomCenter.origin(area, bubble, 24),
+            (2620, 941)
+        );
+        assert_eq!(
+            BubblePlacement::BottomRight.origin(area, bubble, 24),
+            (3296, 941)
+        );
+        assert_eq!(
+            BubblePlacement::TopCenter.origin(area, bubble, 24),
+            (2620, 49)
+        );
+    }
+}
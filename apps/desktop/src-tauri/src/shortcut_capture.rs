@@ -0,0 +1,335 @@
+use std::time::Duration;
+use tauri::AppHandle;
+
+use crate::{i18n, keys, register_shortcuts, ShortcutConfig};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Key names understood by `ShortcutConfig::to_shortcut`, by macOS virtual key code
+#[cfg(target_os = "macos")]
+const KEY_CODES: &[(i64, &str)] = &[
+    (0, "A"),
+    (1, "S"),
+    (2, "D"),
+    (3, "F"),
+    (4, "H"),
+    (5, "G"),
+    (6, "Z"),
+    (7, "X"),
+    (8, "C"),
+    (9, "V"),
+    (11, "B"),
+    (12, "Q"),
+    (13, "W"),
+    (14, "E"),
+    (15, "R"),
+    (16, "Y"),
+    (17, "T"),
+    (18, "1"),
+    (19, "2"),
+    (20, "3"),
+    (21, "4"),
+    (22, "6"),
+    (23, "5"),
+    (24, "Equal"),
+    (25, "9"),
+    (26, "7"),
+    (27, "Minus"),
+    (28, "8"),
+    (29, "0"),
+    (30, "BracketRight"),
+    (31, "O"),
+    (32, "U"),
+    (33, "BracketLeft"),
+    (34, "I"),
+    (35, "P"),
+    (36, "Enter"),
+    (37, "L"),
+    (38, "J"),
+    (39, "Quote"),
+    (40, "K"),
+    (41, "Semicolon"),
+    (42, "Backslash"),
+    (43, "Comma"),
+    (44, "Slash"),
+    (45, "N"),
+    (46, "M"),
+    (47, "Period"),
+    (48, "Tab"),
+    (49, "Space"),
+    (50, "Backquote"),
+    (51, "Backspace"),
+    (57, "CapsLock"),
+    (64, "F17"),
+    (65, "NumpadDecimal"),
+    (67, "NumpadMultiply"),
+    (69, "NumpadAdd"),
+    (70, "PrintScreen"),
+    (71, "NumLock"),
+    (72, "AudioVolumeUp"),
+    (73, "AudioVolumeDown"),
+    (74, "AudioVolumeMute"),
+    (75, "NumpadDivide"),
+    (76, "NumpadEnter"),
+    (78, "NumpadSubtract"),
+    (79, "F18"),
+    (80, "F19"),
+    (81, "NumpadEqual"),
+    (82, "Numpad0"),
+    (83, "Numpad1"),
+    (84, "Numpad2"),
+    (85, "Numpad3"),
+    (86, "Numpad4"),
+    (87, "Numpad5"),
+    (88, "Numpad6"),
+    (89, "Numpad7"),
+    (90, "F20"),
+    (91, "Numpad8"),
+    (92, "Numpad9"),
+    (96, "F5"),
+    (97, "F6"),
+    (98, "F7"),
+    (99, "F3"),
+    (100, "F8"),
+    (101, "F9"),
+    (103, "F11"),
+    (105, "F13"),
+    (106, "F16"),
+    (107, "F14"),
+    (109, "F10"),
+    (111, "F12"),
+    (113, "F15"),
+    (114, "Insert"),
+    (115, "Home"),
+    (116, "PageUp"),
+    (117, "Delete"),
+    (118, "F4"),
+    (119, "End"),
+    (120, "F2"),
+    (121, "PageDown"),
+    (122, "F1"),
+    (123, "ArrowLeft"),
+    (124, "ArrowRight"),
+    (125, "ArrowDown"),
+    (126, "ArrowUp"),
+];
+
+#[cfg(target_os = "macos")]
+mod tap {
+    use std::ffi::c_void;
+    use std::ptr;
+    use std::sync::atomic::{AtomicPtr, Ordering};
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    use super::KEY_CODES;
+    use crate::{i18n, ShortcutConfig};
+
+    type TapCallback = extern "C" fn(*mut c_void, u32, *mut c_void, *mut c_void) -> *mut c_void;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventTapCreate(
+            tap: u32,
+            place: u32,
+            options: u32,
+            events_of_interest: u64,
+            callback: TapCallback,
+            user_info: *mut c_void,
+        ) -> *mut c_void;
+        fn CGEventTapEnable(tap: *mut c_void, enable: bool);
+        fn CGEventGetIntegerValueField(event: *mut c_void, field: u32) -> i64;
+        fn CGEventGetFlags(event: *mut c_void) -> u64;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        static kCFRunLoopDefaultMode: *const c_void;
+        fn CFMachPortCreateRunLoopSource(
+            allocator: *const c_void,
+            port: *mut c_void,
+            order: isize,
+        ) -> *mut c_void;
+        fn CFMachPortInvalidate(port: *mut c_void);
+        fn CFRunLoopGetCurrent() -> *mut c_void;
+        fn CFRunLoopAddSource(run_loop: *mut c_void, source: *mut c_void, mode: *const c_void);
+        fn CFRunLoopRemoveSource(run_loop: *mut c_void, source: *mut c_void, mode: *const c_void);
+        fn CFRunLoopRunInMode(mode: *const c_void, seconds: f64, return_after_source: bool) -> i32;
+        fn CFRunLoopStop(run_loop: *mut c_void);
+        fn CFRelease(cf: *const c_void);
+    }
+
+    const SESSION_EVENT_TAP: u32 = 1;
+    const HEAD_INSERT_EVENT_TAP: u32 = 0;
+    const TAP_OPTION_DEFAULT: u32 = 0;
+    const EVENT_KEY_DOWN: u32 = 10;
+    const EVENT_TAP_DISABLED_BY_TIMEOUT: u32 = 0xFFFF_FFFE;
+    const KEYBOARD_EVENT_KEYCODE: u32 = 9;
+    const KEY_CODE_ESCAPE: i64 = 53;
+
+    const FLAG_SHIFT: u64 = 0x0002_0000;
+    const FLAG_CONTROL: u64 = 0x0004_0000;
+    const FLAG_ALTERNATE: u64 = 0x0008_0000;
+    const FLAG_COMMAND: u64 = 0x0010_0000;
+
+    /// What the tap saw: a key combination, or None when Escape cancelled the capture
+    static CAPTURED: Mutex<Option<Option<ShortcutConfig>>> = Mutex::new(None);
+    static TAP: AtomicPtr<c_void> = AtomicPtr::new(ptr::null_mut());
+    static RUN_LOOP: AtomicPtr<c_void> = AtomicPtr::new(ptr::null_mut());
+
+    fn config_for(key_code: i64, flags: u64) -> Option<Option<ShortcutConfig>> {
+        if key_code == KEY_CODE_ESCAPE {
+            return Some(None);
+        }
+        let (_, key) = KEY_CODES.iter().find(|(code, _)| *code == key_code)?;
+        let modifiers = [
+            (FLAG_CONTROL, "Ctrl"),
+            (FLAG_ALTERNATE, "Alt"),
+            (FLAG_SHIFT, "Shift"),
+            (FLAG_COMMAND, "Super"),
+        ]
+        .iter()
+        .filter(|(flag, _)| flags & flag != 0)
+        .map(|(_, name)| name.to_string())
+        .collect();
+        Some(Some(ShortcutConfig {
+            modifiers,
+            key: key.to_string(),
+            prompt_template: None,
+            incognito: false,
+            mode: crate::ShortcutMode::Hold,
+            target_app: None,
+        }))
+    }
+
+    extern "C" fn callback(
+        _proxy: *mut c_void,
+        event_type: u32,
+        event: *mut c_void,
+        _user_info: *mut c_void,
+    ) -> *mut c_void {
+        if event_type == EVENT_TAP_DISABLED_BY_TIMEOUT {
+            // SAFETY: TAP holds the live tap for as long as the run loop runs
+            unsafe { CGEventTapEnable(TAP.load(Ordering::SeqCst), true) };
+            return event;
+        }
+        if event_type != EVENT_KEY_DOWN {
+            return event;
+        }
+
+        // SAFETY: key-down events always carry a keycode and flags
+        let (key_code, flags) = unsafe {
+            (
+                CGEventGetIntegerValueField(event, KEYBOARD_EVENT_KEYCODE),
+                CGEventGetFlags(event),
+            )
+        };
+        let Some(captured) = config_for(key_code, flags) else {
+            // Unsupported key, keep listening but don't let it reach other apps
+            return ptr::null_mut();
+        };
+        if let Ok(mut slot) = CAPTURED.lock() {
+            *slot = Some(captured);
+        }
+        // SAFETY: RUN_LOOP is the capturing thread's run loop, alive until capture returns
+        unsafe { CFRunLoopStop(RUN_LOOP.load(Ordering::SeqCst)) };
+        // Swallow the combination so it doesn't type into the settings field
+        ptr::null_mut()
+    }
+
+    /// Block until a key combination is pressed, Escape cancels, or the timeout passes
+    pub fn capture(timeout: Duration) -> Result<Option<ShortcutConfig>, String> {
+        if let Ok(mut slot) = CAPTURED.lock() {
+            *slot = None;
+        }
+
+        // SAFETY: every CoreFoundation object created here is released before returning,
+        // and the statics are cleared once the run loop no longer calls back
+        unsafe {
+            let tap = CGEventTapCreate(
+                SESSION_EVENT_TAP,
+                HEAD_INSERT_EVENT_TAP,
+                TAP_OPTION_DEFAULT,
+                1 << EVENT_KEY_DOWN,
+                callback,
+                ptr::null_mut(),
+            );
+            if tap.is_null() {
+                return Err(i18n::t("error.shortcut_capture_permission"));
+            }
+            let source = CFMachPortCreateRunLoopSource(ptr::null(), tap, 0);
+            let run_loop = CFRunLoopGetCurrent();
+            TAP.store(tap, Ordering::SeqCst);
+            RUN_LOOP.store(run_loop, Ordering::SeqCst);
+            CFRunLoopAddSource(run_loop, source, kCFRunLoopDefaultMode);
+            CGEventTapEnable(tap, true);
+
+            let deadline = Instant::now() + timeout;
+            loop {
+                let captured = CAPTURED.lock().map(|slot| slot.is_some()).unwrap_or(true);
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if captured || remaining.is_zero() {
+                    break;
+                }
+                CFRunLoopRunInMode(kCFRunLoopDefaultMode, remaining.as_secs_f64(), true);
+            }
+
+            CGEventTapEnable(tap, false);
+            CFRunLoopRemoveSource(run_loop, source, kCFRunLoopDefaultMode);
+            CFMachPortInvalidate(tap);
+            CFRelease(source);
+            CFRelease(tap);
+            TAP.store(ptr::null_mut(), Ordering::SeqCst);
+            RUN_LOOP.store(ptr::null_mut(), Ordering::SeqCst);
+        }
+
+        let captured = CAPTURED.lock().map_err(|e| e.to_string())?.take();
+        captured.ok_or_else(|| i18n::t("error.shortcut_capture_timeout"))
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn capture(timeout: Duration) -> Result<Option<ShortcutConfig>, String> {
+    tap::capture(timeout)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn capture(_timeout: Duration) -> Result<Option<ShortcutConfig>, String> {
+    Err(i18n::t("error.shortcut_capture_unsupported"))
+}
+
+/// Reject combinations that can't be registered or would swallow ordinary typing
+fn validate(config: &ShortcutConfig) -> Result<(), String> {
+    if config.to_shortcut().is_none() {
+        return Err(i18n::tf(
+            "error.shortcut_key_unsupported",
+            &[("key", &config.key)],
+        ));
+    }
+    let is_function_key = keys::parse(&config.key).is_some_and(keys::is_function_key);
+    if config.modifiers.is_empty() && !is_function_key {
+        return Err(i18n::t("error.shortcut_needs_modifier"));
+    }
+    Ok(())
+}
+
+/// Wait for the next key combination, returning None if the user pressed Escape.
+/// Global shortcuts are suspended meanwhile so the current one can be captured too.
+#[tauri::command]
+pub async fn capture_next_shortcut(
+    app: AppHandle,
+    timeout_ms: Option<u64>,
+) -> Result<Option<ShortcutConfig>, String> {
+    let timeout = timeout_ms.map_or(DEFAULT_TIMEOUT, Duration::from_millis);
+    let _ = tauri_plugin_global_shortcut::GlobalShortcutExt::global_shortcut(&app).unregister_all();
+    let captured = tauri::async_runtime::spawn_blocking(move || capture(timeout))
+        .await
+        .map_err(|e| e.to_string());
+    register_shortcuts(&app);
+
+    let captured = captured??;
+    if let Some(config) = &captured {
+        validate(config)?;
+    }
+    Ok(captured)
+}
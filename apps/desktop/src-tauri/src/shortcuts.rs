@@ -0,0 +1,189 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::{
+    current_shortcut_config, i18n, register_shortcuts, settings, tray, voice_commands,
+    ShortcutConfig,
+};
+
+/// What a named shortcut in settings.json does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShortcutAction {
+    /// Record a dictation and paste it
+    Dictate,
+    /// Record a spoken command instead of text, see voice_commands
+    CommandMode,
+    /// Drop the running dictation without transcribing it
+    Cancel,
+    /// Record a search query and open it in the browser, see search.rs
+    Search,
+}
+
+impl ShortcutAction {
+    pub const ALL: [ShortcutAction; 4] =
+        [Self::Dictate, Self::CommandMode, Self::Cancel, Self::Search];
+
+    /// Event emitted when the shortcut is pressed or released, so the webview can tell
+    /// command mode from dictation
+    pub fn event(self) -> &'static str {
+        match self {
+            Self::Dictate => "shortcut-dictate",
+            Self::CommandMode => "shortcut-command-mode",
+            Self::Cancel => "shortcut-cancel",
+            Self::Search => "shortcut-search",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutEvent {
+    pub pressed: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredShortcuts {
+    /// shortcut.json first held only the dictation shortcut
+    Single(ShortcutConfig),
+    Named(BTreeMap<ShortcutAction, ShortcutConfig>),
+}
+
+/// Named shortcuts from shortcut.json in either format, for moving them to settings
+pub fn parse_stored(content: &str) -> Option<BTreeMap<ShortcutAction, ShortcutConfig>> {
+    match serde_json::from_str(content).ok()? {
+        StoredShortcuts::Single(config) => {
+            Some(BTreeMap::from([(ShortcutAction::Dictate, config)]))
+        }
+        StoredShortcuts::Named(shortcuts) => Some(shortcuts),
+    }
+}
+
+/// Config of the shortcut bound to `action` in the active profile
+pub fn config_for(app: &AppHandle, action: ShortcutAction) -> Option<ShortcutConfig> {
+    match action {
+        ShortcutAction::Dictate => Some(current_shortcut_config(app)),
+        // May still live in voice_commands.json
+        ShortcutAction::CommandMode => voice_commands::command_shortcut_config(app),
+        ShortcutAction::Cancel | ShortcutAction::Search => stored(app, action),
+    }
+}
+
+/// Shortcut as saved in settings, without fallbacks
+pub fn stored(app: &AppHandle, action: ShortcutAction) -> Option<ShortcutConfig> {
+    settings::current(app).shortcuts.get(&action).cloned()
+}
+
+pub fn shortcut(app: &AppHandle, action: ShortcutAction) -> Option<Shortcut> {
+    config_for(app, action)?.to_shortcut()
+}
+
+/// Which named shortcut was pressed, if any
+pub fn action_for(app: &AppHandle, pressed: &Shortcut) -> Option<ShortcutAction> {
+    ShortcutAction::ALL
+        .into_iter()
+        .find(|action| shortcut(app, *action).as_ref() == Some(pressed))
+}
+
+pub fn emit(app: &AppHandle, action: ShortcutAction, state: ShortcutState) {
+    let event = ShortcutEvent {
+        pressed: state == ShortcutState::Pressed,
+    };
+    let _ = app.emit(action.event(), event);
+}
+
+/// Bind `action` to `config`, or unbind it when None. The dictation shortcut can't be
+/// removed, and no two actions can share a combination.
+pub fn set(
+    app: &AppHandle,
+    action: ShortcutAction,
+    config: Option<ShortcutConfig>,
+) -> Result<(), String> {
+    if action == ShortcutAction::Dictate && config.is_none() {
+        return Err(i18n::t("error.dictation_shortcut_required"));
+    }
+    if let Some(config) = &config {
+        let new_shortcut = config
+            .to_shortcut()
+            .ok_or_else(|| i18n::t("error.invalid_shortcut"))?;
+        let taken = ShortcutAction::ALL
+            .into_iter()
+            .filter(|other| *other != action)
+            .find(|other| shortcut(app, *other) == Some(new_shortcut));
+        if taken.is_some() {
+            return Err(i18n::t("error.shortcut_in_use"));
+        }
+        // Registering proves the combination is free; register_shortcuts redoes it below
+        let registered = app.global_shortcut().is_registered(new_shortcut);
+        if !registered {
+            app.global_shortcut()
+                .register(new_shortcut)
+                .map_err(|e| i18n::tf("error.register_shortcut", &[("error", &e.to_string())]))?;
+        }
+    }
+
+    settings::update(app, |settings| match config {
+        Some(config) => {
+            settings.shortcuts.insert(action, config);
+        }
+        None => {
+            settings.shortcuts.remove(&action);
+        }
+    })?;
+    if action == ShortcutAction::CommandMode {
+        voice_commands::clear_legacy_shortcut(app)?;
+    }
+    register_shortcuts(app);
+    tray::refresh_menu(app);
+    Ok(())
+}
+
+/// Every named shortcut of the active profile, unbound ones left out
+#[tauri::command]
+pub async fn get_shortcuts(app: AppHandle) -> BTreeMap<ShortcutAction, ShortcutConfig> {
+    ShortcutAction::ALL
+        .into_iter()
+        .filter_map(|action| Some((action, config_for(&app, action)?)))
+        .collect()
+}
+
+#[tauri::command]
+pub async fn set_named_shortcut(
+    app: AppHandle,
+    action: ShortcutAction,
+    shortcut: Option<ShortcutConfig>,
+) -> Result<(), String> {
+    set(&app, action, shortcut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_single_shortcut_format() {
+        let single = r#"{"modifiers": ["Alt"], "key": "Space"}"#;
+        let shortcuts = parse_stored(single).unwrap();
+        assert_eq!(shortcuts.len(), 1);
+        assert_eq!(shortcuts[&ShortcutAction::Dictate].key, "Space");
+    }
+
+    #[test]
+    fn reads_and_writes_named_shortcuts() {
+        let named = r#"{
+            "dictate": {"modifiers": ["Alt"], "key": "Space"},
+            "cancel": {"modifiers": ["Alt"], "key": "Escape"}
+        }"#;
+        let shortcuts = parse_stored(named).unwrap();
+        assert_eq!(shortcuts[&ShortcutAction::Cancel].key, "Escape");
+        assert!(!shortcuts.contains_key(&ShortcutAction::CommandMode));
+
+        let saved = serde_json::to_string(&shortcuts).unwrap();
+        assert!(saved.contains("\"cancel\""));
+        assert_eq!(parse_stored(&saved).unwrap().len(), 2);
+        assert!(parse_stored("[]").is_none());
+    }
+}
@@ -0,0 +1,126 @@
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::batch::BatchJobStore;
+use crate::history::HistoryStore;
+use crate::scratchpad::Scratchpad;
+use crate::{
+    append_e2e_log, continuous, demo, settings, show_or_create_main_app, stop_recording,
+    stop_sidecar, IS_RECORDING,
+};
+
+/// How long to wait for the webview to transcribe and record the final dictation
+const FINISH_TIMEOUT: Duration = Duration::from_secs(10);
+
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+// Set once cleanup is done so the final exit request isn't intercepted again
+static COMPLETE: AtomicBool = AtomicBool::new(false);
+static SESSIONS_RECORDED: AtomicU64 = AtomicU64::new(0);
+
+/// Held by `record_transcription` so shutdown can wait for the dictation in flight
+pub struct SessionGuard;
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        SESSIONS_RECORDED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+pub fn is_complete() -> bool {
+    COMPLETE.load(Ordering::SeqCst)
+}
+
+/// Quit from the tray or an exit request, asking the webview first if a dictation is running
+pub fn request_quit(app: &AppHandle) {
+    if SHUTTING_DOWN.load(Ordering::SeqCst) {
+        return;
+    }
+    let recording = IS_RECORDING.load(Ordering::SeqCst);
+    if recording && !settings::current(app).quit_without_confirmation {
+        append_e2e_log("quit-confirmation-requested");
+        show_or_create_main_app(app);
+        let _ = app.emit("quit-confirmation-requested", ());
+        return;
+    }
+    let app = app.clone();
+    thread::spawn(move || shutdown(&app, true, Then::Exit));
+}
+
+/// What to do once everything is wound down
+enum Then {
+    Exit,
+    Restart,
+}
+
+/// Wind down in order: finish or cancel the active dictation, wait for pending
+/// writes, stop the sidecar and exit or relaunch
+fn shutdown(app: &AppHandle, finish_session: bool, then: Then) {
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    append_e2e_log("shutdown-start");
+
+    if continuous::is_active() {
+        continuous::stop(app);
+    }
+    if IS_RECORDING.load(Ordering::SeqCst) {
+        if finish_session {
+            finish_active_session(app);
+        } else {
+            IS_RECORDING.store(false, Ordering::SeqCst);
+            let _ = app.emit("recording-cancel", ());
+            append_e2e_log("shutdown-session-cancelled");
+        }
+    }
+
+    // Stores write through on every change, so holding each lock once waits out a write in progress
+    drop(app.state::<Mutex<HistoryStore>>().lock());
+    drop(app.state::<Mutex<BatchJobStore>>().lock());
+    drop(app.state::<Mutex<Scratchpad>>().lock());
+    let _ = std::io::stderr().flush();
+
+    if let Err(error) = stop_sidecar(app) {
+        eprintln!("[voiceflow] Failed to stop sidecar during shutdown: {error}");
+    }
+    // A demo relaunch starts from scratch as well
+    demo::wipe();
+    append_e2e_log("shutdown-complete");
+    COMPLETE.store(true, Ordering::SeqCst);
+    match then {
+        Then::Exit => app.exit(0),
+        Then::Restart => app.restart(),
+    }
+}
+
+/// Stop recording and give the webview time to transcribe and record the result
+fn finish_active_session(app: &AppHandle) {
+    let recorded = SESSIONS_RECORDED.load(Ordering::SeqCst);
+    stop_recording(app);
+
+    let deadline = Instant::now() + FINISH_TIMEOUT;
+    while SESSIONS_RECORDED.load(Ordering::SeqCst) == recorded {
+        if Instant::now() >= deadline {
+            eprintln!("[voiceflow] Gave up waiting for the final dictation before quitting");
+            append_e2e_log("shutdown-session-timeout");
+            return;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    append_e2e_log("shutdown-session-finished");
+}
+
+/// Answer to "quit-confirmation-requested": quit keeping or discarding the active dictation
+#[tauri::command]
+pub async fn quit_app(app: AppHandle, finish_session: bool) {
+    tauri::async_runtime::spawn_blocking(move || shutdown(&app, finish_session, Then::Exit));
+}
+
+/// Restart the app after a change that only applies at launch, finishing any active dictation
+#[tauri::command]
+pub async fn relaunch_app(app: AppHandle) {
+    tauri::async_runtime::spawn_blocking(move || shutdown(&app, true, Then::Restart));
+}
@@ -0,0 +1,265 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::voice_commands::normalize_phrase;
+use crate::{form_fill, i18n, insertion, prompts};
+
+pub const SNIPPET_HISTORY_TAG: &str = "snippet";
+
+/// Placeholders VoiceFlow fills in itself; any other `{name}` is asked for
+const BUILTIN_PLACEHOLDERS: &[&str] = &["date", "time", "weekday"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snippet {
+    pub name: String,
+    /// Spoken phrase that inserts the snippet when it is the whole transcript
+    pub trigger: String,
+    /// Text to insert; supports {date}, {time}, {weekday}, custom `{name}` fill-ins and
+    /// numbered `{1:Label}` fields dictated one by one after insertion
+    pub body: String,
+}
+
+/// Sent when a triggered snippet has fill-ins the user has to provide
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnippetFillRequest {
+    pub snippet: Snippet,
+    pub placeholders: Vec<String>,
+}
+
+pub struct SnippetManager {
+    snippets: Vec<Snippet>,
+    snippets_path: PathBuf,
+}
+
+impl SnippetManager {
+    pub fn new(config_dir: PathBuf) -> Self {
+        let snippets_path = config_dir.join("snippets.json");
+        let snippets = Self::load_snippets(&snippets_path).unwrap_or_default();
+        Self {
+            snippets,
+            snippets_path,
+        }
+    }
+
+    fn load_snippets(path: &PathBuf) -> Option<Vec<Snippet>> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn save_snippets(&self) -> Result<(), String> {
+        if let Some(parent) = self.snippets_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string_pretty(&self.snippets).map_err(|e| e.to_string())?;
+        fs::write(&self.snippets_path, content).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    pub fn list(&self) -> Vec<Snippet> {
+        self.snippets.clone()
+    }
+
+    fn get(&self, name: &str) -> Option<Snippet> {
+        self.snippets
+            .iter()
+            .find(|snippet| snippet.name == name)
+            .cloned()
+    }
+
+    /// Snippet whose trigger phrase is exactly what was said
+    pub fn find_triggered(&self, transcript: &str) -> Option<Snippet> {
+        let spoken = normalize_phrase(transcript);
+        self.snippets
+            .iter()
+            .find(|snippet| normalize_phrase(&snippet.trigger) == spoken)
+            .cloned()
+    }
+
+    fn save(&mut self, snippet: Snippet) -> Result<(), String> {
+        if let Some(other) = self.snippets.iter().find(|other| {
+            other.name != snippet.name
+                && normalize_phrase(&other.trigger) == normalize_phrase(&snippet.trigger)
+        }) {
+            return Err(i18n::tf(
+                "error.snippet_trigger_taken",
+                &[("name", &other.name)],
+            ));
+        }
+        match self
+            .snippets
+            .iter_mut()
+            .find(|existing| existing.name == snippet.name)
+        {
+            Some(existing) => *existing = snippet,
+            None => self.snippets.push(snippet),
+        }
+        self.save_snippets()
+    }
+
+    fn delete(&mut self, name: &str) -> Result<(), String> {
+        let before = self.snippets.len();
+        self.snippets.retain(|snippet| snippet.name != name);
+        if self.snippets.len() == before {
+            return Err(i18n::tf("error.snippet_not_found", &[("name", name)]));
+        }
+        self.save_snippets()
+    }
+}
+
+/// Distinct `{name}` placeholders in the body, in order of first appearance
+pub fn placeholders(body: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        let name = &rest[..end];
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+        if valid && !names.iter().any(|existing| existing == name) {
+            names.push(name.to_string());
+        }
+        if valid {
+            rest = &rest[end + 1..];
+        }
+    }
+    names
+}
+
+/// Placeholders the user has to provide, i.e. all but the built-in ones and the
+/// numbered fields that are dictated after insertion
+pub fn fill_ins(body: &str) -> Vec<String> {
+    placeholders(body)
+        .into_iter()
+        .filter(|name| !BUILTIN_PLACEHOLDERS.contains(&name.as_str()))
+        .filter(|name| name.parse::<u32>().is_err())
+        .collect()
+}
+
+/// Paste the rendered snippet, or start dictating its numbered fields if it has any
+pub fn insert(app: &AppHandle, text: &str) -> Result<(), String> {
+    if form_fill::has_fields(text) {
+        return form_fill::start(app, text);
+    }
+    let (strategy, result) = insertion::paste_or_type(app, Some(text));
+    insertion::report(app, None, strategy, Some(text.to_string()), &result);
+    result
+}
+
+/// Fill the built-in placeholders plus the given values into the body
+pub fn render(body: &str, values: &HashMap<String, String>) -> String {
+    let now = Local::now();
+    let mut variables: HashMap<&str, String> = HashMap::from([
+        ("date", now.format("%x").to_string()),
+        ("time", now.format("%H:%M").to_string()),
+        ("weekday", now.format("%A").to_string()),
+    ]);
+    for (name, value) in values {
+        variables.insert(name, value.clone());
+    }
+    prompts::render(body, &variables)
+}
+
+pub fn find_triggered(app: &AppHandle, transcript: &str) -> Option<Snippet> {
+    let state = app.state::<Mutex<SnippetManager>>();
+    let manager = state.lock().ok()?;
+    manager.find_triggered(transcript)
+}
+
+/// Ask the frontend for the snippet's fill-ins, returning false when it has none
+pub fn request_fill_ins(app: &AppHandle, snippet: &Snippet) -> bool {
+    let placeholders = fill_ins(&snippet.body);
+    if placeholders.is_empty() {
+        return false;
+    }
+    let request = SnippetFillRequest {
+        snippet: snippet.clone(),
+        placeholders,
+    };
+    let _ = app.emit("snippet-fill-requested", &request);
+    true
+}
+
+#[tauri::command]
+pub async fn list_snippets(app: AppHandle) -> Result<Vec<Snippet>, String> {
+    let state = app.state::<Mutex<SnippetManager>>();
+    let manager = state.lock().map_err(|e| e.to_string())?;
+    Ok(manager.list())
+}
+
+#[tauri::command]
+pub async fn save_snippet(
+    app: AppHandle,
+    name: String,
+    trigger: String,
+    body: String,
+) -> Result<Vec<Snippet>, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(i18n::t("error.snippet_name_empty"));
+    }
+    if normalize_phrase(&trigger).is_empty() {
+        return Err(i18n::t("error.snippet_trigger_empty"));
+    }
+    let state = app.state::<Mutex<SnippetManager>>();
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.save(Snippet {
+        name,
+        trigger: trigger.trim().to_string(),
+        body,
+    })?;
+    Ok(manager.list())
+}
+
+#[tauri::command]
+pub async fn delete_snippet(app: AppHandle, name: String) -> Result<Vec<Snippet>, String> {
+    let state = app.state::<Mutex<SnippetManager>>();
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.delete(&name)?;
+    Ok(manager.list())
+}
+
+/// Insert a snippet into the frontmost app once the user has filled in its placeholders
+#[tauri::command]
+pub async fn fill_snippet(
+    app: AppHandle,
+    name: String,
+    values: HashMap<String, String>,
+) -> Result<String, String> {
+    let snippet = {
+        let state = app.state::<Mutex<SnippetManager>>();
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager
+            .get(&name)
+            .ok_or_else(|| i18n::tf("error.snippet_not_found", &[("name", &name)]))?
+    };
+    let text = render(&snippet.body, &values);
+    insert(&app, &text)?;
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_placeholders_and_fill_ins() {
+        let body = "Dear {name}, on {date} {name} ordered {item}. {not valid} {} {1}";
+        assert_eq!(placeholders(body), vec!["name", "date", "item", "1"]);
+        assert_eq!(fill_ins(body), vec!["name", "item"]);
+
+        let values = HashMap::from([("name".to_string(), "Ada".to_string())]);
+        assert_eq!(render("Hi {name}, {item}", &values), "Hi Ada, {item}");
+    }
+}
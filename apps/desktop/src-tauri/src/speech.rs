@@ -0,0 +1,362 @@
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::{i18n, settings};
+
+// Text-to-speech through the OS: AVSpeechSynthesizer on macOS, SAPI through
+// System.Speech on Windows and speech-dispatcher's spd-say elsewhere. Used to read
+// transcripts back before they are pasted, for users who can't see the screen.
+
+pub const MIN_RATE: f32 = 0.5;
+pub const MAX_RATE: f32 = 2.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ReadBackSettings {
+    /// Speak each transcript and ask before pasting it
+    pub enabled: bool,
+    /// Voice identifier from `list_voices`, or a language code such as "de-DE" on
+    /// macOS; None for the system voice
+    pub voice: Option<String>,
+    /// Speed relative to the voice's normal rate
+    pub rate: f32,
+}
+
+impl Default for ReadBackSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            voice: None,
+            rate: 1.0,
+        }
+    }
+}
+
+impl ReadBackSettings {
+    pub fn is_valid(&self) -> bool {
+        (MIN_RATE..=MAX_RATE).contains(&self.rate)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Voice {
+    /// What goes in `ReadBackSettings::voice`
+    pub id: String,
+    pub name: String,
+    pub language: String,
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use cocoa::base::{id, nil, BOOL};
+    use cocoa::foundation::NSString;
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::ffi::CStr;
+    use std::os::raw::c_char;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::Voice;
+
+    #[link(name = "AVFoundation", kind = "framework")]
+    extern "C" {}
+
+    // AVSpeechUtteranceDefaultSpeechRate, on a scale from 0 to 1
+    const DEFAULT_RATE: f32 = 0.5;
+    // AVSpeechBoundaryImmediate
+    const BOUNDARY_IMMEDIATE: isize = 0;
+
+    // A synthesizer stops talking when it is released, so one is kept for the session
+    static SYNTHESIZER: AtomicUsize = AtomicUsize::new(0);
+
+    /// SAFETY: call on the main thread, which is the only one touching SYNTHESIZER
+    unsafe fn synthesizer() -> id {
+        let existing = SYNTHESIZER.load(Ordering::SeqCst);
+        if existing != 0 {
+            return existing as id;
+        }
+        let created: id = msg_send![class!(AVSpeechSynthesizer), new];
+        SYNTHESIZER.store(created as usize, Ordering::SeqCst);
+        created
+    }
+
+    /// SAFETY: `string` must be nil or an NSString
+    unsafe fn to_string(string: id) -> String {
+        if string == nil {
+            return String::new();
+        }
+        let utf8: *const c_char = msg_send![string, UTF8String];
+        if utf8.is_null() {
+            return String::new();
+        }
+        CStr::from_ptr(utf8).to_string_lossy().into_owned()
+    }
+
+    /// Must run on the main thread
+    pub fn speak(text: &str, voice: Option<&str>, rate: f32) -> Result<(), String> {
+        // SAFETY: on the main thread; the strings created here are released once the
+        // utterance has copied them, and the utterance itself is autoreleased
+        unsafe {
+            let synthesizer = synthesizer();
+            let _: BOOL = msg_send![synthesizer, stopSpeakingAtBoundary: BOUNDARY_IMMEDIATE];
+            let string = NSString::alloc(nil).init_str(text);
+            let utterance: id =
+                msg_send![class!(AVSpeechUtterance), speechUtteranceWithString: string];
+            let _: () = msg_send![string, release];
+            if let Some(voice) = voice {
+                let identifier = NSString::alloc(nil).init_str(voice);
+                let mut selected: id =
+                    msg_send![class!(AVSpeechSynthesisVoice), voiceWithIdentifier: identifier];
+                if selected == nil {
+                    selected =
+                        msg_send![class!(AVSpeechSynthesisVoice), voiceWithLanguage: identifier];
+                }
+                let _: () = msg_send![identifier, release];
+                if selected != nil {
+                    let _: () = msg_send![utterance, setVoice: selected];
+                }
+            }
+            let rate = (DEFAULT_RATE * rate).clamp(0.0, 1.0);
+            let _: () = msg_send![utterance, setRate: rate];
+            let _: () = msg_send![synthesizer, speakUtterance: utterance];
+        }
+        Ok(())
+    }
+
+    /// Must run on the main thread
+    pub fn stop() {
+        let synthesizer = SYNTHESIZER.load(Ordering::SeqCst);
+        if synthesizer == 0 {
+            return;
+        }
+        // SAFETY: the synthesizer is never released once created
+        unsafe {
+            let _: BOOL = msg_send![synthesizer as id, stopSpeakingAtBoundary: BOUNDARY_IMMEDIATE];
+        }
+    }
+
+    pub fn voices() -> Vec<Voice> {
+        // SAFETY: speechVoices returns an autoreleased NSArray of AVSpeechSynthesisVoice,
+        // whose properties are NSStrings
+        unsafe {
+            let voices: id = msg_send![class!(AVSpeechSynthesisVoice), speechVoices];
+            let count: usize = msg_send![voices, count];
+            (0..count)
+                .map(|index| {
+                    let voice: id = msg_send![voices, objectAtIndex: index];
+                    Voice {
+                        id: to_string(msg_send![voice, identifier]),
+                        name: to_string(msg_send![voice, name]),
+                        language: to_string(msg_send![voice, language]),
+                    }
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    use std::io::Write;
+    use std::process::{Child, Command, Stdio};
+    use std::sync::Mutex;
+    use std::thread;
+
+    use super::Voice;
+    use crate::i18n;
+
+    // The process speaking right now, killed when something else is spoken
+    static SPEAKER: Mutex<Option<Child>> = Mutex::new(None);
+
+    // Reads the text from stdin; the voice and rate come in through the environment
+    // so neither has to be quoted into the script
+    #[cfg(target_os = "windows")]
+    const SPEAK_SCRIPT: &str = "[Console]::InputEncoding = [Text.Encoding]::UTF8; \
+        Add-Type -AssemblyName System.Speech; \
+        $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; \
+        if ($env:VOICEFLOW_VOICE) { $s.SelectVoice($env:VOICEFLOW_VOICE) }; \
+        $s.Rate = [int]$env:VOICEFLOW_RATE; \
+        $s.Speak([Console]::In.ReadToEnd())";
+
+    #[cfg(target_os = "windows")]
+    const VOICES_SCRIPT: &str = "[Console]::OutputEncoding = [Text.Encoding]::UTF8; \
+        Add-Type -AssemblyName System.Speech; \
+        (New-Object System.Speech.Synthesis.SpeechSynthesizer).GetInstalledVoices() | \
+        ForEach-Object { $_.VoiceInfo.Name + \"`t\" + $_.VoiceInfo.Culture.Name }";
+
+    /// SAPI rates run from -10 to 10, where 10 is about three times as fast
+    #[cfg(target_os = "windows")]
+    fn command(voice: Option<&str>, rate: f32) -> Command {
+        let rate = (rate.log(3.0) * 10.0).round().clamp(-10.0, 10.0) as i32;
+        let mut command = Command::new("powershell");
+        command
+            .args(["-NoProfile", "-NonInteractive", "-Command", SPEAK_SCRIPT])
+            .env("VOICEFLOW_VOICE", voice.unwrap_or_default())
+            .env("VOICEFLOW_RATE", rate.to_string());
+        command
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    pub(super) fn spd_rate(rate: f32) -> i32 {
+        ((rate - 1.0) * 100.0).round().clamp(-100.0, 100.0) as i32
+    }
+
+    /// spd-say rates run from -100 to 100 around the voice's normal rate
+    #[cfg(not(target_os = "windows"))]
+    fn command(voice: Option<&str>, rate: f32) -> Command {
+        let mut command = Command::new("spd-say");
+        // Pipe mode reads the text from stdin, so it can't be mistaken for an option
+        command.args([
+            "--pipe-mode",
+            "--wait",
+            "--rate",
+            &spd_rate(rate).to_string(),
+        ]);
+        if let Some(voice) = voice {
+            command.args(["--synthesis-voice", voice]);
+        }
+        command
+    }
+
+    pub fn speak(text: &str, voice: Option<&str>, rate: f32) -> Result<(), String> {
+        stop();
+        let mut child = command(voice, rate)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| i18n::tf("error.speech_unavailable", &[("error", &e.to_string())]))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            let text = text.to_string();
+            thread::spawn(move || {
+                let _ = stdin.write_all(text.as_bytes());
+            });
+        }
+        if let Ok(mut speaker) = SPEAKER.lock() {
+            *speaker = Some(child);
+        }
+        Ok(())
+    }
+
+    pub fn stop() {
+        let speaker = SPEAKER.lock().ok().and_then(|mut speaker| speaker.take());
+        if let Some(mut child) = speaker {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn voices() -> Vec<Voice> {
+        let Ok(output) = Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", VOICES_SCRIPT])
+            .output()
+        else {
+            return Vec::new();
+        };
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let (name, language) = line.trim().split_once('\t')?;
+                Some(Voice {
+                    id: name.to_string(),
+                    name: name.to_string(),
+                    language: language.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// speech-dispatcher's voices depend on its output module and aren't listed here;
+    /// any name `spd-say --synthesis-voice` accepts can still be set
+    #[cfg(not(target_os = "windows"))]
+    pub fn voices() -> Vec<Voice> {
+        Vec::new()
+    }
+}
+
+/// Speak `text` with the read-back voice and rate, interrupting anything still being
+/// spoken
+pub fn speak(app: &AppHandle, text: &str) -> Result<(), String> {
+    let config = settings::current(app).read_back;
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Ok(());
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let _ = app.run_on_main_thread(move || {
+            let _ = platform::speak(&text, config.voice.as_deref(), config.rate);
+        });
+        Ok(())
+    }
+    #[cfg(not(target_os = "macos"))]
+    platform::speak(&text, config.voice.as_deref(), config.rate)
+}
+
+/// Stop speaking, e.g. so a new dictation doesn't record the read-back
+pub fn stop(app: &AppHandle) {
+    #[cfg(target_os = "macos")]
+    let _ = app.run_on_main_thread(platform::stop);
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = app;
+        platform::stop();
+    }
+}
+
+/// Read a finished transcript back when that is turned on. Returns whether it is being
+/// spoken, in which case the paste should wait for the user's confirmation.
+pub fn read_back(app: &AppHandle, text: &str) -> bool {
+    if !settings::current(app).read_back.enabled {
+        return false;
+    }
+    match speak(app, text) {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("[voiceflow] Read-back failed: {e}");
+            false
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn speak_text(app: AppHandle, text: String) -> Result<(), String> {
+    speak(&app, &text)
+}
+
+#[tauri::command]
+pub async fn stop_speaking(app: AppHandle) {
+    stop(&app);
+}
+
+/// Voices the OS can read transcripts back with
+#[tauri::command]
+pub async fn list_voices() -> Vec<Voice> {
+    platform::voices()
+}
+
+#[tauri::command]
+pub async fn set_read_back(
+    app: AppHandle,
+    config: ReadBackSettings,
+) -> Result<ReadBackSettings, String> {
+    if !config.is_valid() {
+        return Err(i18n::t("error.read_back_rate"));
+    }
+    let updated = settings::update(&app, |settings| settings.read_back = config)?;
+    Ok(updated.read_back)
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_rates_onto_spd_say() {
+        assert_eq!(platform::spd_rate(1.0), 0);
+        assert_eq!(platform::spd_rate(MIN_RATE), -50);
+        assert_eq!(platform::spd_rate(MAX_RATE), 100);
+        assert!(ReadBackSettings::default().is_valid());
+    }
+}
@@ -0,0 +1,154 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter};
+
+static SPELLING_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Spoken before the letters to spell a single dictation. A bare "spell" would catch
+/// ordinary sentences like "spell check this paragraph".
+const SPOKEN_PREFIXES: &[&str] = &["spell mode", "spelling mode"];
+
+const NATO_ALPHABET: &[&str] = &[
+    "alpha", "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel", "india", "juliett",
+    "kilo", "lima", "mike", "november", "oscar", "papa", "quebec", "romeo", "sierra", "tango",
+    "uniform", "victor", "whiskey", "xray", "yankee", "zulu",
+];
+
+const DIGITS: &[&str] = &[
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+fn symbol(word: &str) -> Option<&'static str> {
+    Some(match word {
+        "at" => "@",
+        "dot" | "period" | "point" => ".",
+        "dash" | "hyphen" | "minus" => "-",
+        "underscore" => "_",
+        "plus" => "+",
+        "slash" => "/",
+        "colon" => ":",
+        "space" => " ",
+        _ => return None,
+    })
+}
+
+fn letter(word: &str) -> Option<char> {
+    let word = match word {
+        "juliet" => "juliett",
+        "x-ray" => "xray",
+        "whisky" => "whiskey",
+        "alfa" => "alpha",
+        other => other,
+    };
+    if let Some(index) = NATO_ALPHABET.iter().position(|&name| name == word) {
+        return Some((b'a' + index as u8) as char);
+    }
+    let mut chars = word.chars();
+    match (chars.next(), chars.next()) {
+        (Some(single), None) if single.is_ascii_alphanumeric() => Some(single),
+        _ => None,
+    }
+}
+
+/// Turn letter-by-letter dictation ("alpha bravo at gmail dot com") into literal text
+pub fn spell_out(text: &str) -> String {
+    let mut output = String::new();
+    let mut uppercase_next = false;
+
+    for raw in text.split_whitespace() {
+        let word = raw
+            .trim_matches(|c: char| matches!(c, ',' | '.' | '!' | '?' | ';'))
+            .to_lowercase();
+        if word.is_empty() {
+            continue;
+        }
+
+        if matches!(word.as_str(), "capital" | "uppercase" | "cap") {
+            uppercase_next = true;
+            continue;
+        }
+
+        if let Some(digit) = DIGITS.iter().position(|&name| name == word) {
+            output.push_str(&digit.to_string());
+        } else if let Some(symbol) = symbol(&word) {
+            output.push_str(symbol);
+        } else if let Some(letter) = letter(&word) {
+            if uppercase_next {
+                output.extend(letter.to_uppercase());
+            } else {
+                output.push(letter);
+            }
+        } else {
+            // Whole words such as domains ("gmail") are kept literally
+            output.push_str(&word);
+        }
+        uppercase_next = false;
+    }
+    output
+}
+
+/// The rest of `text` after a spoken prefix ("Spell mode, alpha bravo"), None without one
+fn after_prefix(text: &str) -> Option<&str> {
+    SPOKEN_PREFIXES.iter().find_map(|prefix| {
+        let head = text.get(..prefix.len())?;
+        let rest = &text[prefix.len()..];
+        let whole_words = rest
+            .chars()
+            .next()
+            .is_none_or(|next| next.is_whitespace() || matches!(next, ',' | ':' | '.'));
+        (head.eq_ignore_ascii_case(prefix) && whole_words)
+            .then(|| rest.trim_start_matches([',', ':', '.']))
+    })
+}
+
+/// Spell the transcript out when spelling mode is on or it starts with "spell mode …"
+pub fn apply(text: &str) -> Option<String> {
+    let trimmed = text.trim();
+    let spoken = after_prefix(trimmed);
+
+    match spoken {
+        Some(rest) => Some(spell_out(rest)),
+        None if SPELLING_MODE.load(Ordering::SeqCst) => Some(spell_out(trimmed)),
+        None => None,
+    }
+}
+
+#[tauri::command]
+pub async fn set_spelling_mode(app: AppHandle, enabled: bool) {
+    SPELLING_MODE.store(enabled, Ordering::SeqCst);
+    let _ = app.emit("spelling-mode-changed", enabled);
+}
+
+#[tauri::command]
+pub async fn toggle_spelling_mode(app: AppHandle) -> bool {
+    let enabled = !SPELLING_MODE.fetch_xor(true, Ordering::SeqCst);
+    let _ = app.emit("spelling-mode-changed", enabled);
+    enabled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spells_email_addresses() {
+        assert_eq!(spell_out("alpha bravo at gmail dot com"), "ab@gmail.com");
+    }
+
+    #[test]
+    fn handles_capitals_digits_and_single_letters() {
+        assert_eq!(spell_out("Capital J o h n underscore two."), "John_2");
+        assert_eq!(spell_out("x-ray dash nine"), "x-9");
+    }
+
+    #[test]
+    fn only_an_explicit_phrase_spells_one_dictation() {
+        assert_eq!(
+            after_prefix("Spell mode, alpha bravo."),
+            Some(" alpha bravo.")
+        );
+        assert_eq!(after_prefix("spelling mode x-ray"), Some(" x-ray"));
+        assert_eq!(after_prefix("Spell check this paragraph"), None);
+        assert_eq!(after_prefix("Spelling is hard"), None);
+        assert_eq!(after_prefix("spell modes differ"), None);
+    }
+}
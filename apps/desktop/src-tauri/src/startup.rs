@@ -0,0 +1,286 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::diagnostics::PermissionReport;
+use crate::history::HistoryEntry;
+use crate::history_db::HistoryDb;
+use crate::postprocess::PostProcessingSettings;
+use crate::session::now_ms;
+use crate::settings::{self, SettingsManager};
+
+const SIDECAR: &str = "voiceflow-server";
+/// Hugging Face cache entry of the transcription model the sidecar loads
+const MODEL_CACHE: &str = ".cache/huggingface/hub/models--mlx-community--parakeet-tdt-0.6b-v3";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckStatus {
+    Ok,
+    /// Something was wrong and has been fixed
+    Repaired,
+    /// Works, but not as configured or not yet
+    Warning,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupCheck {
+    /// settings, history, model, sidecar or permissions
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: Option<String>,
+}
+
+impl StartupCheck {
+    fn new(name: &str, status: CheckStatus, detail: Option<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            detail,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupReport {
+    pub checks: Vec<StartupCheck>,
+    /// No check failed
+    pub healthy: bool,
+}
+
+/// Move an unreadable file aside so it isn't overwritten by a fresh one
+fn back_up(path: &Path) -> Result<PathBuf, String> {
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("file");
+    let backup = path.with_file_name(format!("{name}.corrupt-{}", now_ms()));
+    fs::rename(path, &backup).map_err(|e| e.to_string())?;
+    Ok(backup)
+}
+
+/// Result of the directory audit diagnostics ran before anything else
+pub fn permissions(report: &PermissionReport) -> StartupCheck {
+    let unfixed: Vec<String> = report
+        .issues
+        .iter()
+        .filter(|issue| !issue.fixed)
+        .map(|issue| format!("{}: {}", issue.path, issue.issue))
+        .collect();
+    if !unfixed.is_empty() {
+        return StartupCheck::new(
+            "permissions",
+            CheckStatus::Warning,
+            Some(unfixed.join("\n")),
+        );
+    }
+    if report.issues.is_empty() {
+        return StartupCheck::new("permissions", CheckStatus::Ok, None);
+    }
+    let detail = format!("Tightened access to {} paths", report.issues.len());
+    StartupCheck::new("permissions", CheckStatus::Repaired, Some(detail))
+}
+
+fn moved_aside(path: &Path, problem: &str) -> StartupCheck {
+    match back_up(path) {
+        Ok(backup) => StartupCheck::new(
+            "history",
+            CheckStatus::Repaired,
+            Some(format!("{problem}, moved to {}", backup.display())),
+        ),
+        Err(e) => StartupCheck::new(
+            "history",
+            CheckStatus::Failed,
+            Some(format!("{problem}, backup failed: {e}")),
+        ),
+    }
+}
+
+/// Must run before the history store opens, which starts over on a database it
+/// can't read and only migrates a JSON history it can parse
+pub fn history(data_dir: &Path) -> StartupCheck {
+    let legacy = data_dir.join("history.json");
+    if let Ok(content) = fs::read_to_string(&legacy) {
+        if let Err(e) = serde_json::from_str::<Vec<HistoryEntry>>(&content) {
+            return moved_aside(&legacy, &format!("Unreadable history.json ({e})"));
+        }
+    }
+    let path = data_dir.join("history.db");
+    if !path.exists() {
+        return StartupCheck::new("history", CheckStatus::Ok, None);
+    }
+    match HistoryDb::open(&path) {
+        Ok(db) if db.is_intact() => StartupCheck::new("history", CheckStatus::Ok, None),
+        Ok(db) => {
+            drop(db);
+            moved_aside(&path, "history.db failed its integrity check")
+        }
+        Err(e) => moved_aside(&path, &format!("history.db can't be opened ({e})")),
+    }
+}
+
+fn settings(app: &AppHandle) -> StartupCheck {
+    let state = app.state::<Mutex<SettingsManager>>();
+    let Ok(manager) = state.lock() else {
+        return StartupCheck::new("settings", CheckStatus::Failed, None);
+    };
+    let errors = manager.file_errors();
+    if errors.is_empty() {
+        return StartupCheck::new("settings", CheckStatus::Ok, None);
+    }
+    // An empty field means the whole file was rejected and defaults are in use
+    if errors.iter().any(|error| error.field.is_empty()) {
+        let detail = match back_up(manager.settings_path()) {
+            Ok(backup) => format!("Unreadable settings moved to {}", backup.display()),
+            Err(e) => format!("Unreadable settings, backup failed: {e}"),
+        };
+        return StartupCheck::new("settings", CheckStatus::Repaired, Some(detail));
+    }
+    let fields: Vec<&str> = errors.iter().map(|error| error.field.as_str()).collect();
+    let detail = format!("Invalid settings: {}", fields.join(", "));
+    StartupCheck::new("settings", CheckStatus::Warning, Some(detail))
+}
+
+/// The transcription model is downloaded by the sidecar on first start; the
+/// post-processing model falls back to the default when none is set
+fn models(app: &AppHandle) -> StartupCheck {
+    let mut repaired = Vec::new();
+    if settings::current(app)
+        .post_processing
+        .model
+        .trim()
+        .is_empty()
+    {
+        let model = PostProcessingSettings::default().model;
+        match settings::update(app, |settings| {
+            settings.post_processing.model = model.clone()
+        }) {
+            Ok(_) => repaired.push(format!("Post-processing model set to {model}")),
+            Err(e) => eprintln!("[voiceflow] Failed to restore post-processing model: {e}"),
+        }
+    }
+    let cached = std::env::var_os("HOME")
+        .map(|home| PathBuf::from(home).join(MODEL_CACHE))
+        .is_some_and(|cache| cache.is_dir());
+    if !cached {
+        let detail = "Transcription model not downloaded yet, the first start takes longer";
+        repaired.push(detail.to_string());
+        return StartupCheck::new("model", CheckStatus::Warning, Some(repaired.join("\n")));
+    }
+    if repaired.is_empty() {
+        return StartupCheck::new("model", CheckStatus::Ok, None);
+    }
+    StartupCheck::new("model", CheckStatus::Repaired, Some(repaired.join("\n")))
+}
+
+/// The shell plugin runs sidecars from next to the app executable
+fn sidecar_path() -> Option<PathBuf> {
+    let dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
+    Some(dir.join(format!("{SIDECAR}{}", std::env::consts::EXE_SUFFIX)))
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<bool, String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = fs::metadata(path)
+        .map_err(|e| e.to_string())?
+        .permissions()
+        .mode();
+    if mode & 0o100 != 0 {
+        return Ok(false);
+    }
+    fs::set_permissions(path, fs::Permissions::from_mode(mode | 0o100))
+        .map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<bool, String> {
+    Ok(false)
+}
+
+fn sidecar() -> StartupCheck {
+    let Some(path) = sidecar_path().filter(|path| path.is_file()) else {
+        let detail = format!("{SIDECAR} is missing, transcription won't work");
+        return StartupCheck::new("sidecar", CheckStatus::Failed, Some(detail));
+    };
+    match make_executable(&path) {
+        Ok(false) => StartupCheck::new("sidecar", CheckStatus::Ok, None),
+        Ok(true) => StartupCheck::new(
+            "sidecar",
+            CheckStatus::Repaired,
+            Some(format!("Made {} executable", path.display())),
+        ),
+        Err(e) => StartupCheck::new(
+            "sidecar",
+            CheckStatus::Failed,
+            Some(format!("{} is not executable: {e}", path.display())),
+        ),
+    }
+}
+
+/// Run the remaining checks once settings are loaded and keep the report for the UI
+pub fn finish(app: &AppHandle, mut checks: Vec<StartupCheck>) {
+    // Models first, so a restored model no longer counts as an invalid setting
+    let models = models(app);
+    checks.push(settings(app));
+    checks.push(models);
+    checks.push(sidecar());
+    for check in checks
+        .iter()
+        .filter(|check| check.status != CheckStatus::Ok)
+    {
+        eprintln!(
+            "[voiceflow] Startup check {}: {:?} {}",
+            check.name,
+            check.status,
+            check.detail.as_deref().unwrap_or_default()
+        );
+    }
+    let report = StartupReport {
+        healthy: checks
+            .iter()
+            .all(|check| check.status != CheckStatus::Failed),
+        checks,
+    };
+    app.manage(Mutex::new(report));
+}
+
+#[tauri::command]
+pub async fn get_startup_report(app: AppHandle) -> Result<StartupReport, String> {
+    let state = app.state::<Mutex<StartupReport>>();
+    let report = state.lock().map_err(|e| e.to_string())?;
+    Ok(report.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_unreadable_history_aside() {
+        let dir = std::env::temp_dir().join(format!("voiceflow-startup-test-{}", now_ms()));
+        fs::create_dir_all(&dir).unwrap();
+        assert_eq!(history(&dir).status, CheckStatus::Ok);
+
+        fs::write(dir.join("history.json"), "[]").unwrap();
+        assert_eq!(history(&dir).status, CheckStatus::Ok);
+
+        fs::write(dir.join("history.json"), "[{\"id\": ").unwrap();
+        assert_eq!(history(&dir).status, CheckStatus::Repaired);
+        assert!(!dir.join("history.json").exists());
+
+        fs::write(dir.join("history.db"), "not a database").unwrap();
+        assert_eq!(history(&dir).status, CheckStatus::Repaired);
+        assert!(!dir.join("history.db").exists());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
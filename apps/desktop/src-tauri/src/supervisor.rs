@@ -0,0 +1,188 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::transcription;
+use crate::{append_e2e_log, ensure_sidecar_running, ServerManager};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(2);
+/// The health endpoint only answers once the server has imported its dependencies
+const STARTUP_GRACE: Duration = Duration::from_secs(60);
+/// Failed health checks in a row before a running sidecar counts as hung
+const MAX_FAILED_CHECKS: u32 = 3;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ServerStatus {
+    /// Not running and not supposed to be
+    #[default]
+    Stopped,
+    /// Spawned, the health endpoint hasn't answered yet
+    Starting,
+    Healthy,
+    /// Exited or stopped answering while it should be running
+    Crashed,
+    /// Waiting out the backoff before starting it again
+    Restarting,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStatusEvent {
+    pub status: ServerStatus,
+    /// Automatic restarts since the app started
+    pub restarts: u32,
+}
+
+/// Delay before restart attempt `attempt`, counting from 0 since the sidecar was last healthy
+pub fn backoff(attempt: u32) -> Duration {
+    INITIAL_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_BACKOFF)
+}
+
+/// Record the sidecar's status and tell the webview when it changed
+pub fn set_status(app: &AppHandle, status: ServerStatus) {
+    let state = app.state::<Mutex<ServerManager>>();
+    let Ok(mut manager) = state.lock() else {
+        return;
+    };
+    if manager.status == status {
+        return;
+    }
+    manager.status = status;
+    let event = ServerStatusEvent {
+        status,
+        restarts: manager.restarts,
+    };
+    drop(manager);
+    append_e2e_log(&format!("server-status {status:?}"));
+    let _ = app.emit("server-status", event);
+}
+
+fn is_healthy() -> bool {
+    ureq::get(format!("{}/health", transcription::server_http_url()))
+        .config()
+        .timeout_global(Some(HEALTH_TIMEOUT))
+        .build()
+        .call()
+        .is_ok()
+}
+
+/// Kill a sidecar that is running but no longer answering
+fn kill_hung(app: &AppHandle) {
+    let state = app.state::<Mutex<ServerManager>>();
+    let child = state
+        .lock()
+        .ok()
+        .and_then(|mut manager| manager.child.take());
+    if let Some(child) = child {
+        eprintln!("[voiceflow] Sidecar stopped answering, killing it");
+        if let Err(e) = child.kill() {
+            eprintln!("[voiceflow] Failed to kill hung sidecar: {e}");
+        }
+    }
+    set_status(app, ServerStatus::Crashed);
+}
+
+fn restart(app: &AppHandle) {
+    let attempt = {
+        let state = app.state::<Mutex<ServerManager>>();
+        let Ok(manager) = state.lock() else {
+            return;
+        };
+        manager.failed_restarts
+    };
+    set_status(app, ServerStatus::Restarting);
+    thread::sleep(backoff(attempt));
+
+    let state = app.state::<Mutex<ServerManager>>();
+    {
+        let Ok(mut manager) = state.lock() else {
+            return;
+        };
+        // Stopped or started by someone else in the meantime
+        if !manager.wanted || manager.child.is_some() {
+            return;
+        }
+        manager.restarts += 1;
+        manager.failed_restarts += 1;
+    }
+    eprintln!("[voiceflow] Restarting sidecar (attempt {})", attempt + 1);
+    if let Err(e) = ensure_sidecar_running(app) {
+        eprintln!("[voiceflow] Failed to restart sidecar: {e}");
+        set_status(app, ServerStatus::Crashed);
+    }
+}
+
+/// Watch the sidecar while it should be running: restart it with backoff when it
+/// exits, and kill and restart it when its health endpoint stops answering
+pub fn start(app: AppHandle) {
+    thread::spawn(move || {
+        let mut failed_checks = 0;
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let state = app.state::<Mutex<ServerManager>>();
+            let Ok((wanted, running, started_at)) = state
+                .lock()
+                .map(|manager| (manager.wanted, manager.child.is_some(), manager.started_at))
+            else {
+                continue;
+            };
+            if !wanted {
+                failed_checks = 0;
+                continue;
+            }
+            if !running {
+                failed_checks = 0;
+                restart(&app);
+                continue;
+            }
+            if is_healthy() {
+                failed_checks = 0;
+                if let Ok(mut manager) = state.lock() {
+                    manager.failed_restarts = 0;
+                }
+                set_status(&app, ServerStatus::Healthy);
+                continue;
+            }
+            let starting = started_at.is_some_and(|at| at.elapsed() < STARTUP_GRACE);
+            if starting {
+                continue;
+            }
+            failed_checks += 1;
+            if failed_checks >= MAX_FAILED_CHECKS {
+                failed_checks = 0;
+                kill_hung(&app);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_server_status(app: AppHandle) -> Result<ServerStatusEvent, String> {
+    let state = app.state::<Mutex<ServerManager>>();
+    let manager = state.lock().map_err(|e| e.to_string())?;
+    Ok(ServerStatusEvent {
+        status: manager.status,
+        restarts: manager.restarts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backs_off_exponentially_up_to_a_minute() {
+        assert_eq!(backoff(0), Duration::from_secs(1));
+        assert_eq!(backoff(3), Duration::from_secs(8));
+        assert_eq!(backoff(6), Duration::from_secs(60));
+        assert_eq!(backoff(40), MAX_BACKOFF);
+    }
+}
@@ -0,0 +1,300 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{
+    AppHandle, Emitter, Manager, PhysicalPosition, Position, WebviewUrl, WebviewWindowBuilder,
+};
+
+use crate::{
+    append_e2e_log, continuous, i18n, screen_share, settings, start_recording, stop_recording,
+    IS_RECORDING,
+};
+
+#[cfg(target_os = "macos")]
+use tauri_nspanel::objc2::{runtime::NSObjectProtocol, ClassType, Message};
+#[cfg(target_os = "macos")]
+use tauri_nspanel::WebviewWindowExt;
+
+pub const WINDOW_LABEL: &str = "switch-button";
+const SIZE: f64 = 160.0;
+const MARGIN: i32 = 48;
+/// How often the pointer is checked against the button while dwell is on
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+pub const MIN_DWELL_MS: u64 = 300;
+pub const MAX_DWELL_MS: u64 = 10_000;
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+// Clickable but never key, so the app being dictated into keeps focus
+#[cfg(target_os = "macos")]
+tauri_nspanel::panel!(SwitchButtonPanel {
+    config: {
+        can_become_key_window: false,
+        can_become_main_window: false,
+        is_floating_panel: true,
+    }
+});
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SwitchAccessSettings {
+    /// Show the on-screen button that toggles recording
+    pub enabled: bool,
+    /// Toggle when the pointer rests on the button, for head pointers and eye trackers
+    pub dwell: bool,
+    pub dwell_ms: u64,
+}
+
+impl Default for SwitchAccessSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dwell: true,
+            dwell_ms: 1000,
+        }
+    }
+}
+
+/// How long the pointer has rested on the button
+#[derive(Debug, Default)]
+struct Dwell {
+    since: Option<Instant>,
+    /// Already toggled during this hover; the pointer has to leave before it fires again
+    fired: bool,
+}
+
+impl Dwell {
+    /// Returns true exactly once per hover, when the pointer has rested for `dwell`
+    fn update(&mut self, inside: bool, now: Instant, dwell: Duration) -> bool {
+        if !inside {
+            *self = Self::default();
+            return false;
+        }
+        let since = *self.since.get_or_insert(now);
+        if self.fired || now.duration_since(since) < dwell {
+            return false;
+        }
+        self.fired = true;
+        true
+    }
+
+    /// Fraction of the dwell time elapsed, for the progress ring around the button
+    fn progress(&self, now: Instant, dwell: Duration) -> f32 {
+        match self.since {
+            Some(_) if self.fired => 1.0,
+            Some(since) => (now.duration_since(since).as_secs_f32() / dwell.as_secs_f32()).min(1.0),
+            None => 0.0,
+        }
+    }
+}
+
+/// Start a dictation, or finish the running one
+fn toggle(app: &AppHandle) {
+    if continuous::is_active() {
+        continuous::stop(app);
+    } else if IS_RECORDING.load(Ordering::SeqCst) {
+        stop_recording(app);
+    } else if start_recording(app) {
+        append_e2e_log("switch-recording-start");
+    }
+}
+
+/// Bottom-right corner of the primary monitor, clear of the bubble
+fn position(app: &AppHandle, window: &tauri::WebviewWindow) {
+    let (Some(monitor), Ok(size)) = (app.primary_monitor().ok().flatten(), window.outer_size())
+    else {
+        return;
+    };
+    let screen = monitor.size();
+    let screen_pos = monitor.position();
+    let x = screen_pos.x + screen.width as i32 - size.width as i32 - MARGIN;
+    let y = screen_pos.y + screen.height as i32 - size.height as i32 - MARGIN;
+    let _ = window.set_position(Position::Physical(PhysicalPosition::new(x, y)));
+}
+
+#[cfg(target_os = "macos")]
+fn setup_panel(window: &tauri::WebviewWindow) {
+    use tauri_nspanel::objc2_app_kit::{NSWindowCollectionBehavior, NSWindowStyleMask};
+
+    let Ok(panel) = window.to_panel::<SwitchButtonPanel<_>>() else {
+        return;
+    };
+    panel.set_level(25);
+    panel.set_style_mask(NSWindowStyleMask::NonactivatingPanel);
+    panel.set_collection_behavior(
+        NSWindowCollectionBehavior::CanJoinAllSpaces
+            | NSWindowCollectionBehavior::Stationary
+            | NSWindowCollectionBehavior::FullScreenAuxiliary,
+    );
+}
+
+fn create_window(app: &AppHandle) -> Option<tauri::WebviewWindow> {
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        return Some(window);
+    }
+    let builder =
+        WebviewWindowBuilder::new(app, WINDOW_LABEL, WebviewUrl::App("/switch-button".into()))
+            .title(i18n::t("window.switch_button"))
+            .inner_size(SIZE, SIZE)
+            .resizable(false)
+            .decorations(false)
+            .transparent(true)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .focused(false)
+            .visible(false);
+    match builder.build() {
+        Ok(window) => {
+            #[cfg(target_os = "macos")]
+            setup_panel(&window);
+            position(app, &window);
+            Some(window)
+        }
+        Err(e) => {
+            eprintln!("[voiceflow] Failed to create the switch button: {e}");
+            None
+        }
+    }
+}
+
+fn pointer_inside(app: &AppHandle, window: &tauri::WebviewWindow) -> bool {
+    let (Ok(cursor), Ok(origin), Ok(size)) = (
+        app.cursor_position(),
+        window.outer_position(),
+        window.outer_size(),
+    ) else {
+        return false;
+    };
+    cursor.x >= origin.x as f64
+        && cursor.y >= origin.y as f64
+        && cursor.x < origin.x as f64 + size.width as f64
+        && cursor.y < origin.y as f64 + size.height as f64
+}
+
+/// Create the button on the main thread, which the panel conversion requires
+fn window(app: &AppHandle) -> Option<tauri::WebviewWindow> {
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        return Some(window);
+    }
+    let (sender, receiver) = mpsc::channel();
+    let handle = app.clone();
+    app.run_on_main_thread(move || {
+        let _ = sender.send(create_window(&handle));
+    })
+    .ok()?;
+    receiver.recv().ok().flatten()
+}
+
+// Keeps the button on screen and turns resting on it into a toggle
+fn run(app: &AppHandle) {
+    let mut dwell = Dwell::default();
+    let mut reported = 0.0;
+    loop {
+        let config = settings::current(app).switch_access;
+        if !config.enabled {
+            break;
+        }
+        let Some(window) = window(app) else {
+            break;
+        };
+        // Kept off the shared screen like the other overlays
+        if screen_share::should_hide_overlay(app) {
+            let _ = window.hide();
+            dwell = Dwell::default();
+            thread::sleep(POLL_INTERVAL);
+            continue;
+        }
+        let visible = window.is_visible().unwrap_or(false);
+        if !visible {
+            let _ = window.show();
+        }
+
+        let now = Instant::now();
+        let duration = Duration::from_millis(config.dwell_ms);
+        let inside = config.dwell && visible && pointer_inside(app, &window);
+        if dwell.update(inside, now, duration) {
+            append_e2e_log("switch-dwell");
+            toggle(app);
+        }
+        let progress = dwell.progress(now, duration);
+        if progress != reported {
+            reported = progress;
+            let _ = app.emit_to(WINDOW_LABEL, "switch-dwell-progress", progress);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        let _ = window.hide();
+    }
+}
+
+/// Show the button while switch access is enabled. Safe to call again after
+/// the setting changes: the running loop picks up new values and exits when disabled.
+pub fn start(app: AppHandle) {
+    if !settings::current(&app).switch_access.enabled || RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    thread::spawn(move || {
+        run(&app);
+        RUNNING.store(false, Ordering::SeqCst);
+    });
+}
+
+/// A click or switch press on the button
+#[tauri::command]
+pub async fn press_switch_button(app: AppHandle) {
+    append_e2e_log("switch-pressed");
+    toggle(&app);
+}
+
+#[tauri::command]
+pub async fn set_switch_access(
+    app: AppHandle,
+    enabled: bool,
+    dwell: Option<bool>,
+    dwell_ms: Option<u64>,
+) -> Result<SwitchAccessSettings, String> {
+    if dwell_ms.is_some_and(|ms| !(MIN_DWELL_MS..=MAX_DWELL_MS).contains(&ms)) {
+        return Err(i18n::t("error.dwell_time"));
+    }
+    let updated = settings::update(&app, |settings| {
+        settings.switch_access.enabled = enabled;
+        if let Some(dwell) = dwell {
+            settings.switch_access.dwell = dwell;
+        }
+        if let Some(dwell_ms) = dwell_ms {
+            settings.switch_access.dwell_ms = dwell_ms;
+        }
+    })?;
+    start(app);
+    Ok(updated.switch_access)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dwell_fires_once_per_hover() {
+        let dwell = Duration::from_millis(1000);
+        let start = Instant::now();
+        let mut state = Dwell::default();
+
+        assert!(!state.update(true, start, dwell));
+        let halfway = start + Duration::from_millis(500);
+        assert!(!state.update(true, halfway, dwell));
+        assert_eq!(state.progress(halfway, dwell), 0.5);
+
+        let done = start + Duration::from_millis(1000);
+        assert!(state.update(true, done, dwell));
+        assert!(!state.update(true, done + Duration::from_secs(5), dwell));
+        assert_eq!(state.progress(done, dwell), 1.0);
+
+        assert!(!state.update(false, done, dwell));
+        assert_eq!(state.progress(done, dwell), 0.0);
+        assert!(!state.update(true, done, dwell));
+    }
+}
@@ -0,0 +1,348 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::{i18n, settings};
+
+// Saying "thumbs up emoji" or "degree sign" inserts 👍 or °. Each language has its own
+// phrases, and symbols.json in the config dir adds more or overrides them.
+
+/// Symbols joined to the word before them, as in "20°"
+const ATTACHED: &[&str] = &["°", "™", "®"];
+
+struct BuiltinSymbols {
+    codes: &'static [&'static str],
+    symbols: &'static [(&'static str, &'static str)],
+}
+
+const ENGLISH: BuiltinSymbols = BuiltinSymbols {
+    codes: &["en"],
+    symbols: &[
+        ("thumbs up emoji", "👍"),
+        ("thumbs down emoji", "👎"),
+        ("smiley face emoji", "🙂"),
+        ("smiley emoji", "🙂"),
+        ("winking emoji", "😉"),
+        ("laughing emoji", "😂"),
+        ("crying emoji", "😢"),
+        ("heart emoji", "❤️"),
+        ("fire emoji", "🔥"),
+        ("party emoji", "🎉"),
+        ("clapping emoji", "👏"),
+        ("rocket emoji", "🚀"),
+        ("check mark emoji", "✅"),
+        ("check mark", "✓"),
+        ("degree sign", "°"),
+        ("em dash", "—"),
+        ("en dash", "–"),
+        ("copyright sign", "©"),
+        ("registered sign", "®"),
+        ("trademark sign", "™"),
+        ("euro sign", "€"),
+        ("section sign", "§"),
+        ("bullet point", "•"),
+        ("right arrow", "→"),
+        ("left arrow", "←"),
+        ("plus minus sign", "±"),
+        ("multiplication sign", "×"),
+        ("division sign", "÷"),
+        ("infinity sign", "∞"),
+    ],
+};
+
+const BUILTIN: &[BuiltinSymbols] = &[
+    ENGLISH,
+    BuiltinSymbols {
+        codes: &["de"],
+        symbols: &[
+            ("Daumen hoch Emoji", "👍"),
+            ("Daumen runter Emoji", "👎"),
+            ("Smiley Emoji", "🙂"),
+            ("lachendes Emoji", "😂"),
+            ("Herz Emoji", "❤️"),
+            ("Feuer Emoji", "🔥"),
+            ("Party Emoji", "🎉"),
+            ("Häkchen", "✓"),
+            ("Gradzeichen", "°"),
+            ("Geviertstrich", "—"),
+            ("Halbgeviertstrich", "–"),
+            ("Copyright Zeichen", "©"),
+            ("Eurozeichen", "€"),
+            ("Paragrafenzeichen", "§"),
+            ("Pfeil nach rechts", "→"),
+            ("Pfeil nach links", "←"),
+            ("Unendlichzeichen", "∞"),
+        ],
+    },
+    BuiltinSymbols {
+        codes: &["es"],
+        symbols: &[
+            ("emoji de pulgar arriba", "👍"),
+            ("emoji de pulgar abajo", "👎"),
+            ("emoji sonriente", "🙂"),
+            ("emoji de risa", "😂"),
+            ("emoji de corazón", "❤️"),
+            ("emoji de fuego", "🔥"),
+            ("emoji de fiesta", "🎉"),
+            ("marca de verificación", "✓"),
+            ("signo de grado", "°"),
+            ("guion largo", "—"),
+            ("signo de copyright", "©"),
+            ("signo del euro", "€"),
+            ("signo de párrafo", "§"),
+            ("flecha a la derecha", "→"),
+            ("flecha a la izquierda", "←"),
+            ("signo de infinito", "∞"),
+        ],
+    },
+    BuiltinSymbols {
+        codes: &["fr"],
+        symbols: &[
+            ("emoji pouce levé", "👍"),
+            ("emoji pouce baissé", "👎"),
+            ("emoji sourire", "🙂"),
+            ("emoji mort de rire", "😂"),
+            ("emoji cœur", "❤️"),
+            ("emoji feu", "🔥"),
+            ("emoji fête", "🎉"),
+            ("coche de validation", "✓"),
+            ("signe degré", "°"),
+            ("tiret cadratin", "—"),
+            ("tiret demi-cadratin", "–"),
+            ("signe copyright", "©"),
+            ("signe euro", "€"),
+            ("signe paragraphe", "§"),
+            ("flèche droite", "→"),
+            ("flèche gauche", "←"),
+            ("signe infini", "∞"),
+        ],
+    },
+    BuiltinSymbols {
+        codes: &["it"],
+        symbols: &[
+            ("emoji pollice in su", "👍"),
+            ("emoji pollice in giù", "👎"),
+            ("emoji sorriso", "🙂"),
+            ("emoji risata", "😂"),
+            ("emoji cuore", "❤️"),
+            ("emoji fuoco", "🔥"),
+            ("emoji festa", "🎉"),
+            ("segno di spunta", "✓"),
+            ("simbolo del grado", "°"),
+            ("trattino lungo", "—"),
+            ("simbolo del copyright", "©"),
+            ("simbolo dell'euro", "€"),
+            ("simbolo di paragrafo", "§"),
+            ("freccia a destra", "→"),
+            ("freccia a sinistra", "←"),
+            ("simbolo di infinito", "∞"),
+        ],
+    },
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolMapping {
+    /// What is said, matched case-insensitively as whole words
+    pub phrase: String,
+    pub symbol: String,
+    /// Join the symbol to the word before it instead of keeping the space
+    #[serde(default)]
+    pub attach: bool,
+    /// Language code the phrase is said in, any language when None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+pub struct SymbolManager {
+    custom: Vec<SymbolMapping>,
+    symbols_path: PathBuf,
+}
+
+impl SymbolManager {
+    pub fn new(config_dir: PathBuf) -> Self {
+        let symbols_path = config_dir.join("symbols.json");
+        let custom = Self::load_symbols(&symbols_path).unwrap_or_default();
+        Self {
+            custom,
+            symbols_path,
+        }
+    }
+
+    fn load_symbols(path: &PathBuf) -> Option<Vec<SymbolMapping>> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn save(&mut self, custom: Vec<SymbolMapping>) -> Result<(), String> {
+        if let Some(parent) = self.symbols_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string_pretty(&custom).map_err(|e| e.to_string())?;
+        fs::write(&self.symbols_path, content).map_err(|e| e.to_string())?;
+        self.custom = custom;
+        Ok(())
+    }
+}
+
+fn base_language(language: Option<&str>) -> Option<String> {
+    let base = language?.split(['-', '_']).next()?.to_lowercase();
+    (!base.is_empty()).then_some(base)
+}
+
+/// The user's mappings for `language` followed by the built-in ones, English when
+/// the language has none
+fn mappings(custom: &[SymbolMapping], language: Option<&str>) -> Vec<SymbolMapping> {
+    let base = base_language(language);
+    let builtin = BUILTIN
+        .iter()
+        .find(|builtin| {
+            base.as_deref()
+                .is_some_and(|base| builtin.codes.contains(&base))
+        })
+        .unwrap_or(&ENGLISH);
+    let mut builtin: Vec<SymbolMapping> = builtin
+        .symbols
+        .iter()
+        .map(|(phrase, symbol)| SymbolMapping {
+            phrase: phrase.to_string(),
+            symbol: symbol.to_string(),
+            attach: ATTACHED.contains(symbol),
+            language: Some(builtin.codes[0].to_string()),
+        })
+        .collect();
+    // "tiret demi-cadratin" before "tiret cadratin"
+    builtin.sort_by_key(|mapping| std::cmp::Reverse(mapping.phrase.chars().count()));
+    custom
+        .iter()
+        .filter(|mapping| {
+            mapping.language.is_none() || base_language(mapping.language.as_deref()) == base
+        })
+        .cloned()
+        .chain(builtin)
+        .collect()
+}
+
+/// Words of the phrase separated by spaces or hyphens, so "em-dash" matches too
+fn pattern(mapping: &SymbolMapping) -> Option<Regex> {
+    let words: Vec<String> = mapping
+        .phrase
+        .split(|c: char| c.is_whitespace() || c == '-')
+        .filter(|word| !word.is_empty())
+        .map(regex::escape)
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+    let space = if mapping.attach { r"\s*" } else { "" };
+    Regex::new(&format!(r"(?i){space}\b{}\b", words.join(r"[\s-]+"))).ok()
+}
+
+fn replace(text: &str, mappings: &[SymbolMapping]) -> String {
+    mappings
+        .iter()
+        .fold(text.to_string(), |text, mapping| match pattern(mapping) {
+            Some(pattern) => pattern
+                .replace_all(&text, regex::NoExpand(&mapping.symbol))
+                .into_owned(),
+            None => text,
+        })
+}
+
+/// Replace spoken emoji and symbol names in a transcript, when enabled
+pub fn apply(app: &AppHandle, text: &str, language: Option<&str>) -> String {
+    if !settings::current(app).spoken_symbols {
+        return text.to_string();
+    }
+    let state = app.state::<Mutex<SymbolManager>>();
+    let Ok(manager) = state.lock() else {
+        return text.to_string();
+    };
+    replace(text, &mappings(&manager.custom, language))
+}
+
+/// Every phrase that is replaced for `language`, the user's first
+#[tauri::command]
+pub async fn list_symbols(
+    app: AppHandle,
+    language: Option<String>,
+) -> Result<Vec<SymbolMapping>, String> {
+    let state = app.state::<Mutex<SymbolManager>>();
+    let manager = state.lock().map_err(|e| e.to_string())?;
+    Ok(mappings(&manager.custom, language.as_deref()))
+}
+
+#[tauri::command]
+pub async fn get_custom_symbols(app: AppHandle) -> Result<Vec<SymbolMapping>, String> {
+    let state = app.state::<Mutex<SymbolManager>>();
+    let manager = state.lock().map_err(|e| e.to_string())?;
+    Ok(manager.custom.clone())
+}
+
+/// Replace the user's mappings in symbols.json
+#[tauri::command]
+pub async fn save_custom_symbols(
+    app: AppHandle,
+    symbols: Vec<SymbolMapping>,
+) -> Result<Vec<SymbolMapping>, String> {
+    if symbols
+        .iter()
+        .any(|mapping| mapping.phrase.trim().is_empty() || mapping.symbol.is_empty())
+    {
+        return Err(i18n::t("error.symbol_mapping_empty"));
+    }
+    let state = app.state::<Mutex<SymbolManager>>();
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.save(symbols)?;
+    Ok(manager.custom.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_spoken_symbols() {
+        let english = mappings(&[], Some("en-US"));
+        assert_eq!(
+            replace(
+                "Great job thumbs up emoji. It is 20 degree sign outside",
+                &english
+            ),
+            "Great job 👍. It is 20° outside"
+        );
+        assert_eq!(replace("this Em-dash that", &english), "this — that");
+        assert_eq!(replace("them dash", &english), "them dash");
+
+        let french = mappings(&[], Some("fr"));
+        assert_eq!(
+            replace("de 9 tiret demi-cadratin 17 h", &french),
+            "de 9 – 17 h"
+        );
+    }
+
+    #[test]
+    fn custom_symbols_come_first() {
+        let custom = [
+            SymbolMapping {
+                phrase: "heart emoji".to_string(),
+                symbol: "💙".to_string(),
+                attach: false,
+                language: None,
+            },
+            SymbolMapping {
+                phrase: "Herz".to_string(),
+                symbol: "♥".to_string(),
+                attach: false,
+                language: Some("de".to_string()),
+            },
+        ];
+        let english = mappings(&custom, Some("en"));
+        assert_eq!(replace("thanks heart emoji", &english), "thanks 💙");
+        assert_eq!(replace("ein Herz", &english), "ein Herz");
+        assert_eq!(replace("ein Herz", &mappings(&custom, Some("de"))), "ein ♥");
+    }
+}
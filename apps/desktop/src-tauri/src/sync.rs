@@ -0,0 +1,363 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::demo;
+use crate::profiles::{self, PROFILE_FILES};
+use crate::prompts::PromptTemplateManager;
+use crate::snippets::SnippetManager;
+use crate::symbols::SymbolManager;
+
+/// Config files shared by every profile, relative to the config dir
+const GLOBAL_FILES: &[&str] = &["prompt_templates.json", "snippets.json", "symbols.json"];
+const SYNC_INTERVAL: Duration = Duration::from_secs(60);
+/// Where the losing side of a conflict is kept, inside the config dir
+const BACKUP_DIR: &str = "sync-backups";
+
+/// Machine-local sync state; kept out of settings.json since that file is itself synced
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct SyncState {
+    folder: Option<PathBuf>,
+    /// Content hash of each file at the last sync, keyed by its path in the sync folder
+    synced: HashMap<String, u64>,
+    last_sync_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Outcome {
+    Unchanged,
+    Pushed,
+    Pulled,
+    /// Both sides changed; the newer one won and the other was backed up
+    Conflict {
+        kept_local: bool,
+        backup: PathBuf,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConflict {
+    pub file: String,
+    pub kept: String,
+    pub backup: PathBuf,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    pub folder: Option<PathBuf>,
+    pub last_sync_ms: Option<u64>,
+    pub pushed: Vec<String>,
+    pub pulled: Vec<String>,
+    pub conflicts: Vec<SyncConflict>,
+}
+
+pub struct SyncManager {
+    state: SyncState,
+    state_path: PathBuf,
+    config_dir: PathBuf,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+// FNV-1a, stable across Rust versions unlike the std hasher
+fn content_hash(content: &[u8]) -> u64 {
+    content.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn modified(path: &Path) -> SystemTime {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .unwrap_or(UNIX_EPOCH)
+}
+
+/// Write through a temporary file so sync clients never upload a half-written file
+/// and a crash mid-write leaves the previous version in place
+pub fn write_atomic(path: &Path, content: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let temporary = path.with_extension("json.tmp");
+    fs::write(&temporary, content).map_err(|e| e.to_string())?;
+    fs::rename(&temporary, path).map_err(|e| e.to_string())
+}
+
+/// Bring one file in line with its copy in the sync folder, last writer wins.
+/// Returns the outcome and the hash both sides now share.
+fn sync_file(
+    local: &Path,
+    remote: &Path,
+    last_hash: Option<u64>,
+    backup_dir: &Path,
+) -> Result<(Outcome, Option<u64>), String> {
+    let local_content = fs::read(local).ok();
+    let remote_content = fs::read(remote).ok();
+    let local_hash = local_content.as_deref().map(content_hash);
+    let remote_hash = remote_content.as_deref().map(content_hash);
+
+    let (local_content, remote_content) = match (local_content, remote_content) {
+        (None, None) => return Ok((Outcome::Unchanged, None)),
+        (Some(content), None) => {
+            write_atomic(remote, &content)?;
+            return Ok((Outcome::Pushed, local_hash));
+        }
+        (None, Some(content)) => {
+            write_atomic(local, &content)?;
+            return Ok((Outcome::Pulled, remote_hash));
+        }
+        (Some(local), Some(remote)) => (local, remote),
+    };
+
+    if local_hash == remote_hash {
+        return Ok((Outcome::Unchanged, local_hash));
+    }
+    if remote_hash == last_hash {
+        write_atomic(remote, &local_content)?;
+        return Ok((Outcome::Pushed, local_hash));
+    }
+    if local_hash == last_hash {
+        write_atomic(local, &remote_content)?;
+        return Ok((Outcome::Pulled, remote_hash));
+    }
+
+    let kept_local = modified(local) >= modified(remote);
+    let name = local
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("file");
+    let backup = backup_dir.join(format!("{name}-{}.json", now_ms()));
+    if kept_local {
+        write_atomic(&backup, &remote_content)?;
+        write_atomic(remote, &local_content)?;
+        Ok((Outcome::Conflict { kept_local, backup }, local_hash))
+    } else {
+        write_atomic(&backup, &local_content)?;
+        write_atomic(local, &remote_content)?;
+        Ok((Outcome::Conflict { kept_local, backup }, remote_hash))
+    }
+}
+
+impl SyncManager {
+    pub fn new(config_dir: PathBuf) -> Self {
+        let state_path = config_dir.join("sync.json");
+        let state = fs::read_to_string(&state_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            state,
+            state_path,
+            config_dir,
+        }
+    }
+
+    fn save_state(&self) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(&self.state).map_err(|e| e.to_string())?;
+        fs::write(&self.state_path, content).map_err(|e| e.to_string())
+    }
+
+    fn report(&self) -> SyncReport {
+        SyncReport {
+            folder: self.state.folder.clone(),
+            last_sync_ms: self.state.last_sync_ms,
+            ..SyncReport::default()
+        }
+    }
+
+    /// Local path and sync-folder key of every synced file
+    fn files(&self, profile: &str, profile_dir: &Path) -> Vec<(PathBuf, String)> {
+        let global = GLOBAL_FILES
+            .iter()
+            .map(|file| (self.config_dir.join(file), file.to_string()));
+        let profile_files = PROFILE_FILES
+            .iter()
+            .map(|file| (profile_dir.join(file), format!("profiles/{profile}/{file}")));
+        global.chain(profile_files).collect()
+    }
+
+    fn sync(&mut self, profile: &str, profile_dir: &Path) -> Result<SyncReport, String> {
+        let Some(folder) = self.state.folder.clone() else {
+            return Ok(self.report());
+        };
+        if !folder.is_dir() {
+            return Err(format!("Sync folder {} is not available", folder.display()));
+        }
+
+        let backup_dir = self.config_dir.join(BACKUP_DIR);
+        let mut report = SyncReport::default();
+        for (local, key) in self.files(profile, profile_dir) {
+            let remote = folder.join(&key);
+            let last_hash = self.state.synced.get(&key).copied();
+            let (outcome, hash) = sync_file(&local, &remote, last_hash, &backup_dir)?;
+            match hash {
+                Some(hash) => self.state.synced.insert(key.clone(), hash),
+                None => self.state.synced.remove(&key),
+            };
+            match outcome {
+                Outcome::Unchanged => {}
+                Outcome::Pushed => report.pushed.push(key),
+                Outcome::Pulled => report.pulled.push(key),
+                Outcome::Conflict { kept_local, backup } => {
+                    if !kept_local {
+                        report.pulled.push(key.clone());
+                    }
+                    report.conflicts.push(SyncConflict {
+                        file: key,
+                        kept: if kept_local { "local" } else { "remote" }.to_string(),
+                        backup,
+                    });
+                }
+            }
+        }
+
+        self.state.last_sync_ms = Some(now_ms());
+        self.save_state()?;
+        report.folder = Some(folder);
+        report.last_sync_ms = self.state.last_sync_ms;
+        Ok(report)
+    }
+}
+
+/// Sync the active profile and shared files, reloading whatever was pulled
+pub fn sync(app: &AppHandle) -> Result<SyncReport, String> {
+    let profile = profiles::list(app).active;
+    let profile_dir = profiles::active_dir(app)?;
+    let report = {
+        let state = app.state::<Mutex<SyncManager>>();
+        let mut manager = state.lock().map_err(|e| e.to_string())?;
+        manager.sync(&profile, &profile_dir)?
+    };
+
+    if report
+        .pulled
+        .iter()
+        .any(|key| GLOBAL_FILES.contains(&key.as_str()))
+    {
+        if let Ok(config_dir) = demo::config_dir(app) {
+            *app.state::<Mutex<PromptTemplateManager>>()
+                .lock()
+                .map_err(|e| e.to_string())? = PromptTemplateManager::new(config_dir.clone());
+            *app.state::<Mutex<SnippetManager>>()
+                .lock()
+                .map_err(|e| e.to_string())? = SnippetManager::new(config_dir.clone());
+            *app.state::<Mutex<SymbolManager>>()
+                .lock()
+                .map_err(|e| e.to_string())? = SymbolManager::new(config_dir);
+        }
+    }
+    if report.pulled.iter().any(|key| key.starts_with("profiles/")) {
+        profiles::reload(app)?;
+    }
+    for conflict in &report.conflicts {
+        eprintln!(
+            "[voiceflow] Sync conflict in {}, kept the {} copy and saved the other to {}",
+            conflict.file,
+            conflict.kept,
+            conflict.backup.display()
+        );
+    }
+    if !report.pulled.is_empty() || !report.conflicts.is_empty() {
+        let _ = app.emit("sync-completed", &report);
+    }
+    Ok(report)
+}
+
+/// Sync periodically in the background while a folder is configured
+pub fn start(app: AppHandle) {
+    thread::spawn(move || loop {
+        let configured = app
+            .state::<Mutex<SyncManager>>()
+            .lock()
+            .map(|manager| manager.state.folder.is_some())
+            .unwrap_or(false);
+        if configured {
+            if let Err(error) = sync(&app) {
+                eprintln!("[voiceflow] Sync failed: {error}");
+            }
+        }
+        thread::sleep(SYNC_INTERVAL);
+    });
+}
+
+#[tauri::command]
+pub async fn get_sync_status(app: AppHandle) -> Result<SyncReport, String> {
+    let state = app.state::<Mutex<SyncManager>>();
+    let manager = state.lock().map_err(|e| e.to_string())?;
+    Ok(manager.report())
+}
+
+/// Choose the cloud-synced folder to mirror config files to, or None to stop syncing
+#[tauri::command]
+pub async fn set_sync_folder(
+    app: AppHandle,
+    folder: Option<PathBuf>,
+) -> Result<SyncReport, String> {
+    if folder.as_ref().is_some_and(|folder| !folder.is_dir()) {
+        return Err("Sync folder does not exist".to_string());
+    }
+    {
+        let state = app.state::<Mutex<SyncManager>>();
+        let mut manager = state.lock().map_err(|e| e.to_string())?;
+        manager.state.folder = folder;
+        // A different folder has its own history, so every file is compared afresh
+        manager.state.synced.clear();
+        manager.save_state()?;
+    }
+    sync(&app)
+}
+
+#[tauri::command]
+pub async fn sync_config_now(app: AppHandle) -> Result<SyncReport, String> {
+    sync(&app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pushes_local_edits_and_backs_up_conflicts() {
+        let dir = std::env::temp_dir().join(format!("voiceflow-sync-test-{}", now_ms()));
+        let (local, remote, backups) = (
+            dir.join("local.json"),
+            dir.join("remote.json"),
+            dir.join("backups"),
+        );
+        fs::create_dir_all(&dir).unwrap();
+
+        fs::write(&local, "a").unwrap();
+        let (outcome, hash) = sync_file(&local, &remote, None, &backups).unwrap();
+        assert_eq!(outcome, Outcome::Pushed);
+        assert_eq!(fs::read_to_string(&remote).unwrap(), "a");
+
+        fs::write(&local, "b").unwrap();
+        let (outcome, hash) = sync_file(&local, &remote, hash, &backups).unwrap();
+        assert_eq!(outcome, Outcome::Pushed);
+
+        fs::write(&remote, "c").unwrap();
+        fs::write(&local, "d").unwrap();
+        let (outcome, _) = sync_file(&local, &remote, hash, &backups).unwrap();
+        let Outcome::Conflict { kept_local, backup } = outcome else {
+            panic!("expected a conflict, got {outcome:?}");
+        };
+        assert!(kept_local);
+        assert_eq!(fs::read_to_string(backup).unwrap(), "c");
+        assert_eq!(fs::read_to_string(&remote).unwrap(), "d");
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,167 @@
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use crate::i18n;
+
+// A shortcut can name the app its dictations go to, e.g. Notes or Obsidian. Before
+// the transcript is handed back for pasting, that app is brought to the front and
+// launched first when it isn't running, whatever had focus when recording started.
+
+// How long a launched app gets to come to the front
+#[cfg(target_os = "macos")]
+const ACTIVATE_TIMEOUT: Duration = Duration::from_secs(5);
+#[cfg(target_os = "macos")]
+const ACTIVATE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+// Without a way to ask which app is frontmost, give the launch this long to settle
+#[cfg(not(target_os = "macos"))]
+const ACTIVATE_SETTLE: Duration = Duration::from_millis(800);
+
+// Target app of the shortcut that started the running dictation
+static SESSION: Mutex<Option<String>> = Mutex::new(None);
+
+/// `open -a` takes an app name or path, `open -b` a bundle identifier
+#[cfg(target_os = "macos")]
+fn is_bundle_id(target: &str) -> bool {
+    target.contains('.') && !target.contains(['/', ' ']) && !target.to_lowercase().ends_with(".app")
+}
+
+#[cfg(target_os = "macos")]
+fn launch(target: &str) -> Result<(), String> {
+    use std::process::Command;
+
+    let flag = if is_bundle_id(target) { "-b" } else { "-a" };
+    let output = Command::new("open")
+        .args([flag, target])
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(())
+}
+
+/// Executables are started through `start`, which also finds apps registered under
+/// App Paths and hands a running single-instance app the focus
+#[cfg(target_os = "windows")]
+fn launch(target: &str) -> Result<(), String> {
+    use std::process::Command;
+
+    let status = Command::new("cmd")
+        .args(["/C", "start", "", target])
+        .status()
+        .map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err(format!("start exited with {status}"));
+    }
+    Ok(())
+}
+
+/// A running window whose class matches is raised with wmctrl; otherwise the target
+/// is launched as a desktop entry id, then as a command
+#[cfg(target_os = "linux")]
+fn launch(target: &str) -> Result<(), String> {
+    use std::process::{Command, Stdio};
+
+    let raised = Command::new("wmctrl")
+        .args(["-x", "-a", target])
+        .output()
+        .is_ok_and(|output| output.status.success());
+    if raised {
+        return Ok(());
+    }
+    let launched = Command::new("gtk-launch")
+        .arg(target)
+        .output()
+        .is_ok_and(|output| output.status.success());
+    if launched {
+        return Ok(());
+    }
+    Command::new(target)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn launch(_target: &str) -> Result<(), String> {
+    Err("Opening apps is not supported on this platform".to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn is_frontmost(target: &str) -> bool {
+    let target = target.trim_end_matches(".app");
+    let target = target.rsplit('/').next().unwrap_or(target);
+    crate::active_app::frontmost_app().is_some_and(|frontmost| {
+        frontmost.bundle_id.eq_ignore_ascii_case(target)
+            || frontmost.name.eq_ignore_ascii_case(target)
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn wait_until_frontmost(target: &str) -> bool {
+    use std::time::Instant;
+
+    let deadline = Instant::now() + ACTIVATE_TIMEOUT;
+    loop {
+        if is_frontmost(target) {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(ACTIVATE_POLL_INTERVAL);
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn wait_until_frontmost(_target: &str) -> bool {
+    thread::sleep(ACTIVATE_SETTLE);
+    true
+}
+
+/// Bring `target` to the front, launching it when it isn't running
+pub fn activate(target: &str) -> Result<(), String> {
+    launch(target).map_err(|error| {
+        i18n::tf(
+            "error.target_app_launch",
+            &[("app", target), ("error", &error)],
+        )
+    })?;
+    if !wait_until_frontmost(target) {
+        return Err(i18n::tf(
+            "error.target_app_not_frontmost",
+            &[("app", target)],
+        ));
+    }
+    Ok(())
+}
+
+pub fn begin_session(target: Option<String>) {
+    if let Ok(mut session) = SESSION.lock() {
+        *session = target;
+    }
+}
+
+/// Consume the target app of the dictation that just finished
+pub fn take_session() -> Option<String> {
+    SESSION.lock().ok().and_then(|mut session| session.take())
+}
+
+#[cfg(all(test, target_os = "macos"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tells_bundle_ids_from_app_names() {
+        assert!(is_bundle_id("md.obsidian"));
+        assert!(is_bundle_id("com.apple.Notes"));
+        assert!(!is_bundle_id("Notes"));
+        assert!(!is_bundle_id("Visual Studio Code"));
+        assert!(!is_bundle_id("/Applications/Obsidian.app"));
+        assert!(!is_bundle_id("Obsidian.app"));
+    }
+}
@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+
+/// Above this many word pairs the changed middle is reported as a single edit
+/// instead of running the quadratic diff
+const MAX_DIFF_CELLS: usize = 1_000_000;
+
+/// One change between two versions of a text, in whole words
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextEdit {
+    /// Index of the first changed word in the original text
+    pub at: usize,
+    /// Original words that were replaced, empty for an insertion
+    pub removed: String,
+    /// New words, empty for a deletion
+    pub inserted: String,
+}
+
+#[derive(Default)]
+struct Hunk<'a> {
+    at: usize,
+    removed: Vec<&'a str>,
+    inserted: Vec<&'a str>,
+}
+
+impl Hunk<'_> {
+    fn flush(&mut self, edits: &mut Vec<TextEdit>) {
+        if !self.removed.is_empty() || !self.inserted.is_empty() {
+            edits.push(TextEdit {
+                at: self.at,
+                removed: self.removed.join(" "),
+                inserted: self.inserted.join(" "),
+            });
+        }
+        self.removed.clear();
+        self.inserted.clear();
+    }
+}
+
+/// Word-level changes that turn `original` into `edited`. Whitespace differences are ignored.
+pub fn word_diff(original: &str, edited: &str) -> Vec<TextEdit> {
+    let before: Vec<&str> = original.split_whitespace().collect();
+    let after: Vec<&str> = edited.split_whitespace().collect();
+    let prefix = before
+        .iter()
+        .zip(&after)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = before[prefix..]
+        .iter()
+        .rev()
+        .zip(after[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &before[prefix..before.len() - suffix];
+    let b = &after[prefix..after.len() - suffix];
+
+    let mut edits = Vec::new();
+    let mut hunk = Hunk::default();
+    if (a.len() + 1) * (b.len() + 1) > MAX_DIFF_CELLS {
+        hunk.at = prefix;
+        hunk.removed.extend_from_slice(a);
+        hunk.inserted.extend_from_slice(b);
+        hunk.flush(&mut edits);
+        return edits;
+    }
+
+    // lcs[i][j]: longest common subsequence of a[i..] and b[j..]
+    let width = b.len() + 1;
+    let mut lcs = vec![0u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i * width + j] = if a[i] == b[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            hunk.flush(&mut edits);
+            i += 1;
+            j += 1;
+            continue;
+        }
+        if hunk.removed.is_empty() && hunk.inserted.is_empty() {
+            hunk.at = prefix + i;
+        }
+        if j == b.len() || (i < a.len() && lcs[(i + 1) * width + j] >= lcs[i * width + j + 1]) {
+            hunk.removed.push(a[i]);
+            i += 1;
+        } else {
+            hunk.inserted.push(b[j]);
+            j += 1;
+        }
+    }
+    hunk.flush(&mut edits);
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(at: usize, removed: &str, inserted: &str) -> TextEdit {
+        TextEdit {
+            at,
+            removed: removed.to_string(),
+            inserted: inserted.to_string(),
+        }
+    }
+
+    #[test]
+    fn diffs_words() {
+        assert_eq!(
+            word_diff("send it to jon at noon", "send it to John at noon today"),
+            vec![edit(3, "jon", "John"), edit(6, "", "today")]
+        );
+        assert_eq!(
+            word_diff("the quick brown fox", "the fox"),
+            vec![edit(1, "quick brown", "")]
+        );
+        assert!(word_diff("same  text", "same text").is_empty());
+    }
+}
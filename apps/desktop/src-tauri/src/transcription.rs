@@ -0,0 +1,291 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::time::Duration;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{connect, Message, WebSocket};
+
+use crate::{i18n, providers};
+
+/// Port the sidecar listens on unless it is taken
+pub const DEFAULT_SERVER_PORT: u16 = 8765;
+pub const SAMPLE_RATE: u32 = 16_000;
+/// Model the sidecar transcribes with
+pub const MODEL: &str = "mlx-community/parakeet-tdt-0.6b-v3";
+/// Most alternative hypotheses the sidecar returns for one transcript
+pub const MAX_ALTERNATIVES: u32 = 5;
+
+// One second of audio per binary frame
+const CHUNK_SAMPLES: usize = SAMPLE_RATE as usize;
+// Long recordings can take a while to transcribe after the end message
+const READ_TIMEOUT: Duration = Duration::from_secs(300);
+// A live session is only worth having if the model is already loaded
+const LIVE_READY_TIMEOUT: Duration = Duration::from_secs(5);
+// How long a live session waits for a hypothesis before sending more audio
+const LIVE_POLL_TIMEOUT: Duration = Duration::from_millis(20);
+
+type ServerSocket = WebSocket<MaybeTlsStream<TcpStream>>;
+
+// Chosen by ServerManager each time it spawns the sidecar
+static SERVER_PORT: AtomicU16 = AtomicU16::new(DEFAULT_SERVER_PORT);
+
+pub fn server_port() -> u16 {
+    SERVER_PORT.load(Ordering::SeqCst)
+}
+
+pub fn set_server_port(port: u16) {
+    SERVER_PORT.store(port, Ordering::SeqCst);
+}
+
+pub fn server_http_url() -> String {
+    format!("http://127.0.0.1:{}", server_port())
+}
+
+pub fn server_ws_url() -> String {
+    format!("ws://127.0.0.1:{}/ws", server_port())
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionOptions {
+    /// Ask the sidecar to label segments by speaker
+    pub diarize: bool,
+    /// Ask the sidecar for per-word start/end times
+    pub word_timestamps: bool,
+    /// Number of alternative hypotheses (n-best) to return besides the best one
+    pub alternatives: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptSegment {
+    pub text: String,
+    pub start: f64,
+    pub end: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    /// Model confidence in 0.0..=1.0, when the sidecar reports it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+}
+
+/// A single word with its start/end offset in seconds from the start of the recording
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptWord {
+    pub text: String,
+    pub start: f64,
+    pub end: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transcript {
+    pub text: String,
+    #[serde(default)]
+    pub segments: Vec<TranscriptSegment>,
+    #[serde(default)]
+    pub words: Vec<TranscriptWord>,
+    /// Lower-ranked hypotheses for the whole utterance, best first
+    #[serde(default)]
+    pub alternatives: Vec<String>,
+    /// Detected language as a BCP 47 tag (e.g. "en", "fr-FR"), when the model reports one
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+impl Transcript {
+    /// Duration-weighted mean of the segment confidences the sidecar reported
+    pub fn overall_confidence(&self) -> Option<f32> {
+        let (weighted, total) = self
+            .segments
+            .iter()
+            .filter_map(|segment| {
+                let weight = (segment.end - segment.start).max(0.01) as f32;
+                segment
+                    .confidence
+                    .map(|confidence| (confidence * weight, weight))
+            })
+            .fold((0.0, 0.0), |(sum, total), (value, weight)| {
+                (sum + value, total + weight)
+            });
+        (total > 0.0).then(|| weighted / total)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum ServerMessage {
+    Ready,
+    /// Hypothesis for the audio so far, sent while a live session streams
+    Partial {
+        text: String,
+    },
+    Final(Transcript),
+    Error {
+        error: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+fn read_message(socket: &mut ServerSocket) -> Result<ServerMessage, String> {
+    loop {
+        let message = socket.read().map_err(|e| {
+            i18n::tf(
+                "error.voice_server_connection",
+                &[("error", &e.to_string())],
+            )
+        })?;
+        match message {
+            Message::Text(text) => {
+                return serde_json::from_str(&text).map_err(|e| {
+                    i18n::tf("error.voice_server_message", &[("error", &e.to_string())])
+                });
+            }
+            Message::Close(_) => return Err(i18n::t("error.voice_server_closed")),
+            _ => {}
+        }
+    }
+}
+
+fn send_json(socket: &mut ServerSocket, value: serde_json::Value) -> Result<(), String> {
+    socket
+        .send(Message::Text(value.to_string().into()))
+        .map_err(|e| i18n::tf("error.voice_server_send", &[("error", &e.to_string())]))
+}
+
+/// Run a full start → audio → end session against the sidecar and wait for the final result
+pub fn transcribe(samples: &[i16], options: &TranscriptionOptions) -> Result<Transcript, String> {
+    if let Some(result) = providers::transcribe(samples, options) {
+        return result;
+    }
+    let (mut socket, _) = connect(server_ws_url())
+        .map_err(|e| i18n::tf("error.voice_server_connect", &[("error", &e.to_string())]))?;
+    if let MaybeTlsStream::Plain(stream) = socket.get_ref() {
+        let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    }
+
+    // The server announces readiness (or loading progress) right after accepting
+    loop {
+        match read_message(&mut socket)? {
+            ServerMessage::Ready => break,
+            ServerMessage::Error { error } => return Err(error),
+            _ => {}
+        }
+    }
+
+    let mut start = serde_json::to_value(options).map_err(|e| e.to_string())?;
+    start["type"] = "start".into();
+    send_json(&mut socket, start)?;
+
+    for chunk in samples.chunks(CHUNK_SAMPLES) {
+        let bytes: Vec<u8> = chunk
+            .iter()
+            .flat_map(|sample| sample.to_le_bytes())
+            .collect();
+        socket
+            .send(Message::Binary(bytes.into()))
+            .map_err(|e| i18n::tf("error.stream_audio", &[("error", &e.to_string())]))?;
+    }
+    send_json(&mut socket, serde_json::json!({ "type": "end" }))?;
+
+    let result = loop {
+        match read_message(&mut socket)? {
+            ServerMessage::Final(transcript) => break Ok(transcript),
+            ServerMessage::Error { error } => break Err(error),
+            _ => {}
+        }
+    };
+    let _ = socket.close(None);
+    result
+}
+
+/// A session that streams audio while it is recorded, for the partial hypotheses
+/// the sidecar sends back. It never asks for a final result.
+pub struct LiveSession {
+    socket: ServerSocket,
+}
+
+impl LiveSession {
+    pub fn open(options: &TranscriptionOptions) -> Result<Self, String> {
+        let (mut socket, _) = connect(server_ws_url())
+            .map_err(|e| i18n::tf("error.voice_server_connect", &[("error", &e.to_string())]))?;
+        if let MaybeTlsStream::Plain(stream) = socket.get_ref() {
+            let _ = stream.set_read_timeout(Some(LIVE_READY_TIMEOUT));
+        }
+        loop {
+            match read_message(&mut socket)? {
+                ServerMessage::Ready => break,
+                ServerMessage::Error { error } => return Err(error),
+                _ => {}
+            }
+        }
+        let mut start = serde_json::to_value(options).map_err(|e| e.to_string())?;
+        start["type"] = "start".into();
+        send_json(&mut socket, start)?;
+        if let MaybeTlsStream::Plain(stream) = socket.get_ref() {
+            let _ = stream.set_read_timeout(Some(LIVE_POLL_TIMEOUT));
+        }
+        Ok(Self { socket })
+    }
+
+    pub fn send_audio(&mut self, samples: &[i16]) -> Result<(), String> {
+        let bytes: Vec<u8> = samples
+            .iter()
+            .flat_map(|sample| sample.to_le_bytes())
+            .collect();
+        self.socket
+            .send(Message::Binary(bytes.into()))
+            .map_err(|e| i18n::tf("error.stream_audio", &[("error", &e.to_string())]))
+    }
+
+    /// The next partial hypothesis, None when nothing arrived within the poll timeout
+    pub fn poll_partial(&mut self) -> Result<Option<String>, String> {
+        loop {
+            let message = match self.socket.read() {
+                Ok(Message::Text(text)) => text,
+                Ok(Message::Close(_)) => return Err(i18n::t("error.voice_server_closed")),
+                Ok(_) => continue,
+                Err(tungstenite::Error::Io(e))
+                    if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+                {
+                    return Ok(None)
+                }
+                Err(e) => {
+                    return Err(i18n::tf(
+                        "error.voice_server_connection",
+                        &[("error", &e.to_string())],
+                    ))
+                }
+            };
+            match serde_json::from_str(&message) {
+                Ok(ServerMessage::Partial { text }) => return Ok(Some(text)),
+                Ok(ServerMessage::Error { error }) => return Err(error),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn close(mut self) {
+        let _ = self.socket.close(None);
+        let _ = self.socket.flush();
+    }
+}
+
+/// Replace raw diarization labels (e.g. `SPEAKER_00`) with "Speaker N" in order of appearance
+pub fn label_speakers(segments: &mut [TranscriptSegment]) {
+    let mut labels: HashMap<String, String> = HashMap::new();
+    for segment in segments.iter_mut() {
+        let Some(raw) = segment.speaker.take() else {
+            continue;
+        };
+        let next = labels.len() + 1;
+        let label = labels
+            .entry(raw)
+            .or_insert_with(|| format!("Speaker {next}"));
+        segment.speaker = Some(label.clone());
+    }
+}
@@ -0,0 +1,282 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
+use tauri::{
+    image::Image,
+    menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, Submenu},
+    tray::{TrayIcon, TrayIconBuilder},
+    AppHandle, Emitter, Manager,
+};
+
+use crate::{
+    current_shortcut_config, i18n, profiles, scratchpad, show_main_window, show_or_create_main_app,
+    shutdown, ShortcutMode, IS_RECORDING,
+};
+
+// The tray icon shows what dictation is doing: a pulsing red dot while recording,
+// an amber one while the transcript is processed, and a faded icon while dictation
+// is paused from the menu.
+
+// Half a pulse of the recording dot
+const PULSE_INTERVAL: Duration = Duration::from_millis(500);
+// A transcription the webview never reports back shouldn't leave the icon busy
+const PROCESSING_TIMEOUT: Duration = Duration::from_secs(60);
+const RECORDING_COLOR: [u8; 3] = [0xe5, 0x48, 0x4d];
+const PROCESSING_COLOR: [u8; 3] = [0xf5, 0xa5, 0x24];
+
+// Deliberately not persisted, so a restart always listens again
+static PAUSED: AtomicBool = AtomicBool::new(false);
+// Bumped by every state change so only the latest animation keeps running
+static STATE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayState {
+    Idle,
+    Recording,
+    Processing,
+}
+
+/// The tray icon and its state variants, kept in managed state
+pub struct Tray {
+    icon: TrayIcon,
+    idle: Image<'static>,
+    paused: Image<'static>,
+    recording: [Image<'static>; 2],
+    processing: Image<'static>,
+}
+
+fn owned(image: &Image<'_>, rgba: Vec<u8>) -> Image<'static> {
+    Image::new_owned(rgba, image.width(), image.height())
+}
+
+/// `base` with a dot of `color` in its lower right corner
+fn with_dot(base: &Image<'_>, color: [u8; 3], opacity: f32) -> Image<'static> {
+    let (width, height) = (base.width() as f32, base.height() as f32);
+    let radius = width.min(height) / 5.0;
+    let (center_x, center_y) = (width - radius - 1.0, height - radius - 1.0);
+    let mut rgba = base.rgba().to_vec();
+    for (index, pixel) in rgba.chunks_exact_mut(4).enumerate() {
+        let x = (index % base.width() as usize) as f32 + 0.5;
+        let y = (index / base.width() as usize) as f32 + 0.5;
+        // Anti-aliased over the last pixel of the radius
+        let coverage = (radius - ((x - center_x).powi(2) + (y - center_y).powi(2)).sqrt() + 0.5)
+            .clamp(0.0, 1.0)
+            * opacity;
+        if coverage == 0.0 {
+            continue;
+        }
+        for (channel, value) in pixel.iter_mut().zip(color) {
+            *channel = (f32::from(*channel) * (1.0 - coverage) + f32::from(value) * coverage) as u8;
+        }
+        pixel[3] = pixel[3].max((coverage * 255.0) as u8);
+    }
+    owned(base, rgba)
+}
+
+fn faded(base: &Image<'_>) -> Image<'static> {
+    let mut rgba = base.rgba().to_vec();
+    for pixel in rgba.chunks_exact_mut(4) {
+        pixel[3] /= 2;
+    }
+    owned(base, rgba)
+}
+
+pub fn is_paused() -> bool {
+    PAUSED.load(Ordering::SeqCst)
+}
+
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
+    let shortcut_config = current_shortcut_config(app);
+    let shortcut_display = shortcut_config.display_string();
+    let quit_item =
+        MenuItem::with_id(app, "quit", i18n::t("tray.quit"), true, Some("CmdOrCtrl+Q"))?;
+    let record_key = match shortcut_config.mode {
+        ShortcutMode::Hold => "tray.record",
+        ShortcutMode::Toggle => "tray.record_toggle",
+    };
+    let record_text = i18n::tf(record_key, &[("shortcut", &shortcut_display)]);
+    let record_item = MenuItem::with_id(app, "record", &record_text, true, None::<&str>)?;
+    let pause_item = CheckMenuItem::with_id(
+        app,
+        "pause",
+        i18n::t("tray.pause"),
+        true,
+        is_paused(),
+        None::<&str>,
+    )?;
+    let settings_item = MenuItem::with_id(
+        app,
+        "settings",
+        i18n::t("tray.settings"),
+        true,
+        Some("CmdOrCtrl+,"),
+    )?;
+    let scratchpad_item = MenuItem::with_id(
+        app,
+        "scratchpad",
+        i18n::t("tray.scratchpad"),
+        true,
+        None::<&str>,
+    )?;
+
+    let profiles = profiles::list(app);
+    let profile_items = profiles
+        .profiles
+        .iter()
+        .map(|name| {
+            let id = format!("{}{}", profiles::MENU_ID_PREFIX, name);
+            CheckMenuItem::with_id(app, id, name, true, *name == profiles.active, None::<&str>)
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let profile_refs: Vec<&dyn IsMenuItem<tauri::Wry>> = profile_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<tauri::Wry>)
+        .collect();
+    let profiles_menu = Submenu::with_items(app, i18n::t("tray.profile"), true, &profile_refs)?;
+
+    Menu::with_items(
+        app,
+        &[
+            &record_item,
+            &pause_item,
+            &scratchpad_item,
+            &profiles_menu,
+            &settings_item,
+            &quit_item,
+        ],
+    )
+}
+
+/// Rebuild the tray menu after the shortcut, profiles, language or pause change
+pub fn refresh_menu(app: &AppHandle) {
+    let Some(tray) = app.try_state::<Tray>() else {
+        return;
+    };
+    match build_menu(app) {
+        Ok(menu) => {
+            let _ = tray.icon.set_menu(Some(menu));
+        }
+        Err(e) => eprintln!("[voiceflow] Failed to rebuild tray menu: {}", e),
+    }
+}
+
+/// Show `state` in the tray icon, animating it until the next change
+pub fn set_state(app: &AppHandle, state: TrayState) {
+    let Some(tray) = app.try_state::<Tray>() else {
+        return;
+    };
+    let generation = STATE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let icon = match state {
+        TrayState::Idle if is_paused() => tray.paused.clone(),
+        TrayState::Idle => tray.idle.clone(),
+        TrayState::Recording => tray.recording[0].clone(),
+        TrayState::Processing => tray.processing.clone(),
+    };
+    let _ = tray.icon.set_icon(Some(icon));
+
+    let app = app.clone();
+    match state {
+        TrayState::Idle => {}
+        TrayState::Recording => {
+            thread::spawn(move || {
+                for frame in [1, 0].into_iter().cycle() {
+                    thread::sleep(PULSE_INTERVAL);
+                    if STATE_GENERATION.load(Ordering::SeqCst) != generation {
+                        return;
+                    }
+                    let tray = app.state::<Tray>();
+                    let _ = tray.icon.set_icon(Some(tray.recording[frame].clone()));
+                }
+            });
+        }
+        TrayState::Processing => {
+            thread::spawn(move || {
+                thread::sleep(PROCESSING_TIMEOUT);
+                if STATE_GENERATION.load(Ordering::SeqCst) == generation {
+                    set_state(&app, TrayState::Idle);
+                }
+            });
+        }
+    }
+}
+
+fn set_paused(app: &AppHandle, paused: bool) {
+    if PAUSED.swap(paused, Ordering::SeqCst) == paused {
+        return;
+    }
+    let _ = app.emit("dictation-paused", paused);
+    refresh_menu(app);
+    // Pausing doesn't end a dictation that is already running
+    if !IS_RECORDING.load(Ordering::SeqCst) {
+        set_state(app, TrayState::Idle);
+    }
+}
+
+/// Stop shortcuts and other triggers from starting dictations until resumed
+#[tauri::command]
+pub async fn set_dictation_paused(app: AppHandle, paused: bool) {
+    set_paused(&app, paused);
+}
+
+#[tauri::command]
+pub async fn get_dictation_paused() -> bool {
+    is_paused()
+}
+
+pub fn setup(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let menu = build_menu(app)?;
+
+    let icon_bytes = include_bytes!("../icons/tray-icon.png");
+    let idle = Image::from_bytes(icon_bytes)?.to_owned();
+
+    let icon = TrayIconBuilder::with_id("main")
+        .icon(idle.clone())
+        .menu(&menu)
+        .show_menu_on_left_click(true)
+        .on_menu_event(|app, event| match event.id.as_ref() {
+            "quit" => shutdown::request_quit(app),
+            "record" => show_main_window(app),
+            "pause" => set_paused(app, !is_paused()),
+            "scratchpad" => scratchpad::show(app),
+            "settings" => {
+                // Open the main app window (which has Settings tab)
+                show_or_create_main_app(app);
+            }
+            id => {
+                if let Some(name) = id.strip_prefix(profiles::MENU_ID_PREFIX) {
+                    if let Err(e) = profiles::switch(app, name) {
+                        eprintln!("[voiceflow] Failed to switch profile: {}", e);
+                    }
+                }
+            }
+        })
+        .build(app)?;
+
+    app.manage(Tray {
+        icon,
+        paused: faded(&idle),
+        recording: [
+            with_dot(&idle, RECORDING_COLOR, 1.0),
+            with_dot(&idle, RECORDING_COLOR, 0.4),
+        ],
+        processing: with_dot(&idle, PROCESSING_COLOR, 1.0),
+        idle,
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dots_the_lower_right_corner() {
+        let size = 20;
+        let base = Image::new_owned(vec![0; size * size * 4], size as u32, size as u32);
+        let dotted = with_dot(&base, RECORDING_COLOR, 1.0);
+        let pixel = |x: usize, y: usize| &dotted.rgba()[(y * size + x) * 4..][..4];
+        assert_eq!(pixel(size - 5, size - 5), [0xe5, 0x48, 0x4d, 0xff]);
+        assert_eq!(pixel(0, 0), [0, 0, 0, 0]);
+        assert_eq!(pixel(size - 1, 0), [0, 0, 0, 0]);
+    }
+}
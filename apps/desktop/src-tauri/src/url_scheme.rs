@@ -0,0 +1,340 @@
+use serde::Serialize;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Url};
+
+use crate::pipeline::RecordedTranscription;
+use crate::voice_commands::open_url;
+use crate::{
+    append_e2e_log, auth, cancel_recording, history, i18n, result_panel, settings,
+    show_or_create_main_app, start_recording, stop_recording, IS_RECORDING,
+};
+
+// x-callback-url actions on the voiceflow:// scheme, for Alfred, Raycast and Keyboard
+// Maestro workflows, e.g.
+//
+//   voiceflow://x-callback-url/dictate?x-success=raycast://...&x-error=raycast://...
+//
+//   dictate  start a dictation; x-success receives `transcript` and `id` once it has
+//            been transcribed, x-cancel fires if it is cancelled. With `paste=0` the
+//            transcript is only handed to the callback, not pasted, and with
+//            `output=panel` it is shown in the result panel instead.
+//   stop     finish the running dictation, as releasing the shortcut would
+//   cancel   drop the running dictation
+//   last     send the most recent dictation to x-success, once the user allowed it in
+//            the app and unlocked history
+//
+// Failures go to x-error with `errorCode` and `errorMessage`. Callbacks only open apps
+// whose scheme is allowed, so a web page opening a voiceflow:// link can't have
+// transcripts sent to a website, a mail draft or a text message.
+
+const CALLBACK_HOST: &str = "x-callback-url";
+
+const ERROR_DISABLED: u32 = 1;
+const ERROR_UNKNOWN_ACTION: u32 = 2;
+const ERROR_BUSY: u32 = 3;
+const ERROR_BLOCKED: u32 = 4;
+const ERROR_NOTHING: u32 = 5;
+const ERROR_DICTATION: u32 = 6;
+const ERROR_DECLINED: u32 = 7;
+
+/// Workflow apps callbacks may open, besides the ones added in settings
+const CALLBACK_SCHEMES: &[&str] = &[
+    "alfred",
+    "raycast",
+    "kmtrigger",
+    "shortcuts",
+    "hammerspoon",
+    "btt",
+];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Callbacks {
+    success: Option<Url>,
+    error: Option<Url>,
+    cancel: Option<Url>,
+    /// Paste the transcript like a shortcut dictation would
+    paste: bool,
+    /// Show the transcript in the result panel instead of pasting it
+    panel: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Request {
+    action: String,
+    callbacks: Callbacks,
+}
+
+/// Sent with `url-last-requested` so the user can see where the transcript would go
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LastRequest {
+    /// Scheme of the app that receives the transcript
+    callback: String,
+}
+
+// Callbacks of the running dictation when a URL started it
+static PENDING: Mutex<Option<Callbacks>> = Mutex::new(None);
+// Callbacks of a `last` request waiting for the user's answer
+static PENDING_LAST: Mutex<Option<Callbacks>> = Mutex::new(None);
+
+/// Callback URLs are opened with the system handler, so only workflow apps are allowed
+fn callback_url(value: &str, allowed: &[String]) -> Option<Url> {
+    let url = Url::parse(value).ok()?;
+    let scheme = url.scheme();
+    let allowed = CALLBACK_SCHEMES.contains(&scheme)
+        || allowed
+            .iter()
+            .any(|allowed| allowed.trim().eq_ignore_ascii_case(scheme));
+    allowed.then_some(url)
+}
+
+fn parse(url: &Url, allowed: &[String]) -> Option<Request> {
+    if url.scheme() != "voiceflow" {
+        return None;
+    }
+    // Both voiceflow://x-callback-url/dictate and the short voiceflow://dictate
+    let action = match url.host_str() {
+        Some(CALLBACK_HOST) => url.path().trim_matches('/').to_string(),
+        Some(host) => host.to_string(),
+        None => url.path().trim_matches('/').to_string(),
+    };
+    let mut callbacks = Callbacks {
+        paste: true,
+        ..Callbacks::default()
+    };
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "x-success" => callbacks.success = callback_url(&value, allowed),
+            "x-error" => callbacks.error = callback_url(&value, allowed),
+            "x-cancel" => callbacks.cancel = callback_url(&value, allowed),
+            "paste" => callbacks.paste = !matches!(value.as_ref(), "0" | "false" | "no"),
+            "output" => callbacks.panel = value == "panel",
+            _ => {}
+        }
+    }
+    Some(Request {
+        action: action.to_lowercase(),
+        callbacks,
+    })
+}
+
+fn with_params(url: &Url, params: &[(&str, &str)]) -> Url {
+    let mut url = url.clone();
+    url.query_pairs_mut().extend_pairs(params);
+    url
+}
+
+fn call(url: Option<&Url>, params: &[(&str, &str)]) {
+    let Some(url) = url else {
+        return;
+    };
+    if let Err(e) = open_url(with_params(url, params).as_str()) {
+        eprintln!("[voiceflow] Failed to open callback URL: {e}");
+    }
+}
+
+fn fail(callbacks: &Callbacks, code: u32, message: &str) {
+    eprintln!("[voiceflow] URL action failed: {message}");
+    let code = code.to_string();
+    call(
+        callbacks.error.as_ref(),
+        &[("errorCode", &code), ("errorMessage", message)],
+    );
+}
+
+/// Run the actions in URLs the system opened VoiceFlow with
+pub fn handle_urls(app: &AppHandle, urls: &[Url]) {
+    let allowed = settings::current(app).url_callback_schemes;
+    for request in urls.iter().filter_map(|url| parse(url, &allowed)) {
+        handle(app, request);
+    }
+}
+
+fn handle(app: &AppHandle, request: Request) {
+    let callbacks = request.callbacks;
+    append_e2e_log(&format!("url-action {}", request.action));
+    if !settings::current(app).url_actions_enabled {
+        fail(
+            &callbacks,
+            ERROR_DISABLED,
+            &i18n::t("error.url_actions_disabled"),
+        );
+        return;
+    }
+    match request.action.as_str() {
+        "dictate" => {
+            if IS_RECORDING.load(Ordering::SeqCst) {
+                fail(&callbacks, ERROR_BUSY, &i18n::t("error.already_recording"));
+                return;
+            }
+            if let Ok(mut pending) = PENDING.lock() {
+                *pending = Some(callbacks.clone());
+            }
+            if callbacks.panel {
+                result_panel::begin_session();
+            }
+            if !start_recording(app) {
+                take_pending();
+                result_panel::dictation_cancelled();
+                fail(
+                    &callbacks,
+                    ERROR_BLOCKED,
+                    &i18n::t("error.dictation_blocked"),
+                );
+            }
+        }
+        "stop" => {
+            stop_recording(app);
+            call(callbacks.success.as_ref(), &[]);
+        }
+        "cancel" => {
+            cancel_recording(app);
+            call(callbacks.success.as_ref(), &[]);
+        }
+        "last" => {
+            let Some(success) = &callbacks.success else {
+                return;
+            };
+            // Any page can open the link, so the user decides in the app
+            let request = LastRequest {
+                callback: success.scheme().to_string(),
+            };
+            if let Ok(mut pending) = PENDING_LAST.lock() {
+                *pending = Some(callbacks);
+            }
+            show_or_create_main_app(app);
+            let _ = app.emit("url-last-requested", request);
+        }
+        action => fail(
+            &callbacks,
+            ERROR_UNKNOWN_ACTION,
+            &i18n::tf("error.unknown_url_action", &[("action", action)]),
+        ),
+    }
+}
+
+/// Answer to "url-last-requested": send the most recent dictation to the workflow
+/// when the user allowed it and history unlocks
+#[tauri::command]
+pub async fn answer_url_last_request(app: AppHandle, allow: bool) -> Result<(), String> {
+    let Some(callbacks) = PENDING_LAST
+        .lock()
+        .ok()
+        .and_then(|mut pending| pending.take())
+    else {
+        return Ok(());
+    };
+    if !allow {
+        fail(
+            &callbacks,
+            ERROR_DECLINED,
+            &i18n::t("error.url_action_declined"),
+        );
+        return Ok(());
+    }
+    if let Err(error) = auth::unlock(&app, "auth.history").await {
+        fail(&callbacks, ERROR_DECLINED, &error);
+        return Err(error);
+    }
+    match history::last_entry(&app) {
+        Some(entry) => {
+            let id = entry.id.to_string();
+            call(
+                callbacks.success.as_ref(),
+                &[("transcript", &entry.text), ("id", &id)],
+            );
+        }
+        None => fail(
+            &callbacks,
+            ERROR_NOTHING,
+            &i18n::t("error.no_previous_dictation"),
+        ),
+    }
+    Ok(())
+}
+
+fn take_pending() -> Option<Callbacks> {
+    PENDING.lock().ok().and_then(|mut pending| pending.take())
+}
+
+/// Hand the result of a URL-started dictation to its callbacks, marking it handled
+/// when the workflow asked for the transcript not to be pasted
+pub fn dictation_finished(
+    recorded: Result<RecordedTranscription, String>,
+) -> Result<RecordedTranscription, String> {
+    let Some(callbacks) = take_pending() else {
+        return recorded;
+    };
+    match recorded {
+        Ok(mut recorded) => {
+            let id = recorded.entry.id.to_string();
+            call(
+                callbacks.success.as_ref(),
+                &[("transcript", &recorded.entry.text), ("id", &id)],
+            );
+            recorded.handled |= !callbacks.paste;
+            Ok(recorded)
+        }
+        Err(error) => {
+            fail(&callbacks, ERROR_DICTATION, &error);
+            Err(error)
+        }
+    }
+}
+
+pub fn dictation_cancelled() {
+    if let Some(callbacks) = take_pending() {
+        call(callbacks.cancel.as_ref(), &[]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(url: &str) -> Option<Request> {
+        parse(&Url::parse(url).unwrap(), &["myapp".to_string()])
+    }
+
+    #[test]
+    fn parses_callback_and_short_forms() {
+        let full =
+            request("voiceflow://x-callback-url/dictate?x-success=raycast%3A%2F%2Fdone&paste=0")
+                .unwrap();
+        assert_eq!(full.action, "dictate");
+        assert_eq!(full.callbacks.success.unwrap().as_str(), "raycast://done");
+        assert!(!full.callbacks.paste);
+
+        let short = request("voiceflow://Last").unwrap();
+        assert_eq!(short.action, "last");
+        assert!(short.callbacks.paste);
+        assert!(request("raycast://dictate").is_none());
+    }
+
+    #[test]
+    fn allows_only_workflow_callbacks() {
+        let web = request(
+            "voiceflow://dictate?x-success=https%3A%2F%2Fexample.com&x-error=kmtrigger%3A%2F%2Fm",
+        )
+        .unwrap();
+        assert!(web.callbacks.success.is_none());
+        assert!(web.callbacks.error.is_some());
+
+        let mail =
+            request("voiceflow://last?x-success=mailto%3Aa%40example.com&x-cancel=MyApp%3A%2F%2Fx")
+                .unwrap();
+        assert!(mail.callbacks.success.is_none());
+        assert!(mail.callbacks.cancel.is_some());
+    }
+
+    #[test]
+    fn appends_result_parameters() {
+        let url = Url::parse("alfred://runtrigger?id=1").unwrap();
+        let called = with_params(&url, &[("transcript", "hi there & bye")]);
+        assert_eq!(
+            called.as_str(),
+            "alfred://runtrigger?id=1&transcript=hi+there+%26+bye"
+        );
+    }
+}
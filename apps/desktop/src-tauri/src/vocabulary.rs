@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::settings;
+use crate::text_diff::TextEdit;
+
+/// A word has to be fixed this many times before it is corrected automatically
+const LEARN_AFTER: u32 = 2;
+
+/// A word the transcriber keeps getting wrong, learned from history edits
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LearnedWord {
+    /// What was transcribed, lowercase
+    pub heard: String,
+    /// What the user corrected it to
+    pub written: String,
+    /// How often this correction was made
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct VocabularySettings {
+    /// Learn from single-word fixes made when editing history entries
+    pub learn_from_edits: bool,
+    pub learned: Vec<LearnedWord>,
+}
+
+fn bare(word: &str) -> &str {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+}
+
+/// Count the single-word replacements among `edits`
+pub fn count_fixes(learned: &mut Vec<LearnedWord>, edits: &[TextEdit]) {
+    for edit in edits {
+        let (heard, written) = (bare(&edit.removed), bare(&edit.inserted));
+        if heard.is_empty()
+            || written.is_empty()
+            || heard.contains(char::is_whitespace)
+            || written.contains(char::is_whitespace)
+            || heard == written
+        {
+            continue;
+        }
+        let heard = heard.to_lowercase();
+        match learned
+            .iter_mut()
+            .find(|word| word.heard == heard && word.written == written)
+        {
+            Some(word) => word.count += 1,
+            None => learned.push(LearnedWord {
+                heard,
+                written: written.to_string(),
+                count: 1,
+            }),
+        }
+    }
+}
+
+/// The most frequent correction for a transcribed word, once it has been made often enough
+pub fn correction<'a>(learned: &'a [LearnedWord], word: &str) -> Option<&'a str> {
+    let heard = word.to_lowercase();
+    learned
+        .iter()
+        .filter(|learned| learned.count >= LEARN_AFTER && learned.heard == heard)
+        .max_by_key(|learned| learned.count)
+        .map(|learned| learned.written.as_str())
+}
+
+/// Learn a correction straight away, as if it had been made often enough
+pub fn add(learned: &mut Vec<LearnedWord>, heard: &str, written: &str) {
+    let heard = heard.to_lowercase();
+    match learned
+        .iter_mut()
+        .find(|word| word.heard == heard && word.written == written)
+    {
+        Some(word) => word.count = word.count.max(LEARN_AFTER),
+        None => learned.push(LearnedWord {
+            heard,
+            written: written.to_string(),
+            count: LEARN_AFTER,
+        }),
+    }
+}
+
+/// Replace learned words in `text`, keeping surrounding punctuation and spacing
+fn correct(learned: &[LearnedWord], text: &str) -> String {
+    if !learned.iter().any(|word| word.count >= LEARN_AFTER) {
+        return text.to_string();
+    }
+    text.split_inclusive(char::is_whitespace)
+        .map(|token| {
+            let word = bare(token);
+            match correction(learned, word).filter(|_| !word.is_empty()) {
+                Some(written) => token.replacen(word, written, 1),
+                None => token.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Apply the corrections learned from history edits to a new transcript
+pub fn apply(app: &AppHandle, text: &str) -> String {
+    correct(&settings::current(app).vocabulary.learned, text)
+}
+
+/// Remember the words fixed by an edit, when learning is enabled
+pub fn learn_from_edits(app: &AppHandle, edits: &[TextEdit]) {
+    if edits.is_empty() || !settings::current(app).vocabulary.learn_from_edits {
+        return;
+    }
+    if let Err(e) = settings::update(app, |settings| {
+        count_fixes(&mut settings.vocabulary.learned, edits)
+    }) {
+        eprintln!("[voiceflow] Failed to save learned words: {e}");
+    }
+}
+
+#[tauri::command]
+pub async fn set_learn_from_edits(app: AppHandle, enabled: bool) -> Result<(), String> {
+    settings::update(&app, |settings| {
+        settings.vocabulary.learn_from_edits = enabled
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fix(removed: &str, inserted: &str) -> TextEdit {
+        TextEdit {
+            at: 0,
+            removed: removed.to_string(),
+            inserted: inserted.to_string(),
+        }
+    }
+
+    #[test]
+    fn corrects_words_fixed_repeatedly() {
+        let mut learned = Vec::new();
+        count_fixes(&mut learned, &[fix("cooper netties,", "Kubernetes,")]);
+        count_fixes(&mut learned, &[fix("jon", "John"), fix("", "today")]);
+        assert_eq!(correct(&learned, "ask jon"), "ask jon");
+
+        count_fixes(&mut learned, &[fix("Jon.", "John.")]);
+        assert_eq!(learned.len(), 1);
+        assert_eq!(
+            correct(&learned, "Ask Jon, then\njon again"),
+            "Ask John, then\nJohn again"
+        );
+    }
+}
@@ -0,0 +1,317 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::Shortcut;
+
+use crate::shortcuts::{self, ShortcutAction};
+use crate::{i18n, keyboard, ShortcutConfig};
+
+// Whether the dictation in progress was started with the command-mode shortcut
+static COMMAND_SESSION: AtomicBool = AtomicBool::new(false);
+
+/// Placeholder capturing the rest of the utterance, e.g. "search for {text}"
+pub const TEXT_PLACEHOLDER: &str = "{text}";
+
+pub const COMMAND_HISTORY_TAG: &str = "command";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CommandAction {
+    OpenUrl {
+        url: String,
+    },
+    /// Runs through the shell; the captured text is passed as `$VOICEFLOW_TEXT`, never spliced in
+    RunShell {
+        command: String,
+    },
+    PressKeys {
+        chord: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceCommand {
+    pub phrase: String,
+    pub action: CommandAction,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct VoiceCommandConfig {
+    /// Where the command-mode shortcut was kept before settings.json held it
+    pub shortcut: Option<ShortcutConfig>,
+    pub commands: Vec<VoiceCommand>,
+    /// Only emit the preview event instead of executing
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandPreview {
+    pub transcript: String,
+    pub command: Option<VoiceCommand>,
+    pub captured_text: Option<String>,
+    pub dry_run: bool,
+}
+
+pub struct VoiceCommandManager {
+    config: VoiceCommandConfig,
+    config_path: PathBuf,
+}
+
+impl VoiceCommandManager {
+    pub fn new(config_dir: PathBuf) -> Self {
+        let config_path = config_dir.join("voice_commands.json");
+        let config = Self::load_config(&config_path).unwrap_or_default();
+        Self {
+            config,
+            config_path,
+        }
+    }
+
+    fn load_config(path: &PathBuf) -> Option<VoiceCommandConfig> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn save_config(&self) -> Result<(), String> {
+        if let Some(parent) = self.config_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string_pretty(&self.config).map_err(|e| e.to_string())?;
+        fs::write(&self.config_path, content).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    pub fn get_config(&self) -> VoiceCommandConfig {
+        self.config.clone()
+    }
+
+    fn update(&mut self, apply: impl FnOnce(&mut VoiceCommandConfig)) -> Result<(), String> {
+        apply(&mut self.config);
+        self.save_config()
+    }
+}
+
+fn config(app: &AppHandle) -> VoiceCommandConfig {
+    let state = app.state::<Mutex<VoiceCommandManager>>();
+    let config = state.lock().map(|manager| manager.get_config());
+    config.unwrap_or_default()
+}
+
+/// Lowercase words stripped of surrounding punctuation, for comparing spoken phrases
+pub fn normalize_phrase(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| {
+            word.trim_matches(|c: char| !c.is_alphanumeric() && c != '{' && c != '}')
+                .to_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Match an utterance against a phrase, returning the `{text}` capture if the phrase has one
+fn match_phrase(phrase: &str, transcript: &str) -> Option<Option<String>> {
+    let phrase = normalize_phrase(phrase);
+    let spoken = normalize_phrase(transcript);
+
+    match phrase.strip_suffix(TEXT_PLACEHOLDER) {
+        Some(prefix) => {
+            let rest = spoken.strip_prefix(prefix)?.trim();
+            (!rest.is_empty()).then(|| Some(rest.to_string()))
+        }
+        None => (phrase == spoken).then_some(None),
+    }
+}
+
+fn find_command(
+    commands: &[VoiceCommand],
+    transcript: &str,
+) -> Option<(VoiceCommand, Option<String>)> {
+    commands.iter().find_map(|command| {
+        match_phrase(&command.phrase, transcript).map(|captured| (command.clone(), captured))
+    })
+}
+
+pub fn encode_url_component(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            b' ' => "+".to_string(),
+            other => format!("%{other:02X}"),
+        })
+        .collect()
+}
+
+pub fn open_url(url: &str) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = Command::new("xdg-open");
+
+    command
+        .arg(url)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open URL: {e}"))
+}
+
+fn execute(action: &CommandAction, captured: Option<&str>) -> Result<(), String> {
+    match action {
+        CommandAction::OpenUrl { url } => {
+            let url = url.replace(
+                TEXT_PLACEHOLDER,
+                &encode_url_component(captured.unwrap_or_default()),
+            );
+            open_url(&url)
+        }
+        CommandAction::RunShell { command } => {
+            #[cfg(target_os = "windows")]
+            let mut shell = {
+                let mut shell = Command::new("cmd");
+                shell.arg("/C");
+                shell
+            };
+            #[cfg(not(target_os = "windows"))]
+            let mut shell = {
+                let mut shell = Command::new("sh");
+                shell.arg("-c");
+                shell
+            };
+            shell
+                .arg(command)
+                .env("VOICEFLOW_TEXT", captured.unwrap_or_default())
+                .spawn()
+                .map(|_| ())
+                .map_err(|e| format!("Failed to run command: {e}"))
+        }
+        CommandAction::PressKeys { chord } => keyboard::press_chord(chord),
+    }
+}
+
+pub fn command_shortcut_config(app: &AppHandle) -> Option<ShortcutConfig> {
+    shortcuts::stored(app, ShortcutAction::CommandMode).or(config(app).shortcut)
+}
+
+/// Drop the shortcut kept here before it moved to settings.json
+pub fn clear_legacy_shortcut(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<Mutex<VoiceCommandManager>>();
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    if manager.config.shortcut.is_none() {
+        return Ok(());
+    }
+    manager.update(|config| config.shortcut = None)
+}
+
+pub fn command_shortcut(app: &AppHandle) -> Option<Shortcut> {
+    command_shortcut_config(app)?.to_shortcut()
+}
+
+pub fn begin_session(is_command: bool) {
+    COMMAND_SESSION.store(is_command, Ordering::SeqCst);
+}
+
+pub fn is_command_session() -> bool {
+    COMMAND_SESSION.load(Ordering::SeqCst)
+}
+
+/// Consume the command-mode flag for the dictation that just finished
+pub fn take_session() -> bool {
+    COMMAND_SESSION.swap(false, Ordering::SeqCst)
+}
+
+/// Match and run a command-mode transcript, emitting a preview first
+pub fn handle(app: &AppHandle, transcript: &str) -> Result<CommandPreview, String> {
+    let config = config(app);
+    let matched = find_command(&config.commands, transcript);
+    let preview = CommandPreview {
+        transcript: transcript.to_string(),
+        command: matched.as_ref().map(|(command, _)| command.clone()),
+        captured_text: matched.as_ref().and_then(|(_, captured)| captured.clone()),
+        dry_run: config.dry_run,
+    };
+    let _ = app.emit("voice-command-preview", &preview);
+
+    if config.dry_run {
+        return Ok(preview);
+    }
+    let (command, captured) =
+        matched.ok_or_else(|| i18n::tf("error.no_voice_command", &[("transcript", transcript)]))?;
+    execute(&command.action, captured.as_deref())?;
+    Ok(preview)
+}
+
+#[tauri::command]
+pub async fn list_voice_commands(app: AppHandle) -> VoiceCommandConfig {
+    config(&app)
+}
+
+#[tauri::command]
+pub async fn save_voice_commands(
+    app: AppHandle,
+    commands: Vec<VoiceCommand>,
+    dry_run: bool,
+) -> Result<(), String> {
+    let state = app.state::<Mutex<VoiceCommandManager>>();
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.update(|config| {
+        config.commands = commands;
+        config.dry_run = dry_run;
+    })
+}
+
+/// Preview which command an utterance would trigger without running it
+#[tauri::command]
+pub async fn preview_voice_command(app: AppHandle, transcript: String) -> CommandPreview {
+    let config = config(&app);
+    let matched = find_command(&config.commands, &transcript);
+    CommandPreview {
+        command: matched.as_ref().map(|(command, _)| command.clone()),
+        captured_text: matched.and_then(|(_, captured)| captured),
+        transcript,
+        dry_run: true,
+    }
+}
+
+#[tauri::command]
+pub async fn set_command_mode_shortcut(
+    app: AppHandle,
+    shortcut: Option<ShortcutConfig>,
+) -> Result<(), String> {
+    shortcuts::set(&app, ShortcutAction::CommandMode, shortcut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_phrases_ignoring_case_and_punctuation() {
+        assert_eq!(match_phrase("Open mail", "open Mail."), Some(None));
+        assert_eq!(match_phrase("open mail", "open mail now"), None);
+        assert_eq!(
+            match_phrase("search for {text}", "Search for rust lifetimes!"),
+            Some(Some("rust lifetimes".to_string()))
+        );
+        assert_eq!(match_phrase("search for {text}", "search for"), None);
+    }
+
+    #[test]
+    fn encodes_captured_text_for_urls() {
+        assert_eq!(encode_url_component("a b&c/é"), "a+b%26c%2F%C3%A9");
+    }
+}
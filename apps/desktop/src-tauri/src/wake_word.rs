@@ -0,0 +1,324 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::audio_file::resample;
+use crate::microphone::{rms, Microphone, FRAME_DURATION, SPEECH_THRESHOLD};
+use crate::transcription::{self, TranscriptionOptions, SAMPLE_RATE};
+use crate::{append_e2e_log, i18n, settings, start_recording, stop_recording, IS_RECORDING};
+
+static LISTENING: AtomicBool = AtomicBool::new(false);
+
+/// Bursts outside this range are too short or too long to be the wake phrase
+const MIN_BURST: Duration = Duration::from_millis(300);
+const MAX_BURST: Duration = Duration::from_millis(2500);
+const BURST_END_SILENCE: Duration = Duration::from_millis(400);
+/// Silence that ends a dictation started by the wake word
+const DICTATION_END_SILENCE: Duration = Duration::from_millis(1500);
+/// Level events for the VU indicator are sent every this many frames
+const LEVEL_EVERY_FRAMES: u32 = 3;
+/// How far a burst's level must be above the background noise, about 10 dB
+const MIN_SIGNAL_TO_NOISE: f32 = 3.0;
+/// Plausible speaking time per syllable of the wake phrase
+const MIN_SYLLABLE: Duration = Duration::from_millis(100);
+const MAX_SYLLABLE: Duration = Duration::from_millis(450);
+/// Full transcriptions of bursts allowed per minute, so steady chatter near the
+/// microphone doesn't keep the model busy
+const MAX_CHECKS_PER_MINUTE: usize = 6;
+const CHECK_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WakeWordSettings {
+    /// The microphone is only opened for the wake word when explicitly enabled
+    pub enabled: bool,
+    pub phrase: String,
+}
+
+impl Default for WakeWordSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            phrase: "hey voiceflow".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WakeWordState {
+    pub enabled: bool,
+    /// True while the microphone is open for wake-word detection
+    pub listening: bool,
+}
+
+fn state(app: &AppHandle) -> WakeWordState {
+    WakeWordState {
+        enabled: settings::current(app).wake_word.enabled,
+        listening: LISTENING.load(Ordering::SeqCst),
+    }
+}
+
+fn enabled(app: &AppHandle) -> bool {
+    settings::current(app).wake_word.enabled
+}
+
+// Letters and digits only, so "Hey, Voice Flow!" matches "hey voiceflow"
+fn squash(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn is_wake_phrase(transcript: &str, phrase: &str) -> bool {
+    let phrase = squash(phrase);
+    !phrase.is_empty() && squash(transcript).starts_with(&phrase)
+}
+
+/// Vowel groups in the phrase, a rough syllable count; None for scripts without Latin vowels
+fn syllables(phrase: &str) -> Option<u32> {
+    let mut count = 0;
+    let mut in_vowel = false;
+    for c in squash(phrase).chars() {
+        let vowel = matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y');
+        if vowel && !in_vowel {
+            count += 1;
+        }
+        in_vowel = vowel;
+    }
+    (count > 0).then_some(count)
+}
+
+/// How long the speech in a burst can last to be the phrase
+fn phrase_duration(phrase: &str) -> RangeInclusive<Duration> {
+    match syllables(phrase) {
+        Some(count) => {
+            (MIN_SYLLABLE * count).max(MIN_BURST)..=(MAX_SYLLABLE * count).min(MAX_BURST)
+        }
+        None => MIN_BURST..=MAX_BURST,
+    }
+}
+
+/// Cheap checks a burst has to pass before the sidecar transcribes it
+struct BurstGate {
+    /// Running average level of the frames below the speech threshold
+    noise_floor: f32,
+    /// When recent bursts were transcribed
+    checks: VecDeque<Instant>,
+}
+
+impl BurstGate {
+    fn new() -> Self {
+        Self {
+            noise_floor: 0.0,
+            checks: VecDeque::new(),
+        }
+    }
+
+    fn quiet_frame(&mut self, level: f32) {
+        self.noise_floor = self.noise_floor * 0.95 + level * 0.05;
+    }
+
+    /// Whether a burst with `speech` long at an average `level` is worth transcribing,
+    /// counting it against the rate cap when it is
+    fn admit(&mut self, speech: Duration, level: f32, phrase: &str, now: Instant) -> bool {
+        if !phrase_duration(phrase).contains(&speech)
+            || level < self.noise_floor * MIN_SIGNAL_TO_NOISE
+        {
+            return false;
+        }
+        while self
+            .checks
+            .front()
+            .is_some_and(|check| now.duration_since(*check) >= CHECK_WINDOW)
+        {
+            self.checks.pop_front();
+        }
+        if self.checks.len() >= MAX_CHECKS_PER_MINUTE {
+            return false;
+        }
+        self.checks.push_back(now);
+        true
+    }
+}
+
+/// Ask the sidecar whether a short burst of speech was the wake phrase
+fn burst_is_wake_phrase(burst: &[f32], sample_rate: u32, phrase: &str) -> bool {
+    let samples: Vec<i16> = resample(burst, sample_rate, SAMPLE_RATE)
+        .into_iter()
+        .map(|sample| (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+        .collect();
+    match transcription::transcribe(&samples, &TranscriptionOptions::default()) {
+        Ok(transcript) => is_wake_phrase(&transcript.text, phrase),
+        Err(e) => {
+            eprintln!("[voiceflow] Wake word check failed: {e}");
+            false
+        }
+    }
+}
+
+fn listen(app: &AppHandle) -> Result<(), String> {
+    let mut microphone = Microphone::open(app)?;
+    let _ = app.emit("wake-word-listening", state(app));
+
+    let mut gate = BurstGate::new();
+    let mut burst: Vec<f32> = Vec::new();
+    let mut speech = Duration::ZERO;
+    let mut speech_level = 0.0;
+    let mut silence = Duration::ZERO;
+    let mut dictating = false;
+    let mut frames = 0_u32;
+
+    while enabled(app) {
+        for frame in microphone.frames(Duration::from_millis(200)) {
+            let level = rms(&frame);
+            let is_speech = level > SPEECH_THRESHOLD;
+
+            frames = frames.wrapping_add(1);
+            if frames.is_multiple_of(LEVEL_EVERY_FRAMES) {
+                let _ = app.emit("wake-word-level", level);
+            }
+
+            if dictating {
+                silence = if is_speech {
+                    Duration::ZERO
+                } else {
+                    silence + FRAME_DURATION
+                };
+                if !IS_RECORDING.load(Ordering::SeqCst) {
+                    dictating = false;
+                } else if silence >= DICTATION_END_SILENCE {
+                    dictating = false;
+                    stop_recording(app);
+                }
+                continue;
+            }
+            if IS_RECORDING.load(Ordering::SeqCst) {
+                // Another dictation owns the microphone
+                burst.clear();
+                continue;
+            }
+
+            if !is_speech {
+                gate.quiet_frame(level);
+            }
+            if is_speech {
+                speech += FRAME_DURATION;
+                speech_level += level;
+                silence = Duration::ZERO;
+                burst.extend(&frame);
+            } else if !burst.is_empty() {
+                silence += FRAME_DURATION;
+                burst.extend(&frame);
+                if silence < BURST_END_SILENCE {
+                    continue;
+                }
+
+                let phrase = settings::current(app).wake_word.phrase;
+                let frames_of_speech = speech.as_secs_f32() / FRAME_DURATION.as_secs_f32();
+                let level = speech_level / frames_of_speech.max(1.0);
+                let detected = gate.admit(speech, level, &phrase, Instant::now())
+                    && burst_is_wake_phrase(&burst, microphone.sample_rate, &phrase);
+                burst.clear();
+                speech = Duration::ZERO;
+                speech_level = 0.0;
+                silence = Duration::ZERO;
+                if detected {
+                    append_e2e_log("wake-word-detected");
+                    let _ = app.emit("wake-word-detected", ());
+                    dictating = start_recording(app);
+                    // Audio captured while checking belongs to the dictation, not the listener
+                    microphone.discard_pending();
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Start the listener thread if the wake word is enabled and it isn't already running
+pub fn start_listener(app: AppHandle) {
+    if !enabled(&app) || LISTENING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    thread::spawn(move || {
+        if let Err(e) = listen(&app) {
+            eprintln!("[voiceflow] Wake word listener stopped: {e}");
+        }
+        LISTENING.store(false, Ordering::SeqCst);
+        let _ = app.emit("wake-word-listening", state(&app));
+    });
+}
+
+#[tauri::command]
+pub async fn get_wake_word_state(app: AppHandle) -> WakeWordState {
+    state(&app)
+}
+
+#[tauri::command]
+pub async fn set_wake_word(
+    app: AppHandle,
+    enabled: bool,
+    phrase: Option<String>,
+) -> Result<WakeWordState, String> {
+    if phrase
+        .as_deref()
+        .is_some_and(|phrase| squash(phrase).is_empty())
+    {
+        return Err(i18n::t("error.wake_phrase"));
+    }
+    settings::update(&app, |settings| {
+        settings.wake_word.enabled = enabled;
+        if let Some(phrase) = phrase {
+            settings.wake_word.phrase = phrase;
+        }
+    })?;
+    // Disabling is picked up by the listener loop, which releases the microphone
+    start_listener(app.clone());
+    Ok(state(&app))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_wake_phrase_loosely() {
+        assert!(is_wake_phrase("Hey, Voice Flow!", "hey voiceflow"));
+        assert!(is_wake_phrase("hey voiceflow take a note", "hey voiceflow"));
+        assert!(!is_wake_phrase("hey there", "hey voiceflow"));
+        assert!(!is_wake_phrase("anything", "!!"));
+    }
+
+    #[test]
+    fn expects_bursts_as_long_as_the_phrase() {
+        assert_eq!(syllables("hey voiceflow"), Some(4));
+        let range = phrase_duration("hey voiceflow");
+        assert!(range.contains(&Duration::from_millis(900)));
+        assert!(!range.contains(&Duration::from_millis(2400)));
+        assert_eq!(phrase_duration("ねえ"), MIN_BURST..=MAX_BURST);
+    }
+
+    #[test]
+    fn skips_quiet_bursts_and_caps_checks() {
+        let mut gate = BurstGate::new();
+        for _ in 0..100 {
+            gate.quiet_frame(0.02);
+        }
+        let speech = Duration::from_millis(900);
+        let start = Instant::now();
+        assert!(!gate.admit(speech, 0.03, "hey voiceflow", start));
+        for _ in 0..MAX_CHECKS_PER_MINUTE {
+            assert!(gate.admit(speech, 0.2, "hey voiceflow", start));
+        }
+        assert!(!gate.admit(speech, 0.2, "hey voiceflow", start));
+        assert!(gate.admit(speech, 0.2, "hey voiceflow", start + CHECK_WINDOW));
+    }
+}
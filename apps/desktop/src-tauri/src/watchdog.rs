@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, WebviewWindow, WebviewWindowBuilder};
+
+use crate::{append_e2e_log, show_main_window, show_or_create_main_app, IS_RECORDING};
+
+/// Windows whose web content process is watched
+const WATCHED: &[&str] = &["main", "main-app"];
+const PING_INTERVAL: Duration = Duration::from_secs(5);
+/// Unanswered pings before a webview is considered crashed, leaving room for slow reloads
+const MAX_MISSED: u32 = 3;
+const PING_SCRIPT: &str = "window.__TAURI_INTERNALS__.invoke('webview_heartbeat')";
+
+// Pings sent to each window since it last answered
+static MISSED: Mutex<Option<HashMap<String, u32>>> = Mutex::new(None);
+
+fn with_missed<T>(f: impl FnOnce(&mut HashMap<String, u32>) -> T) -> Option<T> {
+    let mut missed = MISSED.lock().ok()?;
+    Some(f(missed.get_or_insert_with(HashMap::new)))
+}
+
+/// Ping the watched webviews and recreate any whose page stopped answering,
+/// e.g. after the web content process crashed
+pub fn start(app: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(PING_INTERVAL);
+        for label in WATCHED {
+            let Some(window) = app.get_webview_window(label) else {
+                with_missed(|missed| missed.remove(*label));
+                continue;
+            };
+            let missed = with_missed(|missed| *missed.get(*label).unwrap_or(&0)).unwrap_or(0);
+            if missed >= MAX_MISSED {
+                with_missed(|missed| missed.remove(*label));
+                recreate(&app, window);
+                continue;
+            }
+            with_missed(|missed| *missed.entry(label.to_string()).or_insert(0) += 1);
+            let _ = window.eval(PING_SCRIPT);
+        }
+    });
+}
+
+fn recreate(app: &AppHandle, window: WebviewWindow) {
+    let label = window.label().to_string();
+    let was_visible = window.is_visible().unwrap_or(false);
+    eprintln!("[voiceflow] Webview \"{label}\" stopped responding, recreating it");
+    append_e2e_log(&format!("webview-recreated {label}"));
+    let _ = window.destroy();
+    // Destroying is asynchronous and the label can't be reused until it completes
+    for _ in 0..20 {
+        if app.get_webview_window(&label).is_none() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    match label.as_str() {
+        "main" => {
+            let Some(config) = app
+                .config()
+                .app
+                .windows
+                .iter()
+                .find(|config| config.label == label)
+            else {
+                return;
+            };
+            let window = match WebviewWindowBuilder::from_config(app, config)
+                .and_then(|builder| builder.build())
+            {
+                Ok(window) => window,
+                Err(e) => {
+                    eprintln!("[voiceflow] Failed to recreate bubble window: {e}");
+                    return;
+                }
+            };
+            #[cfg(target_os = "macos")]
+            crate::setup_macos_panel(&window);
+            #[cfg(not(target_os = "macos"))]
+            let _ = window;
+            // The reloaded page picks up the running session from the page load hook
+            if IS_RECORDING.load(Ordering::SeqCst) {
+                show_main_window(app);
+            }
+        }
+        _ => {
+            // The main app window is created on demand, so only bring it back if it was open
+            if was_visible {
+                show_or_create_main_app(app);
+            }
+        }
+    }
+}
+
+/// Answer to the watchdog's ping from a live page
+#[tauri::command]
+pub async fn webview_heartbeat(window: WebviewWindow) {
+    with_missed(|missed| missed.remove(window.label()));
+}
@@ -0,0 +1,300 @@
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Local, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::app_stats::{self, AppStats};
+use crate::history::{self, HistoryEntry};
+use crate::session::now_ms;
+use crate::voice_commands::COMMAND_HISTORY_TAG;
+use crate::{i18n, settings};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+const WEEK_MS: u64 = 7 * 24 * 60 * 60 * 1000;
+/// Apps listed in the summary
+const TOP_APPS: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WeeklySummarySettings {
+    pub enabled: bool,
+    /// Day the summary is sent, 0 for Monday through 6 for Sunday
+    pub weekday: u32,
+    /// Local hour the summary is sent at
+    pub hour: u32,
+    /// When the last summary was sent
+    pub last_sent_ms: u64,
+}
+
+impl Default for WeeklySummarySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            weekday: 0,
+            hour: 9,
+            last_sent_ms: 0,
+        }
+    }
+}
+
+impl WeeklySummarySettings {
+    pub fn is_valid(&self) -> bool {
+        self.weekday <= 6 && self.hour <= 23
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeeklySummary {
+    pub from_ms: u64,
+    pub to_ms: u64,
+    pub dictations: usize,
+    pub words: usize,
+    /// Busiest apps first
+    pub top_apps: Vec<AppStats>,
+    /// Mean time from stopping a recording until its transcript was stored
+    pub average_latency_ms: Option<u64>,
+}
+
+fn summarize(entries: &[HistoryEntry], from_ms: u64, to_ms: u64) -> WeeklySummary {
+    let dictated: Vec<HistoryEntry> = entries
+        .iter()
+        .filter(|entry| entry.tag.as_deref() != Some(COMMAND_HISTORY_TAG))
+        .cloned()
+        .collect();
+    let latencies: Vec<u64> = dictated
+        .iter()
+        .filter_map(|entry| entry.latency_ms)
+        .collect();
+    let mut top_apps = app_stats::stats(&dictated);
+    top_apps.truncate(TOP_APPS);
+    WeeklySummary {
+        from_ms,
+        to_ms,
+        dictations: dictated.len(),
+        words: dictated
+            .iter()
+            .map(|entry| entry.text.split_whitespace().count())
+            .sum(),
+        top_apps,
+        average_latency_ms: (!latencies.is_empty())
+            .then(|| latencies.iter().sum::<u64>() / latencies.len() as u64),
+    }
+}
+
+/// The most recent scheduled send time at or before `now`
+fn last_scheduled(
+    now: DateTime<Local>,
+    schedule: &WeeklySummarySettings,
+) -> Option<DateTime<Local>> {
+    let days_back = (now.weekday().num_days_from_monday() + 7 - schedule.weekday) % 7;
+    let at = |days_back: u32| {
+        let date = now.date_naive() - ChronoDuration::days(i64::from(days_back));
+        let time = NaiveTime::from_hms_opt(schedule.hour, 0, 0)?;
+        Local.from_local_datetime(&date.and_time(time)).earliest()
+    };
+    let scheduled = at(days_back)?;
+    if scheduled <= now {
+        Some(scheduled)
+    } else {
+        at(days_back + 7)
+    }
+}
+
+fn body(summary: &WeeklySummary) -> String {
+    let mut lines = vec![i18n::tf(
+        "weekly_summary.words",
+        &[
+            ("words", &summary.words.to_string()),
+            ("dictations", &summary.dictations.to_string()),
+        ],
+    )];
+    if !summary.top_apps.is_empty() {
+        let apps: Vec<&str> = summary
+            .top_apps
+            .iter()
+            .map(|app| app.app.as_str())
+            .collect();
+        lines.push(i18n::tf(
+            "weekly_summary.top_apps",
+            &[("apps", &apps.join(", "))],
+        ));
+    }
+    if let Some(latency) = summary.average_latency_ms {
+        lines.push(i18n::tf(
+            "weekly_summary.latency",
+            &[("latency", &latency.to_string())],
+        ));
+    }
+    lines.join("\n")
+}
+
+#[cfg(target_os = "macos")]
+fn notify(title: &str, body: &str) -> Result<(), String> {
+    use crate::keyboard::applescript_string;
+    use std::process::Command;
+
+    let script = format!(
+        "display notification {} with title {}",
+        applescript_string(body),
+        applescript_string(title)
+    );
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .map_err(|e| format!("Failed to run osascript: {e}"))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn notify(title: &str, body: &str) -> Result<(), String> {
+    use std::process::Command;
+
+    let output = Command::new("notify-send")
+        .args(["--app-name=VoiceFlow", title, body])
+        .output()
+        .map_err(|e| format!("Failed to run notify-send: {e}"))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+    Ok(())
+}
+
+// The weekly-summary event is all the webview gets here
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn notify(_title: &str, _body: &str) -> Result<(), String> {
+    Ok(())
+}
+
+/// Send the summary when a scheduled time has passed since the last one.
+/// A week without dictations is skipped silently.
+fn send_if_due(app: &AppHandle) {
+    let schedule = settings::current(app).weekly_summary;
+    if !schedule.enabled || !schedule.is_valid() {
+        return;
+    }
+    let Some(scheduled) = last_scheduled(Local::now(), &schedule) else {
+        return;
+    };
+    let to_ms = scheduled.timestamp_millis().max(0) as u64;
+    if schedule.last_sent_ms >= to_ms {
+        return;
+    }
+    if let Err(e) = settings::update(app, |settings| {
+        settings.weekly_summary.last_sent_ms = now_ms()
+    }) {
+        eprintln!("[voiceflow] Failed to save weekly summary time: {e}");
+        return;
+    }
+
+    let from_ms = to_ms.saturating_sub(WEEK_MS);
+    let summary = summarize(
+        &history::entries_between(app, from_ms, to_ms),
+        from_ms,
+        to_ms,
+    );
+    if summary.dictations == 0 {
+        return;
+    }
+    let _ = app.emit("weekly-summary", &summary);
+    if let Err(e) = notify(&i18n::t("weekly_summary.title"), &body(&summary)) {
+        eprintln!("[voiceflow] Failed to show weekly summary: {e}");
+    }
+}
+
+pub fn start_scheduler(app: AppHandle) {
+    thread::spawn(move || loop {
+        send_if_due(&app);
+        thread::sleep(CHECK_INTERVAL);
+    });
+}
+
+/// Summary of the last seven days
+#[tauri::command]
+pub async fn get_weekly_summary(app: AppHandle) -> WeeklySummary {
+    let to_ms = now_ms();
+    let from_ms = to_ms.saturating_sub(WEEK_MS);
+    summarize(
+        &history::entries_between(&app, from_ms, to_ms),
+        from_ms,
+        to_ms,
+    )
+}
+
+#[tauri::command]
+pub async fn set_weekly_summary(
+    app: AppHandle,
+    enabled: bool,
+    weekday: Option<u32>,
+    hour: Option<u32>,
+) -> Result<WeeklySummarySettings, String> {
+    let mut schedule = settings::current(&app).weekly_summary;
+    schedule.enabled = enabled;
+    schedule.weekday = weekday.unwrap_or(schedule.weekday);
+    schedule.hour = hour.unwrap_or(schedule.hour);
+    if !schedule.is_valid() {
+        return Err(i18n::t("error.weekly_summary_schedule"));
+    }
+    // Only summaries scheduled from now on are sent
+    schedule.last_sent_ms = now_ms();
+    let updated = settings::update(&app, |settings| settings.weekly_summary = schedule)?;
+    Ok(updated.weekly_summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(app: &str, text: &str, latency_ms: Option<u64>) -> HistoryEntry {
+        serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "timestampMs": 1,
+            "text": text,
+            "app": app,
+            "latencyMs": latency_ms,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn summarizes_words_apps_and_latency() {
+        let mut command = entry("Slack", "open mail", Some(900));
+        command.tag = Some(COMMAND_HISTORY_TAG.to_string());
+        let entries = [
+            entry("Slack", "see you soon", Some(300)),
+            entry("Mail", "thanks for the notes", Some(500)),
+            entry("Notes", "buy milk", None),
+            entry("Slack", "on it", None),
+            entry("Xcode", "todo", None),
+            command,
+        ];
+        let summary = summarize(&entries, 0, WEEK_MS);
+        assert_eq!(summary.dictations, 5);
+        assert_eq!(summary.words, 12);
+        let apps: Vec<&str> = summary
+            .top_apps
+            .iter()
+            .map(|app| app.app.as_str())
+            .collect();
+        assert_eq!(apps, vec!["Slack", "Mail", "Notes"]);
+        assert_eq!(summary.average_latency_ms, Some(400));
+    }
+
+    #[test]
+    fn finds_last_scheduled_time() {
+        let schedule = WeeklySummarySettings::default();
+        // Wednesday 4 March 2026
+        let now = Local.with_ymd_and_hms(2026, 3, 4, 12, 0, 0).unwrap();
+        let monday = Local.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap();
+        assert_eq!(last_scheduled(now, &schedule), Some(monday));
+
+        let early_monday = Local.with_ymd_and_hms(2026, 3, 2, 8, 0, 0).unwrap();
+        let week_before = Local.with_ymd_and_hms(2026, 2, 23, 9, 0, 0).unwrap();
+        assert_eq!(last_scheduled(early_monday, &schedule), Some(week_before));
+    }
+}
@@ -12,9 +12,13 @@ import {
 import {
   ensureServerRunning,
   getErrorMessage,
-  SERVER_WS_URL,
+  getServerEndpoint,
 } from '../utils/serverControl';
 
+async function resolveServerUrl(): Promise<string> {
+  return (await getServerEndpoint()).wsUrl;
+}
+
 interface UseTranscriptionOptions {
   autoStart?: boolean;
   listenForGlobalShortcuts?: boolean;
@@ -108,7 +112,7 @@ export function useTranscription({
     isReady,
     loadingProgress,
   } = useWebSocket({
-    url: SERVER_WS_URL,
+    resolveUrl: resolveServerUrl,
     onPartial: setPartialTranscription,
     onFinal: handleFinalTranscription,
     onError: ({ message, affectsReadiness }: WebSocketErrorInfo) => {
@@ -22,7 +22,8 @@ interface WebSocketMessage {
 }
 
 interface UseWebSocketOptions {
-  url: string;
+  /** Looked up before every connection attempt */
+  resolveUrl: () => Promise<string>;
   onPartial?: (text: string) => void;
   onFinal?: (text: string) => void;
   onError?: (error: WebSocketErrorInfo) => void;
@@ -30,7 +31,7 @@ interface UseWebSocketOptions {
 }
 
 export function useWebSocket({
-  url,
+  resolveUrl,
   onPartial,
   onFinal,
   onError,
@@ -103,83 +104,91 @@ export function useWebSocket({
 
     isConnectingRef.current = true;
 
-    try {
-      const ws = new WebSocket(url);
-      wsRef.current = ws;
+    void resolveUrl()
+      .then((url) => {
+        // disconnect() may have run while the address was looked up
+        if (!shouldReconnectRef.current) {
+          isConnectingRef.current = false;
+          return;
+        }
 
-      ws.onopen = () => {
-        isConnectingRef.current = false;
-        reconnectAttemptsRef.current = 0;
-        setIsConnected(true);
-        console.log('WebSocket connected');
-      };
-
-      ws.onmessage = (event) => {
-        try {
-          const message: WebSocketMessage = JSON.parse(event.data);
-
-          switch (message.type) {
-            case 'ready':
-              setIsReady(true);
-              setLoadingProgress(null);
-              break;
-            case 'loading': {
-              const progress: LoadingProgress = {
-                stage: (message.stage as LoadingProgress['stage']) || 'loading',
-                progress: message.progress || 0,
-                message: message.message || 'Loading...',
-              };
-              setIsReady(progress.stage === 'ready');
-              setLoadingProgress(progress.stage === 'ready' ? null : progress);
-              onLoadingRef.current?.(progress);
-              break;
-            }
-            case 'partial':
-              if (message.text) onPartialRef.current?.(message.text);
-              break;
-            case 'final':
-              onFinalRef.current?.(message.text || '');
-              break;
-            case 'error':
-              if (message.affectsReadiness) {
-                setIsReady(false);
+        const ws = new WebSocket(url);
+        wsRef.current = ws;
+
+        ws.onopen = () => {
+          isConnectingRef.current = false;
+          reconnectAttemptsRef.current = 0;
+          setIsConnected(true);
+          console.log('WebSocket connected');
+        };
+
+        ws.onmessage = (event) => {
+          try {
+            const message: WebSocketMessage = JSON.parse(event.data);
+
+            switch (message.type) {
+              case 'ready':
+                setIsReady(true);
                 setLoadingProgress(null);
+                break;
+              case 'loading': {
+                const progress: LoadingProgress = {
+                  stage: (message.stage as LoadingProgress['stage']) || 'loading',
+                  progress: message.progress || 0,
+                  message: message.message || 'Loading...',
+                };
+                setIsReady(progress.stage === 'ready');
+                setLoadingProgress(progress.stage === 'ready' ? null : progress);
+                onLoadingRef.current?.(progress);
+                break;
               }
-              if (message.error) {
-                onErrorRef.current?.({
-                  message: message.error,
-                  affectsReadiness: message.affectsReadiness ?? false,
-                });
-              }
-              break;
+              case 'partial':
+                if (message.text) onPartialRef.current?.(message.text);
+                break;
+              case 'final':
+                onFinalRef.current?.(message.text || '');
+                break;
+              case 'error':
+                if (message.affectsReadiness) {
+                  setIsReady(false);
+                  setLoadingProgress(null);
+                }
+                if (message.error) {
+                  onErrorRef.current?.({
+                    message: message.error,
+                    affectsReadiness: message.affectsReadiness ?? false,
+                  });
+                }
+                break;
+            }
+          } catch {
+            console.error('Failed to parse WebSocket message');
           }
-        } catch {
-          console.error('Failed to parse WebSocket message');
-        }
-      };
-
-      ws.onerror = (error) => {
-        isConnectingRef.current = false;
-        console.error('WebSocket error:', error);
-        onErrorRef.current?.({
-          message: 'WebSocket connection error',
-          affectsReadiness: true,
-        });
-      };
-
-      ws.onclose = () => {
+        };
+
+        ws.onerror = (error) => {
+          isConnectingRef.current = false;
+          console.error('WebSocket error:', error);
+          onErrorRef.current?.({
+            message: 'WebSocket connection error',
+            affectsReadiness: true,
+          });
+        };
+
+        ws.onclose = () => {
+          isConnectingRef.current = false;
+          wsRef.current = null;
+          setIsConnected(false);
+          setIsReady(false);
+          scheduleReconnect();
+        };
+      })
+      .catch((error) => {
         isConnectingRef.current = false;
-        wsRef.current = null;
-        setIsConnected(false);
-        setIsReady(false);
+        console.error('Failed to connect WebSocket:', error);
         scheduleReconnect();
-      };
-    } catch (error) {
-      isConnectingRef.current = false;
-      console.error('Failed to connect WebSocket:', error);
-      scheduleReconnect();
-    }
-  }, [url, clearReconnectTimeout, scheduleReconnect]);
+      });
+  }, [resolveUrl, clearReconnectTimeout, scheduleReconnect]);
 
   connectRef.current = connect;
 
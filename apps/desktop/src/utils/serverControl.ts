@@ -1,9 +1,11 @@
 import { invoke, isTauri } from '@tauri-apps/api/core';
 
-export const SERVER_BASE_URL = 'http://127.0.0.1:8765';
-export const SERVER_WS_URL = 'ws://127.0.0.1:8765/ws';
+export interface ServerEndpoint {
+  port: number;
+  httpUrl: string;
+  wsUrl: string;
+}
 
-const MODEL_RELOAD_URL = `${SERVER_BASE_URL}/model/reload`;
 const RELOAD_RETRY_DELAYS_MS = [250, 500, 1000, 1500];
 
 function wait(ms: number): Promise<void> {
@@ -24,6 +26,11 @@ export function getErrorMessage(error: unknown, fallback: string): string {
   return fallback;
 }
 
+/** The sidecar's address, asked for on every connection since it moves when its port is taken. */
+export async function getServerEndpoint(): Promise<ServerEndpoint> {
+  return invoke<ServerEndpoint>('get_server_endpoint');
+}
+
 export async function ensureServerRunning(): Promise<void> {
   if (!isTauri()) {
     return;
@@ -37,7 +44,8 @@ export async function requestModelReload(): Promise<void> {
 
   for (let attempt = 0; attempt < RELOAD_RETRY_DELAYS_MS.length; attempt += 1) {
     try {
-      const response = await fetch(MODEL_RELOAD_URL, { method: 'POST' });
+      const { httpUrl } = await getServerEndpoint();
+      const response = await fetch(`${httpUrl}/model/reload`, { method: 'POST' });
 
       if (!response.ok) {
         const detail = await response.text();
@@ -0,0 +1,132 @@
+"""Speaker diarization for meeting transcripts.
+
+Every transcript segment gets a voice fingerprint, the average shape of its
+spectral envelope, and segments are clustered bottom-up until the closest
+clusters sound too different to be one voice. It only needs numpy and is meant
+for a handful of speakers recorded through the same microphone.
+"""
+
+from typing import Optional, Sequence
+
+import numpy as np
+
+FRAME_SECONDS = 0.025
+HOP_SECONDS = 0.010
+BANDS = 32
+MIN_FREQUENCY = 80.0
+MAX_FREQUENCY = 7600.0
+# Frames quieter than this share of the segment's frames are pauses and breaths
+QUIET_PERCENTILE = 30
+# Segments shorter than this hold too little speech for a reliable fingerprint
+MIN_FINGERPRINT_SECONDS = 1.0
+# RMS difference between envelopes, in dB, above which two clusters are different voices
+SAME_SPEAKER_DISTANCE = 3.0
+MAX_SPEAKERS = 8
+
+
+def speaker_label(index: int) -> str:
+    return f"SPEAKER_{index:02d}"
+
+
+def fingerprint(audio: np.ndarray, sample_rate: int) -> Optional[np.ndarray]:
+    """Average log band energies of the voiced frames, relative to their mean.
+
+    Subtracting the mean makes the fingerprint independent of loudness, so only
+    the timbre of the voice is compared. None when there is too little audio.
+    """
+    frame = int(FRAME_SECONDS * sample_rate)
+    hop = int(HOP_SECONDS * sample_rate)
+    if len(audio) < frame:
+        return None
+
+    count = 1 + (len(audio) - frame) // hop
+    indices = np.arange(frame)[None, :] + hop * np.arange(count)[:, None]
+    frames = audio[indices] * np.hanning(frame)
+    n_fft = 1 << (frame - 1).bit_length()
+    power = np.abs(np.fft.rfft(frames, n=n_fft)) ** 2
+
+    energy = power.sum(axis=1)
+    voiced = power[energy > np.percentile(energy, QUIET_PERCENTILE)]
+    if len(voiced) < 10:
+        return None
+
+    edges_hz = np.geomspace(MIN_FREQUENCY, min(MAX_FREQUENCY, sample_rate / 2), BANDS + 1)
+    edges = np.clip(np.round(edges_hz * n_fft / sample_rate).astype(int), 1, n_fft // 2)
+    bands = np.stack(
+        [voiced[:, low:max(high, low + 1)].sum(axis=1) for low, high in zip(edges[:-1], edges[1:])],
+        axis=1,
+    )
+    decibels = 10.0 * np.log10(bands + 1e-10)
+    decibels -= decibels.mean(axis=1, keepdims=True)
+    return decibels.mean(axis=0)
+
+
+def cluster(fingerprints: np.ndarray) -> list[int]:
+    """Cluster index for each fingerprint, numbered in order of first appearance."""
+    count = len(fingerprints)
+    centroids = fingerprints.astype(np.float64)
+    sizes = np.ones(count)
+    active = np.ones(count, dtype=bool)
+    members = list(range(count))
+
+    squared = (centroids ** 2).sum(axis=1)
+    gram = squared[:, None] + squared[None, :] - 2.0 * centroids @ centroids.T
+    distances = np.sqrt(np.maximum(gram, 0.0) / centroids.shape[1])
+    np.fill_diagonal(distances, np.inf)
+
+    while active.sum() > 1:
+        masked = np.where(active[:, None] & active[None, :], distances, np.inf)
+        keep, merged = np.unravel_index(np.argmin(masked), masked.shape)
+        if masked[keep, merged] > SAME_SPEAKER_DISTANCE and active.sum() <= MAX_SPEAKERS:
+            break
+
+        total = sizes[keep] + sizes[merged]
+        centroids[keep] = (centroids[keep] * sizes[keep] + centroids[merged] * sizes[merged]) / total
+        sizes[keep] = total
+        active[merged] = False
+        members = [keep if member == merged else member for member in members]
+
+        row = np.sqrt(((centroids - centroids[keep]) ** 2).mean(axis=1))
+        distances[keep, :] = row
+        distances[:, keep] = row
+        distances[keep, keep] = np.inf
+
+    order: dict[int, int] = {}
+    return [order.setdefault(member, len(order)) for member in members]
+
+
+def assign_speakers(
+    audio: np.ndarray,
+    sample_rate: int,
+    spans: Sequence[tuple[float, float]],
+) -> list[str]:
+    """A raw speaker label (SPEAKER_00, SPEAKER_01, ...) for each (start, end) span.
+
+    Spans too short to fingerprint, like a quick "yes", keep the speaker of the
+    span before them, since short replies rarely interrupt a turn mid-sentence.
+    """
+    fingerprints: list[Optional[np.ndarray]] = []
+    for start, end in spans:
+        if end - start < MIN_FINGERPRINT_SECONDS:
+            fingerprints.append(None)
+            continue
+        piece = audio[int(start * sample_rate):int(end * sample_rate)]
+        fingerprints.append(fingerprint(piece, sample_rate))
+
+    anchors = [index for index, value in enumerate(fingerprints) if value is not None]
+    if not anchors:
+        return [speaker_label(0)] * len(spans)
+
+    clusters = cluster(np.stack([fingerprints[index] for index in anchors]))
+    speakers: list[Optional[int]] = [None] * len(spans)
+    for index, speaker in zip(anchors, clusters):
+        speakers[index] = speaker
+
+    previous = clusters[0]
+    labels = []
+    for speaker in speakers:
+        if speaker is None:
+            speaker = previous
+        previous = speaker
+        labels.append(speaker_label(speaker))
+    return labels
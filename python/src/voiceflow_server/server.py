@@ -1,14 +1,17 @@
 """WebSocket server for real-time speech-to-text transcription."""
 
+import argparse
 import asyncio
 import json
 import logging
+import os
 import signal
 import sys
 import tempfile
 from contextlib import asynccontextmanager, suppress
+from dataclasses import dataclass
 from pathlib import Path
-from typing import Optional, Set
+from typing import Any, Optional, Set
 
 import numpy as np
 import soundfile as sf
@@ -16,6 +19,8 @@ import uvicorn
 from fastapi import FastAPI, HTTPException, WebSocket, WebSocketDisconnect
 from fastapi.middleware.cors import CORSMiddleware
 
+from voiceflow_server.diarization import assign_speakers
+
 logging.basicConfig(
     level=logging.INFO,
     format="%(asctime)s - %(name)s - %(levelname)s - %(message)s",
@@ -28,6 +33,19 @@ transcriber: Optional["Transcriber"] = None
 # Connected WebSocket clients for broadcasting loading progress
 connected_clients: Set[WebSocket] = set()
 
+# Beam search settings decoded in turn for alternative hypotheses. Each one trades
+# off length and token durations differently, so they disagree where the audio is
+# ambiguous.
+ALTERNATIVE_BEAMS = [
+    {"beam_size": 4},
+    {"beam_size": 8, "length_penalty": 0.5},
+    {"beam_size": 8, "length_penalty": -0.5},
+    {"beam_size": 8, "duration_reward": 0.3},
+    {"beam_size": 8, "duration_reward": 0.9},
+]
+# Every alternative decodes the audio again, which isn't worth it for long recordings
+MAX_ALTERNATIVES_SECONDS = 120
+
 
 async def broadcast_loading_status(stage: str, progress: float, message: str):
     """Broadcast loading status to all connected clients."""
@@ -92,6 +110,63 @@ app.add_middleware(
 )
 
 
+@dataclass
+class TranscriptionOptions:
+    """Extras a client asks for in its start message."""
+
+    # Label each segment with its speaker
+    diarize: bool = False
+    # Include every word with its start and end time
+    word_timestamps: bool = False
+    # Number of hypotheses to send besides the best one
+    alternatives: int = 0
+
+    @classmethod
+    def from_message(cls, data: dict[str, Any]) -> "TranscriptionOptions":
+        try:
+            alternatives = int(data.get("alternatives", 0))
+        except (TypeError, ValueError):
+            alternatives = 0
+        return cls(
+            diarize=bool(data.get("diarize", False)),
+            word_timestamps=bool(data.get("wordTimestamps", False)),
+            alternatives=min(max(alternatives, 0), len(ALTERNATIVE_BEAMS)),
+        )
+
+    @property
+    def detailed(self) -> bool:
+        """Whether the final message needs more than the plain text."""
+        return self.diarize or self.word_timestamps or self.alternatives > 0
+
+
+def token_confidence(tokens) -> Optional[float]:
+    """Mean confidence of the tokens, None when the model doesn't report it."""
+    scores = [token.confidence for token in tokens if getattr(token, "confidence", None) is not None]
+    return float(np.mean(scores)) if scores else None
+
+
+def merge_tokens(tokens) -> list[dict[str, Any]]:
+    """Join subword tokens into words with their start and end times.
+
+    The tokenizer marks the first piece of a word with a leading space, so a piece
+    without one continues the word before it.
+    """
+    words: list[dict[str, Any]] = []
+    for token in tokens:
+        if not token.text.strip():
+            continue
+        if words and not token.text[0].isspace():
+            words[-1]["text"] += token.text
+            words[-1]["end"] = float(token.end)
+        else:
+            words.append({
+                "text": token.text.strip(),
+                "start": float(token.start),
+                "end": float(token.end),
+            })
+    return words
+
+
 class Transcriber:
     """Wrapper for parakeet-mlx transcription."""
 
@@ -280,8 +355,31 @@ class Transcriber:
             logger.error(f"Transcription error: {e}")
             raise
 
-    def _transcribe_sync(self, audio_data: np.ndarray, sample_rate: int) -> str:
-        """Synchronous transcription using temp file."""
+    async def transcribe_detailed(
+        self,
+        audio_data: np.ndarray,
+        options: TranscriptionOptions,
+        sample_rate: int = 16000,
+    ) -> dict[str, Any]:
+        """Transcribe audio into text plus the segments and extras in `options`."""
+        await self._loaded.wait()
+
+        if self.model is None:
+            error_message = self.load_error or "Transcription model is unavailable"
+            raise RuntimeError(error_message)
+
+        try:
+            loop = asyncio.get_event_loop()
+            return await loop.run_in_executor(
+                None, lambda: self._transcribe_detailed_sync(audio_data, sample_rate, options)
+            )
+        except Exception as e:
+            logger.error(f"Transcription error: {e}")
+            raise
+
+    def _run_model(self, audio_data: np.ndarray, sample_rate: int, alternatives: int = 0):
+        """Run the model on the audio and return its aligned result, along with up
+        to `alternatives` other hypotheses for the whole text."""
         # Save audio to temp file (parakeet-mlx expects file path)
         with tempfile.NamedTemporaryFile(suffix=".wav", delete=False) as f:
             temp_path = Path(f.name)
@@ -292,11 +390,76 @@ class Transcriber:
 
             # Transcribe
             result = self.model.transcribe(temp_path)
-            return result.text.strip() if hasattr(result, 'text') else str(result).strip()
+            others: list[str] = []
+            if alternatives and len(audio_data) <= MAX_ALTERNATIVES_SECONDS * sample_rate:
+                others = self._other_hypotheses(temp_path, result.text.strip(), alternatives)
+            return result, others
         finally:
             # Clean up temp file
             temp_path.unlink(missing_ok=True)
 
+    def _other_hypotheses(self, path: Path, best: str, count: int) -> list[str]:
+        """Distinct texts from decoding the audio again with other beam settings."""
+        try:
+            from parakeet_mlx import Beam, DecodingConfig
+        except ImportError:
+            logger.warning("parakeet-mlx has no beam search, skipping alternatives")
+            return []
+
+        hypotheses: list[str] = []
+        for beam in ALTERNATIVE_BEAMS:
+            if len(hypotheses) >= count:
+                break
+            config = DecodingConfig(decoding=Beam(**beam))
+            text = self.model.transcribe(path, decoding_config=config).text.strip()
+            if text and text != best and text not in hypotheses:
+                hypotheses.append(text)
+        return hypotheses
+
+    def _transcribe_sync(self, audio_data: np.ndarray, sample_rate: int) -> str:
+        """Synchronous transcription using temp file."""
+        result, _ = self._run_model(audio_data, sample_rate)
+        return result.text.strip() if hasattr(result, 'text') else str(result).strip()
+
+    def _transcribe_detailed_sync(
+        self,
+        audio_data: np.ndarray,
+        sample_rate: int,
+        options: TranscriptionOptions,
+    ) -> dict[str, Any]:
+        """Synchronous transcription keeping the model's sentence alignment."""
+        result, alternatives = self._run_model(audio_data, sample_rate, options.alternatives)
+        sentences = [
+            sentence for sentence in getattr(result, "sentences", []) if sentence.text.strip()
+        ]
+        segments: list[dict[str, Any]] = []
+        for sentence in sentences:
+            segment: dict[str, Any] = {
+                "text": sentence.text.strip(),
+                "start": float(sentence.start),
+                "end": float(sentence.end),
+            }
+            confidence = getattr(sentence, "confidence", None)
+            if confidence is None:
+                confidence = token_confidence(sentence.tokens)
+            if confidence is not None:
+                segment["confidence"] = min(max(float(confidence), 0.0), 1.0)
+            segments.append(segment)
+
+        if options.diarize and segments:
+            spans = [(segment["start"], segment["end"]) for segment in segments]
+            for segment, speaker in zip(segments, assign_speakers(audio_data, sample_rate, spans)):
+                segment["speaker"] = speaker
+
+        payload: dict[str, Any] = {"text": result.text.strip(), "segments": segments}
+        if options.word_timestamps:
+            payload["words"] = [
+                word for sentence in sentences for word in merge_tokens(sentence.tokens)
+            ]
+        if options.alternatives:
+            payload["alternatives"] = alternatives
+        return payload
+
 
 class AudioBuffer:
     """Buffer for accumulating audio chunks."""
@@ -370,6 +533,7 @@ async def websocket_endpoint(websocket: WebSocket):
 
     audio_buffer = AudioBuffer()
     is_recording = False
+    options = TranscriptionOptions()
 
     try:
         # Send current server state immediately so the client can recover in place.
@@ -437,6 +601,7 @@ async def websocket_endpoint(websocket: WebSocket):
                             continue
 
                         is_recording = True
+                        options = TranscriptionOptions.from_message(data)
                         audio_buffer.clear()
                         logger.info("Recording started")
 
@@ -447,11 +612,14 @@ async def websocket_endpoint(websocket: WebSocket):
                         # Final transcription
                         audio = audio_buffer.get_audio()
                         logger.info(f"Processing {len(audio)} samples...")
-                        text = ""
+                        result: dict[str, Any] = {"text": ""}
                         if len(audio) > 0 and transcriber:
                             try:
-                                text = await transcriber.transcribe(audio)
-                                logger.info(f"Transcription: {text}")
+                                if options.detailed:
+                                    result = await transcriber.transcribe_detailed(audio, options)
+                                else:
+                                    result = {"text": await transcriber.transcribe(audio)}
+                                logger.info(f"Transcription: {result['text']}")
                             except Exception as transcribe_error:
                                 logger.error(f"Transcription failed: {transcribe_error}")
                                 await websocket.send_json(
@@ -461,7 +629,7 @@ async def websocket_endpoint(websocket: WebSocket):
                                 continue
                         else:
                             logger.info("No audio or transcriber not available")
-                        await websocket.send_json({"type": "final", "text": text})
+                        await websocket.send_json({"type": "final", **result})
 
                         audio_buffer.clear()
 
@@ -494,6 +662,14 @@ async def websocket_endpoint(websocket: WebSocket):
 
 def main():
     """Main entry point."""
+    parser = argparse.ArgumentParser(description="VoiceFlow transcription server")
+    parser.add_argument(
+        "--port",
+        type=int,
+        default=int(os.environ.get("VOICEFLOW_PORT", "8765")),
+        help="Port to listen on (default: $VOICEFLOW_PORT or 8765)",
+    )
+    args = parser.parse_args()
 
     def signal_handler(sig, frame):
         logger.info("Shutting down...")
@@ -502,11 +678,11 @@ def main():
     signal.signal(signal.SIGINT, signal_handler)
     signal.signal(signal.SIGTERM, signal_handler)
 
-    logger.info("Starting VoiceFlow server on ws://127.0.0.1:8765")
+    logger.info("Starting VoiceFlow server on ws://127.0.0.1:%d", args.port)
     uvicorn.run(
         app,
         host="127.0.0.1",
-        port=8765,
+        port=args.port,
         log_level="info",
         ws_ping_interval=20,
         ws_ping_timeout=20,
@@ -0,0 +1,105 @@
+import sys
+from types import SimpleNamespace
+
+import numpy as np
+
+from voiceflow_server import server
+
+
+def token(text, start, end, confidence=None):
+    return SimpleNamespace(text=text, start=start, end=end, confidence=confidence)
+
+
+class AlignedModel:
+    def __init__(self, sentences):
+        self.sentences = sentences
+
+    def transcribe(self, _path):
+        text = " ".join(sentence.text.strip() for sentence in self.sentences)
+        return SimpleNamespace(text=text, sentences=self.sentences)
+
+
+def test_merges_subword_tokens_into_words():
+    tokens = [token(" Hel", 0.0, 0.2), token("lo", 0.2, 0.4), token(" world", 0.5, 0.9)]
+    assert server.merge_tokens(tokens) == [
+        {"text": "Hello", "start": 0.0, "end": 0.4},
+        {"text": "world", "start": 0.5, "end": 0.9},
+    ]
+
+
+def test_detailed_transcription_reports_words_and_confidence():
+    sentence = SimpleNamespace(
+        text=" Hello world.",
+        start=0.0,
+        end=0.9,
+        tokens=[
+            token(" Hel", 0.0, 0.2, 0.9),
+            token("lo", 0.2, 0.4, 0.7),
+            token(" world.", 0.5, 0.9, 0.8),
+        ],
+    )
+    transcriber = server.Transcriber()
+    transcriber.model = AlignedModel([sentence])
+
+    result = transcriber._transcribe_detailed_sync(
+        np.zeros(16000, dtype=np.float32),
+        16000,
+        server.TranscriptionOptions(word_timestamps=True),
+    )
+
+    assert result["text"] == "Hello world."
+    [segment] = result["segments"]
+    assert abs(segment["confidence"] - 0.8) < 1e-6
+    assert [word["text"] for word in result["words"]] == ["Hello", "world."]
+    assert result["words"][0]["end"] == 0.4
+
+
+def test_words_are_only_sent_when_requested():
+    sentence = SimpleNamespace(text="Hi.", start=0.0, end=0.3, tokens=[token(" Hi.", 0.0, 0.3)])
+    transcriber = server.Transcriber()
+    transcriber.model = AlignedModel([sentence])
+
+    result = transcriber._transcribe_detailed_sync(
+        np.zeros(16000, dtype=np.float32),
+        16000,
+        server.TranscriptionOptions(diarize=True),
+    )
+
+    assert "words" not in result
+    assert "confidence" not in result["segments"][0]
+
+
+def test_alternatives_come_from_other_beam_settings(monkeypatch):
+    fake_parakeet = SimpleNamespace(
+        Beam=lambda **settings: settings,
+        DecodingConfig=lambda decoding: decoding,
+    )
+    monkeypatch.setitem(sys.modules, "parakeet_mlx", fake_parakeet)
+
+    class BeamModel:
+        def transcribe(self, _path, decoding_config=None):
+            if decoding_config is None:
+                text = "we met on Tuesday"
+            elif decoding_config.get("length_penalty", 0) < 0:
+                text = "we met on Thursday"
+            else:
+                text = "we meet on Tuesday"
+            return SimpleNamespace(text=text, sentences=[])
+
+    transcriber = server.Transcriber()
+    transcriber.model = BeamModel()
+
+    result = transcriber._transcribe_detailed_sync(
+        np.zeros(16000, dtype=np.float32),
+        16000,
+        server.TranscriptionOptions(alternatives=2),
+    )
+
+    assert result["text"] == "we met on Tuesday"
+    assert result["alternatives"] == ["we meet on Tuesday", "we met on Thursday"]
+
+
+def test_alternative_count_is_capped():
+    options = server.TranscriptionOptions.from_message({"alternatives": 50})
+    assert options.alternatives == len(server.ALTERNATIVE_BEAMS)
+    assert server.TranscriptionOptions.from_message({"alternatives": "many"}).alternatives == 0
@@ -0,0 +1,42 @@
+import numpy as np
+
+from voiceflow_server.diarization import assign_speakers
+
+SAMPLE_RATE = 16000
+
+
+def bright_voice(seconds, seed):
+    return np.random.default_rng(seed).standard_normal(int(seconds * SAMPLE_RATE)) * 0.1
+
+
+def dark_voice(seconds, seed):
+    # A moving average damps the upper bands, giving a clearly different envelope
+    return np.convolve(bright_voice(seconds, seed), np.ones(8) / 8, mode="same")
+
+
+def spans_of(pieces):
+    spans = []
+    start = 0.0
+    for piece in pieces:
+        end = start + len(piece) / SAMPLE_RATE
+        spans.append((start, end))
+        start = end
+    return spans
+
+
+def test_alternating_voices_get_two_speakers():
+    pieces = [bright_voice(2, 1), dark_voice(2, 2), bright_voice(2, 3), dark_voice(2, 4)]
+    labels = assign_speakers(np.concatenate(pieces), SAMPLE_RATE, spans_of(pieces))
+    assert labels == ["SPEAKER_00", "SPEAKER_01", "SPEAKER_00", "SPEAKER_01"]
+
+
+def test_one_voice_stays_one_speaker():
+    pieces = [bright_voice(2, seed) for seed in range(4)]
+    labels = assign_speakers(np.concatenate(pieces), SAMPLE_RATE, spans_of(pieces))
+    assert labels == ["SPEAKER_00"] * 4
+
+
+def test_short_replies_keep_the_previous_speaker():
+    pieces = [dark_voice(2, 1), bright_voice(0.4, 2), bright_voice(2, 3)]
+    labels = assign_speakers(np.concatenate(pieces), SAMPLE_RATE, spans_of(pieces))
+    assert labels == ["SPEAKER_00", "SPEAKER_00", "SPEAKER_01"]
@@ -187,3 +187,43 @@ def test_reload_endpoint_requests_forced_reload(client):
         "message": "Model reload requested",
     }
     assert calls == [True]
+
+
+def test_websocket_passes_diarization_request(client):
+    requests = []
+
+    class DiarizingTranscriber:
+        _loading = False
+        load_error = None
+        model = object()
+        loading_stage = "ready"
+        loading_progress = 1.0
+        loading_message = "ready"
+
+        async def wait_until_ready(self):
+            return None
+
+        async def transcribe_detailed(self, _audio_data, options, sample_rate=16000):
+            requests.append(options)
+            return {
+                "text": "hello there",
+                "segments": [
+                    {"text": "hello", "start": 0.0, "end": 0.5, "speaker": "SPEAKER_00"},
+                    {"text": "there", "start": 0.5, "end": 1.0, "speaker": "SPEAKER_01"},
+                ],
+            }
+
+    server.transcriber = DiarizingTranscriber()
+
+    with client.websocket_connect("/ws") as ws:
+        assert ws.receive_json() == {"type": "ready"}
+        ws.send_text(json.dumps({"type": "start", "diarize": True}))
+        ws.send_bytes(np.array([0, 500, -500, 0], dtype=np.int16).tobytes())
+        ws.send_text(json.dumps({"type": "end"}))
+        message = ws.receive_json()
+        assert message["type"] == "final"
+        assert [segment["speaker"] for segment in message["segments"]] == [
+            "SPEAKER_00",
+            "SPEAKER_01",
+        ]
+    assert requests == [server.TranscriptionOptions(diarize=True)]